@@ -1,6 +1,9 @@
-use actix_web::{web, Scope, post, HttpResponse, HttpRequest, Responder, CustomizeResponder};
-use crate::helper::trace::trace_logs;
+use actix_web::{web, Scope, get, post, HttpResponse, HttpRequest, Responder};
+use crate::helper::trace::{trace_logs, trace_warn};
+use crate::helper::security;
+use crate::helper::i18n::{self, negotiate};
 use serde_json::json;
+use std::collections::HashMap;
 use futures::StreamExt;
 use serde_json::Value;
 
@@ -10,10 +13,20 @@ use crate::api::mods::*;
 
 
 const MAX_SIZE: usize = 262_144; // max payload size is 256k
+const MAX_IMPORT_SIZE: usize = 16_777_216; // referential files can be large, 16M
 
 #[post("/{path:.*}")]
 pub async fn handler(path: web::Path<String>, mut payload: web::Payload, req: HttpRequest) -> impl Responder {
 
+    // only the pages of MatryRiska, and the allowed origins, may call the API
+    if let Err(reason) = security::verify(&req) {
+        trace_warn(format!("API call to {} refused: {}", path, reason));
+        return HttpResponse::Forbidden().content_type("application/json").body(json!({"status": "error", "error": reason}).to_string()).customize();
+    }
+
+    // only the referential imports carry whole files
+    let max_size = if path.starts_with("referential/") { MAX_IMPORT_SIZE } else { MAX_SIZE };
+
     // payload is a stream of Bytes objects
     let mut body = web::BytesMut::new();
     while let Some(chunk) = payload.next().await {
@@ -24,7 +37,7 @@ pub async fn handler(path: web::Path<String>, mut payload: web::Payload, req: Ht
             }
         };
         // limit max size of in-memory payload
-        if (body.len() + chunk.len()) > MAX_SIZE {
+        if (body.len() + chunk.len()) > max_size {
             return HttpResponse::Ok().content_type("application/json").body("{\"status\": \"error\"}").customize();
         }
         body.extend_from_slice(&chunk);
@@ -36,94 +49,218 @@ pub async fn handler(path: web::Path<String>, mut payload: web::Payload, req: Ht
 
     match path.to_string().as_str() {
         "" => {
-            return HttpResponse::Ok().content_type("application/json").body("{\"status\": \"OK\"}").customize();
+            HttpResponse::Ok().content_type("application/json").body("{\"status\": \"OK\"}").customize()
         },
         "risk/create" => {
-            return risk::create(parsed_json).await;
+            risk::create(parsed_json).await
         }
         "risk/update" => {
-            return risk::update(parsed_json).await;
+            risk::update(parsed_json).await
         }
         "risk/delete" => {
-            return risk::delete(parsed_json).await;
+            risk::delete(parsed_json).await
+        }
+        "risk/review" => {
+            risk::review(parsed_json).await
+        }
+        "acceptance/create" => {
+            acceptance::create(parsed_json).await
+        }
+        "acceptance/revoke" => {
+            acceptance::revoke(parsed_json).await
+        }
+        "snapshot/create" => {
+            snapshot::create(parsed_json).await
+        }
+        "snapshot/delete" => {
+            snapshot::delete(parsed_json).await
+        }
+        "webhook/create" => {
+            webhook::create(parsed_json).await
+        }
+        "webhook/delete" => {
+            webhook::delete(parsed_json).await
+        }
+        "webhook/test" => {
+            webhook::test(parsed_json).await
+        }
+        "notification/save" => {
+            notification::save(parsed_json).await
+        }
+        "notification/delete" => {
+            notification::delete(parsed_json).await
+        }
+        "notification/test" => {
+            notification::test(parsed_json).await
+        }
+        "job/run" => {
+            job::run(parsed_json).await
         }
         "scenario/create" => {
-            return scenario::create(parsed_json).await;
+            scenario::create(parsed_json).await
         }
         "scenario/update" => {
-            return scenario::update(parsed_json).await;
+            scenario::update(parsed_json).await
         }
         "scenario/delete" => {
-            return scenario::delete(parsed_json).await;
+            scenario::delete(parsed_json).await
         }
         "countermeasure/create" => {
-            return countermeasure::create(parsed_json).await;
+            countermeasure::create(parsed_json).await
         }
         "countermeasure/update" => {
-            return countermeasure::update(parsed_json).await;
+            countermeasure::update(parsed_json).await
+        }
+        "countermeasure/status" => {
+            countermeasure::status(parsed_json).await
+        }
+        "countermeasure/link" => {
+            countermeasure::link(parsed_json).await
+        }
+        "countermeasure/unlink" => {
+            countermeasure::unlink(parsed_json).await
+        }
+        "countermeasure/link_gap" => {
+            countermeasure::link_gap(parsed_json).await
+        }
+        "countermeasure/unlink_gap" => {
+            countermeasure::unlink_gap(parsed_json).await
         }
         "countermeasure/delete" => {
-            return countermeasure::delete(parsed_json).await;
+            countermeasure::delete(parsed_json).await
         }
 
         "mission/create" => {
-            return mission::create(parsed_json).await;
+            mission::create(parsed_json).await
+        }
+        "mission/update" => {
+            mission::update(parsed_json).await
         }
         "mission/delete" => {
-            return mission::delete(parsed_json).await;
+            mission::delete(parsed_json).await
         }
         
         "vm/create" => {
-            return vm::create(parsed_json).await;
+            vm::create(parsed_json).await
+        }
+        "vm/update" => {
+            vm::update(parsed_json).await
         }
         "vm/delete" => {
-            return vm::delete(parsed_json).await;
+            vm::delete(parsed_json).await
         }
         
         "asset/create" => {
-            return asset::create(parsed_json).await;
+            asset::create(parsed_json).await
+        }
+        "asset/update" => {
+            asset::update(parsed_json).await
         }
         "asset/delete" => {
-            return asset::delete(parsed_json).await;
+            asset::delete(parsed_json).await
+        }
+        "asset/link" => {
+            asset::link(parsed_json).await
+        }
+        "asset/unlink" => {
+            asset::unlink(parsed_json).await
+        }
+        "asset/dependency/add" => {
+            asset::dependency_add(parsed_json).await
+        }
+        "asset/dependency/remove" => {
+            asset::dependency_remove(parsed_json).await
         }
 
         "fevnt/create" => {
-            return fevnt::create(parsed_json).await;
+            fevnt::create(parsed_json).await
+        }
+        "fevnt/update" => {
+            fevnt::update(parsed_json).await
         }
         "fevnt/delete" => {
-            return fevnt::delete(parsed_json).await;
+            fevnt::delete(parsed_json).await
+        }
+
+        "gravity/create" => {
+            gravity::create(parsed_json).await
+        }
+        "gravity/update" => {
+            gravity::update(parsed_json).await
+        }
+        "gravity/delete" => {
+            gravity::delete(parsed_json).await
         }
 
         "gaps/create" => {
-            return gaps::create(parsed_json).await;
+            gaps::create(parsed_json).await
+        }
+        "gaps/update" => {
+            gaps::update(parsed_json).await
         }
         "gaps/delete" => {
-            return gaps::delete(parsed_json).await;
+            gaps::delete(parsed_json).await
+        }
+        "gaps/assess" => {
+            gaps::assess(parsed_json).await
+        }
+        "gaps/maturity" => {
+            gaps::maturity(parsed_json).await
+        }
+
+        "referential/preview" => {
+            referential::preview(parsed_json).await
+        }
+        "referential/import" => {
+            referential::import(parsed_json).await
+        }
+        "referential/delete" => {
+            referential::delete(parsed_json).await
         }
 
         "risk_source/create" => {
-            return risk_source::create(parsed_json).await;
+            risk_source::create(parsed_json).await
+        }
+        "risk_source/update" => {
+            risk_source::update(parsed_json).await
         }
         "risk_source/delete" => {
-            return risk_source::delete(parsed_json).await;
+            risk_source::delete(parsed_json).await
         }
 
         "stakeholder/create" => {
-            return stakeholder::create(parsed_json).await;
+            stakeholder::create(parsed_json).await
+        }
+        "stakeholder/update" => {
+            stakeholder::update(parsed_json).await
         }
         "stakeholder/delete" => {
-            return stakeholder::delete(parsed_json).await;
+            stakeholder::delete(parsed_json).await
         }
 
         _ => {
             trace_logs("Path not found".to_string());
-            return HttpResponse::Ok().content_type("application/json").body("{\"error\": \"path not found\"}").customize();
+            HttpResponse::Ok().content_type("application/json").body("{\"error\": \"path not found\"}").customize()
         }
     }
     
 }
 
 
+#[get("/export/oscal/{document}")]
+pub async fn export_oscal(document: web::Path<String>, req: HttpRequest) -> impl Responder {
+    // in the language of the UI
+    return i18n::scope(negotiate(&req), export::oscal(document.as_str())).await;
+}
+
+
+#[get("/search")]
+pub async fn search_study(query: web::Query<HashMap<String, String>>, req: HttpRequest) -> impl Responder {
+    let q = query.get("q").cloned().unwrap_or_default();
+    return i18n::scope(negotiate(&req), search::search(&q)).await;
+}
+
+
 pub fn init_api() -> Scope {
-    web::scope("/api").service(handler)
+    web::scope("/api").service(export_oscal).service(search_study).service(handler)
 }
@@ -7,4 +7,13 @@ pub mod asset;
 pub mod fevnt;
 pub mod gaps;
 pub mod risk_source;
-pub mod stakeholder;
\ No newline at end of file
+pub mod stakeholder;
+pub mod gravity;
+pub mod referential;
+pub mod export;
+pub mod acceptance;
+pub mod snapshot;
+pub mod webhook;
+pub mod search;
+pub mod notification;
+pub mod job;
\ No newline at end of file
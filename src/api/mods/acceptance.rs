@@ -0,0 +1,184 @@
+use actix_web::{CustomizeResponder, HttpResponse, Responder};
+use serde_json::{json, Value};
+use crate::helper::config::{config, AcceptanceConfig};
+use crate::helper::functions::{extract_string_from_obj_value, is_uuid_v4, is_valid_date, risk_level_rank, sql_escape};
+use crate::helper::security::same_secret;
+use crate::helper::database::{Risk, RiskAcceptance, Scenario, ScenarioRisk};
+use crate::web::routes::countermeasure::today;
+use crate::web::routes::scenario::calculate_risk;
+
+/// Whether accepting `level` takes an elevated user, the max level of the
+/// config is checked against RISK_LEVELS at startup
+pub fn requires_elevation(acceptance: &AcceptanceConfig, level: &str) -> bool {
+    risk_level_rank(level) > risk_level_rank(&acceptance.max_level.to_uppercase())
+}
+
+/// An elevated user is listed and gives the token of the config; the
+/// name is typed by the caller, only the token is proof
+pub fn is_elevated(acceptance: &AcceptanceConfig, user: &str, token: &str) -> bool {
+    !acceptance.elevation_token.is_empty()
+        && same_secret(token, &acceptance.elevation_token)
+        && acceptance.elevated_users.iter().any(|u| u.eq_ignore_ascii_case(user.trim()))
+}
+
+/// Current level of a scenario, or the highest level of the scenarios of a risk
+pub async fn residual_level(target_type: &str, target_uuid: &str) -> &'static str {
+    let scenarios = match target_type {
+        "scenario" => vec![target_uuid.to_string()],
+        _ => Scenario::get_all_scenario_of_risk(target_uuid.to_string()).await
+            .iter().map(|s| s.scenario_uuid.to_string()).collect(),
+    };
+
+    let mut level = "N/A";
+    for scenario_uuid in scenarios {
+        if let Some(r) = ScenarioRisk::get_scenario_risk(scenario_uuid).await.pop() {
+            let current = calculate_risk(r.likelihood, r.operational, r.legal_compliance, r.financial, r.reputation);
+            if risk_level_rank(current) > risk_level_rank(level) {
+                level = current;
+            }
+        }
+    }
+    level
+}
+
+/// Sign off the residual level of a risk or of a scenario
+pub async fn create(body:Value) -> CustomizeResponder<HttpResponse> {
+
+    // check the body contain good key
+    for key in ["target_type", "target_uuid", "accepted_by", "justification", "expires_on"] {
+        if body.get(key).is_some() {
+            continue;
+        } else {
+            return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"missing_args\"}").customize();
+        }
+    }
+
+    let target_type = extract_string_from_obj_value(body.get("target_type"));
+    let target_uuid = extract_string_from_obj_value(body.get("target_uuid"));
+    let accepted_by = extract_string_from_obj_value(body.get("accepted_by"));
+    let justification = extract_string_from_obj_value(body.get("justification"));
+    let expires_on = extract_string_from_obj_value(body.get("expires_on"));
+    // only above max_level
+    let elevation_token = extract_string_from_obj_value(body.get("elevation_token"));
+
+    if target_type != "risk" && target_type != "scenario" {
+        return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"invalid_target_type\"}").customize();
+    }
+
+    if !is_uuid_v4(&target_uuid) {
+        return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"invalid_uuid\"}").customize();
+    }
+
+    if accepted_by.trim().is_empty() || accepted_by.len() > 255 {
+        return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"invalid_accepted_by\"}").customize();
+    }
+
+    if justification.trim().is_empty() {
+        return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"missing_justification\"}").customize();
+    }
+
+    if justification.len() > 1000 {
+        return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"justification_too_long\"}").customize();
+    }
+
+    // an acceptance is always limited in time
+    if !is_valid_date(&expires_on) || expires_on <= today() {
+        return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"invalid_expires_on\"}").customize();
+    }
+
+    let exists = match target_type.as_str() {
+        "risk" => !Risk::get_risk_detail(target_uuid.clone()).await.is_empty(),
+        _ => !Scenario::get_scenario_detail(target_uuid.clone()).await.is_empty(),
+    };
+    if !exists {
+        return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"target_not_found\"}").customize();
+    }
+
+    // the standing acceptance must be revoked before a new sign off
+    let today = today();
+    if RiskAcceptance::get_acceptances_of(target_uuid.clone()).await.iter().any(|a| a.is_active(&today)) {
+        return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"already_accepted\"}").customize();
+    }
+
+    let level = residual_level(&target_type, &target_uuid).await;
+    let acceptance = &config().risk_acceptance;
+    if requires_elevation(acceptance, level) && acceptance.elevation_token.is_empty() {
+        return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"elevation_not_configured\"}").customize();
+    }
+    if requires_elevation(acceptance, level) && !is_elevated(acceptance, &accepted_by, &elevation_token) {
+        return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"elevated_role_required\"}").customize();
+    }
+
+    // sql format to cancel sql injection
+    let accepted_by = sql_escape(accepted_by.trim());
+    let justification = sql_escape(&justification);
+
+    if RiskAcceptance::create_acceptance(target_type.clone(), target_uuid.clone(), accepted_by, justification, level.to_string(), expires_on).await.is_err() {
+        return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"database_error\"}").customize();
+    }
+
+    // the register follows the sign off
+    if target_type == "risk" {
+        let _ = Risk::set_risk_status(target_uuid, "accepted".to_string()).await;
+    }
+
+    HttpResponse::Ok().content_type("application/json").body(json!({"status": "success", "residual_level": level}).to_string()).customize()
+}
+
+pub async fn revoke(body:Value) -> CustomizeResponder<HttpResponse> {
+
+    // check the body contain good key
+    for key in ["acceptance_id", "revoked_by", "reason"] {
+        if body.get(key).is_some() {
+            continue;
+        } else {
+            return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"missing_args\"}").customize();
+        }
+    }
+
+    let acceptance_id = extract_string_from_obj_value(body.get("acceptance_id"));
+    let revoked_by = extract_string_from_obj_value(body.get("revoked_by"));
+    let reason = extract_string_from_obj_value(body.get("reason"));
+
+    let acceptance_id = match acceptance_id.parse::<i32>() {
+        Ok(id) => id,
+        Err(_) => {
+            return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"invalid_acceptance_id\"}").customize();
+        }
+    };
+
+    if revoked_by.trim().is_empty() || revoked_by.len() > 255 {
+        return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"invalid_revoked_by\"}").customize();
+    }
+
+    if reason.len() > 1000 {
+        return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"reason_too_long\"}").customize();
+    }
+
+    let acceptance = match RiskAcceptance::get_acceptance(acceptance_id).await.pop() {
+        Some(a) => a,
+        None => {
+            return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"acceptance_not_found\"}").customize();
+        }
+    };
+
+    if !acceptance.revoked_at.is_empty() {
+        return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"already_revoked\"}").customize();
+    }
+
+    // sql format to cancel sql injection
+    let revoked_by = sql_escape(revoked_by.trim());
+    let reason = sql_escape(&reason);
+
+    let _ = RiskAcceptance::revoke_acceptance(acceptance_id, revoked_by, reason).await;
+
+    // a risk no longer accepted goes back to treatment
+    if acceptance.target_type == "risk" {
+        let risk = Risk::get_risk_detail(acceptance.target_uuid.to_string()).await;
+        if risk.first().map(|r| r.risk_status == "accepted").unwrap_or(false) {
+            let _ = Risk::set_risk_status(acceptance.target_uuid.to_string(), "treated".to_string()).await;
+        }
+    }
+
+    HttpResponse::Ok().content_type("application/json").body(json!({"status": "success"}).to_string()).customize()
+}
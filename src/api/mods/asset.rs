@@ -2,13 +2,13 @@
 use actix_web::{CustomizeResponder, HttpResponse, Responder};
 use serde_json::{json, Value};
 use crate::helper::functions::extract_string_from_obj_value;
-use crate::helper::database::{ValeurMetier, BienSupport};
+use crate::helper::database::{ValeurMetier, BienSupport, AssetLink, AssetDependency};
 
 
 pub async fn create(body:Value) -> CustomizeResponder<HttpResponse> {
 
     // check the body contain good key
-    for key in vec!["name", "description", "owner", "vm_id"].iter() {
+    for key in ["name", "description", "owner"].iter() {
         if body.get(key).is_some() {
             continue;
         } else {
@@ -40,17 +40,20 @@ pub async fn create(body:Value) -> CustomizeResponder<HttpResponse> {
         return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"owner_too_long\"}").customize();
     }
 
-    // convert mission id to i32
-    let m_vm_id = match m_vm_id.parse::<i32>() {
-        Ok(v) => v,
-        Err(_) => {
-            return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"vm_id_not_valid\"}").customize();
+    // an asset can be created on its own, or from a business value it is then linked to
+    let m_vm_id = if m_vm_id.is_empty() {
+        0
+    } else {
+        match m_vm_id.parse::<i32>() {
+            Ok(v) => v,
+            Err(_) => {
+                return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"vm_id_not_valid\"}").customize();
+            }
         }
     };
 
-    // check mission exist
-    let m = ValeurMetier::c1_get_all_valeurmetier(m_vm_id).await;
-    if m.len() == 0 {
+    // check business value exist
+    if m_vm_id != 0 && ValeurMetier::c1_get_valermetier_by_id(m_vm_id).await.is_empty() {
         return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"vm_not_found\"}").customize();
     }
 
@@ -66,6 +69,60 @@ pub async fn create(body:Value) -> CustomizeResponder<HttpResponse> {
 
 
 
+pub async fn update(body:Value) -> CustomizeResponder<HttpResponse> {
+
+    // check the body contain good key
+    for key in ["asset_id", "name", "description", "owner"].iter() {
+        if body.get(key).is_some() {
+            continue;
+        } else {
+            return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"missing_args\"}").customize();
+        }
+    }
+
+    let asset_id = extract_string_from_obj_value(body.get("asset_id"));
+    let m_name = extract_string_from_obj_value(body.get("name"));
+    let m_description = extract_string_from_obj_value(body.get("description"));
+    let m_owner = extract_string_from_obj_value(body.get("owner"));
+
+    // convert asset id to i32
+    let asset_id = match asset_id.parse::<i32>() {
+        Ok(v) => v,
+        Err(_) => {
+            return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"asset_id_not_valid\"}").customize();
+        }
+    };
+
+    // check asset exist
+    if BienSupport::c1_get_asset_by_id(asset_id).await.is_empty() {
+        return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"asset_not_found\"}").customize();
+    }
+
+    // check if name < 255 char
+    if m_name.len() > 255 {
+        return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"name_too_long\"}").customize();
+    }
+
+    // check description < 1000 char
+    if m_description.len() > 1000 {
+        return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"description_too_long\"}").customize();
+    }
+
+    // check m_owner is a valid String
+    if m_owner.len() > 255 {
+        return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"owner_too_long\"}").customize();
+    }
+
+    // replace ' by \' for all
+    let m_owner = m_owner.replace("'", "\\'");
+    let m_description = m_description.replace("'", "\\'");
+    let m_name = m_name.replace("'", "\\'");
+
+    let _ = BienSupport::c1_update_asset(asset_id, m_name, m_description, m_owner).await;
+
+    HttpResponse::Ok().content_type("application/json").body(json!({"status": "success"}).to_string()).customize()
+}
+
 
 pub async fn delete(body:Value) -> CustomizeResponder<HttpResponse> {
 
@@ -94,3 +151,131 @@ pub async fn delete(body:Value) -> CustomizeResponder<HttpResponse> {
     return HttpResponse::Ok().content_type("application/json").body(json!({"status": "success"}).to_string()).customize();
 }
 
+
+pub async fn link(body:Value) -> CustomizeResponder<HttpResponse> {
+    let (asset_id, vm_id) = match parse_link(&body).await {
+        Ok(v) => v,
+        Err(status) => {
+            return HttpResponse::Ok().content_type("application/json").body(format!("{{\"error\": true, \"status\": \"{}\"}}", status)).customize();
+        }
+    };
+
+    // check the link does not already exist
+    if AssetLink::c1_get_all_asset_links().await.iter().any(|l| l.support_id == asset_id && l.valeur_id == vm_id) {
+        return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"already_linked\"}").customize();
+    }
+
+    let _ = AssetLink::c1_link_asset(asset_id, vm_id).await;
+
+    HttpResponse::Ok().content_type("application/json").body(json!({"status": "success"}).to_string()).customize()
+}
+
+
+pub async fn unlink(body:Value) -> CustomizeResponder<HttpResponse> {
+    let (asset_id, vm_id) = match parse_link(&body).await {
+        Ok(v) => v,
+        Err(status) => {
+            return HttpResponse::Ok().content_type("application/json").body(format!("{{\"error\": true, \"status\": \"{}\"}}", status)).customize();
+        }
+    };
+
+    let _ = AssetLink::c1_unlink_asset(asset_id, vm_id).await;
+
+    HttpResponse::Ok().content_type("application/json").body(json!({"status": "success"}).to_string()).customize()
+}
+
+
+pub async fn dependency_add(body:Value) -> CustomizeResponder<HttpResponse> {
+    let (asset_id, depends_on) = match parse_dependency(&body).await {
+        Ok(v) => v,
+        Err(status) => {
+            return HttpResponse::Ok().content_type("application/json").body(format!("{{\"error\": true, \"status\": \"{}\"}}", status)).customize();
+        }
+    };
+
+    if asset_id == depends_on {
+        return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"self_dependency\"}").customize();
+    }
+
+    let edges = AssetDependency::c1_get_all_asset_dependencies().await;
+
+    if edges.iter().any(|e| e.support_id == asset_id && e.depend_de == depends_on) {
+        return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"already_linked\"}").customize();
+    }
+
+    // refuse an edge closing a cycle: depends_on must not already (transitively) depend on asset_id
+    let mut stack = vec![depends_on];
+    let mut seen:Vec<i32> = Vec::new();
+    while let Some(current) = stack.pop() {
+        if current == asset_id {
+            return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"dependency_cycle\"}").customize();
+        }
+        if seen.contains(&current) {
+            continue;
+        }
+        seen.push(current);
+        for e in edges.iter().filter(|e| e.support_id == current) {
+            stack.push(e.depend_de);
+        }
+    }
+
+    let _ = AssetDependency::c1_add_asset_dependency(asset_id, depends_on).await;
+
+    HttpResponse::Ok().content_type("application/json").body(json!({"status": "success"}).to_string()).customize()
+}
+
+
+pub async fn dependency_remove(body:Value) -> CustomizeResponder<HttpResponse> {
+    let (asset_id, depends_on) = match parse_dependency(&body).await {
+        Ok(v) => v,
+        Err(status) => {
+            return HttpResponse::Ok().content_type("application/json").body(format!("{{\"error\": true, \"status\": \"{}\"}}", status)).customize();
+        }
+    };
+
+    let _ = AssetDependency::c1_remove_asset_dependency(asset_id, depends_on).await;
+
+    HttpResponse::Ok().content_type("application/json").body(json!({"status": "success"}).to_string()).customize()
+}
+
+
+/// Read and check the `asset_id` and `vm_id` of a link request
+async fn parse_link(body:&Value) -> Result<(i32, i32), &'static str> {
+    for key in ["asset_id", "vm_id"].iter() {
+        if body.get(key).is_none() {
+            return Err("missing_args");
+        }
+    }
+
+    let asset_id = extract_string_from_obj_value(body.get("asset_id")).parse::<i32>().map_err(|_| "asset_id_not_valid")?;
+    let vm_id = extract_string_from_obj_value(body.get("vm_id")).parse::<i32>().map_err(|_| "vm_id_not_valid")?;
+
+    if BienSupport::c1_get_asset_by_id(asset_id).await.is_empty() {
+        return Err("asset_not_found");
+    }
+
+    if ValeurMetier::c1_get_valermetier_by_id(vm_id).await.is_empty() {
+        return Err("vm_not_found");
+    }
+
+    Ok((asset_id, vm_id))
+}
+
+
+/// Read and check the `asset_id` and `depends_on` of a dependency request
+async fn parse_dependency(body:&Value) -> Result<(i32, i32), &'static str> {
+    for key in ["asset_id", "depends_on"].iter() {
+        if body.get(key).is_none() {
+            return Err("missing_args");
+        }
+    }
+
+    let asset_id = extract_string_from_obj_value(body.get("asset_id")).parse::<i32>().map_err(|_| "asset_id_not_valid")?;
+    let depends_on = extract_string_from_obj_value(body.get("depends_on")).parse::<i32>().map_err(|_| "depends_on_not_valid")?;
+
+    if BienSupport::c1_get_asset_by_id(asset_id).await.is_empty() || BienSupport::c1_get_asset_by_id(depends_on).await.is_empty() {
+        return Err("asset_not_found");
+    }
+
+    Ok((asset_id, depends_on))
+}
@@ -1,15 +1,102 @@
 // export the home route handler
 use actix_web::{CustomizeResponder, HttpResponse, Responder};
 use serde_json::{json, Value};
-use crate::helper::functions::{extract_string_from_obj_value, is_uuid_v4};
-use crate::helper::database::{Countermeasure, Scenario};
+use crate::helper::functions::{extract_string_from_obj_value, is_uuid_v4, is_valid_date, sql_escape};
+use uuid::Uuid;
+
+use crate::api::mods::gaps::{gap_of_control, sync_compliance};
+use crate::helper::database::{Countermeasure, CountermeasureHistory, CountermeasureLink, Gap, GapMeasure, Scenario};
+use crate::helper::{notification, webhook};
+
+/// Steps of the countermeasure workflow, in order
+pub const STATUSES: [(&str, &str); 5] = [
+    ("proposed", "Proposed"),
+    ("approved", "Approved"),
+    ("in_progress", "In progress"),
+    ("implemented", "Implemented"),
+    ("verified", "Verified"),
+];
+
+pub const PRIORITIES: [&str; 4] = ["low", "medium", "high", "critical"];
+
+/// Priority of a measure created or updated without one
+pub const DEFAULT_PRIORITY: &str = "medium";
+
+/// An open measure is never fully solved: it reaches 100% once implemented,
+/// and sending it back from implemented reopens it at this level
+pub const OPEN_SOLVED_MAX: i32 = 90;
+
+/// The `solved` percentages a measure may have at a status, implemented and
+/// verified measures are fully solved
+pub fn solved_range(status: &str) -> (i32, i32) {
+    if status == "implemented" || status == "verified" { (100, 100) } else { (0, OPEN_SOLVED_MAX) }
+}
+
+/// A countermeasure moves one step forward, or one step back when it is
+/// rejected, stalled or fails its verification
+pub fn allowed_transitions(status: &str) -> Vec<&'static str> {
+    let position = match STATUSES.iter().position(|(s, _)| *s == status) {
+        Some(p) => p,
+        None => return vec![STATUSES[0].0],
+    };
+
+    let mut next = Vec::new();
+    if position + 1 < STATUSES.len() {
+        next.push(STATUSES[position + 1].0);
+    }
+    if position > 0 {
+        next.push(STATUSES[position - 1].0);
+    }
+    next
+}
+
+pub fn status_label(status: &str) -> &'static str {
+    STATUSES.iter().find(|(s, _)| *s == status).map(|(_, label)| *label).unwrap_or("Proposed")
+}
+
+/// Owner, due date, priority, cost and effort of a countermeasure, all
+/// optional: the clients older than the planning send none of them
+fn read_planning(body: &Value) -> Result<(String, String, String, i32, i32), &'static str> {
+    let owner = extract_string_from_obj_value(body.get("owner"));
+    let due_date = extract_string_from_obj_value(body.get("due_date"));
+    let priority = extract_string_from_obj_value(body.get("priority"));
+    let priority = if priority.is_empty() { DEFAULT_PRIORITY.to_string() } else { priority };
+    let cost = extract_string_from_obj_value(body.get("cost"));
+    let effort = extract_string_from_obj_value(body.get("effort"));
+
+    if owner.len() > 255 {
+        return Err("owner_too_long");
+    }
+
+    if !due_date.is_empty() && !is_valid_date(&due_date) {
+        return Err("invalid_due_date");
+    }
+
+    if !PRIORITIES.contains(&priority.as_str()) {
+        return Err("invalid_priority");
+    }
+
+    let cost = if cost.is_empty() { Ok(0) } else { cost.parse::<i32>() };
+    let cost = match cost {
+        Ok(c) if c >= 0 => c,
+        _ => return Err("invalid_cost"),
+    };
+
+    let effort = if effort.is_empty() { Ok(0) } else { effort.parse::<i32>() };
+    let effort = match effort {
+        Ok(e) if e >= 0 => e,
+        _ => return Err("invalid_effort"),
+    };
+
+    Ok((sql_escape(&owner), due_date, priority, cost, effort))
+}
 
 
 pub async fn create(body:Value) -> CustomizeResponder<HttpResponse> {
 
 
     // check the body contain good key
-    for key in vec!["name", "description", "scenario_uuid"] {
+    for key in ["name", "description", "scenario_uuid"] {
         if body.get(key).is_some() {
             continue;
         } else {
@@ -26,15 +113,25 @@ pub async fn create(body:Value) -> CustomizeResponder<HttpResponse> {
         return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"name_too_long\"}").customize();
     }
 
+    // without scenario, the measure goes to the catalogue
+    let scenario_uuid = if scenario_uuid.is_empty() { Uuid::nil().to_string() } else { scenario_uuid };
+
     // check scenario_uuid is a valid uuid
-    if !is_uuid_v4(&scenario_uuid) {
+    if scenario_uuid != Uuid::nil().to_string() && !is_uuid_v4(&scenario_uuid) {
         return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"invalid_uuid\"}").customize();
     }
 
+    let (owner, due_date, priority, cost, effort) = match read_planning(&body) {
+        Ok(planning) => planning,
+        Err(e) => {
+            return HttpResponse::Ok().content_type("application/json").body(json!({"error": true, "status": e}).to_string()).customize();
+        }
+    };
+
     // check if scenario exist
     let scenario_detail = Scenario::get_scenario_detail(scenario_uuid.clone()).await;
 
-    if scenario_detail.is_empty() {
+    if scenario_uuid != Uuid::nil().to_string() && scenario_detail.is_empty() {
         return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"scenario_not_found\"}").customize();
     }
 
@@ -43,7 +140,12 @@ pub async fn create(body:Value) -> CustomizeResponder<HttpResponse> {
     let doc_description = doc_description.replace("'", "\\'");
 
 
-    let _ = Countermeasure::create_countermeasure(scenario_uuid, doc_name, doc_description).await;
+    let created = Countermeasure::create_countermeasure(scenario_uuid, doc_name, doc_description, owner, due_date, priority, cost, effort).await;
+    CountermeasureHistory::record_changes().await;
+
+    if let Ok(ctm_uuid) = created {
+        notify_assigned(None, &ctm_uuid).await;
+    }
 
     return HttpResponse::Ok().content_type("application/json").body(json!({"status": "success"}).to_string()).customize();
 }
@@ -52,7 +154,7 @@ pub async fn create(body:Value) -> CustomizeResponder<HttpResponse> {
 pub async fn update(body:Value) -> CustomizeResponder<HttpResponse> {
 
     // check the body contain good key
-    for key in vec!["uuid", "name", "description", "solved", "solved_description"] {
+    for key in ["uuid", "name", "description", "solved", "solved_description"] {
         if body.get(key).is_some() {
             continue;
         } else {
@@ -63,7 +165,12 @@ pub async fn update(body:Value) -> CustomizeResponder<HttpResponse> {
     let ctm_uuid = extract_string_from_obj_value(body.get("uuid"));
     let doc_name = extract_string_from_obj_value(body.get("name"));
     let doc_description = extract_string_from_obj_value(body.get("description"));
-    let solved = extract_string_from_obj_value(body.get("solved")).parse::<i32>().unwrap_or(0);
+    let solved = match extract_string_from_obj_value(body.get("solved")).parse::<i32>() {
+        Ok(solved) => solved,
+        Err(_) => {
+            return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"invalid_solved\"}").customize();
+        }
+    };
     let solved_description = extract_string_from_obj_value(body.get("solved_description"));
 
     // check if doc_name < 255 char
@@ -81,17 +188,243 @@ pub async fn update(body:Value) -> CustomizeResponder<HttpResponse> {
         return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"invalid_solved\"}").customize();
     }
 
+    let (owner, due_date, priority, cost, effort) = match read_planning(&body) {
+        Ok(planning) => planning,
+        Err(e) => {
+            return HttpResponse::Ok().content_type("application/json").body(json!({"error": true, "status": e}).to_string()).customize();
+        }
+    };
+
     // sql format to cancel sql injection
     let doc_name = doc_name.replace("'", "\\'");
     let doc_description = doc_description.replace("'", "\\'");
     let solved_description = solved_description.replace("'", "\\'");
 
+    let before = match Countermeasure::get_ctm_by_id(ctm_uuid.clone()).await.pop() {
+        Some(before) => before,
+        None => {
+            return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"countermeasure_not_found\"}").customize();
+        }
+    };
+
+    // the status moves through /status, solved must agree with it
+    let (min, max) = solved_range(&before.status);
+    if !(min..=max).contains(&solved) {
+        return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"solved_does_not_match_status\"}").customize();
+    }
+
     // update the countermeasure
-    let _ = Countermeasure::update_countermeasure(ctm_uuid, doc_name, doc_description, solved, solved_description).await;
+    let _ = Countermeasure::update_countermeasure(ctm_uuid.clone(), doc_name, doc_description, solved, solved_description, owner, due_date, priority, cost, effort).await;
+    CountermeasureHistory::record_changes().await;
+    sync_compliance(gaps_of(&ctm_uuid).await).await;
+
+    notify_solved(&before, solved).await;
+    notify_assigned(Some(&before), &ctm_uuid).await;
+
+    HttpResponse::Ok().content_type("application/json").body(json!({"status": "success"}).to_string()).customize()
+}
+
+pub async fn status(body:Value) -> CustomizeResponder<HttpResponse> {
+
+    // check the body contain good key
+    for key in ["uuid", "status"] {
+        if body.get(key).is_some() {
+            continue;
+        } else {
+            return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"missing_args\"}").customize();
+        }
+    }
+
+    let ctm_uuid = extract_string_from_obj_value(body.get("uuid"));
+    let status = extract_string_from_obj_value(body.get("status"));
+
+    // check if ctm_uuid is a valid uuid
+    if !is_uuid_v4(&ctm_uuid) {
+        return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"invalid_uuid\"}").customize();
+    }
+
+    if !STATUSES.iter().any(|(s, _)| *s == status) {
+        return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"invalid_status\"}").customize();
+    }
+
+    let ctm = match Countermeasure::get_ctm_by_id(ctm_uuid.clone()).await.pop() {
+        Some(ctm) => ctm,
+        None => {
+            return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"countermeasure_not_found\"}").customize();
+        }
+    };
+
+    if !allowed_transitions(&ctm.status).contains(&status.as_str()) {
+        return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"transition_not_allowed\"}").customize();
+    }
+
+    // an implemented action is fully solved, sending it back reopens it
+    let (min, max) = solved_range(&status);
+    let solved = ctm.solved.clamp(min, max);
+
+    let _ = Countermeasure::update_countermeasure_status(ctm_uuid.clone(), status, solved).await;
+    CountermeasureHistory::record_changes().await;
+    sync_compliance(gaps_of(&ctm_uuid).await).await;
+    notify_solved(&ctm, solved).await;
 
     return HttpResponse::Ok().content_type("application/json").body(json!({"status": "success"}).to_string()).customize();
 }
 
+/// Tell the webhooks when a measure becomes fully solved
+async fn notify_solved(before: &Countermeasure, solved: i32) {
+    if before.solved >= 100 || solved < 100 {
+        return;
+    }
+
+    let ctm = Countermeasure::get_ctm_by_id(before.ctm_uuid.to_string()).await.pop().unwrap_or(before.clone());
+    webhook::emit("countermeasure.solved", &ctm.ctm_uuid.to_string(), json!({
+        "ctm_uuid": ctm.ctm_uuid.to_string(),
+        "scenario_uuid": ctm.scenario_uuid.to_string(),
+        "title": ctm.title,
+        "owner": ctm.owner,
+        "status": ctm.status,
+        "solved_description": ctm.solved_description,
+    })).await;
+}
+
+/// Email the owner of a measure when it is given to them
+async fn notify_assigned(before: Option<&Countermeasure>, ctm_uuid: &str) {
+    let ctm = match Countermeasure::get_ctm_by_id(ctm_uuid.to_string()).await.pop() {
+        Some(ctm) => ctm,
+        None => return,
+    };
+
+    if before.is_some_and(|b| notification::is_owner(&b.owner, &ctm.owner)) {
+        return;
+    }
+    notification::assigned(&ctm).await;
+}
+
+/// Share a countermeasure with another scenario
+pub async fn link(body:Value) -> CustomizeResponder<HttpResponse> {
+    let (ctm_uuid, scenario_uuid) = match read_link(&body).await {
+        Ok(link) => link,
+        Err(e) => {
+            return HttpResponse::Ok().content_type("application/json").body(json!({"error": true, "status": e}).to_string()).customize();
+        }
+    };
+
+    CountermeasureLink::link_countermeasure(ctm_uuid, scenario_uuid).await;
+
+    HttpResponse::Ok().content_type("application/json").body(json!({"status": "success"}).to_string()).customize()
+}
+
+/// Stop mitigating a scenario with a countermeasure, the measure stays in the
+/// catalogue
+pub async fn unlink(body:Value) -> CustomizeResponder<HttpResponse> {
+    let (ctm_uuid, scenario_uuid) = match read_link(&body).await {
+        Ok(link) => link,
+        Err(e) => {
+            return HttpResponse::Ok().content_type("application/json").body(json!({"error": true, "status": e}).to_string()).customize();
+        }
+    };
+
+    CountermeasureLink::unlink_countermeasure(ctm_uuid, scenario_uuid).await;
+
+    HttpResponse::Ok().content_type("application/json").body(json!({"status": "success"}).to_string()).customize()
+}
+
+/// Address a gap, or the control of a referential, with a countermeasure
+pub async fn link_gap(body:Value) -> CustomizeResponder<HttpResponse> {
+    let (ctm_uuid, gap_id) = match read_gap_link(&body).await {
+        Ok(link) => link,
+        Err(e) => {
+            return HttpResponse::Ok().content_type("application/json").body(json!({"error": true, "status": e}).to_string()).customize();
+        }
+    };
+
+    GapMeasure::c1_link_measure(ctm_uuid, gap_id).await;
+    sync_compliance(vec![gap_id]).await;
+
+    HttpResponse::Ok().content_type("application/json").body(json!({"status": "success"}).to_string()).customize()
+}
+
+pub async fn unlink_gap(body:Value) -> CustomizeResponder<HttpResponse> {
+    let (ctm_uuid, gap_id) = match read_gap_link(&body).await {
+        Ok(link) => link,
+        Err(e) => {
+            return HttpResponse::Ok().content_type("application/json").body(json!({"error": true, "status": e}).to_string()).customize();
+        }
+    };
+
+    GapMeasure::c1_unlink_measure(ctm_uuid, gap_id).await;
+    sync_compliance(vec![gap_id]).await;
+
+    HttpResponse::Ok().content_type("application/json").body(json!({"status": "success"}).to_string()).customize()
+}
+
+/// The countermeasure and the gap of a link, the gap is given by `gap_id` or
+/// by the `requirement_id` of its control
+async fn read_gap_link(body: &Value) -> Result<(String, i32), &'static str> {
+    if body.get("uuid").is_none() || (body.get("gap_id").is_none() && body.get("requirement_id").is_none()) {
+        return Err("missing_args");
+    }
+
+    let ctm_uuid = extract_string_from_obj_value(body.get("uuid"));
+    if !is_uuid_v4(&ctm_uuid) {
+        return Err("invalid_uuid");
+    }
+
+    if Countermeasure::get_ctm_by_id(ctm_uuid.clone()).await.is_empty() {
+        return Err("countermeasure_not_found");
+    }
+
+    let gap_id = match body.get("requirement_id") {
+        Some(v) => match extract_string_from_obj_value(Some(v)).parse::<i32>() {
+            Ok(requirement_id) => gap_of_control(requirement_id).await?,
+            Err(_) => return Err("requirement_id_not_valid"),
+        },
+        None => match extract_string_from_obj_value(body.get("gap_id")).parse::<i32>() {
+            Ok(gap_id) => gap_id,
+            Err(_) => return Err("gaps_id_not_valid"),
+        },
+    };
+
+    if Gap::c1_get_gaps_by_id(gap_id).await.is_empty() {
+        return Err("gaps_not_found");
+    }
+
+    Ok((ctm_uuid, gap_id))
+}
+
+/// Gaps a countermeasure closes
+async fn gaps_of(ctm_uuid: &str) -> Vec<i32> {
+    GapMeasure::c1_get_all_gap_measures().await.iter()
+        .filter(|l| l.ctm_uuid.to_string() == ctm_uuid)
+        .map(|l| l.gap_id)
+        .collect()
+}
+
+async fn read_link(body: &Value) -> Result<(String, String), &'static str> {
+    for key in ["uuid", "scenario_uuid"] {
+        if body.get(key).is_none() {
+            return Err("missing_args");
+        }
+    }
+
+    let ctm_uuid = extract_string_from_obj_value(body.get("uuid"));
+    let scenario_uuid = extract_string_from_obj_value(body.get("scenario_uuid"));
+
+    if !is_uuid_v4(&ctm_uuid) || !is_uuid_v4(&scenario_uuid) {
+        return Err("invalid_uuid");
+    }
+
+    if Countermeasure::get_ctm_by_id(ctm_uuid.clone()).await.is_empty() {
+        return Err("countermeasure_not_found");
+    }
+
+    if Scenario::get_scenario_detail(scenario_uuid.clone()).await.is_empty() {
+        return Err("scenario_not_found");
+    }
+
+    Ok((ctm_uuid, scenario_uuid))
+}
+
 pub async fn delete(body:Value) -> CustomizeResponder<HttpResponse> {
 
     // check the body contain good key
@@ -110,8 +443,10 @@ pub async fn delete(body:Value) -> CustomizeResponder<HttpResponse> {
         return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"invalid_uuid\"}").customize();
     }
 
-    // delete the countermeasure
+    // delete the countermeasure, the gaps it closed lose its contribution
+    let gaps = gaps_of(&ctm_uuid).await;
     let _ = Countermeasure::delete_countermeasure(ctm_uuid).await;
+    sync_compliance(gaps).await;
 
     return HttpResponse::Ok().content_type("application/json").body(json!({"status": "success"}).to_string()).customize();
 }
\ No newline at end of file
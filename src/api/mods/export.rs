@@ -0,0 +1,23 @@
+// export the home route handler
+use actix_web::{CustomizeResponder, HttpResponse, Responder};
+use crate::helper::oscal::OscalExport;
+
+
+/// Download the study as an OSCAL `assessment-results` or `poam` document
+pub async fn oscal(document:&str) -> CustomizeResponder<HttpResponse> {
+    let export = OscalExport::load().await;
+
+    let (body, filename) = match document {
+        "assessment-results" => (export.assessment_results(), "matryriska-assessment-results.json"),
+        "poam" => (export.poam(), "matryriska-poam.json"),
+        _ => {
+            return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"document_not_found\"}").customize();
+        }
+    };
+
+    HttpResponse::Ok()
+        .content_type("application/json")
+        .insert_header(("Content-Disposition", format!("attachment; filename=\"{}\"", filename)))
+        .body(serde_json::to_string_pretty(&body).unwrap())
+        .customize()
+}
@@ -1,15 +1,15 @@
 // export the home route handler
 use actix_web::{CustomizeResponder, HttpResponse, Responder};
 use serde_json::{json, Value};
-use crate::helper::functions::extract_string_from_obj_value;
-use crate::helper::database::{FearedEvent, ValeurMetier};
+use crate::helper::functions::{extract_string_from_obj_value, extract_vecstring_from_obj_value};
+use crate::helper::database::{FearedEvent, GravityScale, ValeurMetier};
 
 
 pub async fn create(body:Value) -> CustomizeResponder<HttpResponse> {
 
 
     // check the body contain good key
-    for key in vec!["name", "impacts", "bv", "gravity"].iter() {
+    for key in ["name", "impacts", "bv", "gravity", "critere"].iter() {
         if body.get(key).is_some() {
             continue;
         } else {
@@ -51,15 +51,22 @@ pub async fn create(body:Value) -> CustomizeResponder<HttpResponse> {
         }
     };
 
-    // gravity must be between 1 and 4 (included)
-    if m_gravity < 1 || m_gravity > 4 {
+    // gravity must be a level of the study gravity scale
+    if !GravityScale::c1_get_gravity_scale().await.iter().any(|l| l.niveau == m_gravity) {
         return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"gravity_not_valid\"}").customize();
     }
 
+    let (m_critere, m_categories) = match parse_criterion(&body) {
+        Ok(v) => v,
+        Err(status) => {
+            return HttpResponse::Ok().content_type("application/json").body(format!("{{\"error\": true, \"status\": \"{}\"}}", status)).customize();
+        }
+    };
+
     
 
-    // check mission exist
-    let m = ValeurMetier::c1_get_all_valeurmetier(m_bv).await;
+    // check business value exist
+    let m = ValeurMetier::c1_get_valermetier_by_id(m_bv).await;
     if m.len() == 0 {
         return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"business_value_not_found\"}").customize();
     }
@@ -68,13 +75,93 @@ pub async fn create(body:Value) -> CustomizeResponder<HttpResponse> {
     let m_impacts = m_impacts.replace("'", "\\'");
     let m_name = m_name.replace("'", "\\'");
 
-    let _ = FearedEvent::c1_feared_event_create(m_name, m_impacts, m_bv, m_gravity).await;
+    let _ = FearedEvent::c1_feared_event_create(m_name, m_impacts, m_bv, m_gravity, m_critere, m_categories).await;
 
     return HttpResponse::Ok().content_type("application/json").body(json!({"status": "success"}).to_string()).customize();
 }
 
 
 
+pub async fn update(body:Value) -> CustomizeResponder<HttpResponse> {
+
+    // check the body contain good key
+    for key in ["event_id", "name", "impacts", "bv", "gravity", "critere"].iter() {
+        if body.get(key).is_some() {
+            continue;
+        } else {
+            return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"missing_args\"}").customize();
+        }
+    }
+
+    let event_id = extract_string_from_obj_value(body.get("event_id"));
+    let m_name = extract_string_from_obj_value(body.get("name"));
+    let m_impacts = extract_string_from_obj_value(body.get("impacts"));
+    let m_bv = extract_string_from_obj_value(body.get("bv"));
+    let m_gravity = extract_string_from_obj_value(body.get("gravity"));
+
+    // convert event id to i32
+    let event_id = match event_id.parse::<i32>() {
+        Ok(v) => v,
+        Err(_) => {
+            return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"event_id_not_valid\"}").customize();
+        }
+    };
+
+    // check event exist
+    if FearedEvent::c1_get_feared_event_by_id(event_id).await.is_empty() {
+        return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"event_not_found\"}").customize();
+    }
+
+    // check if name < 255 char
+    if m_name.len() > 255 {
+        return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"name_too_long\"}").customize();
+    }
+
+    // check impacts < 2000 char
+    if m_impacts.len() > 2000 {
+        return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"impacts_too_long\"}").customize();
+    }
+
+    let m_bv = match m_bv.parse::<i32>() {
+        Ok(v) => v,
+        Err(_) => {
+            return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"m_bv_not_valid\"}").customize();
+        }
+    };
+
+    let m_gravity = match m_gravity.parse::<i32>() {
+        Ok(v) => v,
+        Err(_) => {
+            return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"m_gravity_not_valid\"}").customize();
+        }
+    };
+
+    // gravity must be a level of the study gravity scale
+    if !GravityScale::c1_get_gravity_scale().await.iter().any(|l| l.niveau == m_gravity) {
+        return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"gravity_not_valid\"}").customize();
+    }
+
+    let (m_critere, m_categories) = match parse_criterion(&body) {
+        Ok(v) => v,
+        Err(status) => {
+            return HttpResponse::Ok().content_type("application/json").body(format!("{{\"error\": true, \"status\": \"{}\"}}", status)).customize();
+        }
+    };
+
+    // check business value exist
+    if ValeurMetier::c1_get_valermetier_by_id(m_bv).await.is_empty() {
+        return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"business_value_not_found\"}").customize();
+    }
+
+    // replace ' by \' for all
+    let m_impacts = m_impacts.replace("'", "\\'");
+    let m_name = m_name.replace("'", "\\'");
+
+    let _ = FearedEvent::c1_update_feared_event(event_id, m_name, m_impacts, m_bv, m_gravity, m_critere, m_categories).await;
+
+    HttpResponse::Ok().content_type("application/json").body(json!({"status": "success"}).to_string()).customize()
+}
+
 
 pub async fn delete(body:Value) -> CustomizeResponder<HttpResponse> {
 
@@ -103,3 +190,24 @@ pub async fn delete(body:Value) -> CustomizeResponder<HttpResponse> {
     return HttpResponse::Ok().content_type("application/json").body(json!({"status": "success"}).to_string()).customize();
 }
 
+
+/// Read the security criterion and the impact categories of a feared event,
+/// both must be one of the known keys of `FearedEvent`
+fn parse_criterion(body:&Value) -> Result<(String, String), &'static str> {
+    let critere = extract_string_from_obj_value(body.get("critere"));
+    if !FearedEvent::CRITERIA.iter().any(|(key, _)| *key == critere) {
+        return Err("critere_not_valid");
+    }
+
+    let mut categories:Vec<String> = Vec::new();
+    for category in extract_vecstring_from_obj_value(body.get("impact_categories")) {
+        if !FearedEvent::IMPACT_CATEGORIES.iter().any(|(key, _)| *key == category) {
+            return Err("impact_category_not_valid");
+        }
+        if !categories.contains(&category) {
+            categories.push(category);
+        }
+    }
+
+    Ok((critere, categories.join(",")))
+}
@@ -2,7 +2,8 @@
 use actix_web::{CustomizeResponder, HttpResponse, Responder};
 use serde_json::{json, Value};
 use crate::helper::functions::extract_string_from_obj_value;
-use crate::helper::database::Gap;
+use crate::helper::database::{Countermeasure, Gap, Referential, Requirement};
+use crate::helper::referential::{derived_state, is_control, rollup};
 
 
 pub async fn create(body:Value) -> CustomizeResponder<HttpResponse> {
@@ -64,7 +65,89 @@ pub async fn create(body:Value) -> CustomizeResponder<HttpResponse> {
     let g_gap_why = g_gap_why.replace("'", "\\'");
     let g_gap_counter = g_gap_counter.replace("'", "\\'");
 
-    let _ = Gap::c1_create_gap(g_ref_type, g_ref_name, g_state, g_gap, g_gap_why, g_gap_counter).await;
+    let _ = Gap::c1_create_gap(g_ref_type, g_ref_name, g_state, g_gap, g_gap_why, g_gap_counter, 0).await;
+
+    HttpResponse::Ok().content_type("application/json").body(json!({"status": "success"}).to_string()).customize()
+}
+
+
+pub async fn update(body:Value) -> CustomizeResponder<HttpResponse> {
+
+    // check the body contain good key
+    for key in ["gaps_id", "g_ref_type", "g_ref_name", "g_state", "g_gap", "g_gap_why", "g_gap_counter"].iter() {
+        if body.get(key).is_some() {
+            continue;
+        } else {
+            return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"missing_args\"}").customize();
+        }
+    }
+
+    let gaps_id = extract_string_from_obj_value(body.get("gaps_id"));
+    let g_ref_type = extract_string_from_obj_value(body.get("g_ref_type"));
+    let g_ref_name = extract_string_from_obj_value(body.get("g_ref_name"));
+    let g_state = extract_string_from_obj_value(body.get("g_state"));
+    let g_gap = extract_string_from_obj_value(body.get("g_gap"));
+    let g_gap_why = extract_string_from_obj_value(body.get("g_gap_why"));
+    let g_gap_counter = extract_string_from_obj_value(body.get("g_gap_counter"));
+
+    // convert gaps id to i32
+    let gaps_id = match gaps_id.parse::<i32>() {
+        Ok(v) => v,
+        Err(_) => {
+            return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"gaps_id_not_valid\"}").customize();
+        }
+    };
+
+    // check gap exist
+    if Gap::c1_get_gaps_by_id(gaps_id).await.is_empty() {
+        return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"gaps_not_found\"}").customize();
+    }
+
+    // check if g_ref_type < 255 char
+    if g_ref_type.len() > 255 {
+        return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"g_ref_type_too_long\"}").customize();
+    }
+
+    // check if g_ref_name < 255 char
+    if g_ref_name.len() > 255 {
+        return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"g_ref_name_too_long\"}").customize();
+    }
+
+    // check if gstate is a numeric value between 0 and 100 included
+    let g_state = match g_state.parse::<i32>() {
+        Ok(v) if (0..=100).contains(&v) => v,
+        _ => {
+            return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"g_state_not_valid\"}").customize();
+        }
+    };
+
+    // get other is < 2000 char
+    if g_gap.len() > 2000 {
+        return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"g_gap_too_long\"}").customize();
+    }
+
+    // get other is < 2000 char
+    if g_gap_why.len() > 2000 {
+        return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"g_gap_why_too_long\"}").customize();
+    }
+
+    // get other is < 2000 char
+    if g_gap_counter.len() > 2000 {
+        return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"g_gap_counter_too_long\"}").customize();
+    }
+
+    // replace ' by \' for all
+    let g_ref_type = g_ref_type.replace("'", "\\'");
+    let g_ref_name = g_ref_name.replace("'", "\\'");
+    let g_gap = g_gap.replace("'", "\\'");
+    let g_gap_why = g_gap_why.replace("'", "\\'");
+    let g_gap_counter = g_gap_counter.replace("'", "\\'");
+
+    if let Err(status) = check_manual_state(gaps_id, g_state).await {
+        return HttpResponse::Ok().content_type("application/json").body(format!("{{\"error\": true, \"status\": \"{}\"}}", status)).customize();
+    }
+
+    let _ = Gap::c1_update_gap(gaps_id, g_ref_type, g_ref_name, g_state, g_gap, g_gap_why, g_gap_counter).await;
 
     return HttpResponse::Ok().content_type("application/json").body(json!({"status": "success"}).to_string()).customize();
 }
@@ -97,3 +180,204 @@ pub async fn delete(body:Value) -> CustomizeResponder<HttpResponse> {
     return HttpResponse::Ok().content_type("application/json").body(json!({"status": "success"}).to_string()).customize();
 }
 
+
+
+/// Record the compliance of a control of an imported referential, the gap of
+/// the control is created on its first assessment
+pub async fn assess(body:Value) -> CustomizeResponder<HttpResponse> {
+
+    // check the body contain good key
+    for key in ["requirement_id", "g_state"].iter() {
+        if body.get(key).is_some() {
+            continue;
+        } else {
+            return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"missing_args\"}").customize();
+        }
+    }
+
+    let requirement_id = extract_string_from_obj_value(body.get("requirement_id"));
+    let g_state = extract_string_from_obj_value(body.get("g_state"));
+
+    let requirement_id = match requirement_id.parse::<i32>() {
+        Ok(v) => v,
+        Err(_) => {
+            return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"requirement_id_not_valid\"}").customize();
+        }
+    };
+
+    // check gstate is a numeric value between 0 and 100 included
+    let g_state = match g_state.parse::<i32>() {
+        Ok(v) if (0..=100).contains(&v) => v,
+        _ => {
+            return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"g_state_not_valid\"}").customize();
+        }
+    };
+
+    let requirement = match Requirement::c1_get_requirement_by_id(requirement_id).await.pop() {
+        Some(r) => r,
+        None => {
+            return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"requirement_not_found\"}").customize();
+        }
+    };
+
+    // only the leaves of the tree are assessed, domains are rolled up
+    let requirements = Requirement::c1_get_requirements_by_referential(requirement.referential_id).await;
+    if !is_control(&requirements, &requirement) {
+        return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"requirement_not_a_control\"}").customize();
+    }
+
+    // the text fields are optional, the current ones are kept when missing
+    let existing = Gap::c1_get_gap_by_requirement(requirement_id).await.pop().unwrap_or(Gap::default());
+    let g_gap = body.get("g_gap").map(|v| extract_string_from_obj_value(Some(v))).unwrap_or(existing.gap.clone());
+    let g_gap_why = body.get("g_gap_why").map(|v| extract_string_from_obj_value(Some(v))).unwrap_or(existing.gap_justification.clone());
+    let g_gap_counter = body.get("g_gap_counter").map(|v| extract_string_from_obj_value(Some(v))).unwrap_or(existing.proposed_measures.clone());
+
+    // get other is < 2000 char
+    if g_gap.len() > 2000 || g_gap_why.len() > 2000 || g_gap_counter.len() > 2000 {
+        return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"g_gap_too_long\"}").customize();
+    }
+
+    let referential = Referential::c1_get_referential_by_id(requirement.referential_id).await.pop().unwrap_or(Referential::default());
+
+    // replace ' by \' for all
+    let g_ref_type = format!("{} {}", referential.name, referential.version).replace("'", "\\'");
+    let g_ref_name = format!("{} {}", requirement.code, requirement.title).chars().take(255).collect::<String>().replace("'", "\\'");
+    let g_gap = g_gap.replace("'", "\\'");
+    let g_gap_why = g_gap_why.replace("'", "\\'");
+    let g_gap_counter = g_gap_counter.replace("'", "\\'");
+
+    if let Err(status) = check_manual_state(existing.gap_id, g_state).await {
+        return HttpResponse::Ok().content_type("application/json").body(format!("{{\"error\": true, \"status\": \"{}\"}}", status)).customize();
+    }
+
+    if existing.gap_id == 0 {
+        let _ = Gap::c1_create_gap(g_ref_type, g_ref_name, g_state, g_gap, g_gap_why, g_gap_counter, requirement_id).await;
+    } else {
+        let _ = Gap::c1_update_gap(existing.gap_id, g_ref_type, g_ref_name, g_state, g_gap, g_gap_why, g_gap_counter).await;
+    }
+
+    HttpResponse::Ok().content_type("application/json").body(json!({"status": "success"}).to_string()).customize()
+}
+
+
+/// Compliance of every control of a referential and maturity of every domain
+pub async fn maturity(body:Value) -> CustomizeResponder<HttpResponse> {
+
+    // check the body contain good key
+    for key in ["referential_id"].iter() {
+        if body.get(key).is_some() {
+            continue;
+        } else {
+            return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"missing_args\"}").customize();
+        }
+    }
+
+    let referential_id = extract_string_from_obj_value(body.get("referential_id"));
+
+    let referential_id = match referential_id.parse::<i32>() {
+        Ok(v) => v,
+        Err(_) => {
+            return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"referential_id_not_valid\"}").customize();
+        }
+    };
+
+    let referential = match Referential::c1_get_referential_by_id(referential_id).await.pop() {
+        Some(r) => r,
+        None => {
+            return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"referential_not_found\"}").customize();
+        }
+    };
+
+    let requirements = Requirement::c1_get_requirements_by_referential(referential_id).await;
+    let gaps = Gap::c1_get_all_gaps().await;
+
+    let domains = rollup(&requirements, &gaps).iter().map(|d| json!({
+        "code": d.code,
+        "title": d.title,
+        "depth": d.depth,
+        "total": d.total,
+        "assessed": d.assessed,
+        "score": (d.score * 10.0).round() / 10.0,
+        "level": d.level(),
+    })).collect::<Vec<Value>>();
+
+    let controls = requirements.iter().filter(|r| is_control(&requirements, r)).map(|r| {
+        let gap = gaps.iter().find(|g| g.requirement_id == r.requirement_id);
+        json!({
+            "requirement_id": r.requirement_id,
+            "code": r.code,
+            "parent_code": r.parent_code,
+            "title": r.title,
+            "gap_id": gap.map(|g| g.gap_id),
+            "application_state": gap.map(|g| g.application_state),
+        })
+    }).collect::<Vec<Value>>();
+
+    HttpResponse::Ok().content_type("application/json").body(json!({
+        "status": "success",
+        "referential": {
+            "referential_id": referential.referential_id,
+            "key": referential.ref_key,
+            "name": referential.name,
+            "version": referential.version,
+        },
+        "domains": domains,
+        "controls": controls,
+    }).to_string()).customize()
+}
+
+
+
+/// Derive the application state of gaps from the status of their
+/// countermeasures, the gaps without measure keep their state
+pub async fn sync_compliance(gap_ids: Vec<i32>) {
+    for gap_id in gap_ids {
+        if gap_id == 0 {
+            continue;
+        }
+        if let Some(state) = derived_state(&Countermeasure::get_countermeasures_of_gap(gap_id).await) {
+            Gap::c1_set_application_state(gap_id, state).await;
+        }
+    }
+}
+
+/// The state of a gap addressed by countermeasures is derived from them and
+/// cannot be entered by hand, only the derived value is accepted
+async fn check_manual_state(gap_id: i32, g_state: i32) -> Result<(), &'static str> {
+    if gap_id == 0 {
+        return Ok(());
+    }
+    match derived_state(&Countermeasure::get_countermeasures_of_gap(gap_id).await) {
+        Some(state) if state != g_state => Err("g_state_derived_from_countermeasures"),
+        _ => Ok(()),
+    }
+}
+
+/// Gap of a control, created without assessment the first time a
+/// countermeasure addresses the control
+pub async fn gap_of_control(requirement_id: i32) -> Result<i32, &'static str> {
+    if let Some(gap) = Gap::c1_get_gap_by_requirement(requirement_id).await.pop() {
+        return Ok(gap.gap_id);
+    }
+
+    let requirement = match Requirement::c1_get_requirement_by_id(requirement_id).await.pop() {
+        Some(r) => r,
+        None => return Err("requirement_not_found"),
+    };
+
+    let requirements = Requirement::c1_get_requirements_by_referential(requirement.referential_id).await;
+    if !is_control(&requirements, &requirement) {
+        return Err("requirement_not_a_control");
+    }
+
+    let referential = Referential::c1_get_referential_by_id(requirement.referential_id).await.pop().unwrap_or(Referential::default());
+    let g_ref_type = format!("{} {}", referential.name, referential.version).replace("'", "\\'");
+    let g_ref_name = format!("{} {}", requirement.code, requirement.title).chars().take(255).collect::<String>().replace("'", "\\'");
+
+    let _ = Gap::c1_create_gap(g_ref_type, g_ref_name, 0, String::new(), String::new(), String::new(), requirement_id).await;
+
+    match Gap::c1_get_gap_by_requirement(requirement_id).await.pop() {
+        Some(gap) => Ok(gap.gap_id),
+        None => Err("gap_not_created"),
+    }
+}
@@ -0,0 +1,135 @@
+// export the home route handler
+use actix_web::{CustomizeResponder, HttpResponse, Responder};
+use serde_json::{json, Value};
+use crate::helper::functions::{extract_string_from_obj_value, sql_escape};
+use crate::helper::database::{FearedEvent, GravityScale};
+
+
+pub async fn create(body:Value) -> CustomizeResponder<HttpResponse> {
+
+    // check the body contain good key
+    for key in ["libelle", "description"].iter() {
+        if body.get(key).is_some() {
+            continue;
+        } else {
+            return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"missing_args\"}").customize();
+        }
+    }
+
+    let libelle = extract_string_from_obj_value(body.get("libelle"));
+    let description = extract_string_from_obj_value(body.get("description"));
+
+    if let Some(status) = check_level(&libelle, &description) {
+        return HttpResponse::Ok().content_type("application/json").body(format!("{{\"error\": true, \"status\": \"{}\"}}", status)).customize();
+    }
+
+    // a new level is always added on top of the scale
+    let niveau = GravityScale::c1_get_gravity_scale().await.iter().map(|l| l.niveau).max().unwrap_or(0) + 1;
+
+    // sql format to cancel sql injection
+    let libelle = sql_escape(&libelle);
+    let description = sql_escape(&description);
+
+    let _ = GravityScale::c1_create_gravity_level(niveau, libelle, description).await;
+
+    HttpResponse::Ok().content_type("application/json").body(json!({"status": "success"}).to_string()).customize()
+}
+
+
+pub async fn update(body:Value) -> CustomizeResponder<HttpResponse> {
+
+    // check the body contain good key
+    for key in ["niveau", "libelle", "description"].iter() {
+        if body.get(key).is_some() {
+            continue;
+        } else {
+            return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"missing_args\"}").customize();
+        }
+    }
+
+    let niveau = extract_string_from_obj_value(body.get("niveau"));
+    let libelle = extract_string_from_obj_value(body.get("libelle"));
+    let description = extract_string_from_obj_value(body.get("description"));
+
+    let niveau = match niveau.parse::<i32>() {
+        Ok(v) => v,
+        Err(_) => {
+            return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"niveau_not_valid\"}").customize();
+        }
+    };
+
+    // check level exist
+    if !GravityScale::c1_get_gravity_scale().await.iter().any(|l| l.niveau == niveau) {
+        return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"niveau_not_found\"}").customize();
+    }
+
+    if let Some(status) = check_level(&libelle, &description) {
+        return HttpResponse::Ok().content_type("application/json").body(format!("{{\"error\": true, \"status\": \"{}\"}}", status)).customize();
+    }
+
+    // sql format to cancel sql injection
+    let libelle = sql_escape(&libelle);
+    let description = sql_escape(&description);
+
+    let _ = GravityScale::c1_update_gravity_level(niveau, libelle, description).await;
+
+    HttpResponse::Ok().content_type("application/json").body(json!({"status": "success"}).to_string()).customize()
+}
+
+
+pub async fn delete(body:Value) -> CustomizeResponder<HttpResponse> {
+
+    // check the body contain good key
+    for key in ["niveau"].iter() {
+        if body.get(key).is_some() {
+            continue;
+        } else {
+            return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"missing_args\"}").customize();
+        }
+    }
+
+    let niveau = extract_string_from_obj_value(body.get("niveau"));
+
+    let niveau = match niveau.parse::<i32>() {
+        Ok(v) => v,
+        Err(_) => {
+            return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"niveau_not_valid\"}").customize();
+        }
+    };
+
+    // only the top level can be removed, so the scale never has holes
+    let scale = GravityScale::c1_get_gravity_scale().await;
+    if scale.iter().map(|l| l.niveau).max() != Some(niveau) {
+        return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"niveau_not_last\"}").customize();
+    }
+
+    if scale.len() == 1 {
+        return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"scale_cannot_be_empty\"}").customize();
+    }
+
+    // a level still used by a feared event cannot be removed
+    if FearedEvent::c1_get_all_feared_event().await.iter().any(|e| e.gravite == niveau) {
+        return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"niveau_in_use\"}").customize();
+    }
+
+    let _ = GravityScale::c1_delete_gravity_level(niveau).await;
+
+    HttpResponse::Ok().content_type("application/json").body(json!({"status": "success"}).to_string()).customize()
+}
+
+
+fn check_level(libelle:&str, description:&str) -> Option<&'static str> {
+    if libelle.is_empty() {
+        return Some("libelle_empty");
+    }
+
+    if libelle.len() > 255 {
+        return Some("libelle_too_long");
+    }
+
+    if description.len() > 2000 {
+        return Some("description_too_long");
+    }
+
+    None
+}
@@ -0,0 +1,50 @@
+use actix_web::{CustomizeResponder, HttpResponse, Responder};
+use serde_json::{json, Value};
+use crate::helper::functions::extract_string_from_obj_value;
+use crate::helper::database::Job;
+use crate::helper::scheduler::job_spec;
+use crate::helper::webhook::now_after;
+
+/// Run a job now, the scheduler starts it within a few seconds
+pub async fn run(body:Value) -> CustomizeResponder<HttpResponse> {
+
+    // check the body contain good key
+    for key in ["job_name"] {
+        if body.get(key).is_some() {
+            continue;
+        } else {
+            return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"missing_args\"}").customize();
+        }
+    }
+
+    let job_name = extract_string_from_obj_value(body.get("job_name"));
+
+    let spec = match job_spec(&job_name) {
+        Some(spec) => spec,
+        None => {
+            return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"job_not_found\"}").customize();
+        }
+    };
+
+    if spec.disabled.is_some() {
+        return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"job_disabled\"}").customize();
+    }
+
+    let state = Job::get_all_jobs().await.into_iter().find(|j| j.job_name == spec.name);
+    match state {
+        Some(state) if state.last_status == "running" => {
+            return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"job_running\"}").customize();
+        }
+        // registered by the scheduler when it starts
+        None => {
+            return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"scheduler_not_started\"}").customize();
+        }
+        _ => {}
+    }
+
+    if Job::schedule_job(spec.name.to_string(), now_after(0)).await.is_err() {
+        return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"database_error\"}").customize();
+    }
+
+    HttpResponse::Ok().content_type("application/json").body(json!({"status": "success"}).to_string()).customize()
+}
@@ -2,8 +2,8 @@
 use actix_web::{CustomizeResponder, HttpResponse, Responder};
 use serde_json::{json, Value};
 use crate::helper::functions::extract_string_from_obj_value;
-use crate::helper::database::{Mission, ValeurMetier, BienSupport};
-use super::asset;
+use crate::helper::database::{Mission, ValeurMetier};
+use super::vm;
 
 pub async fn create(body:Value) -> CustomizeResponder<HttpResponse> {
 
@@ -34,6 +34,50 @@ pub async fn create(body:Value) -> CustomizeResponder<HttpResponse> {
 }
 
 
+pub async fn update(body:Value) -> CustomizeResponder<HttpResponse> {
+    // check the body contain good key
+    for key in ["mission_id", "name"] {
+        if body.get(key).is_some() {
+            continue;
+        } else {
+            return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"missing_args\"}").customize();
+        }
+    }
+
+    let mission_id = extract_string_from_obj_value(body.get("mission_id"));
+    let doc_name = extract_string_from_obj_value(body.get("name"));
+
+    // convert mission id to i32
+    let mission_id = match mission_id.parse::<i32>() {
+        Ok(v) => v,
+        Err(_) => {
+            return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"mission_id_not_valid\"}").customize();
+        }
+    };
+
+    // check mission exist
+    if Mission::c1_get_mission_by_id(mission_id).await.is_empty() {
+        return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"mission_not_found\"}").customize();
+    }
+
+    // check if doc_name < 255 char
+    if doc_name.len() > 255 {
+        return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"name_too_long\"}").customize();
+    }
+
+    if doc_name.is_empty() {
+        return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"name_empty\"}").customize();
+    }
+
+    // sql format to cancel sql injection
+    let doc_name = doc_name.replace("'", "\\'");
+
+    let _ = Mission::c1_update_mission(mission_id, doc_name).await;
+
+    HttpResponse::Ok().content_type("application/json").body(json!({"status": "success"}).to_string()).customize()
+}
+
+
 pub async fn delete(body:Value) -> CustomizeResponder<HttpResponse> {
     // check the body contain good key
     for key in vec!["mission_id"] {
@@ -58,13 +102,7 @@ pub async fn delete(body:Value) -> CustomizeResponder<HttpResponse> {
     let vms = ValeurMetier::c1_get_all_valeurmetier(mission_id).await;
 
     for vm in vms {
-        let asset = BienSupport::c1_get_asset_by_vmid(vm.valeur_id).await;
-
-        for a in asset {
-            let _ = asset::delete(json!({"asset_id": a.support_id})).await;
-        }
-
-        let _ = asset::delete(json!({"valeurmetier_id": vm.valeur_id})).await;
+        let _ = vm::delete(json!({"vm_id": vm.valeur_id.to_string()})).await;
     }
 
     let _ = Mission::c1_delete_mission_by_id(mission_id).await;
@@ -0,0 +1,101 @@
+use actix_web::{CustomizeResponder, HttpResponse, Responder};
+use serde_json::{json, Value};
+use crate::helper::functions::{extract_string_from_obj_value, is_valid_email, sql_escape};
+use crate::helper::database::NotificationPreference;
+use crate::helper::i18n::is_locale;
+use crate::helper::notification::{enabled, test_send};
+
+/// Create or replace the email preferences of an owner
+pub async fn save(body:Value) -> CustomizeResponder<HttpResponse> {
+
+    // check the body contain good key
+    for key in ["user_name", "email", "locale", "assignments", "overdue", "reviews", "digest"] {
+        if body.get(key).is_some() {
+            continue;
+        } else {
+            return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"missing_args\"}").customize();
+        }
+    }
+
+    let user_name = extract_string_from_obj_value(body.get("user_name")).trim().to_string();
+    let email = extract_string_from_obj_value(body.get("email")).trim().to_string();
+    let locale = extract_string_from_obj_value(body.get("locale"));
+
+    if user_name.is_empty() || user_name.len() > 255 {
+        return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"invalid_user_name\"}").customize();
+    }
+
+    if email.len() > 255 || !is_valid_email(&email) {
+        return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"invalid_email\"}").customize();
+    }
+
+    if !is_locale(&locale) {
+        return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"invalid_locale\"}").customize();
+    }
+
+    // the owner keeps the name of the risks and countermeasures, whatever the case typed
+    let user_name = match NotificationPreference::get_preference(&user_name).await {
+        Some(existing) => existing.user_name,
+        None => user_name,
+    };
+
+    let flag = |key: &str| extract_string_from_obj_value(body.get(key)) == "true";
+    let preference = NotificationPreference {
+        // sql format to cancel sql injection
+        user_name: sql_escape(&user_name),
+        email,
+        locale,
+        assignments: flag("assignments"),
+        overdue: flag("overdue"),
+        reviews: flag("reviews"),
+        digest: flag("digest"),
+    };
+
+    if NotificationPreference::save_preference(preference).await.is_err() {
+        return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"database_error\"}").customize();
+    }
+
+    HttpResponse::Ok().content_type("application/json").body(json!({"status": "success"}).to_string()).customize()
+}
+
+/// Read the `user_name` of the body
+async fn read_preference(body: &Value) -> Result<NotificationPreference, &'static str> {
+    if body.get("user_name").is_none() {
+        return Err("missing_args");
+    }
+
+    let user_name = extract_string_from_obj_value(body.get("user_name"));
+    NotificationPreference::get_preference(&user_name).await.ok_or("preference_not_found")
+}
+
+/// Stop emailing an owner
+pub async fn delete(body:Value) -> CustomizeResponder<HttpResponse> {
+    let preference = match read_preference(&body).await {
+        Ok(preference) => preference,
+        Err(e) => {
+            return HttpResponse::Ok().content_type("application/json").body(json!({"error": true, "status": e}).to_string()).customize();
+        }
+    };
+
+    let _ = NotificationPreference::delete_preference(sql_escape(&preference.user_name)).await;
+
+    HttpResponse::Ok().content_type("application/json").body(json!({"status": "success"}).to_string()).customize()
+}
+
+/// Queue a test email to an owner, whatever their preferences
+pub async fn test(body:Value) -> CustomizeResponder<HttpResponse> {
+    let preference = match read_preference(&body).await {
+        Ok(preference) => preference,
+        Err(e) => {
+            return HttpResponse::Ok().content_type("application/json").body(json!({"error": true, "status": e}).to_string()).customize();
+        }
+    };
+
+    if !enabled() {
+        return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"notifications_disabled\"}").customize();
+    }
+
+    test_send(&preference).await;
+
+    HttpResponse::Ok().content_type("application/json").body(json!({"status": "success"}).to_string()).customize()
+}
@@ -0,0 +1,177 @@
+// export the home route handler
+use actix_web::{CustomizeResponder, HttpResponse, Responder};
+use serde_json::{json, Value};
+use crate::helper::functions::{extract_string_from_obj_value, sql_escape};
+use crate::helper::database::{Gap, Referential, Requirement};
+use crate::helper::referential::{Catalogue, CatalogueDiff, CatalogueError};
+
+
+/// Show what importing a catalogue would add, change and remove, without
+/// touching the study
+pub async fn preview(body:Value) -> CustomizeResponder<HttpResponse> {
+    let catalogue = match read_catalogue(&body) {
+        Ok(c) => c,
+        Err(e) => {
+            return HttpResponse::Ok().content_type("application/json").body(json!({"error": true, "status": e.status, "message": e.message}).to_string()).customize();
+        }
+    };
+
+    let (current, diff) = compare(&catalogue).await;
+
+    return HttpResponse::Ok().content_type("application/json").body(report(&catalogue, current.as_ref(), &diff).await.to_string()).customize();
+}
+
+
+/// Import a built-in catalogue by `key`, or the `content` of a catalogue file.
+/// A new version of an imported referential replaces the current one
+pub async fn import(body:Value) -> CustomizeResponder<HttpResponse> {
+    let catalogue = match read_catalogue(&body) {
+        Ok(c) => c,
+        Err(e) => {
+            return HttpResponse::Ok().content_type("application/json").body(json!({"error": true, "status": e.status, "message": e.message}).to_string()).customize();
+        }
+    };
+
+    let (current, diff) = compare(&catalogue).await;
+
+    if let Some(r) = current.as_ref() {
+        if r.version == catalogue.version {
+            return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"already_imported\"}").customize();
+        }
+    }
+
+    // the report is built before the import flags the gaps
+    let report = report(&catalogue, current.as_ref(), &diff).await;
+
+    // sql format to cancel sql injection
+    let requirements = catalogue.flatten().into_iter().map(|mut r| {
+        r.code = sql_escape(&r.code);
+        r.parent_code = sql_escape(&r.parent_code);
+        r.title = sql_escape(&r.title);
+        r.description = sql_escape(&r.description);
+        r
+    }).collect();
+    let escape = |codes: &Vec<String>| codes.iter().map(|c| sql_escape(c)).collect::<Vec<String>>();
+
+    match current {
+        None => {
+            let _ = Referential::c1_import_referential(
+                sql_escape(&catalogue.key),
+                sql_escape(&catalogue.name),
+                sql_escape(&catalogue.version),
+                sql_escape(&catalogue.publisher),
+                sql_escape(&catalogue.description),
+                requirements,
+            ).await;
+        }
+        Some(r) => {
+            let _ = Referential::c1_upgrade_referential(
+                r.referential_id,
+                sql_escape(&catalogue.name),
+                sql_escape(&catalogue.version),
+                sql_escape(&catalogue.publisher),
+                sql_escape(&catalogue.description),
+                requirements,
+                escape(&diff.added),
+                escape(&diff.changed),
+                escape(&diff.removed),
+            ).await;
+        }
+    }
+
+    HttpResponse::Ok().content_type("application/json").body(report.to_string()).customize()
+}
+
+
+pub async fn delete(body:Value) -> CustomizeResponder<HttpResponse> {
+
+    // check the body contain good key
+    for key in ["referential_id"].iter() {
+        if body.get(key).is_some() {
+            continue;
+        } else {
+            return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"missing_args\"}").customize();
+        }
+    }
+
+    let referential_id = extract_string_from_obj_value(body.get("referential_id"));
+
+    let referential_id = match referential_id.parse::<i32>() {
+        Ok(v) => v,
+        Err(_) => {
+            return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"referential_id_not_valid\"}").customize();
+        }
+    };
+
+    let _ = Referential::c1_delete_referential(referential_id).await;
+
+    HttpResponse::Ok().content_type("application/json").body(json!({"status": "success"}).to_string()).customize()
+}
+
+
+/// The catalogue of a request: a built-in one when `key` is alone, else the
+/// parsed `content`, whose key can be overridden by `key`
+fn read_catalogue(body:&Value) -> Result<Catalogue, CatalogueError> {
+    let key = extract_string_from_obj_value(body.get("key"));
+
+    if body.get("content").is_none() {
+        if key.is_empty() {
+            return Err("missing_args".into());
+        }
+        return Catalogue::find(&key).ok_or("catalogue_not_found".into());
+    }
+
+    let content = extract_string_from_obj_value(body.get("content"));
+    let format = extract_string_from_obj_value(body.get("format"));
+
+    let mut catalogue = Catalogue::parse(&content, &format)?;
+    if !key.is_empty() {
+        catalogue.key = key;
+        catalogue.check()?;
+    }
+
+    Ok(catalogue)
+}
+
+
+/// The imported referential sharing the key of the catalogue, and the
+/// requirements the catalogue adds, changes and removes from it
+async fn compare(catalogue:&Catalogue) -> (Option<Referential>, CatalogueDiff) {
+    let current = Referential::c1_get_all_referentials().await.into_iter().find(|r| r.ref_key == catalogue.key);
+
+    let old = match current.as_ref() {
+        Some(r) => Requirement::c1_get_requirements_by_referential(r.referential_id).await,
+        None => Vec::new(),
+    };
+
+    let diff = CatalogueDiff::between(&old, &catalogue.flatten());
+    (current, diff)
+}
+
+
+async fn report(catalogue:&Catalogue, current:Option<&Referential>, diff:&CatalogueDiff) -> Value {
+    // the gaps of changed and removed requirements need a new look
+    let mut gaps_to_review: Vec<Value> = Vec::new();
+    if let Some(r) = current {
+        let old = Requirement::c1_get_requirements_by_referential(r.referential_id).await;
+        for g in Gap::c1_get_all_gaps().await.iter() {
+            if let Some(req) = old.iter().find(|req| req.requirement_id == g.requirement_id && g.requirement_id != 0) {
+                if diff.changed.contains(&req.code) || diff.removed.contains(&req.code) {
+                    gaps_to_review.push(json!({"gap_id": g.gap_id, "code": req.code, "removed": diff.removed.contains(&req.code)}));
+                }
+            }
+        }
+    }
+
+    json!({
+        "status": "success",
+        "key": catalogue.key,
+        "name": catalogue.name,
+        "version": catalogue.version,
+        "current_version": current.map(|r| r.version.clone()),
+        "added": diff.added,
+        "changed": diff.changed,
+        "removed": diff.removed,
+        "gaps_to_review": gaps_to_review,
+    })
+}
@@ -1,14 +1,70 @@
 use actix_web::{CustomizeResponder, HttpResponse, Responder};
 use serde_json::{json, Value};
-use crate::helper::functions::{extract_string_from_obj_value, is_uuid_v4};
-use crate::helper::database::{Risk, Scenario, Countermeasure};
+use crate::helper::functions::{extract_string_from_obj_value, is_uuid_v4, is_valid_date, sql_escape};
+use crate::helper::database::{Risk, RiskAcceptance, Scenario, Countermeasure};
+use crate::helper::webhook;
+
+/// Lifecycle of a risk in the register, in order
+pub const STATUSES: [(&str, &str); 5] = [
+    ("identified", "Identified"),
+    ("assessed", "Assessed"),
+    ("treated", "Treated"),
+    ("accepted", "Accepted"),
+    ("closed", "Closed"),
+];
+
+/// Category taxonomy of the register
+pub const CATEGORIES: [(&str, &str); 8] = [
+    ("strategic", "Strategic"),
+    ("operational", "Operational"),
+    ("financial", "Financial"),
+    ("compliance", "Legal & compliance"),
+    ("reputational", "Reputational"),
+    ("cyber", "Cyber security"),
+    ("third_party", "Third party"),
+    ("human", "Human resources"),
+];
+
+pub fn status_label(status: &str) -> &'static str {
+    STATUSES.iter().find(|(s, _)| *s == status).map(|(_, label)| *label).unwrap_or("Identified")
+}
+
+pub fn category_label(category: &str) -> &'static str {
+    CATEGORIES.iter().find(|(c, _)| *c == category).map(|(_, label)| *label).unwrap_or("Uncategorized")
+}
+
+/// Owner, category, status and next review date of a risk
+fn read_register(body: &Value) -> Result<(String, String, String, String), &'static str> {
+    let owner = extract_string_from_obj_value(body.get("owner"));
+    let category = extract_string_from_obj_value(body.get("category"));
+    let status = extract_string_from_obj_value(body.get("status"));
+    let next_review = extract_string_from_obj_value(body.get("next_review"));
+
+    if owner.len() > 255 {
+        return Err("owner_too_long");
+    }
+
+    if !category.is_empty() && !CATEGORIES.iter().any(|(c, _)| *c == category) {
+        return Err("invalid_category");
+    }
+
+    if !STATUSES.iter().any(|(s, _)| *s == status) {
+        return Err("invalid_status");
+    }
+
+    if !next_review.is_empty() && !is_valid_date(&next_review) {
+        return Err("invalid_next_review");
+    }
+
+    Ok((sql_escape(&owner), category, status, next_review))
+}
 
 
 pub async fn create(body:Value) -> CustomizeResponder<HttpResponse> {
 
 
     // check the body contain good key
-    for key in vec!["name", "description"] {
+    for key in ["name", "description", "owner", "category", "status", "next_review"] {
         if body.get(key).is_some() {
             continue;
         } else {
@@ -24,19 +80,38 @@ pub async fn create(body:Value) -> CustomizeResponder<HttpResponse> {
         return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"name_too_long\"}").customize();
     }
 
+    let (owner, category, status, next_review) = match read_register(&body) {
+        Ok(register) => register,
+        Err(e) => {
+            return HttpResponse::Ok().content_type("application/json").body(json!({"error": true, "status": e}).to_string()).customize();
+        }
+    };
+
+    let mut event = json!({
+        "name": doc_name,
+        "description": doc_description,
+        "owner": extract_string_from_obj_value(body.get("owner")),
+        "category": category,
+        "status": status,
+        "next_review": next_review,
+    });
+
     // sql format to cancel sql injection
     let doc_name = doc_name.replace("'", "\\'");
     let doc_description = doc_description.replace("'", "\\'");
 
 
-    let _ = Risk::create_new_risk(doc_name, doc_description).await;
+    if let Ok(risk_uuid) = Risk::create_new_risk(doc_name, doc_description, owner, category, status, next_review).await {
+        event["risk_uuid"] = json!(risk_uuid);
+        webhook::emit("risk.created", &risk_uuid, event).await;
+    }
 
     return HttpResponse::Ok().content_type("application/json").body(json!({"status": "success"}).to_string()).customize();
 }
 
 pub async fn update(body:Value) -> CustomizeResponder<HttpResponse> {
     // check the body contain good key
-    for key in vec!["uuid", "name", "description"] {
+    for key in ["uuid", "name", "description", "owner", "category", "status", "next_review"] {
         if body.get(key).is_some() {
             continue;
         } else {
@@ -53,6 +128,13 @@ pub async fn update(body:Value) -> CustomizeResponder<HttpResponse> {
         return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"name_too_long\"}").customize();
     }
 
+    let (owner, category, status, next_review) = match read_register(&body) {
+        Ok(register) => register,
+        Err(e) => {
+            return HttpResponse::Ok().content_type("application/json").body(json!({"error": true, "status": e}).to_string()).customize();
+        }
+    };
+
     // sql format to cancel sql injection
     let doc_name = doc_name.replace("'", "\\'");
     let doc_description = doc_description.replace("'", "\\'");
@@ -62,7 +144,38 @@ pub async fn update(body:Value) -> CustomizeResponder<HttpResponse> {
         return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"invalid_uuid\"}").customize();
     }
 
-    let _ = Risk::update_risk(doc_uuid, doc_name, doc_description).await;
+    let _ = Risk::update_risk(doc_uuid, doc_name, doc_description, owner, category, status, next_review).await;
+
+    HttpResponse::Ok().content_type("application/json").body(json!({"status": "success"}).to_string()).customize()
+}
+
+pub async fn review(body:Value) -> CustomizeResponder<HttpResponse> {
+    // check the body contain good key
+    for key in ["uuid", "next_review"] {
+        if body.get(key).is_some() {
+            continue;
+        } else {
+            return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"missing_args\"}").customize();
+        }
+    }
+
+    let doc_uuid = extract_string_from_obj_value(body.get("uuid"));
+    let next_review = extract_string_from_obj_value(body.get("next_review"));
+
+    // check if the uuid is a valid uuid
+    if !is_uuid_v4(&doc_uuid) {
+        return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"invalid_uuid\"}").customize();
+    }
+
+    if !next_review.is_empty() && !is_valid_date(&next_review) {
+        return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"invalid_next_review\"}").customize();
+    }
+
+    if Risk::get_risk_detail(doc_uuid.clone()).await.is_empty() {
+        return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"risk_not_found\"}").customize();
+    }
+
+    let _ = Risk::review_risk(doc_uuid, next_review).await;
 
     return HttpResponse::Ok().content_type("application/json").body(json!({"status": "success"}).to_string()).customize();
 }
@@ -86,12 +199,14 @@ pub async fn delete(body:Value) -> CustomizeResponder<HttpResponse> {
 
     // delete the risk
     let _ = Risk::delete_risk(doc_uuid.clone()).await;
+    let _ = RiskAcceptance::delete_acceptances_of(doc_uuid.clone()).await;
     let all_sc = Scenario::get_all_scenario_of_risk(doc_uuid.clone()).await;
 
     for sc in all_sc {
         let _ = Scenario::delete_scenario(sc.scenario_uuid.to_string()).await;
         let _ = Scenario::delete_scenario_risk(sc.scenario_uuid.to_string()).await;
         let _ = Countermeasure::delete_countermeasure_from_sc(sc.scenario_uuid.to_string()).await;
+        let _ = RiskAcceptance::delete_acceptances_of(sc.scenario_uuid.to_string()).await;
     }
 
     return HttpResponse::Ok().content_type("application/json").body(json!({"status": "success"}).to_string()).customize();
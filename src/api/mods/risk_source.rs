@@ -1,6 +1,6 @@
 use actix_web::{CustomizeResponder, HttpResponse, Responder};
 use serde_json::{json, Value};
-use crate::helper::functions::extract_string_from_obj_value;
+use crate::helper::functions::{extract_string_from_obj_value, parse_scales};
 use crate::helper::database::C2RiskSources;
 
 pub async fn create(body: Value) -> CustomizeResponder<HttpResponse> {
@@ -66,6 +66,95 @@ pub async fn create(body: Value) -> CustomizeResponder<HttpResponse> {
         .customize()
 }
 
+pub async fn update(body: Value) -> CustomizeResponder<HttpResponse> {
+    // Check if the body contains the required keys
+    for key in ["risk_id", "source_risque", "objectifs_vises", "motivation", "ressources", "pertinence_sr_ov", "priorite", "retenu", "justification_exclusion_sr_ov"].iter() {
+        if body.get(key).is_some() {
+            continue;
+        } else {
+            return HttpResponse::Ok()
+                .content_type("application/json")
+                .body("{\"error\": true, \"status\": \"missing_args\"}")
+                .customize();
+        }
+    }
+
+    let risk_id = match extract_string_from_obj_value(body.get("risk_id")).parse::<i32>() {
+        Ok(v) => v,
+        Err(_) => {
+            return HttpResponse::Ok()
+                .content_type("application/json")
+                .body("{\"error\": true, \"status\": \"risk_id_not_valid\"}")
+                .customize();
+        }
+    };
+
+    // Check the risk source exist
+    if C2RiskSources::c2_get_risk_detail(risk_id).await.is_empty() {
+        return HttpResponse::Ok()
+            .content_type("application/json")
+            .body("{\"error\": true, \"status\": \"risk_not_found\"}")
+            .customize();
+    }
+
+    let source_risque = extract_string_from_obj_value(body.get("source_risque"));
+    let objectifs_vises = extract_string_from_obj_value(body.get("objectifs_vises"));
+    let motivation = extract_string_from_obj_value(body.get("motivation"));
+    let ressources = extract_string_from_obj_value(body.get("ressources"));
+    // pertinence and priority are rated on the EBIOS scale, from 1 to 4
+    let (pertinence_sr_ov, priorite) = match parse_scales(&body, &["pertinence_sr_ov", "priorite"]) {
+        Ok(scores) => (scores[0], scores[1]),
+        Err(key) => {
+            return HttpResponse::Ok()
+                .content_type("application/json")
+                .body(format!("{{\"error\": true, \"status\": \"invalid_{}\"}}", key))
+                .customize();
+        }
+    };
+    let retenu = extract_string_from_obj_value(body.get("retenu")) == "true";
+    let justification_exclusion_sr_ov = extract_string_from_obj_value(body.get("justification_exclusion_sr_ov"));
+
+    // Ensure field lengths
+    if source_risque.len() > 255 || objectifs_vises.len() > 255 || motivation.len() > 1000 || ressources.len() > 1000 || justification_exclusion_sr_ov.len() > 1000 {
+        return HttpResponse::Ok()
+            .content_type("application/json")
+            .body("{\"error\": true, \"status\": \"field_too_long\"}")
+            .customize();
+    }
+
+    // all fields are required
+    if source_risque.is_empty() || objectifs_vises.is_empty() || motivation.is_empty() || ressources.is_empty() || justification_exclusion_sr_ov.is_empty() {
+        return HttpResponse::Ok()
+            .content_type("application/json")
+            .body("{\"error\": true, \"status\": \"field_empty\"}")
+            .customize();
+    }
+
+    // Escape single quotes to prevent SQL injection
+    let source_risque = source_risque.replace("'", "\\'");
+    let objectifs_vises = objectifs_vises.replace("'", "\\'");
+    let motivation = motivation.replace("'", "\\'");
+    let ressources = ressources.replace("'", "\\'");
+    let justification_exclusion_sr_ov = justification_exclusion_sr_ov.replace("'", "\\'");
+
+    let _ = C2RiskSources::c2_update_risk(
+        risk_id,
+        source_risque,
+        objectifs_vises,
+        motivation,
+        ressources,
+        pertinence_sr_ov,
+        priorite,
+        retenu,
+        justification_exclusion_sr_ov,
+    ).await;
+
+    HttpResponse::Ok()
+        .content_type("application/json")
+        .body(json!({"status": "success"}).to_string())
+        .customize()
+}
+
 pub async fn delete(body: Value) -> CustomizeResponder<HttpResponse> {
     // Check if the body contains the required keys
     if let Some(id) = body.get("risk_id") {
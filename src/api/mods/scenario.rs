@@ -2,7 +2,9 @@
 use actix_web::{CustomizeResponder, HttpResponse, Responder};
 use serde_json::{json, Value};
 use crate::helper::functions::extract_string_from_obj_value;
-use crate::helper::database::{Scenario, Countermeasure, Risk};
+use crate::helper::database::{Scenario, ScenarioRisk, ScenarioRiskHistory, Countermeasure, Risk, RiskAcceptance};
+use crate::helper::webhook;
+use crate::web::routes::scenario::calculate_risk;
 
 
 pub async fn create(body:Value) -> CustomizeResponder<HttpResponse> {
@@ -118,6 +120,7 @@ pub async fn create(body:Value) -> CustomizeResponder<HttpResponse> {
     
     // create the scenario risk
     let _ = Scenario::create_scenario_risk(scenario_uuid.to_string(), sc_likelihood, sc_reputational, sc_operational, sc_legal_compliance, sc_financial).await;
+    ScenarioRiskHistory::record_changes().await;
     
     return HttpResponse::Ok().content_type("application/json").body(json!({"status": "success"}).to_string()).customize();
 }
@@ -230,11 +233,28 @@ pub async fn update(body:Value) -> CustomizeResponder<HttpResponse> {
     let sc_threat_description = sc_threat_description.replace("\"", "").replace("'", "");
     let sc_custom_note = sc_custom_note.replace("\"", "").replace("'", "");
 
+    // the level before the update, for the webhooks
+    let before = ScenarioRisk::get_scenario_risk(scenario_uuid.to_string()).await.pop().unwrap_or(ScenarioRisk::default());
+    let previous_level = calculate_risk(before.likelihood, before.reputation, before.operational, before.legal_compliance, before.financial);
+
     // update the scenario
     let _ = Scenario::update_scenario(scenario_uuid.to_string(), sc_scenario_description, sc_threat_description, sc_custom_note).await;
 
     // update the scenario risk
     let _ = Scenario::update_scenario_risk(scenario_uuid.to_string(), sc_likelihood, sc_reputational, sc_operational, sc_legal_compliance, sc_financial).await;
+    ScenarioRiskHistory::record_changes().await;
+
+    let level = calculate_risk(sc_likelihood, sc_reputational, sc_operational, sc_legal_compliance, sc_financial);
+    if level != previous_level {
+        let scenario = Scenario::get_scenario_detail(scenario_uuid.to_string()).await.pop().unwrap_or(Scenario::default());
+        webhook::emit("scenario.level_changed", &scenario_uuid.to_string(), json!({
+            "scenario_uuid": scenario_uuid.to_string(),
+            "risk_uuid": scenario.risk_uuid.to_string(),
+            "description": scenario.scenario_description,
+            "previous_level": previous_level,
+            "level": level,
+        })).await;
+    }
 
     return HttpResponse::Ok().content_type("application/json").body(json!({"status": "success"}).to_string()).customize();
 }
@@ -271,6 +291,7 @@ pub async fn delete(body:Value) -> CustomizeResponder<HttpResponse> {
     let _ = Scenario::delete_scenario(scenario_uuid.to_string()).await;
     let _ = Scenario::delete_scenario_risk(scenario_uuid.to_string()).await;
     let _ = Countermeasure::delete_countermeasure_from_sc(scenario_uuid.to_string()).await;
+    let _ = RiskAcceptance::delete_acceptances_of(scenario_uuid.to_string()).await;
 
     return HttpResponse::Ok().content_type("application/json").body(json!({"status": "success"}).to_string()).customize();
 }
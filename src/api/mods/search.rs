@@ -0,0 +1,18 @@
+// the search of the study, for the scripts
+use actix_web::{CustomizeResponder, HttpResponse, Responder};
+use serde_json::json;
+
+use crate::helper::search::{search as search_study, terms};
+
+/// The results of `GET /api/search?q=...`, grouped by kind of entity
+pub async fn search(query: &str) -> CustomizeResponder<HttpResponse> {
+    if terms(query).is_empty() {
+        return HttpResponse::Ok().content_type("application/json").body(json!({"status": "query_too_short"}).to_string()).customize();
+    }
+
+    let groups = search_study(query).await;
+    HttpResponse::Ok()
+        .content_type("application/json")
+        .body(json!({"status": "success", "query": query, "groups": groups}).to_string())
+        .customize()
+}
@@ -0,0 +1,68 @@
+use actix_web::{CustomizeResponder, HttpResponse, Responder};
+use serde_json::{json, Value};
+use crate::helper::functions::{extract_string_from_obj_value, sql_escape};
+use crate::helper::database::Snapshot;
+use crate::helper::snapshot::StudyState;
+
+/// Freeze the live state of the study under a name
+pub async fn create(body:Value) -> CustomizeResponder<HttpResponse> {
+
+    // check the body contain good key
+    for key in ["name"] {
+        if body.get(key).is_some() {
+            continue;
+        } else {
+            return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"missing_args\"}").customize();
+        }
+    }
+
+    let name = extract_string_from_obj_value(body.get("name"));
+
+    if name.trim().is_empty() {
+        return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"missing_name\"}").customize();
+    }
+
+    if name.len() > 255 {
+        return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"name_too_long\"}").customize();
+    }
+
+    let content = match serde_json::to_string(&StudyState::load().await) {
+        Ok(c) => c,
+        Err(_) => {
+            return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"serialization_error\"}").customize();
+        }
+    };
+
+    // sql format to cancel sql injection
+    let name = sql_escape(name.trim());
+    let content = sql_escape(&content);
+
+    if Snapshot::create_snapshot(name, content).await.is_err() {
+        return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"database_error\"}").customize();
+    }
+
+    HttpResponse::Ok().content_type("application/json").body(json!({"status": "success"}).to_string()).customize()
+}
+
+pub async fn delete(body:Value) -> CustomizeResponder<HttpResponse> {
+
+    // check the body contain good key
+    for key in ["snapshot_id"] {
+        if body.get(key).is_some() {
+            continue;
+        } else {
+            return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"missing_args\"}").customize();
+        }
+    }
+
+    let snapshot_id = match extract_string_from_obj_value(body.get("snapshot_id")).parse::<i32>() {
+        Ok(id) => id,
+        Err(_) => {
+            return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"invalid_snapshot_id\"}").customize();
+        }
+    };
+
+    let _ = Snapshot::delete_snapshot(snapshot_id).await;
+
+    HttpResponse::Ok().content_type("application/json").body(json!({"status": "success"}).to_string()).customize()
+}
@@ -1,7 +1,10 @@
 use actix_web::{CustomizeResponder, HttpResponse, Responder};
 use serde_json::{json, Value};
-use crate::helper::functions::extract_string_from_obj_value;
+use crate::helper::functions::{extract_string_from_obj_value, parse_scale, parse_scales, sql_escape};
+use crate::helper::config::config;
 use crate::helper::database::C3Stakeholder;
+use crate::helper::webhook;
+use crate::helper::threat::threat_level;
 
 pub async fn create(body: Value) -> CustomizeResponder<HttpResponse> {
     // Check if the body contains the required keys
@@ -18,15 +21,17 @@ pub async fn create(body: Value) -> CustomizeResponder<HttpResponse> {
 
     let category = extract_string_from_obj_value(body.get("category"));
     let stakeholder_name = extract_string_from_obj_value(body.get("stakeholder_name"));
-    let dependance = extract_string_from_obj_value(body.get("dependance"));
-    let penetration = extract_string_from_obj_value(body.get("penetration"));
-    let maturite_ssi = extract_string_from_obj_value(body.get("maturite_ssi"));
-    let confiance = extract_string_from_obj_value(body.get("confiance"));
 
-    let dependance = dependance.parse::<i32>().unwrap_or(0);
-    let penetration = penetration.parse::<i32>().unwrap_or(0);
-    let maturite_ssi = maturite_ssi.parse::<i32>().unwrap_or(0);
-    let confiance = confiance.parse::<i32>().unwrap_or(0);
+    // The EBIOS ratings are all on a 1 to 4 scale
+    let scores = match parse_scales(&body, &["dependance", "penetration", "maturite_ssi", "confiance"]) {
+        Ok(scores) => scores,
+        Err(key) => {
+            return HttpResponse::Ok()
+                .content_type("application/json")
+                .body(format!("{{\"error\": true, \"status\": \"invalid_{}\"}}", key))
+                .customize();
+        }
+    };
 
     // Ensure field lengths
     if category.len() > 255 || stakeholder_name.len() > 255 {
@@ -49,21 +54,163 @@ pub async fn create(body: Value) -> CustomizeResponder<HttpResponse> {
     let stakeholder_name = stakeholder_name.replace("'", "\\'");
 
     // Call the function to create the stakeholder
-    let _ = C3Stakeholder::c3_create_stakeholder(
+    let stakeholder_id = C3Stakeholder::c3_create_stakeholder(
         category,
         stakeholder_name,
-        dependance,
-        penetration,
-        maturite_ssi,
-        confiance,
+        scores[0],
+        scores[1],
+        scores[2],
+        scores[3],
     ).await;
 
+    if let Some(stakeholder_id) = stakeholder_id {
+        notify_threat(None, stakeholder_id).await;
+    }
+
     HttpResponse::Ok()
         .content_type("application/json")
         .body(json!({"status": "success"}).to_string())
         .customize()
 }
 
+pub async fn update(body: Value) -> CustomizeResponder<HttpResponse> {
+    // Check if the body contains the required keys
+    for key in ["stakeholder_id", "category", "stakeholder_name", "dependance", "penetration", "maturite_ssi", "confiance"].iter() {
+        if body.get(key).is_some() {
+            continue;
+        } else {
+            return HttpResponse::Ok()
+                .content_type("application/json")
+                .body("{\"error\": true, \"status\": \"missing_args\"}")
+                .customize();
+        }
+    }
+
+    let stakeholder_id = match extract_string_from_obj_value(body.get("stakeholder_id")).parse::<i32>() {
+        Ok(v) => v,
+        Err(_) => {
+            return HttpResponse::Ok()
+                .content_type("application/json")
+                .body("{\"error\": true, \"status\": \"stakeholder_id_not_valid\"}")
+                .customize();
+        }
+    };
+
+    // Check the stakeholder exist
+    let before = match C3Stakeholder::c3_get_stakeholder_detail(stakeholder_id).await.pop() {
+        Some(s) => s,
+        None => {
+            return HttpResponse::Ok()
+                .content_type("application/json")
+                .body("{\"error\": true, \"status\": \"stakeholder_not_found\"}")
+                .customize();
+        }
+    };
+
+    let category = extract_string_from_obj_value(body.get("category"));
+    let stakeholder_name = extract_string_from_obj_value(body.get("stakeholder_name"));
+    let mesures_securite = extract_string_from_obj_value(body.get("mesures_securite"));
+
+    // The EBIOS ratings are all on a 1 to 4 scale
+    let scores = match parse_scales(&body, &["dependance", "penetration", "maturite_ssi", "confiance"]) {
+        Ok(scores) => scores,
+        Err(key) => {
+            return HttpResponse::Ok()
+                .content_type("application/json")
+                .body(format!("{{\"error\": true, \"status\": \"invalid_{}\"}}", key))
+                .customize();
+        }
+    };
+
+    // The residual ratings (after the security measures) are optional, but
+    // follow the same scale when they are given
+    let mut residuals = Vec::new();
+    for key in ["dependance_residuelle", "penetration_residuelle", "maturite_ssi_residuelle", "confiance_residuelle"] {
+        let value = extract_string_from_obj_value(body.get(key));
+        if value.is_empty() {
+            residuals.push(None);
+            continue;
+        }
+        match parse_scale(&value) {
+            Some(v) => residuals.push(Some(v)),
+            None => {
+                return HttpResponse::Ok()
+                    .content_type("application/json")
+                    .body(format!("{{\"error\": true, \"status\": \"invalid_{}\"}}", key))
+                    .customize();
+            }
+        }
+    }
+
+    // Ensure field lengths
+    if category.len() > 255 || stakeholder_name.len() > 255 || mesures_securite.len() > 2000 {
+        return HttpResponse::Ok()
+            .content_type("application/json")
+            .body("{\"error\": true, \"status\": \"field_too_long\"}")
+            .customize();
+    }
+
+    // Check that required fields are not empty
+    if category.is_empty() || stakeholder_name.is_empty() {
+        return HttpResponse::Ok()
+            .content_type("application/json")
+            .body("{\"error\": true, \"status\": \"field_empty\"}")
+            .customize();
+    }
+
+    // Escape the free texts to prevent SQL injection
+    let category = sql_escape(&category);
+    let stakeholder_name = sql_escape(&stakeholder_name);
+    let mesures_securite = sql_escape(&mesures_securite);
+
+    let _ = C3Stakeholder::c3_update_stakeholder(
+        stakeholder_id,
+        category,
+        stakeholder_name,
+        scores[0],
+        scores[1],
+        scores[2],
+        scores[3],
+        mesures_securite,
+        residuals[0],
+        residuals[1],
+        residuals[2],
+        residuals[3],
+    ).await;
+
+    notify_threat(Some(&before), stakeholder_id).await;
+
+    HttpResponse::Ok()
+        .content_type("application/json")
+        .body(json!({"status": "success"}).to_string())
+        .customize()
+}
+
+/// Tell the webhooks when the threat level of a stakeholder enters the
+/// danger zone of the radar
+async fn notify_threat(before: Option<&C3Stakeholder>, stakeholder_id: i32) {
+    let stakeholder = match C3Stakeholder::c3_get_stakeholder_detail(stakeholder_id).await.pop() {
+        Some(s) => s,
+        None => return,
+    };
+
+    let danger = config().c3_radar.danger;
+    let (_, _, previous_level) = before.map(threat_level).unwrap_or_default();
+    let (_, _, level) = threat_level(&stakeholder);
+    if level < danger || previous_level >= danger {
+        return;
+    }
+
+    webhook::emit("stakeholder.threat_above_threshold", &stakeholder_id.to_string(), json!({
+        "stakeholder_id": stakeholder_id,
+        "stakeholder_name": stakeholder.stakeholder_name,
+        "category": stakeholder.category,
+        "threat_level": level,
+        "previous_threat_level": before.map(|_| previous_level),
+        "threshold": danger,
+    })).await;
+}
+
 pub async fn delete(body: Value) -> CustomizeResponder<HttpResponse> {
     // Check if the body contains the required key
     if let Some(id) = body.get("stakeholder_id") {
@@ -83,4 +230,4 @@ pub async fn delete(body: Value) -> CustomizeResponder<HttpResponse> {
             .body("{\"error\": true, \"status\": \"missing_args\"}")
             .customize();
     }
-}
\ No newline at end of file
+}
@@ -2,7 +2,7 @@
 use actix_web::{CustomizeResponder, HttpResponse, Responder};
 use serde_json::{json, Value};
 use crate::helper::functions::extract_string_from_obj_value;
-use crate::helper::database::{Mission, ValeurMetier, BienSupport};
+use crate::helper::database::{Mission, ValeurMetier, AssetLink};
 
 
 pub async fn create(body:Value) -> CustomizeResponder<HttpResponse> {
@@ -72,6 +72,68 @@ pub async fn create(body:Value) -> CustomizeResponder<HttpResponse> {
 }
 
 
+pub async fn update(body:Value) -> CustomizeResponder<HttpResponse> {
+
+    // check the body contain good key
+    for key in ["vm_id", "name", "description", "source", "owner"].iter() {
+        if body.get(key).is_some() {
+            continue;
+        } else {
+            return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"missing_args\"}").customize();
+        }
+    }
+
+    let vm_id = extract_string_from_obj_value(body.get("vm_id"));
+    let m_name = extract_string_from_obj_value(body.get("name"));
+    let m_description = extract_string_from_obj_value(body.get("description"));
+    let m_source = extract_string_from_obj_value(body.get("source"));
+    let m_owner = extract_string_from_obj_value(body.get("owner"));
+
+    // convert vm id to i32
+    let vm_id = match vm_id.parse::<i32>() {
+        Ok(v) => v,
+        Err(_) => {
+            return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"vm_id_not_valid\"}").customize();
+        }
+    };
+
+    // check vm exist
+    if ValeurMetier::c1_get_valermetier_by_id(vm_id).await.is_empty() {
+        return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"vm_not_found\"}").customize();
+    }
+
+    // check if name < 255 char
+    if m_name.len() > 255 {
+        return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"name_too_long\"}").customize();
+    }
+
+    // check description < 1000 char
+    if m_description.len() > 1000 {
+        return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"description_too_long\"}").customize();
+    }
+
+    // check m_source is "processus" of "information"
+    if m_source != "processus" && m_source != "information" {
+        return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"source_not_valid\"}").customize();
+    }
+
+    // check m_owner is a valid String
+    if m_owner.len() > 255 {
+        return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"owner_too_long\"}").customize();
+    }
+
+    // replace ' by \' for all
+    let m_owner = m_owner.replace("'", "\\'");
+    let m_source = m_source.replace("'", "\\'");
+    let m_description = m_description.replace("'", "\\'");
+    let m_name = m_name.replace("'", "\\'");
+
+    let _ = ValeurMetier::c1_update_valeurmetier(vm_id, m_name, m_source, m_description, m_owner).await;
+
+    HttpResponse::Ok().content_type("application/json").body(json!({"status": "success"}).to_string()).customize()
+}
+
+
 pub async fn delete(body:Value) -> CustomizeResponder<HttpResponse> {
 
     // check the body contain good key
@@ -93,10 +155,8 @@ pub async fn delete(body:Value) -> CustomizeResponder<HttpResponse> {
         }
     };
 
-    let all_asset = BienSupport::c1_get_asset_by_vmid(vm_id).await;
-    for a in all_asset {
-        let _ = BienSupport::c1_delete_asset_by_id(a.support_id).await;
-    }
+    // supporting assets can be shared, only the links to this value are dropped
+    let _ = AssetLink::c1_unlink_vm(vm_id).await;
 
     let _ = ValeurMetier::c1_delete_vm_by_id(vm_id).await;
 
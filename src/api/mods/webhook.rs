@@ -0,0 +1,94 @@
+use actix_web::{CustomizeResponder, HttpResponse, Responder};
+use serde_json::{json, Value};
+use crate::helper::functions::{extract_string_from_obj_value, sql_escape};
+use crate::helper::database::Webhook;
+use crate::helper::webhook::{enabled, generate_secret, is_event_type, test_fire};
+
+/// Register a URL for an event type, the secret is generated when empty
+pub async fn create(body:Value) -> CustomizeResponder<HttpResponse> {
+
+    // check the body contain good key
+    for key in ["url", "event_type"] {
+        if body.get(key).is_some() {
+            continue;
+        } else {
+            return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"missing_args\"}").customize();
+        }
+    }
+
+    let url = extract_string_from_obj_value(body.get("url"));
+    let event_type = extract_string_from_obj_value(body.get("event_type"));
+    let secret = extract_string_from_obj_value(body.get("secret"));
+
+    let url = match reqwest::Url::parse(url.trim()) {
+        Ok(url) if url.scheme() == "http" || url.scheme() == "https" => url.to_string(),
+        _ => {
+            return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"invalid_url\"}").customize();
+        }
+    };
+
+    if url.len() > 2048 {
+        return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"url_too_long\"}").customize();
+    }
+
+    if !is_event_type(&event_type) {
+        return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"invalid_event_type\"}").customize();
+    }
+
+    if secret.len() > 128 {
+        return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"secret_too_long\"}").customize();
+    }
+
+    let secret = if secret.trim().is_empty() { generate_secret() } else { secret.trim().to_string() };
+
+    // sql format to cancel sql injection
+    let url = sql_escape(&url);
+    let secret = sql_escape(&secret);
+
+    if Webhook::create_webhook(url, event_type, secret).await.is_err() {
+        return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"database_error\"}").customize();
+    }
+
+    HttpResponse::Ok().content_type("application/json").body(json!({"status": "success"}).to_string()).customize()
+}
+
+/// Read the `webhook_id` of the body
+async fn read_webhook(body: &Value) -> Result<Webhook, &'static str> {
+    if body.get("webhook_id").is_none() {
+        return Err("missing_args");
+    }
+
+    let webhook_id = extract_string_from_obj_value(body.get("webhook_id")).parse::<i32>().map_err(|_| "invalid_webhook_id")?;
+    Webhook::get_webhook(webhook_id).await.pop().ok_or("webhook_not_found")
+}
+
+pub async fn delete(body:Value) -> CustomizeResponder<HttpResponse> {
+    let webhook = match read_webhook(&body).await {
+        Ok(webhook) => webhook,
+        Err(e) => {
+            return HttpResponse::Ok().content_type("application/json").body(json!({"error": true, "status": e}).to_string()).customize();
+        }
+    };
+
+    let _ = Webhook::delete_webhook(webhook.webhook_id).await;
+
+    HttpResponse::Ok().content_type("application/json").body(json!({"status": "success"}).to_string()).customize()
+}
+
+/// Queue the test event for a webhook, whatever its event type
+pub async fn test(body:Value) -> CustomizeResponder<HttpResponse> {
+    let webhook = match read_webhook(&body).await {
+        Ok(webhook) => webhook,
+        Err(e) => {
+            return HttpResponse::Ok().content_type("application/json").body(json!({"error": true, "status": e}).to_string()).customize();
+        }
+    };
+
+    if !enabled() {
+        return HttpResponse::Ok().content_type("application/json").body("{\"error\": true, \"status\": \"webhooks_disabled\"}").customize();
+    }
+
+    test_fire(&webhook).await;
+
+    HttpResponse::Ok().content_type("application/json").body(json!({"status": "success"}).to_string()).customize()
+}
@@ -0,0 +1,85 @@
+// backups of the database, a JSON copy of every table of db.json written by
+// the scheduler
+use std::fs;
+use std::path::Path;
+
+use chrono::Utc;
+use serde_json::{json, Map, Value};
+
+use crate::helper::config::config;
+use crate::helper::database::open_backend;
+use crate::helper::storage::{dialect, Conn, SqlValue};
+
+/// Prefix of the files of `backup_dir`, only these are ever removed
+pub const FILE_PREFIX: &str = "matryriska-";
+
+fn to_json(value: SqlValue) -> Value {
+    match value {
+        SqlValue::Null => Value::Null,
+        SqlValue::Int(i) => json!(i),
+        SqlValue::Float(f) => json!(f),
+        SqlValue::Text(s) => json!(s),
+    }
+}
+
+/// Every table of `db.json`, `{"tables": {"risk": [{"risk_uuid": ...}]}}`
+pub fn dump(source: &mut Conn) -> Result<Value, String> {
+    let file = fs::read_to_string("assets/_internals/db.json").map_err(|e| e.to_string())?;
+    let tables: Vec<Value> = serde_json::from_str(&file).map_err(|e| e.to_string())?;
+
+    let mut dumped = Map::new();
+    for table in tables.iter() {
+        let table_name = table["name"].as_str().unwrap_or("");
+        let names = table["columns"].as_array().cloned().unwrap_or_default().iter()
+            .map(|c| c["name"].as_str().unwrap_or("").to_string())
+            .collect::<Vec<String>>();
+
+        let rows = source.query_map(format!("SELECT {} FROM {}", names.join(", "), table_name), |row: Vec<SqlValue>| row)
+            .map_err(|e| format!("{}: {}", table_name, e))?;
+        let rows = rows.into_iter()
+            .map(|row| Value::Object(names.iter().cloned().zip(row.into_iter().map(to_json)).collect()))
+            .collect::<Vec<Value>>();
+        dumped.insert(table_name.to_string(), Value::Array(rows));
+    }
+
+    Ok(json!({
+        "backend": config().db_backend,
+        "created_at": Utc::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        "tables": dumped,
+    }))
+}
+
+/// Write a backup of the configured database in `dir`, then remove the
+/// oldest ones beyond `keep`. Returns the path of the backup
+pub fn write_backup(dir: &str, keep: u32) -> Result<String, String> {
+    let mut source = open_backend(dialect())?.get_conn()?;
+    let backup = dump(&mut source)?;
+
+    fs::create_dir_all(dir).map_err(|e| format!("{}: {}", dir, e))?;
+    let path = Path::new(dir).join(format!("{}{}.json", FILE_PREFIX, Utc::now().format("%Y%m%d-%H%M%S")));
+    fs::write(&path, backup.to_string()).map_err(|e| format!("{}: {}", path.display(), e))?;
+
+    prune(dir, keep)?;
+    Ok(path.display().to_string())
+}
+
+/// Remove the oldest backups of `dir` beyond `keep`, 0 keeps them all. The
+/// names sort by date
+pub fn prune(dir: &str, keep: u32) -> Result<usize, String> {
+    if keep == 0 {
+        return Ok(0);
+    }
+
+    let mut backups = fs::read_dir(dir).map_err(|e| format!("{}: {}", dir, e))?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.file_name().and_then(|n| n.to_str()).is_some_and(|n| n.starts_with(FILE_PREFIX) && n.ends_with(".json")))
+        .collect::<Vec<_>>();
+    backups.sort();
+
+    let removed = backups.len().saturating_sub(keep as usize);
+    for path in backups.iter().take(removed) {
+        fs::remove_file(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    }
+    Ok(removed)
+}
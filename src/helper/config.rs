@@ -0,0 +1,515 @@
+// the configuration, loaded once at startup from the defaults, the config
+// file, the MATRYRISKA_* environment variables and the command line flags
+use std::fs;
+
+use once_cell::sync::OnceCell;
+use chrono::Weekday;
+use lettre::message::Mailbox;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::helper::i18n::{is_locale, LOCALE_DIR, SOURCE_LOCALE};
+use crate::helper::functions::RISK_LEVELS;
+
+/// The config file when `--config` is not given
+pub const CONFIG_FILE: &str = "config/default.json";
+
+/// Prefix of the environment variables, `MATRYRISKA_DB_HOST` sets `db_host`
+pub const ENV_PREFIX: &str = "MATRYRISKA_";
+
+/// Flags of the command line that are not config keys, with whether they
+/// take a value
+const OTHER_FLAGS: [(&str, bool); 5] = [
+    ("--config", true),
+    ("--print-config", false),
+    ("--migrate-to-postgres", true),
+    // no effect anymore, kept for the existing command lines
+    ("--prod", false),
+    // same as `--webhooks true`
+    ("--webhook", false),
+];
+
+pub const DB_BACKENDS: [&str; 3] = ["mysql", "postgres", "sqlite"];
+
+pub const SAME_SITE: [&str; 3] = ["Strict", "Lax", "None"];
+
+/// How the connection to the SMTP server is secured: STARTTLS on the
+/// submission port, TLS from the start, or plain text for a local relay
+pub const SMTP_TLS: [&str; 3] = ["starttls", "tls", "none"];
+
+/// Shown instead of the secrets by `--print-config`
+const REDACTED: &str = "********";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// `mysql`, `postgres` or `sqlite`
+    pub db_backend: String,
+    pub db_host: String,
+    pub db_port: u16,
+    pub db_username: String,
+    pub db_password: String,
+    /// SQLite file, relative to the working directory
+    pub db_path: String,
+    pub web_port: u16,
+    /// language of the UI when the browser asks for none of the locales
+    pub default_locale: String,
+    /// deliver the webhooks, see helper/webhook.rs
+    pub webhooks: bool,
+    pub c3_radar: RadarConfig,
+    pub risk_acceptance: AcceptanceConfig,
+    pub security: SecurityConfig,
+    pub smtp: SmtpConfig,
+    pub scheduler: SchedulerConfig,
+}
+
+/// Threat level thresholds of the radar zones, from the center out
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RadarConfig {
+    pub danger: f64,
+    pub control: f64,
+    pub watch: f64,
+}
+
+/// Who may accept which level
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AcceptanceConfig {
+    /// highest `calculate_risk` level any user may accept
+    pub max_level: String,
+    /// users holding the elevated role, they may accept any level
+    pub elevated_users: Vec<String>,
+    /// secret an elevated user gives to sign off above `max_level`, the
+    /// names alone prove nothing; no elevated sign off when empty
+    pub elevation_token: String,
+}
+
+/// Who may call the API, and the flags of the cookies
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SecurityConfig {
+    /// other sites allowed to call the API, `https://intranet.example.org`
+    pub allowed_origins: Vec<String>,
+    /// `Strict`, `Lax` or `None`
+    pub cookie_same_site: String,
+    /// only send the cookies over HTTPS
+    pub cookie_secure: bool,
+}
+
+/// The SMTP server of the email notifications, see helper/notification.rs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SmtpConfig {
+    /// no email is sent when empty
+    pub host: String,
+    pub port: u16,
+    pub username: String,
+    pub password: String,
+    /// one of `SMTP_TLS`
+    pub tls: String,
+    /// sender of the emails, `MatryRiska <matryriska@example.org>`
+    pub from: String,
+    /// address of MatryRiska in the links of the emails
+    pub base_url: String,
+    /// day of the weekly digest, `monday` to `sunday`
+    pub digest_day: String,
+    /// hour of the weekly digest, in the time of the server
+    pub digest_hour: u32,
+}
+
+/// The recurring jobs that can be tuned, see helper/scheduler.rs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SchedulerConfig {
+    /// hours between two backups of the database, 0 for none
+    pub backup_hours: u32,
+    /// directory of the backups, relative to the working directory
+    pub backup_dir: String,
+    /// backups kept in `backup_dir`, the oldest are removed, 0 keeps them all
+    pub backup_keep: u32,
+    /// hours between two snapshots of the study, 0 for none
+    pub snapshot_hours: u32,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            db_backend: "mysql".to_string(),
+            db_host: "127.0.0.1".to_string(),
+            db_port: 3306,
+            db_username: "matryriska".to_string(),
+            db_password: String::new(),
+            db_path: "data/matryriska.db".to_string(),
+            web_port: 8080,
+            default_locale: SOURCE_LOCALE.to_string(),
+            webhooks: false,
+            c3_radar: RadarConfig::default(),
+            risk_acceptance: AcceptanceConfig::default(),
+            security: SecurityConfig::default(),
+            smtp: SmtpConfig::default(),
+            scheduler: SchedulerConfig::default(),
+        }
+    }
+}
+
+impl Default for RadarConfig {
+    fn default() -> Self {
+        RadarConfig { danger: 2.5, control: 0.9, watch: 0.2 }
+    }
+}
+
+impl Default for AcceptanceConfig {
+    fn default() -> Self {
+        AcceptanceConfig { max_level: "MEDIUM".to_string(), elevated_users: Vec::new(), elevation_token: String::new() }
+    }
+}
+
+impl Default for SecurityConfig {
+    fn default() -> Self {
+        SecurityConfig { allowed_origins: Vec::new(), cookie_same_site: "Strict".to_string(), cookie_secure: false }
+    }
+}
+
+impl Default for SmtpConfig {
+    fn default() -> Self {
+        SmtpConfig {
+            host: String::new(),
+            port: 587,
+            username: String::new(),
+            password: String::new(),
+            tls: "starttls".to_string(),
+            from: "MatryRiska <matryriska@localhost>".to_string(),
+            base_url: "http://localhost:8080".to_string(),
+            digest_day: "monday".to_string(),
+            digest_hour: 8,
+        }
+    }
+}
+
+impl Default for SchedulerConfig {
+    fn default() -> Self {
+        SchedulerConfig { backup_hours: 24, backup_dir: "backups".to_string(), backup_keep: 7, snapshot_hours: 0 }
+    }
+}
+
+static CONFIG: OnceCell<Config> = OnceCell::new();
+
+/// The loaded config; outside of the server (tests, tools) it is loaded on
+/// first use, the defaults standing in for an invalid config
+pub fn config() -> &'static Config {
+    CONFIG.get_or_init(|| Config::load(&std::env::args().collect::<Vec<String>>()).unwrap_or_default())
+}
+
+/// Keep the config loaded by the server, before anything reads it
+pub fn init(config: Config) {
+    let _ = CONFIG.set(config);
+}
+
+/// value of a flag of the command line, `--key value` or `--key=value`
+pub fn flag_value(args: &[String], flag: &str) -> Option<String> {
+    let prefix = format!("{}=", flag);
+    for (i, arg) in args.iter().enumerate() {
+        if arg == flag {
+            return args.get(i + 1).cloned();
+        }
+        if let Some(value) = arg.strip_prefix(&prefix) {
+            return Some(value.to_string());
+        }
+    }
+    None
+}
+
+impl Config {
+    /// The keys that can be set one by one, from the environment or the
+    /// command line; the nested ones are joined with a dot
+    pub const KEYS: [&'static str; 31] = [
+        "db_backend",
+        "db_host",
+        "db_port",
+        "db_username",
+        "db_password",
+        "db_path",
+        "web_port",
+        "default_locale",
+        "webhooks",
+        "c3_radar.danger",
+        "c3_radar.control",
+        "c3_radar.watch",
+        "risk_acceptance.max_level",
+        "risk_acceptance.elevated_users",
+        "risk_acceptance.elevation_token",
+        "security.allowed_origins",
+        "security.cookie_same_site",
+        "security.cookie_secure",
+        "smtp.host",
+        "smtp.port",
+        "smtp.username",
+        "smtp.password",
+        "smtp.tls",
+        "smtp.from",
+        "smtp.base_url",
+        "smtp.digest_day",
+        "smtp.digest_hour",
+        "scheduler.backup_hours",
+        "scheduler.backup_dir",
+        "scheduler.backup_keep",
+        "scheduler.snapshot_hours",
+    ];
+
+    /// `MATRYRISKA_C3_RADAR_DANGER` for `c3_radar.danger`
+    pub fn env_name(key: &str) -> String {
+        format!("{}{}", ENV_PREFIX, key.replace('.', "_").to_uppercase())
+    }
+
+    /// `--c3-radar-danger` for `c3_radar.danger`
+    pub fn flag_name(key: &str) -> String {
+        format!("--{}", key.replace(['.', '_'], "-"))
+    }
+
+    /// Defaults, then the config file, then the environment (and `.env`),
+    /// then the flags of `args`. Every problem found is returned
+    pub fn load(args: &[String]) -> Result<Config, Vec<String>> {
+        let mut errors = Vec::new();
+
+        // the file is optional, unless given with --config
+        let path = flag_value(args, "--config");
+        let mut config = match fs::read_to_string(path.as_deref().unwrap_or(CONFIG_FILE)) {
+            Ok(content) => match serde_json::from_str::<Config>(&content) {
+                Ok(config) => config,
+                Err(e) => {
+                    errors.push(format!("{}: {}", path.as_deref().unwrap_or(CONFIG_FILE), e));
+                    Config::default()
+                }
+            },
+            Err(e) => {
+                if let Some(path) = path.as_deref() {
+                    errors.push(format!("{}: {}", path, e));
+                }
+                Config::default()
+            }
+        };
+
+        let _ = dotenvy::dotenv();
+        for key in Config::KEYS {
+            if let Ok(value) = std::env::var(Config::env_name(key)) {
+                if let Err(e) = config.set(key, &value) {
+                    errors.push(format!("{}: {}", Config::env_name(key), e));
+                }
+            }
+        }
+
+        errors.extend(config.apply_flags(args));
+        if args.iter().any(|arg| arg == "--webhook") {
+            config.webhooks = true;
+        }
+        errors.extend(config.validate());
+
+        if errors.is_empty() { Ok(config) } else { Err(errors) }
+    }
+
+    fn apply_flags(&mut self, args: &[String]) -> Vec<String> {
+        let mut errors = Vec::new();
+
+        let mut i = 1;
+        while i < args.len() {
+            let (flag, inline) = match args[i].split_once('=') {
+                Some((flag, value)) => (flag, Some(value.to_string())),
+                None => (args[i].as_str(), None),
+            };
+            i += 1;
+
+            if let Some((_, takes_value)) = OTHER_FLAGS.iter().find(|(f, _)| *f == flag) {
+                if *takes_value && inline.is_none() {
+                    i += 1;
+                }
+                continue;
+            }
+
+            let key = match Config::KEYS.iter().find(|k| Config::flag_name(k) == flag) {
+                Some(key) => key,
+                None => {
+                    errors.push(format!("{}: unknown flag", flag));
+                    continue;
+                }
+            };
+            let value = match inline {
+                Some(value) => value,
+                None => match args.get(i) {
+                    Some(value) => {
+                        i += 1;
+                        value.clone()
+                    }
+                    None => {
+                        errors.push(format!("{}: missing value", flag));
+                        continue;
+                    }
+                },
+            };
+
+            if let Err(e) = self.set(key, &value) {
+                errors.push(format!("{}: {}", flag, e));
+            }
+        }
+
+        errors
+    }
+
+    /// Set a key from a text value, a list is comma separated
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        let port = |value: &str| value.trim().parse::<u16>().map_err(|_| format!("{} is not a port number", value));
+        let count = |value: &str| value.trim().parse::<u32>().map_err(|_| format!("{} is not a positive whole number", value));
+        let number = |value: &str| value.trim().parse::<f64>().map_err(|_| format!("{} is not a number", value));
+        let boolean = |value: &str| match value.trim().to_lowercase().as_str() {
+            "true" | "1" | "yes" | "on" => Ok(true),
+            "false" | "0" | "no" | "off" => Ok(false),
+            _ => Err(format!("{} is not true or false", value)),
+        };
+
+        match key {
+            "db_backend" => self.db_backend = value.trim().to_lowercase(),
+            "db_host" => self.db_host = value.trim().to_string(),
+            "db_port" => self.db_port = port(value)?,
+            "db_username" => self.db_username = value.to_string(),
+            "db_password" => self.db_password = value.to_string(),
+            "db_path" => self.db_path = value.to_string(),
+            "web_port" => self.web_port = port(value)?,
+            "default_locale" => self.default_locale = value.trim().to_lowercase(),
+            "webhooks" => self.webhooks = boolean(value)?,
+            "c3_radar.danger" => self.c3_radar.danger = number(value)?,
+            "c3_radar.control" => self.c3_radar.control = number(value)?,
+            "c3_radar.watch" => self.c3_radar.watch = number(value)?,
+            "risk_acceptance.max_level" => self.risk_acceptance.max_level = value.trim().to_uppercase(),
+            "risk_acceptance.elevated_users" => {
+                self.risk_acceptance.elevated_users = value.split(',').map(|u| u.trim().to_string()).filter(|u| !u.is_empty()).collect();
+            }
+            "risk_acceptance.elevation_token" => self.risk_acceptance.elevation_token = value.trim().to_string(),
+            "security.allowed_origins" => {
+                self.security.allowed_origins = value.split(',').map(|o| o.trim().to_string()).filter(|o| !o.is_empty()).collect();
+            }
+            "security.cookie_same_site" => self.security.cookie_same_site = value.trim().to_string(),
+            "security.cookie_secure" => self.security.cookie_secure = boolean(value)?,
+            "smtp.host" => self.smtp.host = value.trim().to_string(),
+            "smtp.port" => self.smtp.port = port(value)?,
+            "smtp.username" => self.smtp.username = value.to_string(),
+            "smtp.password" => self.smtp.password = value.to_string(),
+            "smtp.tls" => self.smtp.tls = value.trim().to_lowercase(),
+            "smtp.from" => self.smtp.from = value.trim().to_string(),
+            "smtp.base_url" => self.smtp.base_url = value.trim().trim_end_matches('/').to_string(),
+            "smtp.digest_day" => self.smtp.digest_day = value.trim().to_lowercase(),
+            "smtp.digest_hour" => self.smtp.digest_hour = value.trim().parse::<u32>().map_err(|_| format!("{} is not an hour", value))?,
+            "scheduler.backup_hours" => self.scheduler.backup_hours = count(value)?,
+            "scheduler.backup_dir" => self.scheduler.backup_dir = value.trim().to_string(),
+            "scheduler.backup_keep" => self.scheduler.backup_keep = count(value)?,
+            "scheduler.snapshot_hours" => self.scheduler.snapshot_hours = count(value)?,
+            _ => return Err(format!("unknown key {}", key)),
+        }
+        Ok(())
+    }
+
+    /// What is wrong in the config, empty when it can be used
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+
+        if !DB_BACKENDS.contains(&self.db_backend.as_str()) {
+            errors.push(format!("db_backend: {} is not one of {}", self.db_backend, DB_BACKENDS.join(", ")));
+        }
+        if self.db_backend == "sqlite" {
+            if self.db_path.trim().is_empty() {
+                errors.push("db_path: the SQLite file is required".to_string());
+            }
+        } else {
+            if self.db_host.is_empty() {
+                errors.push("db_host: the database host is required".to_string());
+            }
+            if self.db_port == 0 {
+                errors.push("db_port: must be between 1 and 65535".to_string());
+            }
+            if self.db_username.trim().is_empty() {
+                errors.push("db_username: the database user is required".to_string());
+            }
+        }
+        if self.web_port == 0 {
+            errors.push("web_port: must be between 1 and 65535".to_string());
+        }
+
+        if !is_locale(&self.default_locale) {
+            errors.push(format!("default_locale: {} has no catalogue in {}/", self.default_locale, LOCALE_DIR));
+        }
+
+        let radar = &self.c3_radar;
+        if !(radar.danger > radar.control && radar.control > radar.watch && radar.watch > 0.0) {
+            errors.push(format!(
+                "c3_radar: the zones must be nested, danger > control > watch > 0 (got {}, {}, {})",
+                radar.danger, radar.control, radar.watch
+            ));
+        }
+
+        if !RISK_LEVELS.contains(&self.risk_acceptance.max_level.to_uppercase().as_str()) {
+            errors.push(format!(
+                "risk_acceptance.max_level: {} is not one of {}",
+                self.risk_acceptance.max_level, RISK_LEVELS.join(", ")
+            ));
+        }
+
+        let valid = Regex::new(r"^https?://[A-Za-z0-9.-]+(:[0-9]+)?$").unwrap();
+        for origin in self.security.allowed_origins.iter() {
+            if !valid.is_match(origin) {
+                errors.push(format!("security.allowed_origins: {} is not an origin, like https://intranet.example.org", origin));
+            }
+        }
+        if !SAME_SITE.iter().any(|s| s.eq_ignore_ascii_case(&self.security.cookie_same_site)) {
+            errors.push(format!(
+                "security.cookie_same_site: {} is not one of {}",
+                self.security.cookie_same_site, SAME_SITE.join(", ")
+            ));
+        }
+        // browsers drop a SameSite=None cookie that is not Secure
+        if self.security.cookie_same_site.eq_ignore_ascii_case("none") && !self.security.cookie_secure {
+            errors.push("security.cookie_same_site: None requires security.cookie_secure".to_string());
+        }
+
+        let smtp = &self.smtp;
+        if !SMTP_TLS.contains(&smtp.tls.as_str()) {
+            errors.push(format!("smtp.tls: {} is not one of {}", smtp.tls, SMTP_TLS.join(", ")));
+        }
+        if !smtp.host.is_empty() {
+            if smtp.port == 0 {
+                errors.push("smtp.port: must be between 1 and 65535".to_string());
+            }
+            if smtp.from.parse::<Mailbox>().is_err() {
+                errors.push(format!("smtp.from: {} is not an email address, like MatryRiska <matryriska@example.org>", smtp.from));
+            }
+        }
+        if !Regex::new(r"^https?://[^\s/]+(/\S*)?$").unwrap().is_match(&smtp.base_url) {
+            errors.push(format!("smtp.base_url: {} is not an address, like https://matryriska.example.org", smtp.base_url));
+        }
+        if smtp.digest_day.parse::<Weekday>().is_err() {
+            errors.push(format!("smtp.digest_day: {} is not a day of the week", smtp.digest_day));
+        }
+        if smtp.digest_hour > 23 {
+            errors.push(format!("smtp.digest_hour: {} is not between 0 and 23", smtp.digest_hour));
+        }
+
+        if self.scheduler.backup_hours > 0 && self.scheduler.backup_dir.is_empty() {
+            errors.push("scheduler.backup_dir: the directory of the backups is required".to_string());
+        }
+
+        errors
+    }
+
+    /// A copy safe to print, the secrets replaced
+    pub fn redacted(&self) -> Config {
+        let mut config = self.clone();
+        if !config.db_password.is_empty() {
+            config.db_password = REDACTED.to_string();
+        }
+        if !config.risk_acceptance.elevation_token.is_empty() {
+            config.risk_acceptance.elevation_token = REDACTED.to_string();
+        }
+        if !config.smtp.password.is_empty() {
+            config.smtp.password = REDACTED.to_string();
+        }
+        config
+    }
+}
@@ -1,13 +1,14 @@
 use crate::helper::trace::trace_logs;
-use mysql::prelude::*;
-use mysql::*;
-use std::fs;
+use crate::helper::config::config;
+use crate::helper::storage::{dialect, Backend, Dialect};
+use crate::helper::storage::mysql::MysqlBackend;
+use crate::helper::storage::postgres::PostgresBackend;
+use crate::helper::storage::sqlite::SqliteBackend;
 use std::result::Result;
+use serde::Serialize;
 use uuid::Uuid;
 
 use std::sync::{Arc, Mutex};
-use std::{sync::mpsc, thread};
-use tokio::time::{interval, Duration};
 
 use once_cell::sync::Lazy;
 
@@ -17,25 +18,71 @@ use once_cell::sync::Lazy;
 //  |    -|-   -|__   |    -|
 //  |__|__|_____|_____|__|__|
 //
-#[derive(Debug, FromRow, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Risk {
     pub risk_uuid: Uuid,
     pub risk_name: String,
     pub risk_description: String,
+    pub risk_owner: String,
+    pub risk_category: String,
+    pub risk_status: String,
+    /// `YYYY-MM-DD HH:MM`
+    pub risk_created_at: String,
+    /// `YYYY-MM-DD HH:MM`, empty until the first review
+    pub risk_reviewed_at: String,
+    /// `YYYY-MM-DD`, empty when no review is planned
+    pub risk_next_review: String,
 }
 
+fn risk_columns() -> String {
+    let d = dialect();
+    format!(
+        "risk_uuid, risk_name, risk_description, risk_owner, risk_category, risk_status, {}, COALESCE({}, ''), COALESCE({}, '')",
+        d.date_format("risk_created_at", "%Y-%m-%d %H:%i"), d.date_format("risk_reviewed_at", "%Y-%m-%d %H:%i"), d.date_format("risk_next_review", "%Y-%m-%d")
+    )
+}
+
+type RiskRow = (String, String, String, String, String, String, String, String, String);
+
 impl Risk {
     pub fn default() -> Self {
         Risk {
             risk_uuid: Uuid::nil(),
             risk_name: String::new(),
             risk_description: String::new(),
+            risk_owner: String::new(),
+            risk_category: String::new(),
+            risk_status: "identified".to_string(),
+            risk_created_at: String::new(),
+            risk_reviewed_at: String::new(),
+            risk_next_review: String::new(),
+        }
+    }
+
+    fn from_row(
+        (risk_uuid, risk_name, risk_description, risk_owner, risk_category, risk_status, risk_created_at, risk_reviewed_at, risk_next_review): RiskRow,
+    ) -> Risk {
+        Risk {
+            risk_uuid: Uuid::parse_str(&risk_uuid).unwrap(),
+            risk_name,
+            risk_description,
+            risk_owner,
+            risk_category,
+            risk_status,
+            risk_created_at,
+            risk_reviewed_at,
+            risk_next_review,
         }
     }
 
+    /// a closed risk is no longer reviewed, `today` is a `YYYY-MM-DD` date
+    pub fn is_review_overdue(&self, today: &str) -> bool {
+        self.risk_status != "closed" && !self.risk_next_review.is_empty() && self.risk_next_review.as_str() < today
+    }
+
     pub async fn select_all_risk() -> Vec<Risk> {
         // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
-        let lock_result = unsafe { DB_CLIENT.lock() };
+        let lock_result = DB_CLIENT.lock();
 
         if lock_result.is_err() {
             // kill script
@@ -49,7 +96,7 @@ impl Risk {
         }
 
         // perform database operations
-        let db_client = unsafe { DB_CLIENT.lock().unwrap() };
+        let db_client = DB_CLIENT.lock().unwrap();
 
         let db_client = db_client.as_ref();
 
@@ -57,15 +104,11 @@ impl Risk {
 
         if let Some(pool) = db_client {
             let mut conn = pool.get_conn().unwrap();
-            let query = format!("SELECT * FROM risk ORDER BY risk_name ASC");
+            let query = format!("SELECT {} FROM risk ORDER BY risk_name ASC", risk_columns());
 
             let result = conn.query_map(
                 query,
-                |(risk_uuid, risk_name, risk_description): (String, String, String)| Risk {
-                    risk_uuid: Uuid::parse_str(&risk_uuid).unwrap(),
-                    risk_name,
-                    risk_description,
-                },
+                Risk::from_row,
             );
 
             // check how many rows are returned
@@ -90,9 +133,13 @@ impl Risk {
     pub async fn create_new_risk(
         risk_name: String,
         risk_description: String,
-    ) -> Result<(), String> {
+        risk_owner: String,
+        risk_category: String,
+        risk_status: String,
+        risk_next_review: String,
+    ) -> Result<String, String> {
         // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
-        let lock_result = unsafe { DB_CLIENT.lock() };
+        let lock_result = DB_CLIENT.lock();
 
         if lock_result.is_err() {
             // kill script
@@ -106,19 +153,23 @@ impl Risk {
         }
 
         // perform database operations
-        let db_client = unsafe { DB_CLIENT.lock().unwrap() };
+        let db_client = DB_CLIENT.lock().unwrap();
 
         let db_client = db_client.as_ref();
 
         if let Some(pool) = db_client {
             let mut conn = pool.get_conn().unwrap();
-            let query = format!("INSERT INTO risk (risk_uuid, risk_name, risk_description) VALUES ('{}', '{}', '{}')", Uuid::new_v4(), risk_name, risk_description);
+            let risk_uuid = Uuid::new_v4().to_string();
+            let query = format!(
+                "INSERT INTO risk (risk_uuid, risk_name, risk_description, risk_owner, risk_category, risk_status, risk_next_review) VALUES ('{}', '{}', '{}', '{}', '{}', '{}', {})",
+                risk_uuid, risk_name, risk_description, risk_owner, risk_category, risk_status, sql_date(&risk_next_review)
+            );
 
             let result = conn.query_drop(query);
 
             match result {
                 Ok(_) => {
-                    return Ok(());
+                    return Ok(risk_uuid);
                 }
                 Err(_) => {
                     return Err("Failed to insert new risk".to_owned());
@@ -131,7 +182,7 @@ impl Risk {
 
     pub async fn get_risk_detail(risk_uuid: String) -> Vec<Risk> {
         // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
-        let lock_result = unsafe { DB_CLIENT.lock() };
+        let lock_result = DB_CLIENT.lock();
 
         if lock_result.is_err() {
             // kill script
@@ -145,7 +196,7 @@ impl Risk {
         }
 
         // perform database operations
-        let db_client = unsafe { DB_CLIENT.lock().unwrap() };
+        let db_client = DB_CLIENT.lock().unwrap();
 
         let db_client = db_client.as_ref();
 
@@ -154,17 +205,13 @@ impl Risk {
         if let Some(pool) = db_client {
             let mut conn = pool.get_conn().unwrap();
             let query = format!(
-                "SELECT * FROM risk WHERE risk_uuid = '{}' ORDER BY risk_name ASC",
-                risk_uuid
+                "SELECT {} FROM risk WHERE risk_uuid = '{}' ORDER BY risk_name ASC",
+                risk_columns(), risk_uuid
             );
 
             let result = conn.query_map(
                 query,
-                |(risk_uuid, risk_name, risk_description): (String, String, String)| Risk {
-                    risk_uuid: Uuid::parse_str(&risk_uuid).unwrap(),
-                    risk_name,
-                    risk_description,
-                },
+                Risk::from_row,
             );
 
             // check how many rows are returned
@@ -190,9 +237,13 @@ impl Risk {
         risk_uuid: String,
         risk_name: String,
         risk_description: String,
+        risk_owner: String,
+        risk_category: String,
+        risk_status: String,
+        risk_next_review: String,
     ) -> Result<(), String> {
         // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
-        let lock_result = unsafe { DB_CLIENT.lock() };
+        let lock_result = DB_CLIENT.lock();
 
         if lock_result.is_err() {
             // kill script
@@ -206,15 +257,15 @@ impl Risk {
         }
 
         // perform database operations
-        let db_client = unsafe { DB_CLIENT.lock().unwrap() };
+        let db_client = DB_CLIENT.lock().unwrap();
 
         let db_client = db_client.as_ref();
 
         if let Some(pool) = db_client {
             let mut conn = pool.get_conn().unwrap();
             let query = format!(
-                "UPDATE risk SET risk_name = '{}', risk_description = '{}' WHERE risk_uuid = '{}'",
-                risk_name, risk_description, risk_uuid
+                "UPDATE risk SET risk_name = '{}', risk_description = '{}', risk_owner = '{}', risk_category = '{}', risk_status = '{}', risk_next_review = {} WHERE risk_uuid = '{}'",
+                risk_name, risk_description, risk_owner, risk_category, risk_status, sql_date(&risk_next_review), risk_uuid
             );
 
             let result = conn.query_drop(query);
@@ -232,9 +283,10 @@ impl Risk {
         return Err("No database connection".to_owned());
     }
 
-    pub async fn delete_risk(risk_uuid: String) -> Result<(), String> {
+    /// Record a review of the risk now, and plan the next one
+    pub async fn review_risk(risk_uuid: String, risk_next_review: String) -> Result<(), String> {
         // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
-        let lock_result = unsafe { DB_CLIENT.lock() };
+        let lock_result = DB_CLIENT.lock();
 
         if lock_result.is_err() {
             // kill script
@@ -248,13 +300,16 @@ impl Risk {
         }
 
         // perform database operations
-        let db_client = unsafe { DB_CLIENT.lock().unwrap() };
+        let db_client = DB_CLIENT.lock().unwrap();
 
         let db_client = db_client.as_ref();
 
         if let Some(pool) = db_client {
             let mut conn = pool.get_conn().unwrap();
-            let query = format!("DELETE FROM risk WHERE risk_uuid = '{}'", risk_uuid);
+            let query = format!(
+                "UPDATE risk SET risk_reviewed_at = CURRENT_TIMESTAMP, risk_next_review = {} WHERE risk_uuid = '{}'",
+                sql_date(&risk_next_review), risk_uuid
+            );
 
             let result = conn.query_drop(query);
 
@@ -263,524 +318,517 @@ impl Risk {
                     return Ok(());
                 }
                 Err(_) => {
-                    return Err("Failed to delete risk".to_owned());
+                    return Err("Failed to review risk".to_owned());
                 }
             }
         }
 
-        return Err("No database connection".to_owned());
-    }
-}
-
-
-//                                   
-//   _____                     _     
-//  |   __|___ ___ ___ ___ ___|_|___ 
-//  |__   |  _| -_|   | .'|  _| | . |
-//  |_____|___|___|_|_|__,|_| |_|___|
-//                                   
-#[derive(Debug, Clone, FromRow)]
-pub struct Scenario {
-    pub scenario_uuid: Uuid,
-    pub risk_uuid: Uuid,
-    pub scenario_description: String,
-    pub threat_description: String,
-    pub add_note: String,
-}
-
-impl Scenario {
-    pub fn default() -> Self {
-        Scenario {
-            scenario_uuid: Uuid::nil(),
-            risk_uuid: Uuid::nil(),
-            scenario_description: String::new(),
-            threat_description: String::new(),
-            add_note: String::new(),
-        }
+        Err("No database connection".to_owned())
     }
 
-    pub async fn get_all_scenario_of_risk(risk_uuid: String) -> Vec<Scenario> {
+    pub async fn set_risk_status(
+        risk_uuid: String,
+        risk_status: String,
+    ) -> Result<(), String> {
         // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
-        let lock_result = unsafe { DB_CLIENT.lock() };
-    
+        let lock_result = DB_CLIENT.lock();
+
         if lock_result.is_err() {
             // kill script
             trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
             std::process::exit(1);
         }
-    
+
         // check if need to create new client
         if lock_result.unwrap().is_none() {
             new_client().await;
         }
-    
+
         // perform database operations
-        let db_client = unsafe { DB_CLIENT.lock().unwrap() };
-    
+        let db_client = DB_CLIENT.lock().unwrap();
+
         let db_client = db_client.as_ref();
-    
-        let mut scenarios: Vec<Scenario> = Vec::new();
-    
+
         if let Some(pool) = db_client {
             let mut conn = pool.get_conn().unwrap();
             let query = format!(
-                "SELECT * FROM scenario WHERE risk_uuid = '{}' ORDER BY scenario_description ASC",
-                risk_uuid
-            );
-    
-            let result = conn.query_map(
-                query,
-                |(scenario_uuid, risk_uuid, scenario_description, threat_description, add_note): (
-                    String,
-                    String,
-                    String,
-                    String,
-                    String,
-                )| {
-                    Scenario {
-                        scenario_uuid: Uuid::parse_str(&scenario_uuid).unwrap(),
-                        risk_uuid: Uuid::parse_str(&risk_uuid).unwrap(),
-                        scenario_description,
-                        threat_description,
-                        add_note,
-                    }
-                },
+                "UPDATE risk SET risk_status = '{}' WHERE risk_uuid = '{}'",
+                risk_status, risk_uuid
             );
-    
-            // check how many rows are returned
+
+            let result = conn.query_drop(query);
+
             match result {
-                Ok(fetched_scenarios) => {
-                    for scenario in fetched_scenarios {
-                        scenarios.push(scenario);
-                    }
+                Ok(_) => {
+                    return Ok(());
                 }
                 Err(_) => {
-                    return scenarios;
+                    return Err("Failed to update risk status".to_owned());
                 }
             }
-    
-            return scenarios;
         }
-    
-        println!("No database connection");
-        return scenarios;
+
+        Err("No database connection".to_owned())
     }
-    
-    pub async fn get_scenario_detail(scenario_uuid: String) -> Vec<Scenario> {
+
+    pub async fn delete_risk(risk_uuid: String) -> Result<(), String> {
         // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
-        let lock_result = unsafe { DB_CLIENT.lock() };
-    
+        let lock_result = DB_CLIENT.lock();
+
         if lock_result.is_err() {
             // kill script
             trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
             std::process::exit(1);
         }
-    
+
         // check if need to create new client
         if lock_result.unwrap().is_none() {
             new_client().await;
         }
-    
+
         // perform database operations
-        let db_client = unsafe { DB_CLIENT.lock().unwrap() };
-    
+        let db_client = DB_CLIENT.lock().unwrap();
+
         let db_client = db_client.as_ref();
-    
-        let mut scenarios: Vec<Scenario> = Vec::new();
-    
+
         if let Some(pool) = db_client {
             let mut conn = pool.get_conn().unwrap();
-            let query = format!(
-                "SELECT * FROM scenario WHERE scenario_uuid = '{}' ORDER BY scenario_description ASC",
-                scenario_uuid
-            );
-    
-            let result = conn.query_map(
-                query,
-                |(scenario_uuid, risk_uuid, scenario_description, threat_description, add_note): (
-                    String,
-                    String,
-                    String,
-                    String,
-                    String,
-                )| {
-                    Scenario {
-                        scenario_uuid: Uuid::parse_str(&scenario_uuid).unwrap(),
-                        risk_uuid: Uuid::parse_str(&risk_uuid).unwrap(),
-                        scenario_description,
-                        threat_description,
-                        add_note,
-                    }
-                },
-            );
-    
-            // check how many rows are returned
+            let query = format!("DELETE FROM risk WHERE risk_uuid = '{}'", risk_uuid);
+
+            let result = conn.query_drop(query);
+
             match result {
-                Ok(fetched_scenarios) => {
-                    for scenario in fetched_scenarios {
-                        scenarios.push(scenario);
-                    }
+                Ok(_) => {
+                    return Ok(());
                 }
                 Err(_) => {
-                    return scenarios;
+                    return Err("Failed to delete risk".to_owned());
                 }
             }
-    
-            return scenarios;
         }
-    
-        println!("No database connection");
-        return scenarios;
+
+        return Err("No database connection".to_owned());
     }
-    
-    pub async fn create_new_scenario(
-        risk_uuid: String,
-        scenario_description: String,
-        threat_description: String,
-        add_note: String,
-    ) -> Uuid {
+}
+
+/// Formal acceptance of the residual level of a risk or of a scenario,
+/// signed off by a user until it expires or is revoked
+#[derive(Debug, Clone, Serialize)]
+pub struct RiskAcceptance {
+    pub acceptance_id: i32,
+    /// `risk` or `scenario`
+    pub target_type: String,
+    pub target_uuid: Uuid,
+    pub accepted_by: String,
+    pub justification: String,
+    /// `calculate_risk` level when the acceptance was signed off
+    pub residual_level: String,
+    /// `YYYY-MM-DD`
+    pub expires_on: String,
+    /// `YYYY-MM-DD HH:MM`
+    pub signed_off_at: String,
+    /// `YYYY-MM-DD HH:MM`, empty while the acceptance stands
+    pub revoked_at: String,
+    pub revoked_by: String,
+    pub revocation_reason: String,
+}
+
+fn acceptance_columns() -> String {
+    let d = dialect();
+    format!(
+        "acceptance_id, target_type, target_uuid, accepted_by, justification, residual_level, {}, {}, COALESCE({}, ''), revoked_by, COALESCE(revocation_reason, '')",
+        d.date_format("expires_on", "%Y-%m-%d"), d.date_format("signed_off_at", "%Y-%m-%d %H:%i"), d.date_format("revoked_at", "%Y-%m-%d %H:%i")
+    )
+}
+
+type RiskAcceptanceRow = (i32, String, String, String, String, String, String, String, String, String, String);
+
+impl RiskAcceptance {
+    fn from_row(
+        (acceptance_id, target_type, target_uuid, accepted_by, justification, residual_level, expires_on, signed_off_at, revoked_at, revoked_by, revocation_reason): RiskAcceptanceRow,
+    ) -> RiskAcceptance {
+        RiskAcceptance {
+            acceptance_id,
+            target_type,
+            target_uuid: Uuid::parse_str(&target_uuid).unwrap_or(Uuid::nil()),
+            accepted_by,
+            justification,
+            residual_level,
+            expires_on,
+            signed_off_at,
+            revoked_at,
+            revoked_by,
+            revocation_reason,
+        }
+    }
+
+    /// `signed_off`, `expired` or `revoked`, `today` is a `YYYY-MM-DD` date
+    pub fn state(&self, today: &str) -> &'static str {
+        if !self.revoked_at.is_empty() {
+            "revoked"
+        } else if self.expires_on.as_str() < today {
+            "expired"
+        } else {
+            "signed_off"
+        }
+    }
+
+    pub fn is_active(&self, today: &str) -> bool {
+        self.state(today) == "signed_off"
+    }
+
+    pub async fn get_all_acceptances() -> Vec<RiskAcceptance> {
         // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
-        let lock_result = unsafe { DB_CLIENT.lock() };
-    
+        let lock_result = DB_CLIENT.lock();
+
         if lock_result.is_err() {
             // kill script
             trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
             std::process::exit(1);
         }
-    
+
         // check if need to create new client
         if lock_result.unwrap().is_none() {
             new_client().await;
         }
-    
+
         // perform database operations
-        let db_client = unsafe { DB_CLIENT.lock().unwrap() };
-    
+        let db_client = DB_CLIENT.lock().unwrap();
+
         let db_client = db_client.as_ref();
-    
+
+        let mut acceptances: Vec<RiskAcceptance> = Vec::new();
+
         if let Some(pool) = db_client {
             let mut conn = pool.get_conn().unwrap();
-            let scuuid = Uuid::new_v4();
-            let query = format!("INSERT INTO scenario (scenario_uuid, risk_uuid, scenario_description, threat_description, add_note) VALUES ('{}', '{}', '{}', '{}', '{}')", scuuid, risk_uuid, scenario_description, threat_description, add_note);
-    
-            let result = conn.query_drop(query);
-    
+            let query = format!("SELECT {} FROM risk_acceptance ORDER BY signed_off_at DESC, acceptance_id DESC", acceptance_columns());
+
+            let result = conn.query_map(query, RiskAcceptance::from_row);
+
+            // check how many rows are returned
             match result {
-                Ok(_) => {
-                    return scuuid;
+                Ok(fetched_acceptances) => {
+                    for acceptance in fetched_acceptances {
+                        acceptances.push(acceptance);
+                    }
                 }
                 Err(_) => {
-                    return Uuid::nil();
+                    return acceptances;
                 }
             }
+
+            return acceptances;
         }
-    
-        return Uuid::nil();
+
+        println!("No database connection");
+        acceptances
     }
-    
-    pub async fn update_scenario(
-        scenario_uuid: String,
-        scenario_description: String,
-        threat_description: String,
-        add_note: String,
-    ) -> Result<(), String> {
+
+    pub async fn get_acceptances_of(target_uuid: String) -> Vec<RiskAcceptance> {
         // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
-        let lock_result = unsafe { DB_CLIENT.lock() };
-    
+        let lock_result = DB_CLIENT.lock();
+
         if lock_result.is_err() {
             // kill script
             trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
             std::process::exit(1);
         }
-    
+
         // check if need to create new client
         if lock_result.unwrap().is_none() {
             new_client().await;
         }
-    
+
         // perform database operations
-        let db_client = unsafe { DB_CLIENT.lock().unwrap() };
-    
+        let db_client = DB_CLIENT.lock().unwrap();
+
         let db_client = db_client.as_ref();
-    
+
+        let mut acceptances: Vec<RiskAcceptance> = Vec::new();
+
         if let Some(pool) = db_client {
             let mut conn = pool.get_conn().unwrap();
-            let query = format!("UPDATE scenario SET scenario_description = '{}', threat_description = '{}', add_note = '{}' WHERE scenario_uuid = '{}'", scenario_description, threat_description, add_note, scenario_uuid);
-    
-            let result = conn.query_drop(query);
-    
+            let query = format!("SELECT {} FROM risk_acceptance WHERE target_uuid = '{}' ORDER BY signed_off_at DESC, acceptance_id DESC", acceptance_columns(), target_uuid);
+
+            let result = conn.query_map(query, RiskAcceptance::from_row);
+
+            // check how many rows are returned
             match result {
-                Ok(_) => {
-                    return Ok(());
+                Ok(fetched_acceptances) => {
+                    for acceptance in fetched_acceptances {
+                        acceptances.push(acceptance);
+                    }
                 }
                 Err(_) => {
-                    return Err("Failed to update scenario".to_owned());
+                    return acceptances;
                 }
             }
+
+            return acceptances;
         }
-    
-        return Err("No database connection".to_owned());
+
+        println!("No database connection");
+        acceptances
     }
-    
-    pub async fn delete_scenario(scenario_uuid: String) -> Result<(), String> {
+
+    pub async fn get_acceptance(acceptance_id: i32) -> Vec<RiskAcceptance> {
         // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
-        let lock_result = unsafe { DB_CLIENT.lock() };
-    
+        let lock_result = DB_CLIENT.lock();
+
         if lock_result.is_err() {
             // kill script
             trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
             std::process::exit(1);
         }
-    
+
         // check if need to create new client
         if lock_result.unwrap().is_none() {
             new_client().await;
         }
-    
+
         // perform database operations
-        let db_client = unsafe { DB_CLIENT.lock().unwrap() };
-    
+        let db_client = DB_CLIENT.lock().unwrap();
+
         let db_client = db_client.as_ref();
-    
+
+        let mut acceptances: Vec<RiskAcceptance> = Vec::new();
+
         if let Some(pool) = db_client {
             let mut conn = pool.get_conn().unwrap();
-            let query = format!(
-                "DELETE FROM scenario WHERE scenario_uuid = '{}'",
-                scenario_uuid
-            );
-    
-            let result = conn.query_drop(query);
-    
+            let query = format!("SELECT {} FROM risk_acceptance WHERE acceptance_id = '{}' ORDER BY signed_off_at DESC, acceptance_id DESC", acceptance_columns(), acceptance_id);
+
+            let result = conn.query_map(query, RiskAcceptance::from_row);
+
+            // check how many rows are returned
             match result {
-                Ok(_) => {
-                    return Ok(());
+                Ok(fetched_acceptances) => {
+                    for acceptance in fetched_acceptances {
+                        acceptances.push(acceptance);
+                    }
                 }
                 Err(_) => {
-                    return Err("Failed to delete scenario".to_owned());
+                    return acceptances;
                 }
             }
+
+            return acceptances;
         }
-    
-        return Err("No database connection".to_owned());
+
+        println!("No database connection");
+        acceptances
     }
-    
-    pub async fn create_scenario_risk(
-        scenario_uuid: String,
-        likelihood: i32,
-        reputation: i32,
-        operational: i32,
-        legal_compliance: i32,
-        financial: i32,
+
+    pub async fn create_acceptance(
+        target_type: String,
+        target_uuid: String,
+        accepted_by: String,
+        justification: String,
+        residual_level: String,
+        expires_on: String,
     ) -> Result<(), String> {
         // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
-        let lock_result = unsafe { DB_CLIENT.lock() };
-    
+        let lock_result = DB_CLIENT.lock();
+
         if lock_result.is_err() {
             // kill script
             trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
             std::process::exit(1);
         }
-    
+
         // check if need to create new client
         if lock_result.unwrap().is_none() {
             new_client().await;
         }
-    
+
         // perform database operations
-        let db_client = unsafe { DB_CLIENT.lock().unwrap() };
-    
+        let db_client = DB_CLIENT.lock().unwrap();
+
         let db_client = db_client.as_ref();
-    
+
         if let Some(pool) = db_client {
             let mut conn = pool.get_conn().unwrap();
-            let query = format!("INSERT INTO scenario_risk (scenario_uuid, likelihood, reputation, operational, legal_compliance, financial) VALUES ('{}', '{}', '{}', '{}', '{}', '{}')", scenario_uuid, likelihood, reputation, operational, legal_compliance, financial);
-    
+            let query = format!(
+                "INSERT INTO risk_acceptance (target_type, target_uuid, accepted_by, justification, residual_level, expires_on) VALUES ('{}', '{}', '{}', '{}', '{}', '{}')",
+                target_type, target_uuid, accepted_by, justification, residual_level, expires_on
+            );
+
             let result = conn.query_drop(query);
-    
+
             match result {
                 Ok(_) => {
                     return Ok(());
                 }
                 Err(_) => {
-                    return Err("Failed to insert new scenario risk".to_owned());
+                    return Err("Failed to insert new risk acceptance".to_owned());
                 }
             }
         }
-    
-        return Err("No database connection".to_owned());
+
+        Err("No database connection".to_owned())
     }
-    
-    pub async fn update_scenario_risk(
-        scenario_uuid: String,
-        likelihood: i32,
-        reputation: i32,
-        operational: i32,
-        legal_compliance: i32,
-        financial: i32,
+
+    pub async fn revoke_acceptance(
+        acceptance_id: i32,
+        revoked_by: String,
+        revocation_reason: String,
     ) -> Result<(), String> {
         // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
-        let lock_result = unsafe { DB_CLIENT.lock() };
-    
+        let lock_result = DB_CLIENT.lock();
+
         if lock_result.is_err() {
             // kill script
             trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
             std::process::exit(1);
         }
-    
+
         // check if need to create new client
         if lock_result.unwrap().is_none() {
             new_client().await;
         }
-    
+
         // perform database operations
-        let db_client = unsafe { DB_CLIENT.lock().unwrap() };
-    
+        let db_client = DB_CLIENT.lock().unwrap();
+
         let db_client = db_client.as_ref();
-    
+
         if let Some(pool) = db_client {
             let mut conn = pool.get_conn().unwrap();
-            let query = format!("UPDATE scenario_risk SET likelihood = '{}', reputation = '{}', operational = '{}', legal_compliance = '{}', financial = '{}' WHERE scenario_uuid = '{}'", likelihood, reputation, operational, legal_compliance, financial, scenario_uuid);
-    
+            let query = format!(
+                "UPDATE risk_acceptance SET revoked_at = CURRENT_TIMESTAMP, revoked_by = '{}', revocation_reason = '{}' WHERE acceptance_id = '{}' AND revoked_at IS NULL",
+                revoked_by, revocation_reason, acceptance_id
+            );
+
             let result = conn.query_drop(query);
-    
+
             match result {
                 Ok(_) => {
                     return Ok(());
                 }
                 Err(_) => {
-                    return Err("Failed to update scenario risk".to_owned());
+                    return Err("Failed to revoke risk acceptance".to_owned());
                 }
             }
         }
-    
-        return Err("No database connection".to_owned());
+
+        Err("No database connection".to_owned())
     }
-    
-    pub async fn delete_scenario_risk(scenario_uuid: String) -> Result<(), String> {
+
+    /// The acceptances of a deleted risk or scenario go with it
+    pub async fn delete_acceptances_of(
+        target_uuid: String,
+    ) -> Result<(), String> {
         // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
-        let lock_result = unsafe { DB_CLIENT.lock() };
-    
+        let lock_result = DB_CLIENT.lock();
+
         if lock_result.is_err() {
             // kill script
             trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
             std::process::exit(1);
         }
-    
+
         // check if need to create new client
         if lock_result.unwrap().is_none() {
             new_client().await;
         }
-    
+
         // perform database operations
-        let db_client = unsafe { DB_CLIENT.lock().unwrap() };
-    
+        let db_client = DB_CLIENT.lock().unwrap();
+
         let db_client = db_client.as_ref();
-    
+
         if let Some(pool) = db_client {
             let mut conn = pool.get_conn().unwrap();
             let query = format!(
-                "DELETE FROM scenario_risk WHERE scenario_uuid = '{}'",
-                scenario_uuid
+                "DELETE FROM risk_acceptance WHERE target_uuid = '{}'",
+                target_uuid
             );
-    
+
             let result = conn.query_drop(query);
-    
+
             match result {
                 Ok(_) => {
                     return Ok(());
                 }
                 Err(_) => {
-                    return Err("Failed to delete scenario risk".to_owned());
+                    return Err("Failed to delete risk acceptances".to_owned());
                 }
             }
         }
-    
-        return Err("No database connection".to_owned());
+
+        Err("No database connection".to_owned())
     }
-    
 }
 
 
-//                                                   
-//   _____                     _     _____ _     _   
-//  |   __|___ ___ ___ ___ ___|_|___| __  |_|___| |_ 
-//  |__   |  _| -_|   | .'|  _| | . |    -| |_ -| '_|
-//  |_____|___|___|_|_|__,|_| |_|___|__|__|_|___|_,_|
-//                                                   
-
-#[derive(Debug, Clone, FromRow)]
-pub struct ScenarioRisk {
+//                                   
+//   _____                     _     
+//  |   __|___ ___ ___ ___ ___|_|___ 
+//  |__   |  _| -_|   | .'|  _| | . |
+//  |_____|___|___|_|_|__,|_| |_|___|
+//                                   
+#[derive(Debug, Clone, Serialize)]
+pub struct Scenario {
     pub scenario_uuid: Uuid,
-    pub likelihood: i32,
-    pub reputation: i32,
-    pub operational: i32,
-    pub legal_compliance: i32,
-    pub financial: i32,
+    pub risk_uuid: Uuid,
+    pub scenario_description: String,
+    pub threat_description: String,
+    pub add_note: String,
 }
 
-impl ScenarioRisk {
+impl Scenario {
     pub fn default() -> Self {
-        ScenarioRisk {
+        Scenario {
             scenario_uuid: Uuid::nil(),
-            likelihood: 0,
-            reputation: 0,
-            operational: 0,
-            legal_compliance: 0,
-            financial: 0,
+            risk_uuid: Uuid::nil(),
+            scenario_description: String::new(),
+            threat_description: String::new(),
+            add_note: String::new(),
         }
     }
 
-    pub async fn get_scenario_risk(scenario_uuid: String) -> Vec<ScenarioRisk> {
+    /// Scenarios mitigated by a countermeasure
+    pub async fn get_scenarios_of_countermeasure(ctm_uuid: String) -> Vec<Scenario> {
         // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
-        let lock_result = unsafe { DB_CLIENT.lock() };
-    
+        let lock_result = DB_CLIENT.lock();
+
         if lock_result.is_err() {
             // kill script
             trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
             std::process::exit(1);
         }
-    
+
         // check if need to create new client
         if lock_result.unwrap().is_none() {
             new_client().await;
         }
-    
+
         // perform database operations
-        let db_client = unsafe { DB_CLIENT.lock().unwrap() };
-    
+        let db_client = DB_CLIENT.lock().unwrap();
+
         let db_client = db_client.as_ref();
-    
-        let mut scenarios: Vec<ScenarioRisk> = Vec::new();
-    
+
+        let mut scenarios: Vec<Scenario> = Vec::new();
+
         if let Some(pool) = db_client {
             let mut conn = pool.get_conn().unwrap();
             let query = format!(
-                "SELECT * FROM scenario_risk WHERE scenario_uuid = '{}' ORDER BY scenario_uuid ASC",
-                scenario_uuid
+                "SELECT scenario_uuid, risk_uuid, scenario_description, threat_description, add_note FROM scenario WHERE scenario_uuid IN (SELECT scenario_uuid FROM countermeasure_scenario WHERE ctm_uuid = '{}') ORDER BY scenario_description ASC",
+                ctm_uuid
             );
-    
-            let result =
-                conn.query_map(
-                    query,
-                    |(
-                        scenario_uuid,
-                        likelihood,
-                        reputation,
-                        operational,
-                        legal_compliance,
-                        financial,
-                    ): (String, i32, i32, i32, i32, i32)| {
-                        ScenarioRisk {
-                            scenario_uuid: Uuid::parse_str(&scenario_uuid).unwrap(),
-                            likelihood,
-                            reputation,
-                            operational,
-                            legal_compliance,
-                            financial,
-                        }
-                    },
-                );
-    
+
+            let result = conn.query_map(
+                query,
+                |(scenario_uuid, risk_uuid, scenario_description, threat_description, add_note): (String, String, String, String, String)| {
+                    Scenario {
+                        scenario_uuid: Uuid::parse_str(&scenario_uuid).unwrap(),
+                        risk_uuid: Uuid::parse_str(&risk_uuid).unwrap(),
+                        scenario_description,
+                        threat_description,
+                        add_note,
+                    }
+                },
+            );
+
             // check how many rows are returned
             match result {
                 Ok(fetched_scenarios) => {
@@ -792,49 +840,17 @@ impl ScenarioRisk {
                     return scenarios;
                 }
             }
-    
+
             return scenarios;
         }
-    
-        println!("No database connection");
-        return scenarios;
-    }
-
-}
-
-
-//                                                               
-//   _____             _                                         
-//  |     |___ _ _ ___| |_ ___ ___ _____ ___ ___ ___ _ _ ___ ___ 
-//  |   --| . | | |   |  _| -_|  _|     | -_| .'|_ -| | |  _| -_|
-//  |_____|___|___|_|_|_| |___|_| |_|_|_|___|__,|___|___|_| |___|
-//                                                               
-
-#[derive(Debug, Clone, FromRow)]
-pub struct Countermeasure {
-    pub ctm_uuid: Uuid,
-    pub scenario_uuid: Uuid,
-    pub title: String,
-    pub description: String,
-    pub solved: i32,
-    pub solved_description: String,
-}
 
-impl Countermeasure {
-    pub fn default() -> Self {
-        Countermeasure {
-            ctm_uuid: Uuid::nil(),
-            scenario_uuid: Uuid::nil(),
-            title: String::new(),
-            description: String::new(),
-            solved: 0,
-            solved_description: String::new(),
-        }
+        println!("No database connection");
+        scenarios
     }
 
-    pub async fn get_all_countermeasure_of_sc(scenario_uuid: String) -> Vec<Countermeasure> {
+    pub async fn get_all_scenario_of_risk(risk_uuid: String) -> Vec<Scenario> {
         // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
-        let lock_result = unsafe { DB_CLIENT.lock() };
+        let lock_result = DB_CLIENT.lock();
     
         if lock_result.is_err() {
             // kill script
@@ -848,106 +864,60 @@ impl Countermeasure {
         }
     
         // perform database operations
-        let db_client = unsafe { DB_CLIENT.lock().unwrap() };
+        let db_client = DB_CLIENT.lock().unwrap();
     
         let db_client = db_client.as_ref();
     
-        let mut countermeasures: Vec<Countermeasure> = Vec::new();
+        let mut scenarios: Vec<Scenario> = Vec::new();
     
         if let Some(pool) = db_client {
             let mut conn = pool.get_conn().unwrap();
             let query = format!(
-                "SELECT * FROM countermeasure WHERE scenario_uuid = '{}' ORDER BY title ASC",
-                scenario_uuid
+                "SELECT * FROM scenario WHERE risk_uuid = '{}' ORDER BY scenario_description ASC",
+                risk_uuid
             );
     
             let result = conn.query_map(
                 query,
-                |(ctm_uuid, scenario_uuid, title, description, solved, solved_description): (
+                |(scenario_uuid, risk_uuid, scenario_description, threat_description, add_note): (
                     String,
                     String,
                     String,
                     String,
-                    i32,
                     String,
                 )| {
-                    Countermeasure {
-                        ctm_uuid: Uuid::parse_str(&ctm_uuid).unwrap(),
+                    Scenario {
                         scenario_uuid: Uuid::parse_str(&scenario_uuid).unwrap(),
-                        title,
-                        description,
-                        solved,
-                        solved_description,
+                        risk_uuid: Uuid::parse_str(&risk_uuid).unwrap(),
+                        scenario_description,
+                        threat_description,
+                        add_note,
                     }
                 },
             );
     
             // check how many rows are returned
             match result {
-                Ok(fetched_countermeasures) => {
-                    for countermeasure in fetched_countermeasures {
-                        countermeasures.push(countermeasure);
-                    }
+                Ok(fetched_scenarios) => {
+                    for scenario in fetched_scenarios {
+                        scenarios.push(scenario);
+                    }
                 }
                 Err(_) => {
-                    return countermeasures;
+                    return scenarios;
                 }
             }
     
-            return countermeasures;
+            return scenarios;
         }
     
         println!("No database connection");
-        return countermeasures;
-    }
-    
-    pub async fn create_countermeasure(
-        scenario_uuid: String,
-        title: String,
-        description: String,
-    ) -> Result<(), String> {
-        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
-        let lock_result = unsafe { DB_CLIENT.lock() };
-    
-        if lock_result.is_err() {
-            // kill script
-            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
-            std::process::exit(1);
-        }
-    
-        // check if need to create new client
-        if lock_result.unwrap().is_none() {
-            new_client().await;
-        }
-    
-        // perform database operations
-        let db_client = unsafe { DB_CLIENT.lock().unwrap() };
-    
-        let db_client = db_client.as_ref();
-    
-        if let Some(pool) = db_client {
-            let mut conn = pool.get_conn().unwrap();
-            let ctm_uuid = Uuid::new_v4();
-            let query = format!("INSERT INTO countermeasure (ctm_uuid, scenario_uuid, title, description, solved, solved_description) VALUES ('{}', '{}', '{}', '{}', '{}', '{}')", ctm_uuid, scenario_uuid, title, description, 0, "");
-    
-            let result = conn.query_drop(query);
-    
-            match result {
-                Ok(_) => {
-                    return Ok(());
-                }
-                Err(_) => {
-                    return Err("Failed to insert new countermeasure".to_owned());
-                }
-            }
-        }
-    
-        return Err("No database connection".to_owned());
+        return scenarios;
     }
     
-    pub async fn get_all_countermeasure_from_risk_uuid(risk_uuid: String) -> Vec<Countermeasure> {
+    pub async fn get_scenario_detail(scenario_uuid: String) -> Vec<Scenario> {
         // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
-        let lock_result = unsafe { DB_CLIENT.lock() };
+        let lock_result = DB_CLIENT.lock();
     
         if lock_result.is_err() {
             // kill script
@@ -961,59 +931,65 @@ impl Countermeasure {
         }
     
         // perform database operations
-        let db_client = unsafe { DB_CLIENT.lock().unwrap() };
+        let db_client = DB_CLIENT.lock().unwrap();
     
         let db_client = db_client.as_ref();
     
-        let mut countermeasures: Vec<Countermeasure> = Vec::new();
+        let mut scenarios: Vec<Scenario> = Vec::new();
     
         if let Some(pool) = db_client {
             let mut conn = pool.get_conn().unwrap();
-            let query = format!("SELECT * FROM countermeasure WHERE scenario_uuid IN (SELECT scenario_uuid FROM scenario WHERE risk_uuid = '{}') ORDER BY title ASC", risk_uuid);
+            let query = format!(
+                "SELECT * FROM scenario WHERE scenario_uuid = '{}' ORDER BY scenario_description ASC",
+                scenario_uuid
+            );
     
             let result = conn.query_map(
                 query,
-                |(ctm_uuid, scenario_uuid, title, description, solved, solved_description): (
+                |(scenario_uuid, risk_uuid, scenario_description, threat_description, add_note): (
                     String,
                     String,
                     String,
                     String,
-                    i32,
                     String,
                 )| {
-                    Countermeasure {
-                        ctm_uuid: Uuid::parse_str(&ctm_uuid).unwrap(),
+                    Scenario {
                         scenario_uuid: Uuid::parse_str(&scenario_uuid).unwrap(),
-                        title,
-                        description,
-                        solved,
-                        solved_description,
+                        risk_uuid: Uuid::parse_str(&risk_uuid).unwrap(),
+                        scenario_description,
+                        threat_description,
+                        add_note,
                     }
                 },
             );
     
             // check how many rows are returned
             match result {
-                Ok(fetched_countermeasures) => {
-                    for countermeasure in fetched_countermeasures {
-                        countermeasures.push(countermeasure);
+                Ok(fetched_scenarios) => {
+                    for scenario in fetched_scenarios {
+                        scenarios.push(scenario);
                     }
                 }
                 Err(_) => {
-                    return countermeasures;
+                    return scenarios;
                 }
             }
     
-            return countermeasures;
+            return scenarios;
         }
     
         println!("No database connection");
-        return countermeasures;
+        return scenarios;
     }
     
-    pub async fn get_ctm_by_id(ctm_uuid: String) -> Vec<Countermeasure> {
+    pub async fn create_new_scenario(
+        risk_uuid: String,
+        scenario_description: String,
+        threat_description: String,
+        add_note: String,
+    ) -> Uuid {
         // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
-        let lock_result = unsafe { DB_CLIENT.lock() };
+        let lock_result = DB_CLIENT.lock();
     
         if lock_result.is_err() {
             // kill script
@@ -1027,68 +1003,38 @@ impl Countermeasure {
         }
     
         // perform database operations
-        let db_client = unsafe { DB_CLIENT.lock().unwrap() };
+        let db_client = DB_CLIENT.lock().unwrap();
     
         let db_client = db_client.as_ref();
     
-        let mut countermeasures: Vec<Countermeasure> = Vec::new();
-    
         if let Some(pool) = db_client {
             let mut conn = pool.get_conn().unwrap();
-            let query = format!(
-                "SELECT * FROM countermeasure WHERE ctm_uuid = '{}' ORDER BY title ASC",
-                ctm_uuid
-            );
+            let scuuid = Uuid::new_v4();
+            let query = format!("INSERT INTO scenario (scenario_uuid, risk_uuid, scenario_description, threat_description, add_note) VALUES ('{}', '{}', '{}', '{}', '{}')", scuuid, risk_uuid, scenario_description, threat_description, add_note);
     
-            let result = conn.query_map(
-                query,
-                |(ctm_uuid, scenario_uuid, title, description, solved, solved_description): (
-                    String,
-                    String,
-                    String,
-                    String,
-                    i32,
-                    String,
-                )| {
-                    Countermeasure {
-                        ctm_uuid: Uuid::parse_str(&ctm_uuid).unwrap(),
-                        scenario_uuid: Uuid::parse_str(&scenario_uuid).unwrap(),
-                        title,
-                        description,
-                        solved,
-                        solved_description,
-                    }
-                },
-            );
+            let result = conn.query_drop(query);
     
-            // check how many rows are returned
             match result {
-                Ok(fetched_countermeasures) => {
-                    for countermeasure in fetched_countermeasures {
-                        countermeasures.push(countermeasure);
-                    }
+                Ok(_) => {
+                    return scuuid;
                 }
                 Err(_) => {
-                    return countermeasures;
+                    return Uuid::nil();
                 }
             }
-    
-            return countermeasures;
         }
     
-        println!("No database connection");
-        return countermeasures;
+        return Uuid::nil();
     }
     
-    pub async fn update_countermeasure(
-        ctm_uuid: String,
-        title: String,
-        description: String,
-        solved: i32,
-        solved_description: String,
+    pub async fn update_scenario(
+        scenario_uuid: String,
+        scenario_description: String,
+        threat_description: String,
+        add_note: String,
     ) -> Result<(), String> {
         // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
-        let lock_result = unsafe { DB_CLIENT.lock() };
+        let lock_result = DB_CLIENT.lock();
     
         if lock_result.is_err() {
             // kill script
@@ -1102,13 +1048,13 @@ impl Countermeasure {
         }
     
         // perform database operations
-        let db_client = unsafe { DB_CLIENT.lock().unwrap() };
+        let db_client = DB_CLIENT.lock().unwrap();
     
         let db_client = db_client.as_ref();
     
         if let Some(pool) = db_client {
             let mut conn = pool.get_conn().unwrap();
-            let query = format!("UPDATE countermeasure SET title = '{}', description = '{}', solved = '{}', solved_description = '{}' WHERE ctm_uuid = '{}'", title, description, solved, solved_description, ctm_uuid);
+            let query = format!("UPDATE scenario SET scenario_description = '{}', threat_description = '{}', add_note = '{}' WHERE scenario_uuid = '{}'", scenario_description, threat_description, add_note, scenario_uuid);
     
             let result = conn.query_drop(query);
     
@@ -1117,7 +1063,7 @@ impl Countermeasure {
                     return Ok(());
                 }
                 Err(_) => {
-                    return Err("Failed to update countermeasure".to_owned());
+                    return Err("Failed to update scenario".to_owned());
                 }
             }
         }
@@ -1125,9 +1071,9 @@ impl Countermeasure {
         return Err("No database connection".to_owned());
     }
     
-    pub async fn delete_countermeasure(ctm_uuid: String) -> Result<(), String> {
+    pub async fn delete_scenario(scenario_uuid: String) -> Result<(), String> {
         // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
-        let lock_result = unsafe { DB_CLIENT.lock() };
+        let lock_result = DB_CLIENT.lock();
     
         if lock_result.is_err() {
             // kill script
@@ -1141,13 +1087,16 @@ impl Countermeasure {
         }
     
         // perform database operations
-        let db_client = unsafe { DB_CLIENT.lock().unwrap() };
+        let db_client = DB_CLIENT.lock().unwrap();
     
         let db_client = db_client.as_ref();
     
         if let Some(pool) = db_client {
             let mut conn = pool.get_conn().unwrap();
-            let query = format!("DELETE FROM countermeasure WHERE ctm_uuid = '{}'", ctm_uuid);
+            let query = format!(
+                "DELETE FROM scenario WHERE scenario_uuid = '{}'",
+                scenario_uuid
+            );
     
             let result = conn.query_drop(query);
     
@@ -1156,7 +1105,7 @@ impl Countermeasure {
                     return Ok(());
                 }
                 Err(_) => {
-                    return Err("Failed to delete countermeasure".to_owned());
+                    return Err("Failed to delete scenario".to_owned());
                 }
             }
         }
@@ -1164,9 +1113,16 @@ impl Countermeasure {
         return Err("No database connection".to_owned());
     }
     
-    pub async fn delete_countermeasure_from_sc(scenario_uuid: String) -> Result<(), String> {
+    pub async fn create_scenario_risk(
+        scenario_uuid: String,
+        likelihood: i32,
+        reputation: i32,
+        operational: i32,
+        legal_compliance: i32,
+        financial: i32,
+    ) -> Result<(), String> {
         // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
-        let lock_result = unsafe { DB_CLIENT.lock() };
+        let lock_result = DB_CLIENT.lock();
     
         if lock_result.is_err() {
             // kill script
@@ -1180,16 +1136,13 @@ impl Countermeasure {
         }
     
         // perform database operations
-        let db_client = unsafe { DB_CLIENT.lock().unwrap() };
+        let db_client = DB_CLIENT.lock().unwrap();
     
         let db_client = db_client.as_ref();
     
         if let Some(pool) = db_client {
             let mut conn = pool.get_conn().unwrap();
-            let query = format!(
-                "DELETE FROM countermeasure WHERE scenario_uuid = '{}'",
-                scenario_uuid
-            );
+            let query = format!("INSERT INTO scenario_risk (scenario_uuid, likelihood, reputation, operational, legal_compliance, financial) VALUES ('{}', '{}', '{}', '{}', '{}', '{}')", scenario_uuid, likelihood, reputation, operational, legal_compliance, financial);
     
             let result = conn.query_drop(query);
     
@@ -1198,7 +1151,7 @@ impl Countermeasure {
                     return Ok(());
                 }
                 Err(_) => {
-                    return Err("Failed to delete countermeasure".to_owned());
+                    return Err("Failed to insert new scenario risk".to_owned());
                 }
             }
         }
@@ -1206,33 +1159,16 @@ impl Countermeasure {
         return Err("No database connection".to_owned());
     }
     
-
-}
-
-
-//                             
-//   _____ _         _         
-//  |     |_|___ ___|_|___ ___ 
-//  | | | | |_ -|_ -| | . |   |
-//  |_|_|_|_|___|___|_|___|_|_|
-//                             
-#[derive(Debug, Clone)]
-pub struct Mission {
-    pub mission_id: i32,
-    pub mission_name: String,
-}
-
-impl Mission {
-    pub fn default() -> Self {
-        Mission {
-            mission_id: 0,
-            mission_name: String::new(),
-        }
-    }
-
-    pub async fn c1_get_all_missions() -> Vec<Mission> {
+    pub async fn update_scenario_risk(
+        scenario_uuid: String,
+        likelihood: i32,
+        reputation: i32,
+        operational: i32,
+        legal_compliance: i32,
+        financial: i32,
+    ) -> Result<(), String> {
         // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
-        let lock_result = unsafe { DB_CLIENT.lock() };
+        let lock_result = DB_CLIENT.lock();
     
         if lock_result.is_err() {
             // kill script
@@ -1246,43 +1182,32 @@ impl Mission {
         }
     
         // perform database operations
-        let db_client = unsafe { DB_CLIENT.lock().unwrap() };
+        let db_client = DB_CLIENT.lock().unwrap();
     
         let db_client = db_client.as_ref();
     
-        let mut missions: Vec<Mission> = Vec::new();
-    
         if let Some(pool) = db_client {
             let mut conn = pool.get_conn().unwrap();
-            let query = format!("SELECT * FROM c1_mission ORDER BY mission_id ASC");
+            let query = format!("UPDATE scenario_risk SET likelihood = '{}', reputation = '{}', operational = '{}', legal_compliance = '{}', financial = '{}' WHERE scenario_uuid = '{}'", likelihood, reputation, operational, legal_compliance, financial, scenario_uuid);
     
-            let result = conn.query_map(query, |(mission_id, mission_name): (i32, String)| Mission {
-                mission_id,
-                mission_name,
-            });
+            let result = conn.query_drop(query);
     
-            // check how many rows are returned
             match result {
-                Ok(fetched_missions) => {
-                    for mission in fetched_missions {
-                        missions.push(mission);
-                    }
+                Ok(_) => {
+                    return Ok(());
                 }
                 Err(_) => {
-                    return missions;
+                    return Err("Failed to update scenario risk".to_owned());
                 }
             }
-    
-            return missions;
         }
     
-        println!("No database connection");
-        return missions;
+        return Err("No database connection".to_owned());
     }
     
-    pub async fn c1_create_mission(mission_name: String) {
+    pub async fn delete_scenario_risk(scenario_uuid: String) -> Result<(), String> {
         // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
-        let lock_result = unsafe { DB_CLIENT.lock() };
+        let lock_result = DB_CLIENT.lock();
     
         if lock_result.is_err() {
             // kill script
@@ -1296,160 +1221,67 @@ impl Mission {
         }
     
         // perform database operations
-        let db_client = unsafe { DB_CLIENT.lock().unwrap() };
+        let db_client = DB_CLIENT.lock().unwrap();
     
         let db_client = db_client.as_ref();
     
         if let Some(pool) = db_client {
             let mut conn = pool.get_conn().unwrap();
             let query = format!(
-                "INSERT INTO c1_mission (mission_name) VALUES ('{}')",
-                mission_name
+                "DELETE FROM scenario_risk WHERE scenario_uuid = '{}'",
+                scenario_uuid
             );
     
             let result = conn.query_drop(query);
     
             match result {
                 Ok(_) => {
-                    return;
+                    return Ok(());
                 }
                 Err(_) => {
-                    return;
+                    return Err("Failed to delete scenario risk".to_owned());
                 }
             }
         }
     
-        println!("No database connection");
-        return;
-    }
-    
-    pub async fn c1_get_mission_by_id(mission_id: i32) -> Vec<Mission> {
-        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
-        let lock_result = unsafe { DB_CLIENT.lock() };
-    
-        if lock_result.is_err() {
-            // kill script
-            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
-            std::process::exit(1);
-        }
-    
-        // check if need to create new client
-        if lock_result.unwrap().is_none() {
-            new_client().await;
-        }
-    
-        let mut missions: Vec<Mission> = Vec::new();
-    
-        // perform database operations
-        let db_client = unsafe { DB_CLIENT.lock().unwrap() };
-    
-        let db_client = db_client.as_ref();
-    
-        if let Some(pool) = db_client {
-            let mut conn = pool.get_conn().unwrap();
-            let query = format!(
-                "SELECT * FROM c1_mission WHERE mission_id = '{}' ORDER BY mission_id ASC",
-                mission_id
-            );
-    
-            let result = conn.query_map(query, |(mission_id, mission_name): (i32, String)| Mission {
-                mission_id,
-                mission_name,
-            });
-    
-            // check how many rows are returned
-            match result {
-                Ok(fetched_missions) => {
-                    for mission in fetched_missions {
-                        missions.push(mission);
-                    }
-                }
-                Err(_) => {
-                    return missions;
-                }
-            }
-    
-            return missions;
-        }
-    
-        println!("No database connection");
-        return missions;
-    }
-    
-    pub async fn c1_delete_mission_by_id(mission_id: i32) {
-        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
-        let lock_result = unsafe { DB_CLIENT.lock() };
-    
-        if lock_result.is_err() {
-            // kill script
-            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
-            std::process::exit(1);
-        }
-    
-        // check if need to create new client
-        if lock_result.unwrap().is_none() {
-            new_client().await;
-        }
-    
-        // perform database operations
-        let db_client = unsafe { DB_CLIENT.lock().unwrap() };
-    
-        let db_client = db_client.as_ref();
-    
-        if let Some(pool) = db_client {
-            let mut conn = pool.get_conn().unwrap();
-            let query = format!("DELETE FROM c1_mission WHERE mission_id = '{}'", mission_id);
-    
-            let result = conn.query_drop(query);
-    
-            match result {
-                Ok(_) => {
-                    return;
-                }
-                Err(_) => {
-                    return;
-                }
-            }
-        }
-    
-        println!("No database connection");
-        return;
+        return Err("No database connection".to_owned());
     }
     
 }
 
 
 //                                                   
-//   _____     _             _____     _   _         
-//  |  |  |___| |___ _ _ ___|     |___| |_|_|___ ___ 
-//  |  |  | .'| | -_| | |  _| | | | -_|  _| | -_|  _|
-//   \___/|__,|_|___|___|_| |_|_|_|___|_| |_|___|_|  
+//   _____                     _     _____ _     _   
+//  |   __|___ ___ ___ ___ ___|_|___| __  |_|___| |_ 
+//  |__   |  _| -_|   | .'|  _| | . |    -| |_ -| '_|
+//  |_____|___|___|_|_|__,|_| |_|___|__|__|_|___|_,_|
 //                                                   
-#[derive(Debug, Clone)]
-pub struct ValeurMetier {
-    pub valeur_id: i32,
-    pub mission_id: i32,
-    pub valeur_name: String,
-    pub valeur_nature: String,
-    pub valeur_description: String,
-    pub responsable: String,
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ScenarioRisk {
+    pub scenario_uuid: Uuid,
+    pub likelihood: i32,
+    pub reputation: i32,
+    pub operational: i32,
+    pub legal_compliance: i32,
+    pub financial: i32,
 }
 
-impl ValeurMetier {
+impl ScenarioRisk {
     pub fn default() -> Self {
-        ValeurMetier {
-            valeur_id: 0,
-            mission_id: 0,
-            valeur_name: String::new(),
-            valeur_nature: String::new(),
-            valeur_description: String::new(),
-            responsable: String::new(),
+        ScenarioRisk {
+            scenario_uuid: Uuid::nil(),
+            likelihood: 0,
+            reputation: 0,
+            operational: 0,
+            legal_compliance: 0,
+            financial: 0,
         }
     }
 
-    pub async fn c1_get_all_valeurmetier(mission_id: i32) -> Vec<ValeurMetier> {
+    pub async fn get_scenario_risk(scenario_uuid: String) -> Vec<ScenarioRisk> {
         // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
-        let lock_result = unsafe { DB_CLIENT.lock() };
+        let lock_result = DB_CLIENT.lock();
     
         if lock_result.is_err() {
             // kill script
@@ -1462,175 +1294,259 @@ impl ValeurMetier {
             new_client().await;
         }
     
-        let mut valeurs: Vec<ValeurMetier> = Vec::new();
-    
         // perform database operations
-        let db_client = unsafe { DB_CLIENT.lock().unwrap() };
+        let db_client = DB_CLIENT.lock().unwrap();
     
         let db_client = db_client.as_ref();
     
+        let mut scenarios: Vec<ScenarioRisk> = Vec::new();
+    
         if let Some(pool) = db_client {
             let mut conn = pool.get_conn().unwrap();
             let query = format!(
-                "SELECT * FROM c1_valeur_metier WHERE mission_id = '{}' ORDER BY valeur_id ASC",
-                mission_id
+                "SELECT * FROM scenario_risk WHERE scenario_uuid = '{}' ORDER BY scenario_uuid ASC",
+                scenario_uuid
             );
     
-            let result = conn.query_map(
-                query,
-                |(
-                    valeur_id,
-                    mission_id,
-                    valeur_name,
-                    valeur_nature,
-                    valeur_description,
-                    responsable,
-                ): (i32, i32, String, String, String, String)| {
-                    ValeurMetier {
-                        valeur_id,
-                        mission_id,
-                        valeur_name,
-                        valeur_nature,
-                        valeur_description,
-                        responsable,
-                    }
-                },
-            );
+            let result =
+                conn.query_map(
+                    query,
+                    |(
+                        scenario_uuid,
+                        likelihood,
+                        reputation,
+                        operational,
+                        legal_compliance,
+                        financial,
+                    ): (String, i32, i32, i32, i32, i32)| {
+                        ScenarioRisk {
+                            scenario_uuid: Uuid::parse_str(&scenario_uuid).unwrap(),
+                            likelihood,
+                            reputation,
+                            operational,
+                            legal_compliance,
+                            financial,
+                        }
+                    },
+                );
     
             // check how many rows are returned
             match result {
-                Ok(fetched_valeurs) => {
-                    for valeur in fetched_valeurs {
-                        valeurs.push(valeur);
+                Ok(fetched_scenarios) => {
+                    for scenario in fetched_scenarios {
+                        scenarios.push(scenario);
                     }
                 }
                 Err(_) => {
-                    return valeurs;
+                    return scenarios;
                 }
             }
     
-            return valeurs;
+            return scenarios;
         }
     
         println!("No database connection");
-        return valeurs;
+        return scenarios;
     }
-    
-    pub async fn c1_get_all_valeurmetier_no_limit() -> Vec<ValeurMetier> {
+
+}
+
+/// A rating of a scenario, recorded each time it changes
+#[derive(Debug, Clone, Serialize)]
+pub struct ScenarioRiskHistory {
+    pub scenario_uuid: Uuid,
+    pub likelihood: i32,
+    pub reputation: i32,
+    pub operational: i32,
+    pub legal_compliance: i32,
+    pub financial: i32,
+    /// `YYYY-MM-DD HH:MM:SS`
+    pub recorded_at: String,
+}
+
+impl ScenarioRiskHistory {
+    pub async fn get_all_history() -> Vec<ScenarioRiskHistory> {
         // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
-        let lock_result = unsafe { DB_CLIENT.lock() };
-    
+        let lock_result = DB_CLIENT.lock();
+
         if lock_result.is_err() {
             // kill script
             trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
             std::process::exit(1);
         }
-    
+
         // check if need to create new client
         if lock_result.unwrap().is_none() {
             new_client().await;
         }
-    
-        let mut valeurs: Vec<ValeurMetier> = Vec::new();
-    
+
         // perform database operations
-        let db_client = unsafe { DB_CLIENT.lock().unwrap() };
-    
+        let db_client = DB_CLIENT.lock().unwrap();
+
         let db_client = db_client.as_ref();
-    
+
+        let mut history: Vec<ScenarioRiskHistory> = Vec::new();
+
         if let Some(pool) = db_client {
             let mut conn = pool.get_conn().unwrap();
-            let query = format!("SELECT * FROM c1_valeur_metier ORDER BY valeur_id ASC");
-    
+
             let result = conn.query_map(
-                query,
-                |(
-                    valeur_id,
-                    mission_id,
-                    valeur_name,
-                    valeur_nature,
-                    valeur_description,
-                    responsable,
-                ): (i32, i32, String, String, String, String)| {
-                    ValeurMetier {
-                        valeur_id,
-                        mission_id,
-                        valeur_name,
-                        valeur_nature,
-                        valeur_description,
-                        responsable,
-                    }
+                format!("SELECT scenario_uuid, likelihood, reputation, operational, legal_compliance, financial, {} FROM scenario_risk_history ORDER BY history_id ASC", dialect().date_format("recorded_at", "%Y-%m-%d %H:%i:%s")),
+                |(scenario_uuid, likelihood, reputation, operational, legal_compliance, financial, recorded_at): (String, i32, i32, i32, i32, i32, String)| ScenarioRiskHistory {
+                    scenario_uuid: Uuid::parse_str(&scenario_uuid).unwrap(),
+                    likelihood,
+                    reputation,
+                    operational,
+                    legal_compliance,
+                    financial,
+                    recorded_at,
                 },
             );
-    
+
             // check how many rows are returned
             match result {
-                Ok(fetched_valeurs) => {
-                    for valeur in fetched_valeurs {
-                        valeurs.push(valeur);
+                Ok(fetched_history) => {
+                    for entry in fetched_history {
+                        history.push(entry);
                     }
                 }
                 Err(_) => {
-                    return valeurs;
+                    return history;
                 }
             }
-    
-            return valeurs;
+
+            return history;
         }
-    
+
         println!("No database connection");
-        return valeurs;
+        history
     }
-    
-    pub async fn c1_create_valeurmetier(
-        mission_id: i32,
-        valeur_name: String,
-        valeur_nature: String,
-        valeur_description: String,
-        responsable: String,
-    ) {
+
+    /// Record the ratings that differ from their last entry, or have none yet
+    pub async fn record_changes() {
         // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
-        let lock_result = unsafe { DB_CLIENT.lock() };
-    
+        let lock_result = DB_CLIENT.lock();
+
         if lock_result.is_err() {
             // kill script
             trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
             std::process::exit(1);
         }
-    
+
         // check if need to create new client
         if lock_result.unwrap().is_none() {
             new_client().await;
         }
-    
+
         // perform database operations
-        let db_client = unsafe { DB_CLIENT.lock().unwrap() };
-    
+        let db_client = DB_CLIENT.lock().unwrap();
+
         let db_client = db_client.as_ref();
-    
+
         if let Some(pool) = db_client {
             let mut conn = pool.get_conn().unwrap();
-            let query = format!("INSERT INTO c1_valeur_metier (mission_id, valeur_name, valeur_nature, valeur_description, responsable) VALUES ('{}', '{}', '{}', '{}', '{}')", mission_id, valeur_name, valeur_nature, valeur_description, responsable);
-    
-            let result = conn.query_drop(query);
-    
-            match result {
-                Ok(_) => {
-                    return;
-                }
-                Err(_) => {
-                    return;
-                }
-            }
+            let _ = conn.query_drop("INSERT INTO scenario_risk_history (scenario_uuid, likelihood, reputation, operational, legal_compliance, financial) SELECT r.scenario_uuid, r.likelihood, r.reputation, r.operational, r.legal_compliance, r.financial FROM scenario_risk r WHERE NOT EXISTS (SELECT 1 FROM scenario_risk_history h WHERE h.history_id = (SELECT MAX(l.history_id) FROM scenario_risk_history l WHERE l.scenario_uuid = r.scenario_uuid) AND h.likelihood = r.likelihood AND h.reputation = r.reputation AND h.operational = r.operational AND h.legal_compliance = r.legal_compliance AND h.financial = r.financial)");
+            return;
         }
-    
+
         println!("No database connection");
-        return;
     }
-    
-    pub async fn c1_get_valermetier_by_id(vm_id: i32) -> Vec<ValeurMetier> {
+}
+
+//                                                               
+//   _____             _                                         
+//  |     |___ _ _ ___| |_ ___ ___ _____ ___ ___ ___ _ _ ___ ___ 
+//  |   --| . | | |   |  _| -_|  _|     | -_| .'|_ -| | |  _| -_|
+//  |_____|___|___|_|_|_| |___|_| |_|_|_|___|__,|___|___|_| |___|
+//                                                               
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Countermeasure {
+    pub ctm_uuid: Uuid,
+    /// scenario the measure was created for, nil for a measure of the
+    /// catalogue; the scenarios it mitigates are its `CountermeasureLink`
+    pub scenario_uuid: Uuid,
+    pub title: String,
+    pub description: String,
+    pub solved: i32,
+    pub solved_description: String,
+    pub owner: String,
+    /// `YYYY-MM-DD`, empty when the action has no deadline
+    pub due_date: String,
+    pub priority: String,
+    /// estimated cost, in the currency of the study
+    pub cost: i32,
+    /// estimated effort, in man-days
+    pub effort: i32,
+    /// step of the workflow: proposed, approved, in_progress, implemented or verified
+    pub status: String,
+}
+
+/// Columns of a countermeasure, in the order of `CountermeasureRow`
+fn ctm_columns() -> String {
+    format!(
+        "ctm_uuid, scenario_uuid, title, description, solved, COALESCE(solved_description, ''), owner, COALESCE({}, ''), priority, cost, effort, status",
+        dialect().date_format("due_date", "%Y-%m-%d")
+    )
+}
+
+type CountermeasureRow = (String, String, String, String, i32, String, String, String, String, i32, i32, String);
+
+/// A `YYYY-MM-DD` date as a SQL value, NULL when empty
+fn sql_date(date: &str) -> String {
+    if date.is_empty() { "NULL".to_string() } else { format!("'{}'", date) }
+}
+
+impl Countermeasure {
+    pub fn default() -> Self {
+        Countermeasure {
+            ctm_uuid: Uuid::nil(),
+            scenario_uuid: Uuid::nil(),
+            title: String::new(),
+            description: String::new(),
+            solved: 0,
+            solved_description: String::new(),
+            owner: String::new(),
+            due_date: String::new(),
+            priority: "medium".to_string(),
+            cost: 0,
+            effort: 0,
+            status: "proposed".to_string(),
+        }
+    }
+
+    fn from_row(
+        (ctm_uuid, scenario_uuid, title, description, solved, solved_description, owner, due_date, priority, cost, effort, status): CountermeasureRow,
+    ) -> Countermeasure {
+        Countermeasure {
+            ctm_uuid: Uuid::parse_str(&ctm_uuid).unwrap(),
+            scenario_uuid: Uuid::parse_str(&scenario_uuid).unwrap(),
+            title,
+            description,
+            solved,
+            solved_description,
+            owner,
+            due_date,
+            priority,
+            cost,
+            effort,
+            status,
+        }
+    }
+
+    /// implemented and verified actions are done, whatever their due date
+    pub fn is_done(&self) -> bool {
+        self.status == "implemented" || self.status == "verified"
+    }
+
+    /// `today` is a `YYYY-MM-DD` date, compared as a string
+    pub fn is_overdue(&self, today: &str) -> bool {
+        !self.due_date.is_empty() && self.due_date.as_str() < today && !self.is_done()
+    }
+
+    pub async fn get_all_countermeasure_of_sc(scenario_uuid: String) -> Vec<Countermeasure> {
         // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
-        let lock_result = unsafe { DB_CLIENT.lock() };
+        let lock_result = DB_CLIENT.lock();
     
         if lock_result.is_err() {
             // kill script
@@ -1643,137 +1559,98 @@ impl ValeurMetier {
             new_client().await;
         }
     
-        let mut valeurs: Vec<ValeurMetier> = Vec::new();
-    
         // perform database operations
-        let db_client = unsafe { DB_CLIENT.lock().unwrap() };
+        let db_client = DB_CLIENT.lock().unwrap();
     
         let db_client = db_client.as_ref();
     
+        let mut countermeasures: Vec<Countermeasure> = Vec::new();
+    
         if let Some(pool) = db_client {
             let mut conn = pool.get_conn().unwrap();
             let query = format!(
-                "SELECT * FROM c1_valeur_metier WHERE valeur_id = '{}' ORDER BY valeur_id ASC",
-                vm_id
+                "SELECT {} FROM countermeasure WHERE ctm_uuid IN (SELECT ctm_uuid FROM countermeasure_scenario WHERE scenario_uuid = '{}') ORDER BY title ASC",
+                ctm_columns(), scenario_uuid
             );
     
-            let result = conn.query_map(
-                query,
-                |(
-                    valeur_id,
-                    mission_id,
-                    valeur_name,
-                    valeur_nature,
-                    valeur_description,
-                    responsable,
-                ): (i32, i32, String, String, String, String)| {
-                    ValeurMetier {
-                        valeur_id,
-                        mission_id,
-                        valeur_name,
-                        valeur_nature,
-                        valeur_description,
-                        responsable,
-                    }
-                },
-            );
+            let result = conn.query_map(query, Countermeasure::from_row);
     
             // check how many rows are returned
             match result {
-                Ok(fetched_valeurs) => {
-                    for valeur in fetched_valeurs {
-                        valeurs.push(valeur);
+                Ok(fetched_countermeasures) => {
+                    for countermeasure in fetched_countermeasures {
+                        countermeasures.push(countermeasure);
                     }
                 }
                 Err(_) => {
-                    return valeurs;
+                    return countermeasures;
                 }
             }
     
-            return valeurs;
+            return countermeasures;
         }
     
         println!("No database connection");
-        return valeurs;
+        return countermeasures;
     }
-
-    pub async fn c1_delete_vm_by_id(vm_id: i32) {
+    
+    pub async fn create_countermeasure(
+        scenario_uuid: String,
+        title: String,
+        description: String,
+        owner: String,
+        due_date: String,
+        priority: String,
+        cost: i32,
+        effort: i32,
+    ) -> Result<String, String> {
         // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
-        let lock_result = unsafe { DB_CLIENT.lock() };
-
+        let lock_result = DB_CLIENT.lock();
+    
         if lock_result.is_err() {
             // kill script
             trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
             std::process::exit(1);
         }
-
+    
         // check if need to create new client
         if lock_result.unwrap().is_none() {
             new_client().await;
         }
-
+    
         // perform database operations
-        let db_client = unsafe { DB_CLIENT.lock().unwrap() };
-
+        let db_client = DB_CLIENT.lock().unwrap();
+    
         let db_client = db_client.as_ref();
-
+    
         if let Some(pool) = db_client {
             let mut conn = pool.get_conn().unwrap();
-            let query = format!("DELETE FROM c1_valeur_metier WHERE valeur_id = '{}'", vm_id);
+            let ctm_uuid = Uuid::new_v4();
+            let query = format!("INSERT INTO countermeasure (ctm_uuid, scenario_uuid, title, description, solved, solved_description, owner, due_date, priority, cost, effort, status) VALUES ('{}', '{}', '{}', '{}', '{}', '{}', '{}', {}, '{}', '{}', '{}', 'proposed')", ctm_uuid, scenario_uuid, title, description, 0, "", owner, sql_date(&due_date), priority, cost, effort);
 
+            // a measure of the catalogue mitigates no scenario yet
+            if scenario_uuid != Uuid::nil().to_string() {
+                let _ = conn.query_drop(format!("INSERT INTO countermeasure_scenario (ctm_uuid, scenario_uuid) VALUES ('{}', '{}')", ctm_uuid, scenario_uuid));
+            }
+    
             let result = conn.query_drop(query);
-
+    
             match result {
                 Ok(_) => {
-                    return;
+                    return Ok(ctm_uuid.to_string());
                 }
                 Err(_) => {
-                    return;
+                    return Err("Failed to insert new countermeasure".to_owned());
                 }
             }
         }
-
-        println!("No database connection");
-        return;
-    }
-
-}
-
-
-//                                                 
-//   _____ _         _____                     _   
-//  | __  |_|___ ___|   __|_ _ ___ ___ ___ ___| |_ 
-//  | __ -| | -_|   |__   | | | . | . | . |  _|  _|
-//  |_____|_|___|_|_|_____|___|  _|  _|___|_| |_|  
-//                            |_| |_|              
-#[derive(Debug, Clone)]
-pub struct BienSupport {
-    pub support_id: i32,
-    pub valeur_id: i32,
-    pub support_name: String,
-    pub support_description: String,
-    pub support_responsable: String,
-}
-
-impl BienSupport {
-    pub fn default() -> Self {
-        BienSupport {
-            support_id: 0,
-            valeur_id: 0,
-            support_name: String::new(),
-            support_description: String::new(),
-            support_responsable: String::new(),
-        }
+    
+        return Err("No database connection".to_owned());
     }
-
-    pub async fn c1_create_asset(
-        vm_id: i32,
-        asset_name: String,
-        asset_description: String,
-        owner: String,
-    ) {
+    
+    pub async fn get_all_countermeasure_from_risk_uuid(risk_uuid: String) -> Vec<Countermeasure> {
         // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
-        let lock_result = unsafe { DB_CLIENT.lock() };
+        let lock_result = DB_CLIENT.lock();
     
         if lock_result.is_err() {
             // kill script
@@ -1787,33 +1664,40 @@ impl BienSupport {
         }
     
         // perform database operations
-        let db_client = unsafe { DB_CLIENT.lock().unwrap() };
+        let db_client = DB_CLIENT.lock().unwrap();
     
         let db_client = db_client.as_ref();
     
+        let mut countermeasures: Vec<Countermeasure> = Vec::new();
+    
         if let Some(pool) = db_client {
             let mut conn = pool.get_conn().unwrap();
-            let query = format!("INSERT INTO c1_bien_support (valeur_id, support_name, support_description, support_responsable) VALUES ('{}', '{}', '{}', '{}')", vm_id, asset_name, asset_description, owner);
+            let query = format!("SELECT {} FROM countermeasure WHERE ctm_uuid IN (SELECT ctm_uuid FROM countermeasure_scenario WHERE scenario_uuid IN (SELECT scenario_uuid FROM scenario WHERE risk_uuid = '{}')) ORDER BY title ASC", ctm_columns(), risk_uuid);
     
-            let result = conn.query_drop(query);
+            let result = conn.query_map(query, Countermeasure::from_row);
     
+            // check how many rows are returned
             match result {
-                Ok(_) => {
-                    return;
+                Ok(fetched_countermeasures) => {
+                    for countermeasure in fetched_countermeasures {
+                        countermeasures.push(countermeasure);
+                    }
                 }
                 Err(_) => {
-                    return;
+                    return countermeasures;
                 }
             }
+    
+            return countermeasures;
         }
     
         println!("No database connection");
-        return;
+        return countermeasures;
     }
     
-    pub async fn c1_get_asset_by_vmid(vm_id: i32) -> Vec<BienSupport> {
+    pub async fn get_ctm_by_id(ctm_uuid: String) -> Vec<Countermeasure> {
         // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
-        let lock_result = unsafe { DB_CLIENT.lock() };
+        let lock_result = DB_CLIENT.lock();
     
         if lock_result.is_err() {
             // kill script
@@ -1826,61 +1710,55 @@ impl BienSupport {
             new_client().await;
         }
     
-        let mut assets: Vec<BienSupport> = Vec::new();
-    
         // perform database operations
-        let db_client = unsafe { DB_CLIENT.lock().unwrap() };
+        let db_client = DB_CLIENT.lock().unwrap();
     
         let db_client = db_client.as_ref();
     
+        let mut countermeasures: Vec<Countermeasure> = Vec::new();
+    
         if let Some(pool) = db_client {
             let mut conn = pool.get_conn().unwrap();
             let query = format!(
-                "SELECT * FROM c1_bien_support WHERE valeur_id = '{}' ORDER BY support_id ASC",
-                vm_id
+                "SELECT {} FROM countermeasure WHERE ctm_uuid = '{}' ORDER BY title ASC",
+                ctm_columns(), ctm_uuid
             );
     
-            let result = conn.query_map(
-                query,
-                |(support_id, valeur_id, support_name, support_description, support_responsable): (
-                    i32,
-                    i32,
-                    String,
-                    String,
-                    String,
-                )| {
-                    BienSupport {
-                        support_id,
-                        valeur_id,
-                        support_name,
-                        support_description,
-                        support_responsable,
-                    }
-                },
-            );
+            let result = conn.query_map(query, Countermeasure::from_row);
     
             // check how many rows are returned
             match result {
-                Ok(fetched_assets) => {
-                    for asset in fetched_assets {
-                        assets.push(asset);
+                Ok(fetched_countermeasures) => {
+                    for countermeasure in fetched_countermeasures {
+                        countermeasures.push(countermeasure);
                     }
                 }
                 Err(_) => {
-                    return assets;
+                    return countermeasures;
                 }
             }
     
-            return assets;
+            return countermeasures;
         }
     
         println!("No database connection");
-        return assets;
+        return countermeasures;
     }
     
-    pub async fn c1_delete_asset_by_id(asset_id: i32) {
+    pub async fn update_countermeasure(
+        ctm_uuid: String,
+        title: String,
+        description: String,
+        solved: i32,
+        solved_description: String,
+        owner: String,
+        due_date: String,
+        priority: String,
+        cost: i32,
+        effort: i32,
+    ) -> Result<(), String> {
         // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
-        let lock_result = unsafe { DB_CLIENT.lock() };
+        let lock_result = DB_CLIENT.lock();
     
         if lock_result.is_err() {
             // kill script
@@ -1894,260 +1772,223 @@ impl BienSupport {
         }
     
         // perform database operations
-        let db_client = unsafe { DB_CLIENT.lock().unwrap() };
+        let db_client = DB_CLIENT.lock().unwrap();
     
         let db_client = db_client.as_ref();
     
         if let Some(pool) = db_client {
             let mut conn = pool.get_conn().unwrap();
-            let query = format!(
-                "DELETE FROM c1_bien_support WHERE support_id = '{}'",
-                asset_id
-            );
+            let query = format!("UPDATE countermeasure SET title = '{}', description = '{}', solved = '{}', solved_description = '{}', owner = '{}', due_date = {}, priority = '{}', cost = '{}', effort = '{}' WHERE ctm_uuid = '{}'", title, description, solved, solved_description, owner, sql_date(&due_date), priority, cost, effort, ctm_uuid);
     
             let result = conn.query_drop(query);
     
             match result {
                 Ok(_) => {
-                    return;
+                    return Ok(());
                 }
                 Err(_) => {
-                    return;
+                    return Err("Failed to update countermeasure".to_owned());
                 }
             }
         }
     
-        println!("No database connection");
-        return;
+        return Err("No database connection".to_owned());
     }
     
-}
+    /// Countermeasures closing a gap
+    pub async fn get_countermeasures_of_gap(gap_id: i32) -> Vec<Countermeasure> {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
 
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
 
-//                                                   
-//   _____                   _ _____             _   
-//  |   __|___ ___ ___ ___ _| |   __|_ _ ___ ___| |_ 
-//  |   __| -_| .'|  _| -_| . |   __| | | -_|   |  _|
-//  |__|  |___|__,|_| |___|___|_____|\_/|___|_|_|_|  
-//                                                   
-#[derive(Debug, Clone)]
-pub struct FearedEvent {
-    pub event_id: i32,
-    pub valeur_metier: i32,
-    pub evenement_redoute: String,
-    pub impact: String,
-    pub gravite: i32,
-}
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
 
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
 
-impl FearedEvent {
-    pub fn default() -> Self {
-        FearedEvent {
-            event_id: 0,
-            valeur_metier: 0,
-            evenement_redoute: String::new(),
-            impact: String::new(),
-            gravite: 0,
+        let db_client = db_client.as_ref();
+
+        let mut countermeasures: Vec<Countermeasure> = Vec::new();
+
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+            let query = format!("SELECT {} FROM countermeasure WHERE ctm_uuid IN (SELECT ctm_uuid FROM countermeasure_gap WHERE gap_id = '{}') ORDER BY title ASC", ctm_columns(), gap_id);
+
+            let result = conn.query_map(query, Countermeasure::from_row);
+
+            // check how many rows are returned
+            match result {
+                Ok(fetched_countermeasures) => {
+                    for countermeasure in fetched_countermeasures {
+                        countermeasures.push(countermeasure);
+                    }
+                }
+                Err(_) => {
+                    return countermeasures;
+                }
+            }
+
+            return countermeasures;
         }
-    }   
 
-    pub async fn c1_feared_event_create(
-        event_name: String,
-        impacts: String,
-        valeur_metier_id: i32,
-        gravity: i32,
-    ) {
+        println!("No database connection");
+        countermeasures
+    }
+
+    /// Every countermeasure of the study, the catalogue of measures
+    pub async fn get_all_countermeasures() -> Vec<Countermeasure> {
         // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
-        let lock_result = unsafe { DB_CLIENT.lock() };
-    
+        let lock_result = DB_CLIENT.lock();
+
         if lock_result.is_err() {
             // kill script
             trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
             std::process::exit(1);
         }
-    
+
         // check if need to create new client
         if lock_result.unwrap().is_none() {
             new_client().await;
         }
-    
+
         // perform database operations
-        let db_client = unsafe { DB_CLIENT.lock().unwrap() };
-    
+        let db_client = DB_CLIENT.lock().unwrap();
+
         let db_client = db_client.as_ref();
-    
+
+        let mut countermeasures: Vec<Countermeasure> = Vec::new();
+
         if let Some(pool) = db_client {
             let mut conn = pool.get_conn().unwrap();
-            let query = format!("INSERT INTO c1_feared_event (evenement_redoute, impact, valeur_metier, gravite) VALUES ('{}', '{}', '{}', '{}')", event_name, impacts, valeur_metier_id, gravity);
-    
-            let result = conn.query_drop(query);
-    
+            let query = format!("SELECT {} FROM countermeasure ORDER BY title ASC", ctm_columns());
+
+            let result = conn.query_map(query, Countermeasure::from_row);
+
+            // check how many rows are returned
             match result {
-                Ok(_) => {
-                    return;
+                Ok(fetched_countermeasures) => {
+                    for countermeasure in fetched_countermeasures {
+                        countermeasures.push(countermeasure);
+                    }
                 }
                 Err(_) => {
-                    return;
+                    return countermeasures;
                 }
             }
+
+            return countermeasures;
         }
-    
+
         println!("No database connection");
-        return;
+        countermeasures
     }
-    
-    pub async fn c1_delete_feared_event(event_id: i32) {
+
+    /// Move a countermeasure to another step of the workflow, the caller checks
+    /// the transition is allowed
+    pub async fn update_countermeasure_status(ctm_uuid: String, status: String, solved: i32) -> Result<(), String> {
         // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
-        let lock_result = unsafe { DB_CLIENT.lock() };
-    
+        let lock_result = DB_CLIENT.lock();
+
         if lock_result.is_err() {
             // kill script
             trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
             std::process::exit(1);
         }
-    
+
         // check if need to create new client
         if lock_result.unwrap().is_none() {
             new_client().await;
         }
-    
+
         // perform database operations
-        let db_client = unsafe { DB_CLIENT.lock().unwrap() };
-    
+        let db_client = DB_CLIENT.lock().unwrap();
+
         let db_client = db_client.as_ref();
-    
+
         if let Some(pool) = db_client {
             let mut conn = pool.get_conn().unwrap();
-            let query = format!(
-                "DELETE FROM c1_feared_event WHERE event_id = '{}'",
-                event_id
-            );
-    
+            let query = format!("UPDATE countermeasure SET status = '{}', solved = '{}' WHERE ctm_uuid = '{}'", status, solved, ctm_uuid);
+
             let result = conn.query_drop(query);
-    
+
             match result {
                 Ok(_) => {
-                    return;
+                    return Ok(());
                 }
                 Err(_) => {
-                    return;
+                    return Err("Failed to update countermeasure status".to_owned());
                 }
             }
         }
-    
-        println!("No database connection");
-        return;
+
+        Err("No database connection".to_owned())
     }
-    
-    pub async fn c1_get_all_feared_event() -> Vec<FearedEvent> {
+
+    /// Countermeasures of every risk past their due date and not implemented yet,
+    /// the latest first
+    pub async fn get_overdue_countermeasures(today: String) -> Vec<Countermeasure> {
         // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
-        let lock_result = unsafe { DB_CLIENT.lock() };
-    
+        let lock_result = DB_CLIENT.lock();
+
         if lock_result.is_err() {
             // kill script
             trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
             std::process::exit(1);
         }
-    
+
         // check if need to create new client
         if lock_result.unwrap().is_none() {
             new_client().await;
         }
-    
+
         // perform database operations
-        let db_client = unsafe { DB_CLIENT.lock().unwrap() };
-    
+        let db_client = DB_CLIENT.lock().unwrap();
+
         let db_client = db_client.as_ref();
-    
-        let mut events: Vec<FearedEvent> = Vec::new();
-    
+
+        let mut countermeasures: Vec<Countermeasure> = Vec::new();
+
         if let Some(pool) = db_client {
             let mut conn = pool.get_conn().unwrap();
-    
-            let query = format!("SELECT event_id, evenement_redoute, impact, valeur_metier, gravite FROM c1_feared_event ORDER BY event_id ASC");
-    
-            let result = conn.query_map(
-                query,
-                |(event_id, evenement_redoute, impact, valeur_metier, gravite): (
-                    i32,
-                    String,
-                    String,
-                    i32,
-                    i32,
-                )| {
-                    FearedEvent {
-                        event_id,
-                        evenement_redoute,
-                        impact,
-                        valeur_metier,
-                        gravite,
-                    }
-                },
+            let query = format!(
+                "SELECT {} FROM countermeasure WHERE due_date IS NOT NULL AND due_date < '{}' AND status NOT IN ('implemented', 'verified') ORDER BY due_date ASC, title ASC",
+                ctm_columns(), today
             );
-    
+
+            let result = conn.query_map(query, Countermeasure::from_row);
+
             // check how many rows are returned
             match result {
-                Ok(fetched_events) => {
-                    for event in fetched_events {
-                        events.push(event);
+                Ok(fetched_countermeasures) => {
+                    for countermeasure in fetched_countermeasures {
+                        countermeasures.push(countermeasure);
                     }
                 }
                 Err(_) => {
-                    return events;
+                    return countermeasures;
                 }
             }
-    
-            return events;
-        }
-    
-        println!("No database connection");
-        return events;
-    }
-    
-}
-
-
-//                 
-//   _____         
-//  |   __|___ ___ 
-//  |  |  | .'| . |
-//  |_____|__,|  _|
-//            |_|  
-#[derive(Debug, Clone)]
-pub struct Gap {
-    pub gap_id: i32,
-    pub referential_type: String,
-    pub referential_name: String,
-    pub application_state: i32,
-    pub gap: String,
-    pub gap_justification: String,
-    pub proposed_measures: String,
-}
 
-impl Gap {
-    pub fn default() -> Self {
-        Gap {
-            gap_id: 0,
-            referential_type: String::new(),
-            referential_name: String::new(),
-            application_state: 0,
-            gap: String::new(),
-            gap_justification: String::new(),
-            proposed_measures: String::new(),
+            return countermeasures;
         }
-    }
 
+        println!("No database connection");
+        countermeasures
+    }
 
-    pub async fn c1_create_gap(
-        g_ref_type: String,
-        g_ref_name: String,
-        g_state: i32,
-        g_gap: String,
-        g_gap_why: String,
-        g_gap_counter: String,
-    ) {
+    /// Countermeasures created before the workflow get a status matching their
+    /// solved percentage
+    pub async fn migrate_legacy_status() {
         // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
-        let lock_result = unsafe { DB_CLIENT.lock() };
+        let lock_result = DB_CLIENT.lock();
 
         if lock_result.is_err() {
             // kill script
@@ -2161,148 +2002,125 @@ impl Gap {
         }
 
         // perform database operations
-        let db_client = unsafe { DB_CLIENT.lock().unwrap() };
+        let db_client = DB_CLIENT.lock().unwrap();
 
         let db_client = db_client.as_ref();
 
         if let Some(pool) = db_client {
             let mut conn = pool.get_conn().unwrap();
-
-            let query = format!("INSERT INTO c1_gaps (referential_type, referential_name, application_state, gap, gap_justification, proposed_measures) VALUES ('{}', '{}', '{}', '{}', '{}', '{}')", g_ref_type, g_ref_name, g_state, g_gap, g_gap_why, g_gap_counter);
-
-            let result = conn.query_drop(query);
-
-            match result {
-                Ok(_) => {
-                    return;
-                }
-                Err(_) => {
-                    return;
-                }
-            }
+            let _ = conn.query_drop("UPDATE countermeasure SET status = CASE WHEN solved >= 100 THEN 'implemented' WHEN solved > 0 THEN 'in_progress' ELSE 'proposed' END WHERE status = ''");
+            return;
         }
 
         println!("No database connection");
-        return;
     }
 
-    pub async fn c1_delete_gap(gap_id: i32) {
+    pub async fn delete_countermeasure(ctm_uuid: String) -> Result<(), String> {
         // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
-        let lock_result = unsafe { DB_CLIENT.lock() };
-
+        let lock_result = DB_CLIENT.lock();
+    
         if lock_result.is_err() {
             // kill script
             trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
             std::process::exit(1);
         }
-
+    
         // check if need to create new client
         if lock_result.unwrap().is_none() {
             new_client().await;
         }
-
+    
         // perform database operations
-        let db_client = unsafe { DB_CLIENT.lock().unwrap() };
-
+        let db_client = DB_CLIENT.lock().unwrap();
+    
         let db_client = db_client.as_ref();
-
+    
         if let Some(pool) = db_client {
             let mut conn = pool.get_conn().unwrap();
-
-            let query = format!("DELETE FROM c1_gaps WHERE gap_id = '{}'", gap_id);
-
+            let query = format!("DELETE FROM countermeasure WHERE ctm_uuid = '{}'", ctm_uuid);
+            let _ = conn.query_drop(format!("DELETE FROM countermeasure_scenario WHERE ctm_uuid = '{}'", ctm_uuid));
+            let _ = conn.query_drop(format!("DELETE FROM countermeasure_gap WHERE ctm_uuid = '{}'", ctm_uuid));
+    
             let result = conn.query_drop(query);
-
+    
             match result {
                 Ok(_) => {
-                    return;
+                    return Ok(());
                 }
                 Err(_) => {
-                    return;
+                    return Err("Failed to delete countermeasure".to_owned());
                 }
             }
         }
-
-        println!("No database connection");
-        return;
+    
+        return Err("No database connection".to_owned());
     }
-
-    pub async fn c1_get_all_gaps() -> Vec<Gap> {
+    
+    pub async fn delete_countermeasure_from_sc(scenario_uuid: String) -> Result<(), String> {
         // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
-        let lock_result = unsafe { DB_CLIENT.lock() };
-
+        let lock_result = DB_CLIENT.lock();
+    
         if lock_result.is_err() {
             // kill script
-            trace_logs("Error: DB_CLIENT.lock().unwrap().is_none() return any poison".to_owned());
+            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
             std::process::exit(1);
         }
-
+    
         // check if need to create new client
         if lock_result.unwrap().is_none() {
             new_client().await;
         }
-
+    
         // perform database operations
-        let db_client = unsafe { DB_CLIENT.lock().unwrap() };
-
+        let db_client = DB_CLIENT.lock().unwrap();
+    
         let db_client = db_client.as_ref();
-
-        let mut gaps: Vec<Gap> = Vec::new();
-
+    
         if let Some(pool) = db_client {
             let mut conn = pool.get_conn().unwrap();
-
-            let query = format!("SELECT gap_id, referential_type, referential_name, application_state, gap, gap_justification, proposed_measures FROM c1_gaps ORDER BY gap_id ASC");
-
-            let result = conn.query_map(
-                query,
-                |(
-                    gap_id,
-                    referential_type,
-                    referential_name,
-                    application_state,
-                    gap,
-                    gap_justification,
-                    proposed_measures,
-                ): (i32, String, String, i32, String, String, String)| {
-                    Gap {
-                        gap_id,
-                        referential_type,
-                        referential_name,
-                        application_state,
-                        gap,
-                        gap_justification,
-                        proposed_measures,
-                    }
-                },
+            // the measures only this scenario used go with it, the shared ones
+            // stay in the catalogue
+            let _ = conn.query_drop(format!("DELETE FROM countermeasure_scenario WHERE scenario_uuid = '{}'", scenario_uuid));
+            let _ = conn.query_drop(format!("UPDATE countermeasure SET scenario_uuid = '{}' WHERE scenario_uuid = '{}' AND ctm_uuid IN (SELECT ctm_uuid FROM countermeasure_scenario)", Uuid::nil(), scenario_uuid));
+            let query = format!(
+                "DELETE FROM countermeasure WHERE scenario_uuid = '{}'",
+                scenario_uuid
             );
-
-            // check how many rows are returned
+    
+            let result = conn.query_drop(query);
+    
             match result {
-                Ok(fetched_gaps) => {
-                    for gap in fetched_gaps {
-                        gaps.push(gap);
-                    }
+                Ok(_) => {
+                    let _ = conn.query_drop("DELETE FROM countermeasure_gap WHERE ctm_uuid NOT IN (SELECT ctm_uuid FROM countermeasure)");
+                    return Ok(());
                 }
                 Err(_) => {
-                    return gaps;
+                    return Err("Failed to delete countermeasure".to_owned());
                 }
             }
-
-            return gaps;
         }
-
-        println!("No database connection");
-        return gaps;
+    
+        return Err("No database connection".to_owned());
     }
+    
 
-    pub async fn c1_get_gaps_by_id(gap_id: i32) -> Vec<Gap> {
+}
+
+/// Many-to-many link between a countermeasure and a scenario it mitigates
+#[derive(Debug, Clone, Serialize)]
+pub struct CountermeasureLink {
+    pub ctm_uuid: Uuid,
+    pub scenario_uuid: Uuid,
+}
+
+impl CountermeasureLink {
+    pub async fn get_all_countermeasure_links() -> Vec<CountermeasureLink> {
         // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
-        let lock_result = unsafe { DB_CLIENT.lock() };
+        let lock_result = DB_CLIENT.lock();
 
         if lock_result.is_err() {
             // kill script
-            trace_logs("Error: DB_CLIENT.lock().unwrap().is_none() return any poison".to_owned());
+            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
             std::process::exit(1);
         }
 
@@ -2311,255 +2129,337 @@ impl Gap {
             new_client().await;
         }
 
-        let mut gaps: Vec<Gap> = Vec::new();
-
         // perform database operations
-        let db_client = unsafe { DB_CLIENT.lock().unwrap() };
+        let db_client = DB_CLIENT.lock().unwrap();
 
         let db_client = db_client.as_ref();
 
+        let mut links: Vec<CountermeasureLink> = Vec::new();
+
         if let Some(pool) = db_client {
             let mut conn = pool.get_conn().unwrap();
 
-            let query = format!("SELECT gap_id, referential_type, referential_name, application_state, gap, gap_justification, proposed_measures FROM c1_gaps WHERE gap_id = '{}' ORDER BY gap_id ASC", gap_id);
-
             let result = conn.query_map(
-                query,
-                |(
-                    gap_id,
-                    referential_type,
-                    referential_name,
-                    application_state,
-                    gap,
-                    gap_justification,
-                    proposed_measures,
-                ): (i32, String, String, i32, String, String, String)| {
-                    Gap {
-                        gap_id,
-                        referential_type,
-                        referential_name,
-                        application_state,
-                        gap,
-                        gap_justification,
-                        proposed_measures,
+                "SELECT ctm_uuid, scenario_uuid FROM countermeasure_scenario",
+                |(ctm_uuid, scenario_uuid): (String, String)| {
+                    CountermeasureLink {
+                        ctm_uuid: Uuid::parse_str(&ctm_uuid).unwrap(),
+                        scenario_uuid: Uuid::parse_str(&scenario_uuid).unwrap(),
                     }
                 },
             );
 
             // check how many rows are returned
             match result {
-                Ok(fetched_gaps) => {
-                    for gap in fetched_gaps {
-                        gaps.push(gap);
+                Ok(fetched_links) => {
+                    for link in fetched_links {
+                        links.push(link);
                     }
                 }
                 Err(_) => {
-                    return gaps;
+                    return links;
                 }
             }
 
-            return gaps;
+            return links;
         }
 
         println!("No database connection");
-        return gaps;
+        links
     }
 
-}
+    pub async fn link_countermeasure(ctm_uuid: String, scenario_uuid: String) {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
 
-//                                                           
-//   _____ ___ _____ _     _   _____                         
-//  |     |_  | __  |_|___| |_|   __|___ _ _ ___ ___ ___ ___ 
-//  |   --|  _|    -| |_ -| '_|__   | . | | |  _|  _| -_|_ -|
-//  |_____|___|__|__|_|___|_,_|_____|___|___|_| |___|___|___|
-//                                                           
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
 
-#[derive(Debug, Clone)]
-pub struct C2RiskSources {
-    pub risk_id: i32,
-    pub source_risque: String,
-    pub objectifs_vises: String,
-    pub motivation: Option<String>,
-    pub ressources: Option<String>,
-    pub pertinence_sr_ov: Option<i32>,
-    pub priorite: Option<i32>,
-    pub retenu: bool,
-    pub justification_exclusion_sr_ov: Option<String>,
-}
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
 
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
 
-impl C2RiskSources {
-    pub fn default() -> Self {
-        C2RiskSources {
-            risk_id: 0,
-            source_risque: String::new(),
-            objectifs_vises: String::new(),
-            motivation: None,
-            ressources: None,
-            pertinence_sr_ov: None,
-            priorite: None,
-            retenu: false,
-            justification_exclusion_sr_ov: None,
+        let db_client = db_client.as_ref();
+
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+            let query = format!("INSERT INTO countermeasure_scenario (ctm_uuid, scenario_uuid) SELECT '{0}', '{1}' FROM DUAL WHERE NOT EXISTS (SELECT 1 FROM countermeasure_scenario WHERE ctm_uuid = '{0}' AND scenario_uuid = '{1}')", ctm_uuid, scenario_uuid);
+
+            let _ = conn.query_drop(query);
+            return;
         }
+
+        println!("No database connection");
     }
 
-    pub async fn c2_get_all_risk() -> Vec<C2RiskSources> {
+    pub async fn unlink_countermeasure(ctm_uuid: String, scenario_uuid: String) {
         // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
-        let lock_result = unsafe { DB_CLIENT.lock() };
-    
+        let lock_result = DB_CLIENT.lock();
+
         if lock_result.is_err() {
             // kill script
-            trace_logs("Error: DB_CLIENT.lock().unwrap().is_none() return any poison".to_owned());
+            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
             std::process::exit(1);
         }
-    
+
         // check if need to create new client
         if lock_result.unwrap().is_none() {
             new_client().await;
         }
-    
+
         // perform database operations
-        let db_client = unsafe { DB_CLIENT.lock().unwrap() };
-    
+        let db_client = DB_CLIENT.lock().unwrap();
+
         let db_client = db_client.as_ref();
-    
-        let mut risks: Vec<C2RiskSources> = Vec::new();
-    
+
         if let Some(pool) = db_client {
             let mut conn = pool.get_conn().unwrap();
-    
-            let query = format!("SELECT risk_id, source_risque, objectifs_vises, motivation, ressources, pertinence_sr_ov, priorite, retenu, justification_exclusion_sr_ov FROM c2_risk_sources ORDER BY risk_id ASC");
-    
-            let result = conn.query_map(
-                query,
-                |(
-                    risk_id,
-                    source_risque,
-                    objectifs_vises,
-                    motivation,
-                    ressources,
-                    pertinence_sr_ov,
-                    priorite,
-                    retenu,
-                    justification_exclusion_sr_ov,
-                ): (
-                    i32,
-                    String,
-                    String,
-                    Option<String>,
-                    Option<String>,
-                    Option<i32>,
-                    Option<i32>,
-                    bool,
-                    Option<String>,
-                )| {
-                    C2RiskSources {
-                        risk_id,
-                        source_risque,
-                        objectifs_vises,
-                        motivation,
-                        ressources,
-                        pertinence_sr_ov,
-                        priorite,
-                        retenu,
-                        justification_exclusion_sr_ov,
-                    }
-                },
-            );
-    
-            // check how many rows are returned
-            match result {
-                Ok(fetched_risks) => {
-                    for risk in fetched_risks {
-                        risks.push(risk);
-                    }
-                }
-                Err(_) => {
-                    return risks;
-                }
+            let query = format!("DELETE FROM countermeasure_scenario WHERE ctm_uuid = '{}' AND scenario_uuid = '{}'", ctm_uuid, scenario_uuid);
+
+            // unlinked from the scenario it was created for, the measure now
+            // belongs to the catalogue
+            if conn.query_drop(query).is_ok() {
+                let _ = conn.query_drop(format!("UPDATE countermeasure SET scenario_uuid = '{}' WHERE ctm_uuid = '{}' AND scenario_uuid = '{}'", Uuid::nil(), ctm_uuid, scenario_uuid));
             }
-    
-            return risks;
+            return;
         }
-    
+
         println!("No database connection");
-        return risks;
     }
-    
-    pub async fn c2_create_risk(
-        source_risque: String,
-        objectifs_vises: String,
-        motivation: String,
-        ressources: String,
-        pertinence_sr_ov: i32,
-        priorite: i32,
-        retenu: bool,
-        justification_exclusion_sr_ov: String,
-    ) {
+
+    /// Countermeasures used to belong to a single scenario
+    pub async fn migrate_legacy_links() {
         // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
-        let lock_result = unsafe { DB_CLIENT.lock() };
-    
+        let lock_result = DB_CLIENT.lock();
+
         if lock_result.is_err() {
             // kill script
-            trace_logs("Error: DB_CLIENT.lock().unwrap().is_none() return any poison".to_owned());
+            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
             std::process::exit(1);
         }
-    
+
         // check if need to create new client
         if lock_result.unwrap().is_none() {
             new_client().await;
         }
-    
+
         // perform database operations
-        let db_client = unsafe { DB_CLIENT.lock().unwrap() };
-    
+        let db_client = DB_CLIENT.lock().unwrap();
+
         let db_client = db_client.as_ref();
-    
+
         if let Some(pool) = db_client {
             let mut conn = pool.get_conn().unwrap();
-    
-            let query = format!("INSERT INTO c2_risk_sources (source_risque, objectifs_vises, motivation, ressources, pertinence_sr_ov, priorite, retenu, justification_exclusion_sr_ov) VALUES ('{}', '{}', '{}', '{}', '{}', '{}', {}, '{}')", source_risque, objectifs_vises, motivation, ressources, pertinence_sr_ov, priorite, retenu, justification_exclusion_sr_ov);
-    
-            let result = conn.query_drop(query);
-    
-            match result {
-                Ok(_) => {
-                    return;
-                }
-                Err(_) => {
-                    return;
-                }
-            }
+            let query = format!("INSERT INTO countermeasure_scenario (ctm_uuid, scenario_uuid) SELECT ctm_uuid, scenario_uuid FROM countermeasure c WHERE scenario_uuid != '{}' AND NOT EXISTS (SELECT 1 FROM countermeasure_scenario l WHERE l.ctm_uuid = c.ctm_uuid)", Uuid::nil());
+
+            let _ = conn.query_drop(query);
+            return;
         }
-    
+
         println!("No database connection");
-        return;
     }
-    
-    pub async fn c2_delete_risk_by_id(risk_id: i32) {
+}
+
+/// The progress of a countermeasure, recorded each time it changes
+#[derive(Debug, Clone, Serialize)]
+pub struct CountermeasureHistory {
+    pub ctm_uuid: Uuid,
+    pub solved: i32,
+    pub status: String,
+    /// `YYYY-MM-DD HH:MM:SS`
+    pub recorded_at: String,
+}
+
+impl CountermeasureHistory {
+    pub async fn get_all_history() -> Vec<CountermeasureHistory> {
         // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
-        let lock_result = unsafe { DB_CLIENT.lock() };
-    
+        let lock_result = DB_CLIENT.lock();
+
         if lock_result.is_err() {
             // kill script
-            trace_logs("Error: DB_CLIENT.lock().unwrap().is_none() return any poison".to_owned());
+            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
             std::process::exit(1);
         }
-    
+
         // check if need to create new client
         if lock_result.unwrap().is_none() {
             new_client().await;
         }
-    
+
         // perform database operations
-        let db_client = unsafe { DB_CLIENT.lock().unwrap() };
-    
+        let db_client = DB_CLIENT.lock().unwrap();
+
         let db_client = db_client.as_ref();
-    
-        if let Some(pool) = db_client {
+
+        let mut history: Vec<CountermeasureHistory> = Vec::new();
+
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+
+            let result = conn.query_map(
+                format!("SELECT ctm_uuid, solved, status, {} FROM countermeasure_history ORDER BY history_id ASC", dialect().date_format("recorded_at", "%Y-%m-%d %H:%i:%s")),
+                |(ctm_uuid, solved, status, recorded_at): (String, i32, String, String)| CountermeasureHistory {
+                    ctm_uuid: Uuid::parse_str(&ctm_uuid).unwrap(),
+                    solved,
+                    status,
+                    recorded_at,
+                },
+            );
+
+            // check how many rows are returned
+            match result {
+                Ok(fetched_history) => {
+                    for entry in fetched_history {
+                        history.push(entry);
+                    }
+                }
+                Err(_) => {
+                    return history;
+                }
+            }
+
+            return history;
+        }
+
+        println!("No database connection");
+        history
+    }
+
+    /// Record the countermeasures whose progress differs from their last entry, or have none yet
+    pub async fn record_changes() {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+
+        let db_client = db_client.as_ref();
+
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+            let _ = conn.query_drop("INSERT INTO countermeasure_history (ctm_uuid, solved, status) SELECT c.ctm_uuid, c.solved, c.status FROM countermeasure c WHERE NOT EXISTS (SELECT 1 FROM countermeasure_history h WHERE h.history_id = (SELECT MAX(l.history_id) FROM countermeasure_history l WHERE l.ctm_uuid = c.ctm_uuid) AND h.solved = c.solved AND h.status = c.status)");
+            return;
+        }
+
+        println!("No database connection");
+    }
+}
+
+
+//                             
+//   _____ _         _         
+//  |     |_|___ ___|_|___ ___ 
+//  | | | | |_ -|_ -| | . |   |
+//  |_|_|_|_|___|___|_|___|_|_|
+//                             
+#[derive(Debug, Clone, Serialize)]
+pub struct Mission {
+    pub mission_id: i32,
+    pub mission_name: String,
+}
+
+impl Mission {
+    pub fn default() -> Self {
+        Mission {
+            mission_id: 0,
+            mission_name: String::new(),
+        }
+    }
+
+    pub async fn c1_get_all_missions() -> Vec<Mission> {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+    
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+    
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+    
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+    
+        let db_client = db_client.as_ref();
+    
+        let mut missions: Vec<Mission> = Vec::new();
+    
+        if let Some(pool) = db_client {
             let mut conn = pool.get_conn().unwrap();
+            let query = format!("SELECT * FROM c1_mission ORDER BY mission_id ASC");
     
-            let query = format!("DELETE FROM c2_risk_sources WHERE risk_id = '{}'", risk_id);
+            let result = conn.query_map(query, |(mission_id, mission_name): (i32, String)| Mission {
+                mission_id,
+                mission_name,
+            });
+    
+            // check how many rows are returned
+            match result {
+                Ok(fetched_missions) => {
+                    for mission in fetched_missions {
+                        missions.push(mission);
+                    }
+                }
+                Err(_) => {
+                    return missions;
+                }
+            }
+    
+            return missions;
+        }
+    
+        println!("No database connection");
+        return missions;
+    }
+    
+    pub async fn c1_create_mission(mission_name: String) {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+    
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+    
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+    
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+    
+        let db_client = db_client.as_ref();
+    
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+            let query = format!(
+                "INSERT INTO c1_mission (mission_name) VALUES ('{}')",
+                mission_name
+            );
     
             let result = conn.query_drop(query);
     
@@ -2577,13 +2477,13 @@ impl C2RiskSources {
         return;
     }
     
-    pub async fn c2_get_risk_detail(risk_id: i32) -> Vec<C2RiskSources> {
+    pub async fn c1_get_mission_by_id(mission_id: i32) -> Vec<Mission> {
         // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
-        let lock_result = unsafe { DB_CLIENT.lock() };
+        let lock_result = DB_CLIENT.lock();
     
         if lock_result.is_err() {
             // kill script
-            trace_logs("Error: DB_CLIENT.lock().unwrap().is_none() return any poison".to_owned());
+            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
             std::process::exit(1);
         }
     
@@ -2592,340 +2492,4668 @@ impl C2RiskSources {
             new_client().await;
         }
     
-        let mut risks: Vec<C2RiskSources> = Vec::new();
+        let mut missions: Vec<Mission> = Vec::new();
     
         // perform database operations
-        let db_client = unsafe { DB_CLIENT.lock().unwrap() };
+        let db_client = DB_CLIENT.lock().unwrap();
     
         let db_client = db_client.as_ref();
     
         if let Some(pool) = db_client {
             let mut conn = pool.get_conn().unwrap();
-    
-            let query = format!("SELECT risk_id, source_risque, objectifs_vises, motivation, ressources, pertinence_sr_ov, priorite, retenu, justification_exclusion_sr_ov FROM c2_risk_sources WHERE risk_id = '{}' ORDER BY risk_id ASC", risk_id);
-    
-            let result = conn.query_map(
-                query,
-                |(
-                    risk_id,
-                    source_risque,
-                    objectifs_vises,
-                    motivation,
-                    ressources,
-                    pertinence_sr_ov,
-                    priorite,
-                    retenu,
-                    justification_exclusion_sr_ov,
-                ): (
-                    i32,
-                    String,
-                    String,
-                    Option<String>,
-                    Option<String>,
-                    Option<i32>,
-                    Option<i32>,
-                    bool,
-                    Option<String>,
-                )| {
-                    C2RiskSources {
-                        risk_id,
-                        source_risque,
-                        objectifs_vises,
-                        motivation,
-                        ressources,
-                        pertinence_sr_ov,
-                        priorite,
-                        retenu,
-                        justification_exclusion_sr_ov,
-                    }
-                },
+            let query = format!(
+                "SELECT * FROM c1_mission WHERE mission_id = '{}' ORDER BY mission_id ASC",
+                mission_id
             );
     
+            let result = conn.query_map(query, |(mission_id, mission_name): (i32, String)| Mission {
+                mission_id,
+                mission_name,
+            });
+    
             // check how many rows are returned
             match result {
-                Ok(fetched_risks) => {
-                    for risk in fetched_risks {
-                        risks.push(risk);
+                Ok(fetched_missions) => {
+                    for mission in fetched_missions {
+                        missions.push(mission);
                     }
                 }
                 Err(_) => {
-                    return risks;
+                    return missions;
                 }
             }
     
-            return risks;
+            return missions;
         }
     
         println!("No database connection");
-        return risks;
+        return missions;
     }
     
-}
+    pub async fn c1_delete_mission_by_id(mission_id: i32) {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+    
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+    
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+    
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+    
+        let db_client = db_client.as_ref();
+    
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+            let query = format!("DELETE FROM c1_mission WHERE mission_id = '{}'", mission_id);
+    
+            let result = conn.query_drop(query);
+    
+            match result {
+                Ok(_) => {
+                    return;
+                }
+                Err(_) => {
+                    return;
+                }
+            }
+        }
+    
+        println!("No database connection");
+        return;
+    }
+    
+    pub async fn c1_update_mission(mission_id: i32, mission_name: String) {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
 
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
 
-//                                                         
-//   _____ ___ _____ _       _       _       _   _         
-//  |     |_  |   __| |_ ___| |_ ___| |_ ___| |_| |___ ___ 
-//  |   --|_  |__   |  _| .'| '_| -_|   | . | | . | -_|  _|
-//  |_____|___|_____|_| |__,|_,_|___|_|_|___|_|___|___|_|  
-//        
-#[derive(Debug, Clone)]
-pub struct C3Stakeholder {
-    pub stakeholder_id: i32,
-    pub category: String,
-    pub stakeholder_name: String,
-    pub dependance: i32,
-    pub penetration: i32,
-    pub maturite_ssi: i32,
-    pub confiance: i32,
-}                                                 
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
 
-impl C3Stakeholder {
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+
+        let db_client = db_client.as_ref();
+
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+            let query = format!(
+                "UPDATE c1_mission SET mission_name = '{}' WHERE mission_id = '{}'",
+                mission_name, mission_id
+            );
+
+            let result = conn.query_drop(query);
+
+            match result {
+                Ok(_) => {
+                    return;
+                }
+                Err(_) => {
+                    return;
+                }
+            }
+        }
+
+        println!("No database connection");
+    }
+
+}
+
+
+//                                                   
+//   _____     _             _____     _   _         
+//  |  |  |___| |___ _ _ ___|     |___| |_|_|___ ___ 
+//  |  |  | .'| | -_| | |  _| | | | -_|  _| | -_|  _|
+//   \___/|__,|_|___|___|_| |_|_|_|___|_| |_|___|_|  
+//                                                   
+#[derive(Debug, Clone, Serialize)]
+pub struct ValeurMetier {
+    pub valeur_id: i32,
+    pub mission_id: i32,
+    pub valeur_name: String,
+    pub valeur_nature: String,
+    pub valeur_description: String,
+    pub responsable: String,
+}
+
+impl ValeurMetier {
     pub fn default() -> Self {
-        C3Stakeholder {
-            stakeholder_id: 0,
-            category: String::new(),
-            stakeholder_name: String::new(),
-            dependance: 0,
-            penetration: 0,
-            maturite_ssi: 0,
-            confiance: 0,
+        ValeurMetier {
+            valeur_id: 0,
+            mission_id: 0,
+            valeur_name: String::new(),
+            valeur_nature: String::new(),
+            valeur_description: String::new(),
+            responsable: String::new(),
+        }
+    }
+
+    pub async fn c1_get_all_valeurmetier(mission_id: i32) -> Vec<ValeurMetier> {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+    
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+    
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+    
+        let mut valeurs: Vec<ValeurMetier> = Vec::new();
+    
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+    
+        let db_client = db_client.as_ref();
+    
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+            let query = format!(
+                "SELECT * FROM c1_valeur_metier WHERE mission_id = '{}' ORDER BY valeur_id ASC",
+                mission_id
+            );
+    
+            let result = conn.query_map(
+                query,
+                |(
+                    valeur_id,
+                    mission_id,
+                    valeur_name,
+                    valeur_nature,
+                    valeur_description,
+                    responsable,
+                ): (i32, i32, String, String, String, String)| {
+                    ValeurMetier {
+                        valeur_id,
+                        mission_id,
+                        valeur_name,
+                        valeur_nature,
+                        valeur_description,
+                        responsable,
+                    }
+                },
+            );
+    
+            // check how many rows are returned
+            match result {
+                Ok(fetched_valeurs) => {
+                    for valeur in fetched_valeurs {
+                        valeurs.push(valeur);
+                    }
+                }
+                Err(_) => {
+                    return valeurs;
+                }
+            }
+    
+            return valeurs;
+        }
+    
+        println!("No database connection");
+        return valeurs;
+    }
+    
+    pub async fn c1_get_all_valeurmetier_no_limit() -> Vec<ValeurMetier> {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+    
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+    
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+    
+        let mut valeurs: Vec<ValeurMetier> = Vec::new();
+    
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+    
+        let db_client = db_client.as_ref();
+    
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+            let query = format!("SELECT * FROM c1_valeur_metier ORDER BY valeur_id ASC");
+    
+            let result = conn.query_map(
+                query,
+                |(
+                    valeur_id,
+                    mission_id,
+                    valeur_name,
+                    valeur_nature,
+                    valeur_description,
+                    responsable,
+                ): (i32, i32, String, String, String, String)| {
+                    ValeurMetier {
+                        valeur_id,
+                        mission_id,
+                        valeur_name,
+                        valeur_nature,
+                        valeur_description,
+                        responsable,
+                    }
+                },
+            );
+    
+            // check how many rows are returned
+            match result {
+                Ok(fetched_valeurs) => {
+                    for valeur in fetched_valeurs {
+                        valeurs.push(valeur);
+                    }
+                }
+                Err(_) => {
+                    return valeurs;
+                }
+            }
+    
+            return valeurs;
+        }
+    
+        println!("No database connection");
+        return valeurs;
+    }
+    
+    pub async fn c1_create_valeurmetier(
+        mission_id: i32,
+        valeur_name: String,
+        valeur_nature: String,
+        valeur_description: String,
+        responsable: String,
+    ) {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+    
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+    
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+    
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+    
+        let db_client = db_client.as_ref();
+    
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+            let query = format!("INSERT INTO c1_valeur_metier (mission_id, valeur_name, valeur_nature, valeur_description, responsable) VALUES ('{}', '{}', '{}', '{}', '{}')", mission_id, valeur_name, valeur_nature, valeur_description, responsable);
+    
+            let result = conn.query_drop(query);
+    
+            match result {
+                Ok(_) => {
+                    return;
+                }
+                Err(_) => {
+                    return;
+                }
+            }
+        }
+    
+        println!("No database connection");
+        return;
+    }
+    
+    pub async fn c1_get_valermetier_by_id(vm_id: i32) -> Vec<ValeurMetier> {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+    
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+    
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+    
+        let mut valeurs: Vec<ValeurMetier> = Vec::new();
+    
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+    
+        let db_client = db_client.as_ref();
+    
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+            let query = format!(
+                "SELECT * FROM c1_valeur_metier WHERE valeur_id = '{}' ORDER BY valeur_id ASC",
+                vm_id
+            );
+    
+            let result = conn.query_map(
+                query,
+                |(
+                    valeur_id,
+                    mission_id,
+                    valeur_name,
+                    valeur_nature,
+                    valeur_description,
+                    responsable,
+                ): (i32, i32, String, String, String, String)| {
+                    ValeurMetier {
+                        valeur_id,
+                        mission_id,
+                        valeur_name,
+                        valeur_nature,
+                        valeur_description,
+                        responsable,
+                    }
+                },
+            );
+    
+            // check how many rows are returned
+            match result {
+                Ok(fetched_valeurs) => {
+                    for valeur in fetched_valeurs {
+                        valeurs.push(valeur);
+                    }
+                }
+                Err(_) => {
+                    return valeurs;
+                }
+            }
+    
+            return valeurs;
+        }
+    
+        println!("No database connection");
+        return valeurs;
+    }
+
+    pub async fn c1_delete_vm_by_id(vm_id: i32) {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+
+        let db_client = db_client.as_ref();
+
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+            let query = format!("DELETE FROM c1_valeur_metier WHERE valeur_id = '{}'", vm_id);
+
+            let result = conn.query_drop(query);
+
+            match result {
+                Ok(_) => {
+                    return;
+                }
+                Err(_) => {
+                    return;
+                }
+            }
+        }
+
+        println!("No database connection");
+        return;
+    }
+
+    pub async fn c1_update_valeurmetier(
+        vm_id: i32,
+        valeur_name: String,
+        valeur_nature: String,
+        valeur_description: String,
+        responsable: String,
+    ) {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+
+        let db_client = db_client.as_ref();
+
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+            let query = format!("UPDATE c1_valeur_metier SET valeur_name = '{}', valeur_nature = '{}', valeur_description = '{}', responsable = '{}' WHERE valeur_id = '{}'", valeur_name, valeur_nature, valeur_description, responsable, vm_id);
+
+            let result = conn.query_drop(query);
+
+            match result {
+                Ok(_) => {
+                    return;
+                }
+                Err(_) => {
+                    return;
+                }
+            }
+        }
+
+        println!("No database connection");
+    }
+
+}
+
+
+//                                                 
+//   _____ _         _____                     _   
+//  | __  |_|___ ___|   __|_ _ ___ ___ ___ ___| |_ 
+//  | __ -| | -_|   |__   | | | . | . | . |  _|  _|
+//  |_____|_|___|_|_|_____|___|  _|  _|___|_| |_|  
+//                            |_| |_|              
+#[derive(Debug, Clone, Serialize)]
+pub struct BienSupport {
+    pub support_id: i32,
+    pub valeur_id: i32,
+    pub support_name: String,
+    pub support_description: String,
+    pub support_responsable: String,
+}
+
+impl BienSupport {
+    pub fn default() -> Self {
+        BienSupport {
+            support_id: 0,
+            valeur_id: 0,
+            support_name: String::new(),
+            support_description: String::new(),
+            support_responsable: String::new(),
+        }
+    }
+
+    pub async fn c1_create_asset(
+        vm_id: i32,
+        asset_name: String,
+        asset_description: String,
+        owner: String,
+    ) {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+    
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+    
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+    
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+    
+        let db_client = db_client.as_ref();
+    
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+            // the business values are held by c1_bien_support_valeur, valeur_id is legacy
+            let query = format!("INSERT INTO c1_bien_support (valeur_id, support_name, support_description, support_responsable) VALUES ('0', '{}', '{}', '{}')", asset_name, asset_description, owner);
+    
+            let result = conn.query_drop(query);
+    
+            match result {
+                Ok(_) => {
+                    // an asset created from a business value is linked to it
+                    if vm_id > 0 {
+                        let query = format!("INSERT INTO c1_bien_support_valeur (support_id, valeur_id) VALUES ('{}', '{}')", conn.last_insert_id(), vm_id);
+                        let _ = conn.query_drop(query);
+                    }
+                    return;
+                }
+                Err(_) => {
+                    return;
+                }
+            }
+        }
+    
+        println!("No database connection");
+        return;
+    }
+    
+    pub async fn c1_get_asset_by_vmid(vm_id: i32) -> Vec<BienSupport> {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+    
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+    
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+    
+        let mut assets: Vec<BienSupport> = Vec::new();
+    
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+    
+        let db_client = db_client.as_ref();
+    
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+            let query = format!(
+                "SELECT s.support_id, s.valeur_id, s.support_name, s.support_description, s.support_responsable FROM c1_bien_support s INNER JOIN c1_bien_support_valeur l ON l.support_id = s.support_id WHERE l.valeur_id = '{}' ORDER BY s.support_id ASC",
+                vm_id
+            );
+    
+            let result = conn.query_map(
+                query,
+                |(support_id, valeur_id, support_name, support_description, support_responsable): (
+                    i32,
+                    i32,
+                    String,
+                    String,
+                    String,
+                )| {
+                    BienSupport {
+                        support_id,
+                        valeur_id,
+                        support_name,
+                        support_description,
+                        support_responsable,
+                    }
+                },
+            );
+    
+            // check how many rows are returned
+            match result {
+                Ok(fetched_assets) => {
+                    for asset in fetched_assets {
+                        assets.push(asset);
+                    }
+                }
+                Err(_) => {
+                    return assets;
+                }
+            }
+    
+            return assets;
+        }
+    
+        println!("No database connection");
+        return assets;
+    }
+    
+    pub async fn c1_delete_asset_by_id(asset_id: i32) {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+    
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+    
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+    
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+    
+        let db_client = db_client.as_ref();
+    
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+            let query = format!(
+                "DELETE FROM c1_bien_support WHERE support_id = '{}'",
+                asset_id
+            );
+
+            // drop the links and the dependency edges of the asset as well
+            let _ = conn.query_drop(format!("DELETE FROM c1_bien_support_valeur WHERE support_id = '{}'", asset_id));
+            let _ = conn.query_drop(format!("DELETE FROM c1_bien_support_dependance WHERE support_id = '{}' OR depend_de = '{}'", asset_id, asset_id));
+    
+            let result = conn.query_drop(query);
+    
+            match result {
+                Ok(_) => {
+                    return;
+                }
+                Err(_) => {
+                    return;
+                }
+            }
+        }
+    
+        println!("No database connection");
+        return;
+    }
+    
+    pub async fn c1_get_asset_by_id(asset_id: i32) -> Vec<BienSupport> {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+
+        let db_client = db_client.as_ref();
+
+        let mut assets: Vec<BienSupport> = Vec::new();
+
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+            let query = format!(
+                "SELECT * FROM c1_bien_support WHERE support_id = '{}'",
+                asset_id
+            );
+
+            let result = conn.query_map(
+                query,
+                |(
+                    support_id,
+                    valeur_id,
+                    support_name,
+                    support_description,
+                    support_responsable,
+                ): (
+                    i32,
+                    i32,
+                    String,
+                    String,
+                    String,
+                )| {
+                    BienSupport {
+                        support_id,
+                        valeur_id,
+                        support_name,
+                        support_description,
+                        support_responsable,
+                    }
+                },
+            );
+
+            // check how many rows are returned
+            match result {
+                Ok(fetched) => {
+                    for row in fetched {
+                        assets.push(row);
+                    }
+                }
+                Err(_) => {
+                    return assets;
+                }
+            }
+
+            return assets;
+        }
+
+        println!("No database connection");
+        assets
+    }
+
+    pub async fn c1_update_asset(
+        asset_id: i32,
+        asset_name: String,
+        asset_description: String,
+        owner: String,
+    ) {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+
+        let db_client = db_client.as_ref();
+
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+            let query = format!("UPDATE c1_bien_support SET support_name = '{}', support_description = '{}', support_responsable = '{}' WHERE support_id = '{}'", asset_name, asset_description, owner, asset_id);
+
+            let result = conn.query_drop(query);
+
+            match result {
+                Ok(_) => {
+                    return;
+                }
+                Err(_) => {
+                    return;
+                }
+            }
+        }
+
+        println!("No database connection");
+    }
+
+    pub async fn c1_get_all_assets() -> Vec<BienSupport> {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+
+        let db_client = db_client.as_ref();
+
+        let mut assets: Vec<BienSupport> = Vec::new();
+
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+            let query = "SELECT support_id, valeur_id, support_name, support_description, support_responsable FROM c1_bien_support ORDER BY support_id ASC".to_string();
+
+            let result = conn.query_map(
+                query,
+                |(
+                    support_id,
+                    valeur_id,
+                    support_name,
+                    support_description,
+                    support_responsable,
+                ): (
+                    i32,
+                    i32,
+                    String,
+                    String,
+                    String,
+                )| {
+                    BienSupport {
+                        support_id,
+                        valeur_id,
+                        support_name,
+                        support_description,
+                        support_responsable,
+                    }
+                },
+            );
+
+            // check how many rows are returned
+            match result {
+                Ok(fetched) => {
+                    for row in fetched {
+                        assets.push(row);
+                    }
+                }
+                Err(_) => {
+                    return assets;
+                }
+            }
+
+            return assets;
+        }
+
+        println!("No database connection");
+        assets
+    }
+
+}
+
+
+//                                                        
+//   _____             _      _____               _   
+//  |  _  |___ ___ ___| |_   |   __|___ ___ ___ | |_ 
+//  |     |_ -|_ -| -_|  _|  |  |  |  _| .'| . ||   |
+//  |__|__|___|___|___|_|    |_____|_| |__,|  _||_|_|
+//                                         |_|        
+/// Many-to-many link between a supporting asset and a business value
+#[derive(Debug, Clone, Serialize)]
+pub struct AssetLink {
+    pub support_id: i32,
+    pub valeur_id: i32,
+}
+
+/// Dependency edge, the asset `support_id` depends on the asset `depend_de`
+#[derive(Debug, Clone, Serialize)]
+pub struct AssetDependency {
+    pub support_id: i32,
+    pub depend_de: i32,
+}
+
+impl AssetLink {
+    pub async fn c1_get_all_asset_links() -> Vec<AssetLink> {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+
+        let db_client = db_client.as_ref();
+
+        let mut links: Vec<AssetLink> = Vec::new();
+
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+            let query = "SELECT support_id, valeur_id FROM c1_bien_support_valeur ORDER BY support_id ASC".to_string();
+
+            let result = conn.query_map(
+                query,
+                |(
+                    support_id,
+                    valeur_id,
+                ): (
+                    i32,
+                    i32,
+                )| {
+                    AssetLink {
+                        support_id,
+                        valeur_id,
+                    }
+                },
+            );
+
+            // check how many rows are returned
+            match result {
+                Ok(fetched) => {
+                    for row in fetched {
+                        links.push(row);
+                    }
+                }
+                Err(_) => {
+                    return links;
+                }
+            }
+
+            return links;
+        }
+
+        println!("No database connection");
+        links
+    }
+
+    pub async fn c1_link_asset(support_id: i32, valeur_id: i32) {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+
+        let db_client = db_client.as_ref();
+
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+            let query = format!("INSERT INTO c1_bien_support_valeur (support_id, valeur_id) VALUES ('{}', '{}')", support_id, valeur_id);
+
+            let result = conn.query_drop(query);
+
+            match result {
+                Ok(_) => {
+                    return;
+                }
+                Err(_) => {
+                    return;
+                }
+            }
+        }
+
+        println!("No database connection");
+    }
+
+    pub async fn c1_unlink_asset(support_id: i32, valeur_id: i32) {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+
+        let db_client = db_client.as_ref();
+
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+            let query = format!("DELETE FROM c1_bien_support_valeur WHERE support_id = '{}' AND valeur_id = '{}'", support_id, valeur_id);
+
+            let result = conn.query_drop(query);
+
+            match result {
+                Ok(_) => {
+                    return;
+                }
+                Err(_) => {
+                    return;
+                }
+            }
+        }
+
+        println!("No database connection");
+    }
+
+    pub async fn c1_unlink_vm(valeur_id: i32) {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+
+        let db_client = db_client.as_ref();
+
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+            let query = format!("DELETE FROM c1_bien_support_valeur WHERE valeur_id = '{}'", valeur_id);
+
+            let result = conn.query_drop(query);
+
+            match result {
+                Ok(_) => {
+                    return;
+                }
+                Err(_) => {
+                    return;
+                }
+            }
+        }
+
+        println!("No database connection");
+    }
+    /// Move the single business value of the assets created before the
+    /// many-to-many link existed into `c1_bien_support_valeur`
+    pub async fn c1_migrate_legacy_links() {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+
+        let db_client = db_client.as_ref();
+
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+
+            let result = conn.query_drop("INSERT INTO c1_bien_support_valeur (support_id, valeur_id) SELECT support_id, valeur_id FROM c1_bien_support WHERE valeur_id > 0");
+            if result.is_ok() {
+                let _ = conn.query_drop("UPDATE c1_bien_support SET valeur_id = 0 WHERE valeur_id > 0");
+            }
+            return;
+        }
+
+        println!("No database connection");
+    }
+
+}
+
+impl AssetDependency {
+    pub async fn c1_get_all_asset_dependencies() -> Vec<AssetDependency> {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+
+        let db_client = db_client.as_ref();
+
+        let mut dependencies: Vec<AssetDependency> = Vec::new();
+
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+            let query = "SELECT support_id, depend_de FROM c1_bien_support_dependance ORDER BY support_id ASC".to_string();
+
+            let result = conn.query_map(
+                query,
+                |(
+                    support_id,
+                    depend_de,
+                ): (
+                    i32,
+                    i32,
+                )| {
+                    AssetDependency {
+                        support_id,
+                        depend_de,
+                    }
+                },
+            );
+
+            // check how many rows are returned
+            match result {
+                Ok(fetched) => {
+                    for row in fetched {
+                        dependencies.push(row);
+                    }
+                }
+                Err(_) => {
+                    return dependencies;
+                }
+            }
+
+            return dependencies;
+        }
+
+        println!("No database connection");
+        dependencies
+    }
+
+    pub async fn c1_add_asset_dependency(support_id: i32, depend_de: i32) {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+
+        let db_client = db_client.as_ref();
+
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+            let query = format!("INSERT INTO c1_bien_support_dependance (support_id, depend_de) VALUES ('{}', '{}')", support_id, depend_de);
+
+            let result = conn.query_drop(query);
+
+            match result {
+                Ok(_) => {
+                    return;
+                }
+                Err(_) => {
+                    return;
+                }
+            }
+        }
+
+        println!("No database connection");
+    }
+
+    pub async fn c1_remove_asset_dependency(support_id: i32, depend_de: i32) {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+
+        let db_client = db_client.as_ref();
+
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+            let query = format!("DELETE FROM c1_bien_support_dependance WHERE support_id = '{}' AND depend_de = '{}'", support_id, depend_de);
+
+            let result = conn.query_drop(query);
+
+            match result {
+                Ok(_) => {
+                    return;
+                }
+                Err(_) => {
+                    return;
+                }
+            }
+        }
+
+        println!("No database connection");
+    }
+}
+
+
+//                                                   
+//   _____                   _ _____             _   
+//  |   __|___ ___ ___ ___ _| |   __|_ _ ___ ___| |_ 
+//  |   __| -_| .'|  _| -_| . |   __| | | -_|   |  _|
+//  |__|  |___|__,|_| |___|___|_____|\_/|___|_|_|_|  
+//                                                   
+#[derive(Debug, Clone, Serialize)]
+pub struct FearedEvent {
+    pub event_id: i32,
+    pub valeur_metier: i32,
+    pub evenement_redoute: String,
+    pub impact: String,
+    pub gravite: i32,
+    pub critere: String,
+    pub impact_categories: String,
+}
+
+
+impl FearedEvent {
+    pub fn default() -> Self {
+        FearedEvent {
+            event_id: 0,
+            valeur_metier: 0,
+            evenement_redoute: String::new(),
+            impact: String::new(),
+            gravite: 0,
+            critere: String::new(),
+            impact_categories: String::new(),
+        }
+    }
+
+    /// The EBIOS security criteria (DICT) a feared event is assessed on
+    pub const CRITERIA: [(&'static str, &'static str); 4] = [
+        ("disponibilite", "Availability"),
+        ("integrite", "Integrity"),
+        ("confidentialite", "Confidentiality"),
+        ("tracabilite", "Traceability"),
+    ];
+
+    /// The impact categories, stored comma separated in `impact_categories`
+    pub const IMPACT_CATEGORIES: [(&'static str, &'static str); 5] = [
+        ("missions", "Missions"),
+        ("humains", "Humans"),
+        ("financier", "Financial"),
+        ("juridique", "Legal"),
+        ("image", "Image"),
+    ];
+
+    pub fn critere_label(&self) -> &'static str {
+        FearedEvent::CRITERIA.iter()
+            .find(|(key, _)| *key == self.critere)
+            .map(|(_, label)| *label)
+            .unwrap_or("N/A")
+    }
+
+    pub async fn c1_feared_event_create(
+        event_name: String,
+        impacts: String,
+        valeur_metier_id: i32,
+        gravity: i32,
+        critere: String,
+        impact_categories: String,
+    ) {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+    
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+    
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+    
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+    
+        let db_client = db_client.as_ref();
+    
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+            let query = format!("INSERT INTO c1_feared_event (evenement_redoute, impact, valeur_metier, gravite, critere, impact_categories) VALUES ('{}', '{}', '{}', '{}', '{}', '{}')", event_name, impacts, valeur_metier_id, gravity, critere, impact_categories);
+    
+            let result = conn.query_drop(query);
+    
+            match result {
+                Ok(_) => {
+                    return;
+                }
+                Err(_) => {
+                    return;
+                }
+            }
+        }
+    
+        println!("No database connection");
+        return;
+    }
+    
+    pub async fn c1_delete_feared_event(event_id: i32) {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+    
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+    
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+    
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+    
+        let db_client = db_client.as_ref();
+    
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+            let query = format!(
+                "DELETE FROM c1_feared_event WHERE event_id = '{}'",
+                event_id
+            );
+    
+            let result = conn.query_drop(query);
+    
+            match result {
+                Ok(_) => {
+                    return;
+                }
+                Err(_) => {
+                    return;
+                }
+            }
+        }
+    
+        println!("No database connection");
+        return;
+    }
+    
+    pub async fn c1_get_all_feared_event() -> Vec<FearedEvent> {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+    
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+    
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+    
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+    
+        let db_client = db_client.as_ref();
+    
+        let mut events: Vec<FearedEvent> = Vec::new();
+    
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+    
+            let query = "SELECT event_id, evenement_redoute, impact, valeur_metier, gravite, critere, impact_categories FROM c1_feared_event ORDER BY event_id ASC".to_string();
+    
+            let result = conn.query_map(
+                query,
+                |(event_id, evenement_redoute, impact, valeur_metier, gravite, critere, impact_categories): (
+                    i32,
+                    String,
+                    String,
+                    i32,
+                    i32,
+                    String,
+                    String,
+                )| {
+                    FearedEvent {
+                        event_id,
+                        evenement_redoute,
+                        impact,
+                        valeur_metier,
+                        gravite,
+                        critere,
+                        impact_categories,
+                    }
+                },
+            );
+    
+            // check how many rows are returned
+            match result {
+                Ok(fetched_events) => {
+                    for event in fetched_events {
+                        events.push(event);
+                    }
+                }
+                Err(_) => {
+                    return events;
+                }
+            }
+    
+            return events;
+        }
+    
+        println!("No database connection");
+        return events;
+    }
+    
+    pub async fn c1_get_feared_event_by_id(event_id: i32) -> Vec<FearedEvent> {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+
+        let db_client = db_client.as_ref();
+
+        let mut events: Vec<FearedEvent> = Vec::new();
+
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+            let query = format!("SELECT event_id, evenement_redoute, impact, valeur_metier, gravite, critere, impact_categories FROM c1_feared_event WHERE event_id = '{}'", event_id);
+
+            let result = conn.query_map(
+                query,
+                |(
+                    event_id,
+                    evenement_redoute,
+                    impact,
+                    valeur_metier,
+                    gravite,
+                    critere,
+                    impact_categories,
+                ): (
+                    i32,
+                    String,
+                    String,
+                    i32,
+                    i32,
+                    String,
+                    String,
+                )| {
+                    FearedEvent {
+                        event_id,
+                        evenement_redoute,
+                        impact,
+                        valeur_metier,
+                        gravite,
+                        critere,
+                        impact_categories,
+                    }
+                },
+            );
+
+            // check how many rows are returned
+            match result {
+                Ok(fetched) => {
+                    for row in fetched {
+                        events.push(row);
+                    }
+                }
+                Err(_) => {
+                    return events;
+                }
+            }
+
+            return events;
+        }
+
+        println!("No database connection");
+        events
+    }
+
+    pub async fn c1_update_feared_event(
+        event_id: i32,
+        event_name: String,
+        impacts: String,
+        valeur_metier_id: i32,
+        gravity: i32,
+        critere: String,
+        impact_categories: String,
+    ) {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+
+        let db_client = db_client.as_ref();
+
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+            let query = format!("UPDATE c1_feared_event SET evenement_redoute = '{}', impact = '{}', valeur_metier = '{}', gravite = '{}', critere = '{}', impact_categories = '{}' WHERE event_id = '{}'", event_name, impacts, valeur_metier_id, gravity, critere, impact_categories, event_id);
+
+            let result = conn.query_drop(query);
+
+            match result {
+                Ok(_) => {
+                    return;
+                }
+                Err(_) => {
+                    return;
+                }
+            }
+        }
+
+        println!("No database connection");
+    }
+
+}
+
+
+//                                                    
+//   _____             _ _           _____         _     
+//  |   __|___ ___ _ _|_| |_ _ _   |   __|___ ___| |___ 
+//  |  |  |  _| .'| | | |  _| | |  |__   |  _| .'| | -_|
+//  |_____|_| |__,|\_/|_|_| |_  |  |_____|___|__,|_|___|
+//                          |___|                       
+#[derive(Debug, Clone, Serialize)]
+pub struct GravityScale {
+    pub niveau: i32,
+    pub libelle: String,
+    pub description: String,
+}
+
+impl GravityScale {
+    pub fn default() -> Self {
+        GravityScale {
+            niveau: 0,
+            libelle: String::new(),
+            description: String::new(),
+        }
+    }
+
+    pub async fn c1_get_gravity_scale() -> Vec<GravityScale> {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+
+        let db_client = db_client.as_ref();
+
+        let mut levels: Vec<GravityScale> = Vec::new();
+
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+            let query = "SELECT niveau, libelle, description FROM c1_gravity_scale ORDER BY niveau ASC".to_string();
+
+            let result = conn.query_map(
+                query,
+                |(
+                    niveau,
+                    libelle,
+                    description,
+                ): (
+                    i32,
+                    String,
+                    String,
+                )| {
+                    GravityScale {
+                        niveau,
+                        libelle,
+                        description,
+                    }
+                },
+            );
+
+            // check how many rows are returned
+            match result {
+                Ok(fetched) => {
+                    for row in fetched {
+                        levels.push(row);
+                    }
+                }
+                Err(_) => {
+                    return levels;
+                }
+            }
+
+            return levels;
+        }
+
+        println!("No database connection");
+        levels
+    }
+
+    pub async fn c1_create_gravity_level(
+        niveau: i32,
+        libelle: String,
+        description: String,
+    ) {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+
+        let db_client = db_client.as_ref();
+
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+            let query = format!("INSERT INTO c1_gravity_scale (niveau, libelle, description) VALUES ('{}', '{}', '{}')", niveau, libelle, description);
+
+            let result = conn.query_drop(query);
+
+            match result {
+                Ok(_) => {
+                    return;
+                }
+                Err(_) => {
+                    return;
+                }
+            }
+        }
+
+        println!("No database connection");
+    }
+
+    pub async fn c1_update_gravity_level(
+        niveau: i32,
+        libelle: String,
+        description: String,
+    ) {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+
+        let db_client = db_client.as_ref();
+
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+            let query = format!("UPDATE c1_gravity_scale SET libelle = '{}', description = '{}' WHERE niveau = '{}'", libelle, description, niveau);
+
+            let result = conn.query_drop(query);
+
+            match result {
+                Ok(_) => {
+                    return;
+                }
+                Err(_) => {
+                    return;
+                }
+            }
+        }
+
+        println!("No database connection");
+    }
+
+    pub async fn c1_delete_gravity_level(niveau: i32) {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+
+        let db_client = db_client.as_ref();
+
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+            let query = format!(
+                "DELETE FROM c1_gravity_scale WHERE niveau = '{}'",
+                niveau
+            );
+
+            let result = conn.query_drop(query);
+
+            match result {
+                Ok(_) => {
+                    return;
+                }
+                Err(_) => {
+                    return;
+                }
+            }
+        }
+
+        println!("No database connection");
+    }
+}
+
+
+//                 
+//   _____         
+//  |   __|___ ___ 
+//  |  |  | .'| . |
+//  |_____|__,|  _|
+//            |_|  
+#[derive(Debug, Clone, Serialize)]
+pub struct Gap {
+    pub gap_id: i32,
+    pub referential_type: String,
+    pub referential_name: String,
+    pub application_state: i32,
+    pub gap: String,
+    pub gap_justification: String,
+    pub proposed_measures: String,
+    pub requirement_id: i32,
+    /// set when a new version of the referential changed or removed the requirement
+    pub needs_review: bool,
+    pub review_reason: String,
+}
+
+impl Gap {
+    pub fn default() -> Self {
+        Gap {
+            gap_id: 0,
+            referential_type: String::new(),
+            referential_name: String::new(),
+            application_state: 0,
+            gap: String::new(),
+            gap_justification: String::new(),
+            proposed_measures: String::new(),
+            requirement_id: 0,
+            needs_review: false,
+            review_reason: String::new(),
+        }
+    }
+
+
+    pub async fn c1_create_gap(
+        g_ref_type: String,
+        g_ref_name: String,
+        g_state: i32,
+        g_gap: String,
+        g_gap_why: String,
+        g_gap_counter: String,
+        requirement_id: i32,
+    ) {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+
+        let db_client = db_client.as_ref();
+
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+
+            let query = format!("INSERT INTO c1_gaps (referential_type, referential_name, application_state, gap, gap_justification, proposed_measures, requirement_id) VALUES ('{}', '{}', '{}', '{}', '{}', '{}', '{}')", g_ref_type, g_ref_name, g_state, g_gap, g_gap_why, g_gap_counter, requirement_id);
+
+            let result = conn.query_drop(query);
+
+            match result {
+                Ok(_) => {
+                    return;
+                }
+                Err(_) => {
+                    return;
+                }
+            }
+        }
+
+        println!("No database connection");
+        return;
+    }
+
+    pub async fn c1_delete_gap(gap_id: i32) {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+
+        let db_client = db_client.as_ref();
+
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+
+            let query = format!("DELETE FROM c1_gaps WHERE gap_id = '{}'", gap_id);
+
+            let _ = conn.query_drop(format!("DELETE FROM countermeasure_gap WHERE gap_id = '{}'", gap_id));
+            let result = conn.query_drop(query);
+
+            match result {
+                Ok(_) => {
+                    return;
+                }
+                Err(_) => {
+                    return;
+                }
+            }
+        }
+
+        println!("No database connection");
+        return;
+    }
+
+    pub async fn c1_get_all_gaps() -> Vec<Gap> {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap().is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+
+        let db_client = db_client.as_ref();
+
+        let mut gaps: Vec<Gap> = Vec::new();
+
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+
+            let query = "SELECT gap_id, referential_type, referential_name, application_state, gap, gap_justification, proposed_measures, requirement_id, needs_review, review_reason FROM c1_gaps ORDER BY gap_id ASC".to_string();
+
+            let result = conn.query_map(
+                query,
+                |(
+                    gap_id,
+                    referential_type,
+                    referential_name,
+                    application_state,
+                    gap,
+                    gap_justification,
+                    proposed_measures,
+                    requirement_id,
+                    needs_review,
+                    review_reason,
+                ): (i32, String, String, i32, String, String, String, i32, bool, String)| {
+                    Gap {
+                        gap_id,
+                        referential_type,
+                        referential_name,
+                        application_state,
+                        gap,
+                        gap_justification,
+                        proposed_measures,
+                        requirement_id,
+                        needs_review,
+                        review_reason,
+                    }
+                },
+            );
+
+            // check how many rows are returned
+            match result {
+                Ok(fetched_gaps) => {
+                    for gap in fetched_gaps {
+                        gaps.push(gap);
+                    }
+                }
+                Err(_) => {
+                    return gaps;
+                }
+            }
+
+            return gaps;
+        }
+
+        println!("No database connection");
+        return gaps;
+    }
+
+    pub async fn c1_get_gaps_by_id(gap_id: i32) -> Vec<Gap> {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap().is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+
+        let mut gaps: Vec<Gap> = Vec::new();
+
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+
+        let db_client = db_client.as_ref();
+
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+
+            let query = format!("SELECT gap_id, referential_type, referential_name, application_state, gap, gap_justification, proposed_measures, requirement_id, needs_review, review_reason FROM c1_gaps WHERE gap_id = '{}' ORDER BY gap_id ASC", gap_id);
+
+            let result = conn.query_map(
+                query,
+                |(
+                    gap_id,
+                    referential_type,
+                    referential_name,
+                    application_state,
+                    gap,
+                    gap_justification,
+                    proposed_measures,
+                    requirement_id,
+                    needs_review,
+                    review_reason,
+                ): (i32, String, String, i32, String, String, String, i32, bool, String)| {
+                    Gap {
+                        gap_id,
+                        referential_type,
+                        referential_name,
+                        application_state,
+                        gap,
+                        gap_justification,
+                        proposed_measures,
+                        requirement_id,
+                        needs_review,
+                        review_reason,
+                    }
+                },
+            );
+
+            // check how many rows are returned
+            match result {
+                Ok(fetched_gaps) => {
+                    for gap in fetched_gaps {
+                        gaps.push(gap);
+                    }
+                }
+                Err(_) => {
+                    return gaps;
+                }
+            }
+
+            return gaps;
+        }
+
+        println!("No database connection");
+        gaps
+    }
+
+    /// The assessment of a control of an imported referential, if any
+    pub async fn c1_get_gap_by_requirement(requirement_id: i32) -> Vec<Gap> {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap().is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+
+        let mut gaps: Vec<Gap> = Vec::new();
+
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+
+        let db_client = db_client.as_ref();
+
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+
+            let query = format!("SELECT gap_id, referential_type, referential_name, application_state, gap, gap_justification, proposed_measures, requirement_id, needs_review, review_reason FROM c1_gaps WHERE requirement_id = '{}' ORDER BY gap_id ASC", requirement_id);
+
+            let result = conn.query_map(
+                query,
+                |(
+                    gap_id,
+                    referential_type,
+                    referential_name,
+                    application_state,
+                    gap,
+                    gap_justification,
+                    proposed_measures,
+                    requirement_id,
+                    needs_review,
+                    review_reason,
+                ): (i32, String, String, i32, String, String, String, i32, bool, String)| {
+                    Gap {
+                        gap_id,
+                        referential_type,
+                        referential_name,
+                        application_state,
+                        gap,
+                        gap_justification,
+                        proposed_measures,
+                        requirement_id,
+                        needs_review,
+                        review_reason,
+                    }
+                },
+            );
+
+            // check how many rows are returned
+            match result {
+                Ok(fetched_gaps) => {
+                    for gap in fetched_gaps {
+                        gaps.push(gap);
+                    }
+                }
+                Err(_) => {
+                    return gaps;
+                }
+            }
+
+            return gaps;
+        }
+
+        println!("No database connection");
+        return gaps;
+    }
+
+    /// Compliance derived from the countermeasures of the gap
+    pub async fn c1_set_application_state(gap_id: i32, g_state: i32) {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+
+        let db_client = db_client.as_ref();
+
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+            let query = format!("UPDATE c1_gaps SET application_state = '{}' WHERE gap_id = '{}'", g_state, gap_id);
+
+            let _ = conn.query_drop(query);
+            return;
+        }
+
+        println!("No database connection");
+    }
+
+    pub async fn c1_update_gap(
+        gap_id: i32,
+        g_ref_type: String,
+        g_ref_name: String,
+        g_state: i32,
+        g_gap: String,
+        g_gap_why: String,
+        g_gap_counter: String,
+    ) {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+
+        let db_client = db_client.as_ref();
+
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+            let query = format!("UPDATE c1_gaps SET referential_type = '{}', referential_name = '{}', application_state = '{}', gap = '{}', gap_justification = '{}', proposed_measures = '{}', needs_review = 0, review_reason = '' WHERE gap_id = '{}'", g_ref_type, g_ref_name, g_state, g_gap, g_gap_why, g_gap_counter, gap_id);
+
+            let result = conn.query_drop(query);
+
+            match result {
+                Ok(_) => {
+                    return;
+                }
+                Err(_) => {
+                    return;
+                }
+            }
+        }
+
+        println!("No database connection");
+    }
+
+}
+
+/// Many-to-many link between a countermeasure and the gap it closes, the
+/// compliance of the gap is then derived from the status of its measures
+#[derive(Debug, Clone, Serialize)]
+pub struct GapMeasure {
+    pub ctm_uuid: Uuid,
+    pub gap_id: i32,
+}
+
+impl GapMeasure {
+    pub async fn c1_get_all_gap_measures() -> Vec<GapMeasure> {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+
+        let db_client = db_client.as_ref();
+
+        let mut links: Vec<GapMeasure> = Vec::new();
+
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+
+            let result = conn.query_map(
+                "SELECT ctm_uuid, gap_id FROM countermeasure_gap",
+                |(ctm_uuid, gap_id): (String, i32)| {
+                    GapMeasure {
+                        ctm_uuid: Uuid::parse_str(&ctm_uuid).unwrap(),
+                        gap_id,
+                    }
+                },
+            );
+
+            // check how many rows are returned
+            match result {
+                Ok(fetched_links) => {
+                    for link in fetched_links {
+                        links.push(link);
+                    }
+                }
+                Err(_) => {
+                    return links;
+                }
+            }
+
+            return links;
+        }
+
+        println!("No database connection");
+        links
+    }
+
+    pub async fn c1_link_measure(ctm_uuid: String, gap_id: i32) {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+
+        let db_client = db_client.as_ref();
+
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+            let query = format!("INSERT INTO countermeasure_gap (ctm_uuid, gap_id) SELECT '{0}', '{1}' FROM DUAL WHERE NOT EXISTS (SELECT 1 FROM countermeasure_gap WHERE ctm_uuid = '{0}' AND gap_id = '{1}')", ctm_uuid, gap_id);
+
+            let _ = conn.query_drop(query);
+            return;
+        }
+
+        println!("No database connection");
+    }
+
+    pub async fn c1_unlink_measure(ctm_uuid: String, gap_id: i32) {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+
+        let db_client = db_client.as_ref();
+
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+            let query = format!("DELETE FROM countermeasure_gap WHERE ctm_uuid = '{}' AND gap_id = '{}'", ctm_uuid, gap_id);
+
+            let _ = conn.query_drop(query);
+            return;
+        }
+
+        println!("No database connection");
+    }
+}
+
+//                                                
+//   _____     ___                   _   _     _   
+//  | __  |___|  _|___ ___ ___ ___ _| |_|_|___| |  
+//  |    -| -_|  _| -_|  _| -_|   |  _| | .'| |  
+//  |__|__|___|_| |___|_| |___|_|_|_| |_|__,|_|  
+//                                                
+#[derive(Debug, Clone, Serialize)]
+pub struct Referential {
+    pub referential_id: i32,
+    pub ref_key: String,
+    pub name: String,
+    pub version: String,
+    pub publisher: String,
+    pub description: String,
+}
+
+/// A node of the requirement tree of a referential, the leaves are the
+/// controls assessed through a `Gap`
+#[derive(Debug, Clone, Serialize)]
+pub struct Requirement {
+    pub requirement_id: i32,
+    pub referential_id: i32,
+    pub code: String,
+    pub parent_code: String,
+    pub title: String,
+    pub description: String,
+    pub position: i32,
+}
+
+/// An import of a referential, with the requirement codes it added, changed
+/// and removed compared to the previous version
+#[derive(Debug, Clone, Serialize)]
+pub struct ReferentialVersion {
+    pub version_id: i32,
+    pub referential_id: i32,
+    pub version: String,
+    pub imported_at: String,
+    pub added: Vec<String>,
+    pub changed: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+impl Referential {
+    pub fn default() -> Self {
+        Referential {
+            referential_id: 0,
+            ref_key: String::new(),
+            name: String::new(),
+            version: String::new(),
+            publisher: String::new(),
+            description: String::new(),
+        }
+    }
+
+    pub async fn c1_get_all_referentials() -> Vec<Referential> {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap().is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+
+        let db_client = db_client.as_ref();
+
+        let mut referentials: Vec<Referential> = Vec::new();
+
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+
+            let query = "SELECT referential_id, ref_key, name, version, publisher, COALESCE(description, '') FROM c1_referential ORDER BY referential_id ASC";
+
+            let result = conn.query_map(
+                query,
+                |(referential_id, ref_key, name, version, publisher, description): (i32, String, String, String, String, String)| {
+                    Referential { referential_id, ref_key, name, version, publisher, description }
+                },
+            );
+
+            if let Ok(fetched) = result {
+                referentials = fetched;
+            }
+
+            return referentials;
+        }
+
+        println!("No database connection");
+        referentials
+    }
+
+    pub async fn c1_get_referential_by_id(referential_id: i32) -> Vec<Referential> {
+        Referential::c1_get_all_referentials().await
+            .into_iter()
+            .filter(|r| r.referential_id == referential_id)
+            .collect()
+    }
+
+    /// Insert a referential and its whole requirement tree, the requirements
+    /// are stored in the given order
+    pub async fn c1_import_referential(
+        ref_key: String,
+        name: String,
+        version: String,
+        publisher: String,
+        description: String,
+        requirements: Vec<Requirement>,
+    ) {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+
+        let db_client = db_client.as_ref();
+
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+
+            let query = format!("INSERT INTO c1_referential (ref_key, name, version, publisher, description) VALUES ('{}', '{}', '{}', '{}', '{}')", ref_key, name, version, publisher, description);
+
+            if conn.query_drop(query).is_err() {
+                return;
+            }
+
+            let referential_id = conn.last_insert_id();
+
+            for (position, r) in requirements.iter().enumerate() {
+                let query = format!("INSERT INTO c1_requirement (referential_id, code, parent_code, title, description, position) VALUES ('{}', '{}', '{}', '{}', '{}', '{}')", referential_id, r.code, r.parent_code, r.title, r.description, position);
+                let _ = conn.query_drop(query);
+            }
+
+            let query = format!("INSERT INTO c1_referential_version (referential_id, version, added, changed, removed) VALUES ('{}', '{}', '', '', '')", referential_id, version);
+            let _ = conn.query_drop(query);
+
+            return;
+        }
+
+        println!("No database connection");
+    }
+
+    /// Move an imported referential to a new version. Requirements keep their
+    /// id when their code is kept, so do their gaps: the gaps of changed
+    /// requirements are flagged for review, the gaps of removed requirements
+    /// are also detached from the referential
+    pub async fn c1_upgrade_referential(
+        referential_id: i32,
+        name: String,
+        version: String,
+        publisher: String,
+        description: String,
+        requirements: Vec<Requirement>,
+        added: Vec<String>,
+        changed: Vec<String>,
+        removed: Vec<String>,
+    ) {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+
+        let db_client = db_client.as_ref();
+
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+
+            let query = format!("UPDATE c1_referential SET name = '{}', version = '{}', publisher = '{}', description = '{}' WHERE referential_id = '{}'", name, version, publisher, description, referential_id);
+
+            if conn.query_drop(query).is_err() {
+                return;
+            }
+
+            for code in changed.iter() {
+                let _ = conn.query_drop(format!("UPDATE c1_gaps SET needs_review = 1, review_reason = 'Requirement {} changed in version {}' WHERE requirement_id IN (SELECT requirement_id FROM c1_requirement WHERE referential_id = '{}' AND code = '{}')", code, version, referential_id, code));
+            }
+
+            for code in removed.iter() {
+                let _ = conn.query_drop(format!("UPDATE c1_gaps SET needs_review = 1, review_reason = 'Requirement {} removed in version {}', requirement_id = 0 WHERE requirement_id IN (SELECT requirement_id FROM c1_requirement WHERE referential_id = '{}' AND code = '{}')", code, version, referential_id, code));
+                let _ = conn.query_drop(format!("DELETE FROM c1_requirement WHERE referential_id = '{}' AND code = '{}'", referential_id, code));
+            }
+
+            for (position, r) in requirements.iter().enumerate() {
+                let query = if added.contains(&r.code) {
+                    format!("INSERT INTO c1_requirement (referential_id, code, parent_code, title, description, position) VALUES ('{}', '{}', '{}', '{}', '{}', '{}')", referential_id, r.code, r.parent_code, r.title, r.description, position)
+                } else {
+                    format!("UPDATE c1_requirement SET parent_code = '{}', title = '{}', description = '{}', position = '{}' WHERE referential_id = '{}' AND code = '{}'", r.parent_code, r.title, r.description, position, referential_id, r.code)
+                };
+                let _ = conn.query_drop(query);
+            }
+
+            let query = format!("INSERT INTO c1_referential_version (referential_id, version, added, changed, removed) VALUES ('{}', '{}', '{}', '{}', '{}')", referential_id, version, added.join(","), changed.join(","), removed.join(","));
+            let _ = conn.query_drop(query);
+
+            return;
+        }
+
+        println!("No database connection");
+    }
+
+    pub async fn c1_get_versions(referential_id: i32) -> Vec<ReferentialVersion> {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap().is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+
+        let db_client = db_client.as_ref();
+
+        let mut versions: Vec<ReferentialVersion> = Vec::new();
+
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+
+            let query = format!("SELECT version_id, referential_id, version, {}, COALESCE(added, ''), COALESCE(changed, ''), COALESCE(removed, '') FROM c1_referential_version WHERE referential_id = '{}' ORDER BY version_id DESC", dialect().date_format("imported_at", "%Y-%m-%d %H:%i"), referential_id);
+
+            let result = conn.query_map(
+                query,
+                |(version_id, referential_id, version, imported_at, added, changed, removed): (i32, i32, String, String, String, String, String)| {
+                    let split = |list: String| list.split(',').filter(|c| !c.is_empty()).map(|c| c.to_string()).collect::<Vec<String>>();
+                    ReferentialVersion { version_id, referential_id, version, imported_at, added: split(added), changed: split(changed), removed: split(removed) }
+                },
+            );
+
+            if let Ok(fetched) = result {
+                versions = fetched;
+            }
+
+            return versions;
+        }
+
+        println!("No database connection");
+        versions
+    }
+
+    /// Remove a referential and its requirements, the gaps assessed against it
+    /// are kept as free gaps
+    pub async fn c1_delete_referential(referential_id: i32) {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+
+        let db_client = db_client.as_ref();
+
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+
+            let _ = conn.query_drop(format!("UPDATE c1_gaps SET requirement_id = 0 WHERE requirement_id IN (SELECT requirement_id FROM c1_requirement WHERE referential_id = '{}')", referential_id));
+            let _ = conn.query_drop(format!("DELETE FROM c1_requirement WHERE referential_id = '{}'", referential_id));
+            let _ = conn.query_drop(format!("DELETE FROM c1_referential_version WHERE referential_id = '{}'", referential_id));
+            let _ = conn.query_drop(format!("DELETE FROM c1_referential WHERE referential_id = '{}'", referential_id));
+
+            return;
+        }
+
+        println!("No database connection");
+    }
+}
+
+impl Requirement {
+    pub async fn c1_get_requirements_by_referential(referential_id: i32) -> Vec<Requirement> {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap().is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+
+        let db_client = db_client.as_ref();
+
+        let mut requirements: Vec<Requirement> = Vec::new();
+
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+
+            let query = format!("SELECT requirement_id, referential_id, code, parent_code, title, COALESCE(description, ''), position FROM c1_requirement WHERE referential_id = '{}' ORDER BY position ASC", referential_id);
+
+            let result = conn.query_map(
+                query,
+                |(requirement_id, referential_id, code, parent_code, title, description, position): (i32, i32, String, String, String, String, i32)| {
+                    Requirement { requirement_id, referential_id, code, parent_code, title, description, position }
+                },
+            );
+
+            if let Ok(fetched) = result {
+                requirements = fetched;
+            }
+
+            return requirements;
+        }
+
+        println!("No database connection");
+        requirements
+    }
+
+    pub async fn c1_get_requirement_by_id(requirement_id: i32) -> Vec<Requirement> {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap().is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+
+        let db_client = db_client.as_ref();
+
+        let mut requirements: Vec<Requirement> = Vec::new();
+
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+
+            let query = format!("SELECT requirement_id, referential_id, code, parent_code, title, COALESCE(description, ''), position FROM c1_requirement WHERE requirement_id = '{}'", requirement_id);
+
+            let result = conn.query_map(
+                query,
+                |(requirement_id, referential_id, code, parent_code, title, description, position): (i32, i32, String, String, String, String, i32)| {
+                    Requirement { requirement_id, referential_id, code, parent_code, title, description, position }
+                },
+            );
+
+            if let Ok(fetched) = result {
+                requirements = fetched;
+            }
+
+            return requirements;
+        }
+
+        println!("No database connection");
+        requirements
+    }
+}
+
+//                                                           
+//   _____ ___ _____ _     _   _____                         
+//  |     |_  | __  |_|___| |_|   __|___ _ _ ___ ___ ___ ___ 
+//  |   --|  _|    -| |_ -| '_|__   | . | | |  _|  _| -_|_ -|
+//  |_____|___|__|__|_|___|_,_|_____|___|___|_| |___|___|___|
+//                                                           
+
+#[derive(Debug, Clone, Serialize)]
+pub struct C2RiskSources {
+    pub risk_id: i32,
+    pub source_risque: String,
+    pub objectifs_vises: String,
+    pub motivation: Option<String>,
+    pub ressources: Option<String>,
+    pub pertinence_sr_ov: Option<i32>,
+    pub priorite: Option<i32>,
+    pub retenu: bool,
+    pub justification_exclusion_sr_ov: Option<String>,
+}
+
+
+impl C2RiskSources {
+    pub fn default() -> Self {
+        C2RiskSources {
+            risk_id: 0,
+            source_risque: String::new(),
+            objectifs_vises: String::new(),
+            motivation: None,
+            ressources: None,
+            pertinence_sr_ov: None,
+            priorite: None,
+            retenu: false,
+            justification_exclusion_sr_ov: None,
+        }
+    }
+
+    pub async fn c2_get_all_risk() -> Vec<C2RiskSources> {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+    
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap().is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+    
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+    
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+    
+        let db_client = db_client.as_ref();
+    
+        let mut risks: Vec<C2RiskSources> = Vec::new();
+    
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+    
+            let query = format!("SELECT risk_id, source_risque, objectifs_vises, motivation, ressources, pertinence_sr_ov, priorite, retenu, justification_exclusion_sr_ov FROM c2_risk_sources ORDER BY risk_id ASC");
+    
+            let result = conn.query_map(
+                query,
+                |(
+                    risk_id,
+                    source_risque,
+                    objectifs_vises,
+                    motivation,
+                    ressources,
+                    pertinence_sr_ov,
+                    priorite,
+                    retenu,
+                    justification_exclusion_sr_ov,
+                ): (
+                    i32,
+                    String,
+                    String,
+                    Option<String>,
+                    Option<String>,
+                    Option<i32>,
+                    Option<i32>,
+                    bool,
+                    Option<String>,
+                )| {
+                    C2RiskSources {
+                        risk_id,
+                        source_risque,
+                        objectifs_vises,
+                        motivation,
+                        ressources,
+                        pertinence_sr_ov,
+                        priorite,
+                        retenu,
+                        justification_exclusion_sr_ov,
+                    }
+                },
+            );
+    
+            // check how many rows are returned
+            match result {
+                Ok(fetched_risks) => {
+                    for risk in fetched_risks {
+                        risks.push(risk);
+                    }
+                }
+                Err(_) => {
+                    return risks;
+                }
+            }
+    
+            return risks;
+        }
+    
+        println!("No database connection");
+        return risks;
+    }
+    
+    pub async fn c2_create_risk(
+        source_risque: String,
+        objectifs_vises: String,
+        motivation: String,
+        ressources: String,
+        pertinence_sr_ov: i32,
+        priorite: i32,
+        retenu: bool,
+        justification_exclusion_sr_ov: String,
+    ) {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+    
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap().is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+    
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+    
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+    
+        let db_client = db_client.as_ref();
+    
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+    
+            let query = format!("INSERT INTO c2_risk_sources (source_risque, objectifs_vises, motivation, ressources, pertinence_sr_ov, priorite, retenu, justification_exclusion_sr_ov) VALUES ('{}', '{}', '{}', '{}', '{}', '{}', {}, '{}')", source_risque, objectifs_vises, motivation, ressources, pertinence_sr_ov, priorite, retenu, justification_exclusion_sr_ov);
+    
+            let result = conn.query_drop(query);
+    
+            match result {
+                Ok(_) => {
+                    return;
+                }
+                Err(_) => {
+                    return;
+                }
+            }
+        }
+    
+        println!("No database connection");
+        return;
+    }
+    
+    pub async fn c2_delete_risk_by_id(risk_id: i32) {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+    
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap().is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+    
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+    
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+    
+        let db_client = db_client.as_ref();
+    
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+    
+            let query = format!("DELETE FROM c2_risk_sources WHERE risk_id = '{}'", risk_id);
+    
+            let result = conn.query_drop(query);
+    
+            match result {
+                Ok(_) => {
+                    return;
+                }
+                Err(_) => {
+                    return;
+                }
+            }
+        }
+    
+        println!("No database connection");
+        return;
+    }
+    
+    pub async fn c2_get_risk_detail(risk_id: i32) -> Vec<C2RiskSources> {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+    
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap().is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+    
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+    
+        let mut risks: Vec<C2RiskSources> = Vec::new();
+    
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+    
+        let db_client = db_client.as_ref();
+    
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+    
+            let query = format!("SELECT risk_id, source_risque, objectifs_vises, motivation, ressources, pertinence_sr_ov, priorite, retenu, justification_exclusion_sr_ov FROM c2_risk_sources WHERE risk_id = '{}' ORDER BY risk_id ASC", risk_id);
+    
+            let result = conn.query_map(
+                query,
+                |(
+                    risk_id,
+                    source_risque,
+                    objectifs_vises,
+                    motivation,
+                    ressources,
+                    pertinence_sr_ov,
+                    priorite,
+                    retenu,
+                    justification_exclusion_sr_ov,
+                ): (
+                    i32,
+                    String,
+                    String,
+                    Option<String>,
+                    Option<String>,
+                    Option<i32>,
+                    Option<i32>,
+                    bool,
+                    Option<String>,
+                )| {
+                    C2RiskSources {
+                        risk_id,
+                        source_risque,
+                        objectifs_vises,
+                        motivation,
+                        ressources,
+                        pertinence_sr_ov,
+                        priorite,
+                        retenu,
+                        justification_exclusion_sr_ov,
+                    }
+                },
+            );
+    
+            // check how many rows are returned
+            match result {
+                Ok(fetched_risks) => {
+                    for risk in fetched_risks {
+                        risks.push(risk);
+                    }
+                }
+                Err(_) => {
+                    return risks;
+                }
+            }
+    
+            return risks;
+        }
+    
+        println!("No database connection");
+        return risks;
+    }
+    
+    pub async fn c2_update_risk(
+        risk_id: i32,
+        source_risque: String,
+        objectifs_vises: String,
+        motivation: String,
+        ressources: String,
+        pertinence_sr_ov: i32,
+        priorite: i32,
+        retenu: bool,
+        justification_exclusion_sr_ov: String,
+    ) {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+
+        let db_client = db_client.as_ref();
+
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+            let query = format!("UPDATE c2_risk_sources SET source_risque = '{}', objectifs_vises = '{}', motivation = '{}', ressources = '{}', pertinence_sr_ov = '{}', priorite = '{}', retenu = {}, justification_exclusion_sr_ov = '{}' WHERE risk_id = '{}'", source_risque, objectifs_vises, motivation, ressources, pertinence_sr_ov, priorite, retenu, justification_exclusion_sr_ov, risk_id);
+
+            let result = conn.query_drop(query);
+
+            match result {
+                Ok(_) => {
+                    return;
+                }
+                Err(_) => {
+                    return;
+                }
+            }
+        }
+
+        println!("No database connection");
+    }
+
+}
+
+
+//                                                         
+//   _____ ___ _____ _       _       _       _   _         
+//  |     |_  |   __| |_ ___| |_ ___| |_ ___| |_| |___ ___ 
+//  |   --|_  |__   |  _| .'| '_| -_|   | . | | . | -_|  _|
+//  |_____|___|_____|_| |__,|_,_|___|_|_|___|_|___|___|_|  
+//        
+#[derive(Debug, Clone, Serialize)]
+pub struct C3Stakeholder {
+    pub stakeholder_id: i32,
+    pub category: String,
+    pub stakeholder_name: String,
+    pub dependance: i32,
+    pub penetration: i32,
+    pub maturite_ssi: i32,
+    pub confiance: i32,
+    pub mesures_securite: Option<String>,
+    pub dependance_residuelle: Option<i32>,
+    pub penetration_residuelle: Option<i32>,
+    pub maturite_ssi_residuelle: Option<i32>,
+    pub confiance_residuelle: Option<i32>,
+}                                                 
+
+impl C3Stakeholder {
+    pub fn default() -> Self {
+        C3Stakeholder {
+            stakeholder_id: 0,
+            category: String::new(),
+            stakeholder_name: String::new(),
+            dependance: 0,
+            penetration: 0,
+            maturite_ssi: 0,
+            confiance: 0,
+            mesures_securite: None,
+            dependance_residuelle: None,
+            penetration_residuelle: None,
+            maturite_ssi_residuelle: None,
+            confiance_residuelle: None,
+        }
+    }
+
+    pub async fn c3_get_all_stakeholder() -> Vec<C3Stakeholder> {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+    
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap().is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+    
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+    
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+    
+        let db_client = db_client.as_ref();
+    
+        let mut stakeholders: Vec<C3Stakeholder> = Vec::new();
+    
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+    
+            let query = "SELECT stakeholder_id, category, stakeholder_name, dependance, penetration, maturite_ssi, confiance, mesures_securite, dependance_residuelle, penetration_residuelle, maturite_ssi_residuelle, confiance_residuelle FROM c3_stakeholders ORDER BY stakeholder_id ASC".to_string();
+    
+            let result = conn.query_map(
+                query,
+                |(
+                    stakeholder_id,
+                    category,
+                    stakeholder_name,
+                    dependance,
+                    penetration,
+                    maturite_ssi,
+                    confiance,
+                    mesures_securite,
+                    dependance_residuelle,
+                    penetration_residuelle,
+                    maturite_ssi_residuelle,
+                    confiance_residuelle,
+                ): (
+                    i32,
+                    String,
+                    String,
+                    i32,
+                    i32,
+                    i32,
+                    i32,
+                    Option<String>,
+                    Option<i32>,
+                    Option<i32>,
+                    Option<i32>,
+                    Option<i32>,
+                )| {
+                    C3Stakeholder {
+                        stakeholder_id,
+                        category,
+                        stakeholder_name,
+                        dependance,
+                        penetration,
+                        maturite_ssi,
+                        confiance,
+                        mesures_securite,
+                        dependance_residuelle,
+                        penetration_residuelle,
+                        maturite_ssi_residuelle,
+                        confiance_residuelle,
+                    }
+                },
+            );
+    
+            // check how many rows are returned
+            match result {
+                Ok(fetched_stakeholders) => {
+                    for stakeholder in fetched_stakeholders {
+                        stakeholders.push(stakeholder);
+                    }
+                }
+                Err(_) => {
+                    return stakeholders;
+                }
+            }
+    
+            return stakeholders;
+        }
+    
+        println!("No database connection");
+        return stakeholders;
+    }
+    
+    pub async fn c3_create_stakeholder(
+        category: String,
+        stakeholder_name: String,
+        dependance: i32,
+        penetration: i32,
+        maturite_ssi: i32,
+        confiance: i32,
+    ) -> Option<i32> {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+    
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap().is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+    
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+    
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+    
+        let db_client = db_client.as_ref();
+    
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+    
+            let query = format!("INSERT INTO c3_stakeholders (category, stakeholder_name, dependance, penetration, maturite_ssi, confiance) VALUES ('{}', '{}', '{}', '{}', '{}', '{}')", category, stakeholder_name, dependance, penetration, maturite_ssi, confiance);
+    
+            let result = conn.query_drop(query);
+    
+            match result {
+                Ok(_) => {
+                    return Some(conn.last_insert_id() as i32);
+                }
+                Err(_) => {
+                    return None;
+                }
+            }
+        }
+    
+        println!("No database connection");
+        None
+    }
+    
+    pub async fn c3_delete_stakeholder_by_id(stakeholder_id: i32) {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+    
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap().is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+    
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+    
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+    
+        let db_client = db_client.as_ref();
+    
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+    
+            let query = format!(
+                "DELETE FROM c3_stakeholders WHERE stakeholder_id = '{}'",
+                stakeholder_id
+            );
+    
+            let result = conn.query_drop(query);
+    
+            match result {
+                Ok(_) => {
+                    return;
+                }
+                Err(_) => {
+                    return;
+                }
+            }
+        }
+    
+        println!("No database connection");
+        return;
+    }
+    
+    pub async fn c3_update_stakeholder(
+        stakeholder_id: i32,
+        category: String,
+        stakeholder_name: String,
+        dependance: i32,
+        penetration: i32,
+        maturite_ssi: i32,
+        confiance: i32,
+        mesures_securite: String,
+        dependance_residuelle: Option<i32>,
+        penetration_residuelle: Option<i32>,
+        maturite_ssi_residuelle: Option<i32>,
+        confiance_residuelle: Option<i32>,
+    ) {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+    
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap().is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+    
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+    
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+    
+        let db_client = db_client.as_ref();
+    
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+
+            // residual values are optional, store NULL when not evaluated
+            let residual = |v: Option<i32>| v.map_or("NULL".to_string(), |v| format!("'{}'", v));
+    
+            let query = format!("UPDATE c3_stakeholders SET category = '{}', stakeholder_name = '{}', dependance = '{}', penetration = '{}', maturite_ssi = '{}', confiance = '{}', mesures_securite = '{}', dependance_residuelle = {}, penetration_residuelle = {}, maturite_ssi_residuelle = {}, confiance_residuelle = {} WHERE stakeholder_id = '{}'", category, stakeholder_name, dependance, penetration, maturite_ssi, confiance, mesures_securite, residual(dependance_residuelle), residual(penetration_residuelle), residual(maturite_ssi_residuelle), residual(confiance_residuelle), stakeholder_id);
+    
+            let result = conn.query_drop(query);
+    
+            match result {
+                Ok(_) => {
+                    return;
+                }
+                Err(_) => {
+                    return;
+                }
+            }
+        }
+    
+        println!("No database connection");
+    }
+    
+    pub async fn c3_get_stakeholder_detail(stakeholder_id: i32) -> Vec<C3Stakeholder> {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+    
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap().is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+    
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+    
+        let mut stakeholders: Vec<C3Stakeholder> = Vec::new();
+    
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+    
+        let db_client = db_client.as_ref();
+    
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+    
+            let query = format!("SELECT stakeholder_id, category, stakeholder_name, dependance, penetration, maturite_ssi, confiance, mesures_securite, dependance_residuelle, penetration_residuelle, maturite_ssi_residuelle, confiance_residuelle FROM c3_stakeholders WHERE stakeholder_id = '{}' ORDER BY stakeholder_id ASC", stakeholder_id);
+    
+            let result = conn.query_map(
+                query,
+                |(
+                    stakeholder_id,
+                    category,
+                    stakeholder_name,
+                    dependance,
+                    penetration,
+                    maturite_ssi,
+                    confiance,
+                    mesures_securite,
+                    dependance_residuelle,
+                    penetration_residuelle,
+                    maturite_ssi_residuelle,
+                    confiance_residuelle,
+                ): (
+                    i32,
+                    String,
+                    String,
+                    i32,
+                    i32,
+                    i32,
+                    i32,
+                    Option<String>,
+                    Option<i32>,
+                    Option<i32>,
+                    Option<i32>,
+                    Option<i32>,
+                )| {
+                    C3Stakeholder {
+                        stakeholder_id,
+                        category,
+                        stakeholder_name,
+                        dependance,
+                        penetration,
+                        maturite_ssi,
+                        confiance,
+                        mesures_securite,
+                        dependance_residuelle,
+                        penetration_residuelle,
+                        maturite_ssi_residuelle,
+                        confiance_residuelle,
+                    }
+                },
+            );
+    
+            // check how many rows are returned
+            match result {
+                Ok(fetched_stakeholders) => {
+                    for stakeholder in fetched_stakeholders {
+                        stakeholders.push(stakeholder);
+                    }
+                }
+                Err(_) => {
+                    return stakeholders;
+                }
+            }
+    
+            return stakeholders;
+        }
+    
+        println!("No database connection");
+        return stakeholders;
+    }
+    
+    
+}
+
+
+//
+//   _____                 _       _
+//  |   __|___ ___ ___ ___| |_ ___| |_
+//  |__   |   | .'| . |_ -|   | . |  _|
+//  |_____|_|_|__,|  _|___|_|_|___|_|
+//                |_|
+//
+/// A named, frozen state of the study, `content` is a JSON `StudyState`
+#[derive(Debug, Clone, Serialize)]
+pub struct Snapshot {
+    pub snapshot_id: i32,
+    pub snapshot_name: String,
+    /// `YYYY-MM-DD HH:MM`
+    pub created_at: String,
+    pub content: String,
+}
+
+impl Snapshot {
+    /// The snapshots without their content, to list them
+    pub async fn get_all_snapshots() -> Vec<Snapshot> {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+
+        let db_client = db_client.as_ref();
+
+        let mut snapshots: Vec<Snapshot> = Vec::new();
+
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+            let query = format!("SELECT snapshot_id, snapshot_name, {}, '' FROM snapshot ORDER BY created_at DESC, snapshot_id DESC", dialect().date_format("created_at", "%Y-%m-%d %H:%i"));
+
+            let result = conn.query_map(
+                query,
+                |(snapshot_id, snapshot_name, created_at, content): (i32, String, String, String)| Snapshot {
+                    snapshot_id,
+                    snapshot_name,
+                    created_at,
+                    content,
+                },
+            );
+
+            // check how many rows are returned
+            match result {
+                Ok(fetched_snapshots) => {
+                    for snapshot in fetched_snapshots {
+                        snapshots.push(snapshot);
+                    }
+                }
+                Err(_) => {
+                    return snapshots;
+                }
+            }
+
+            return snapshots;
+        }
+
+        println!("No database connection");
+        snapshots
+    }
+
+    pub async fn get_snapshot(snapshot_id: i32) -> Vec<Snapshot> {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+
+        let db_client = db_client.as_ref();
+
+        let mut snapshots: Vec<Snapshot> = Vec::new();
+
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+            let query = format!("SELECT snapshot_id, snapshot_name, {}, content FROM snapshot WHERE snapshot_id = '{}'", dialect().date_format("created_at", "%Y-%m-%d %H:%i"), snapshot_id);
+
+            let result = conn.query_map(
+                query,
+                |(snapshot_id, snapshot_name, created_at, content): (i32, String, String, String)| Snapshot {
+                    snapshot_id,
+                    snapshot_name,
+                    created_at,
+                    content,
+                },
+            );
+
+            // check how many rows are returned
+            match result {
+                Ok(fetched_snapshots) => {
+                    for snapshot in fetched_snapshots {
+                        snapshots.push(snapshot);
+                    }
+                }
+                Err(_) => {
+                    return snapshots;
+                }
+            }
+
+            return snapshots;
+        }
+
+        println!("No database connection");
+        snapshots
+    }
+
+    pub async fn create_snapshot(
+        snapshot_name: String,
+        content: String,
+    ) -> Result<(), String> {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+
+        let db_client = db_client.as_ref();
+
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+            let query = format!(
+                "INSERT INTO snapshot (snapshot_name, content) VALUES ('{}', '{}')",
+                snapshot_name, content
+            );
+
+            let result = conn.query_drop(query);
+
+            match result {
+                Ok(_) => {
+                    return Ok(());
+                }
+                Err(_) => {
+                    return Err("Failed to insert new snapshot".to_owned());
+                }
+            }
+        }
+
+        Err("No database connection".to_owned())
+    }
+
+    pub async fn delete_snapshot(
+        snapshot_id: i32,
+    ) -> Result<(), String> {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+
+        let db_client = db_client.as_ref();
+
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+            let query = format!(
+                "DELETE FROM snapshot WHERE snapshot_id = '{}'",
+                snapshot_id
+            );
+
+            let result = conn.query_drop(query);
+
+            match result {
+                Ok(_) => {
+                    return Ok(());
+                }
+                Err(_) => {
+                    return Err("Failed to delete snapshot".to_owned());
+                }
+            }
+        }
+
+        Err("No database connection".to_owned())
+    }
+}
+
+//  _ _ _     _   _           _
+// | | | |___| |_| |_ ___ ___| |_
+// | | | | -_| . |   | . | . | '_|
+// |_____|___|___|_|_|___|___|_,_|
+//
+/// A URL to POST the events of one type to
+#[derive(Debug, Clone, Serialize)]
+pub struct Webhook {
+    pub webhook_id: i32,
+    pub url: String,
+    pub event_type: String,
+    /// key of the HMAC signature of the payloads
+    pub secret: String,
+    /// `YYYY-MM-DD HH:MM`
+    pub created_at: String,
+}
+
+impl Webhook {
+    pub async fn get_all_webhooks() -> Vec<Webhook> {
+        Webhook::get_webhooks_where("1 = 1".to_string()).await
+    }
+
+    pub async fn get_webhook(webhook_id: i32) -> Vec<Webhook> {
+        Webhook::get_webhooks_where(format!("webhook_id = '{}'", webhook_id)).await
+    }
+
+    /// The webhooks registered for an event type
+    pub async fn get_webhooks_of_event(event_type: String) -> Vec<Webhook> {
+        Webhook::get_webhooks_where(format!("event_type = '{}'", event_type)).await
+    }
+
+    async fn get_webhooks_where(condition: String) -> Vec<Webhook> {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+
+        let db_client = db_client.as_ref();
+
+        let mut webhooks: Vec<Webhook> = Vec::new();
+
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+            let query = format!(
+                "SELECT webhook_id, url, event_type, secret, {} FROM webhook WHERE {} ORDER BY event_type, webhook_id",
+                dialect().date_format("created_at", "%Y-%m-%d %H:%i"), condition
+            );
+
+            let result = conn.query_map(
+                query,
+                |(webhook_id, url, event_type, secret, created_at): (i32, String, String, String, String)| Webhook {
+                    webhook_id,
+                    url,
+                    event_type,
+                    secret,
+                    created_at,
+                },
+            );
+
+            // check how many rows are returned
+            match result {
+                Ok(fetched_webhooks) => {
+                    for webhook in fetched_webhooks {
+                        webhooks.push(webhook);
+                    }
+                }
+                Err(_) => {
+                    return webhooks;
+                }
+            }
+
+            return webhooks;
+        }
+
+        println!("No database connection");
+        webhooks
+    }
+
+    pub async fn create_webhook(
+        url: String,
+        event_type: String,
+        secret: String,
+    ) -> Result<(), String> {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+
+        let db_client = db_client.as_ref();
+
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+            let query = format!(
+                "INSERT INTO webhook (url, event_type, secret) VALUES ('{}', '{}', '{}')",
+                url, event_type, secret
+            );
+
+            let result = conn.query_drop(query);
+
+            match result {
+                Ok(_) => {
+                    return Ok(());
+                }
+                Err(_) => {
+                    return Err("Failed to insert new webhook".to_owned());
+                }
+            }
+        }
+
+        Err("No database connection".to_owned())
+    }
+
+    /// Delete a webhook and its delivery log
+    pub async fn delete_webhook(
+        webhook_id: i32,
+    ) -> Result<(), String> {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+
+        let db_client = db_client.as_ref();
+
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+
+            for query in [
+                format!("DELETE FROM webhook_delivery WHERE webhook_id = '{}'", webhook_id),
+                format!("DELETE FROM webhook WHERE webhook_id = '{}'", webhook_id),
+            ] {
+                if conn.query_drop(query).is_err() {
+                    return Err("Failed to delete webhook".to_owned());
+                }
+            }
+
+            return Ok(());
+        }
+
+        Err("No database connection".to_owned())
+    }
+}
+
+/// One event to POST to one webhook, retried until it is delivered or
+/// has failed `MAX_ATTEMPTS` times
+#[derive(Debug, Clone, Serialize)]
+pub struct WebhookDelivery {
+    pub delivery_id: i32,
+    pub webhook_id: i32,
+    pub event_type: String,
+    /// what the event is about, for the events sent once per subject
+    pub subject: String,
+    /// the JSON body, signed as is
+    pub payload: String,
+    /// pending, delivered or failed
+    pub status: String,
+    pub attempts: i32,
+    pub response_code: Option<i32>,
+    pub last_error: String,
+    /// `YYYY-MM-DD HH:MM:SS`, UTC
+    pub next_attempt_at: String,
+    /// `YYYY-MM-DD HH:MM`
+    pub created_at: String,
+}
+
+type WebhookDeliveryRow = (i32, i32, String, String, String, String, i32, Option<i32>, String, String, String);
+
+impl WebhookDelivery {
+    fn from_row(row: WebhookDeliveryRow) -> WebhookDelivery {
+        let (delivery_id, webhook_id, event_type, subject, payload, status, attempts, response_code, last_error, next_attempt_at, created_at) = row;
+        WebhookDelivery {
+            delivery_id,
+            webhook_id,
+            event_type,
+            subject,
+            payload,
+            status,
+            attempts,
+            response_code,
+            last_error,
+            next_attempt_at,
+            created_at,
+        }
+    }
+
+    /// The last deliveries, newest first
+    pub async fn get_recent_deliveries(limit: i32) -> Vec<WebhookDelivery> {
+        WebhookDelivery::get_deliveries_where(format!("1 = 1 ORDER BY delivery_id DESC LIMIT {}", limit)).await
+    }
+
+    /// The pending deliveries whose next attempt is due at `now`
+    pub async fn get_due_deliveries(now: String) -> Vec<WebhookDelivery> {
+        WebhookDelivery::get_deliveries_where(format!("status = 'pending' AND next_attempt_at <= '{}' ORDER BY delivery_id", now)).await
+    }
+
+    /// The deliveries of an event about a subject to a webhook
+    pub async fn get_deliveries_of_subject(webhook_id: i32, event_type: String, subject: String) -> Vec<WebhookDelivery> {
+        WebhookDelivery::get_deliveries_where(format!(
+            "webhook_id = '{}' AND event_type = '{}' AND subject = '{}'",
+            webhook_id, event_type, subject
+        )).await
+    }
+
+    async fn get_deliveries_where(condition: String) -> Vec<WebhookDelivery> {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+
+        let db_client = db_client.as_ref();
+
+        let mut deliveries: Vec<WebhookDelivery> = Vec::new();
+
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+            let query = format!(
+                "SELECT delivery_id, webhook_id, event_type, subject, payload, status, attempts, response_code, COALESCE(last_error, ''), {}, {} FROM webhook_delivery WHERE {}",
+                dialect().date_format("next_attempt_at", "%Y-%m-%d %H:%i:%s"), dialect().date_format("created_at", "%Y-%m-%d %H:%i"), condition
+            );
+
+            let result = conn.query_map(query, WebhookDelivery::from_row);
+
+            // check how many rows are returned
+            match result {
+                Ok(fetched_deliveries) => {
+                    for delivery in fetched_deliveries {
+                        deliveries.push(delivery);
+                    }
+                }
+                Err(_) => {
+                    return deliveries;
+                }
+            }
+
+            return deliveries;
+        }
+
+        println!("No database connection");
+        deliveries
+    }
+
+    pub async fn create_delivery(
+        webhook_id: i32,
+        event_type: String,
+        subject: String,
+        payload: String,
+        next_attempt_at: String,
+    ) -> Result<(), String> {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+
+        let db_client = db_client.as_ref();
+
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+            let query = format!(
+                "INSERT INTO webhook_delivery (webhook_id, event_type, subject, payload, next_attempt_at) VALUES ('{}', '{}', '{}', '{}', '{}')",
+                webhook_id, event_type, subject, payload, next_attempt_at
+            );
+
+            let result = conn.query_drop(query);
+
+            match result {
+                Ok(_) => {
+                    return Ok(());
+                }
+                Err(_) => {
+                    return Err("Failed to insert new webhook delivery".to_owned());
+                }
+            }
+        }
+
+        Err("No database connection".to_owned())
+    }
+
+    /// Keep the outcome of an attempt
+    pub async fn record_attempt(
+        delivery_id: i32,
+        status: String,
+        attempts: i32,
+        response_code: Option<i32>,
+        last_error: String,
+        next_attempt_at: String,
+    ) -> Result<(), String> {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+
+        let db_client = db_client.as_ref();
+
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+            let query = format!(
+                "UPDATE webhook_delivery SET status = '{}', attempts = '{}', response_code = {}, last_error = '{}', next_attempt_at = '{}' WHERE delivery_id = '{}'",
+                status,
+                attempts,
+                response_code.map(|c| format!("'{}'", c)).unwrap_or("NULL".to_string()),
+                last_error,
+                next_attempt_at,
+                delivery_id
+            );
+
+            let result = conn.query_drop(query);
+
+            match result {
+                Ok(_) => {
+                    return Ok(());
+                }
+                Err(_) => {
+                    return Err("Failed to update webhook delivery".to_owned());
+                }
+            }
+        }
+
+        Err("No database connection".to_owned())
+    }
+}
+
+//                                               
+//   _____     _   _ ___ _         _   _         
+//  |   | |___| |_|_|  _|_|___ ___| |_|_|___ ___ 
+//  | | | | . |  _| |  _| |  _| .'|  _| | . |   |
+//  |_|___|___|_| |_|_| |_|___|__,|_| |_|___|_|_|
+//                                               
+/// Where and which emails an owner receives; the owners are the names
+/// typed in the risks and the countermeasures
+#[derive(Debug, Clone, Serialize)]
+pub struct NotificationPreference {
+    pub user_name: String,
+    pub email: String,
+    /// language of the emails
+    pub locale: String,
+    /// a countermeasure is assigned to the user
+    pub assignments: bool,
+    /// a countermeasure of the user passes its due date
+    pub overdue: bool,
+    /// a risk of the user is due for review
+    pub reviews: bool,
+    /// the weekly digest
+    pub digest: bool,
+}
+
+impl NotificationPreference {
+    pub async fn get_all_preferences() -> Vec<NotificationPreference> {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+
+        let db_client = db_client.as_ref();
+
+        let mut preferences: Vec<NotificationPreference> = Vec::new();
+
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+            let query = "SELECT user_name, email, locale, assignments, overdue, reviews, digest FROM notification_preference ORDER BY user_name";
+
+            let result = conn.query_map(
+                query,
+                |(user_name, email, locale, assignments, overdue, reviews, digest): (String, String, String, bool, bool, bool, bool)| NotificationPreference {
+                    user_name,
+                    email,
+                    locale,
+                    assignments,
+                    overdue,
+                    reviews,
+                    digest,
+                },
+            );
+
+            // check how many rows are returned
+            match result {
+                Ok(fetched_preferences) => {
+                    for preference in fetched_preferences {
+                        preferences.push(preference);
+                    }
+                }
+                Err(_) => {
+                    return preferences;
+                }
+            }
+
+            return preferences;
+        }
+
+        println!("No database connection");
+        preferences
+    }
+
+    /// The preferences of an owner, the names are compared without case
+    pub async fn get_preference(user_name: &str) -> Option<NotificationPreference> {
+        NotificationPreference::get_all_preferences().await.into_iter().find(|p| p.user_name.trim().eq_ignore_ascii_case(user_name.trim()))
+    }
+
+    /// Create or replace the preferences of an owner
+    pub async fn save_preference(preference: NotificationPreference) -> Result<(), String> {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+
+        let db_client = db_client.as_ref();
+
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+            let flag = |on: bool| if on { 1 } else { 0 };
+
+            for query in [
+                format!("DELETE FROM notification_preference WHERE user_name = '{}'", preference.user_name),
+                format!(
+                    "INSERT INTO notification_preference (user_name, email, locale, assignments, overdue, reviews, digest) VALUES ('{}', '{}', '{}', '{}', '{}', '{}', '{}')",
+                    preference.user_name, preference.email, preference.locale,
+                    flag(preference.assignments), flag(preference.overdue), flag(preference.reviews), flag(preference.digest)
+                ),
+            ] {
+                if conn.query_drop(query).is_err() {
+                    return Err("Failed to save notification preference".to_owned());
+                }
+            }
+
+            return Ok(());
+        }
+
+        Err("No database connection".to_owned())
+    }
+
+    pub async fn delete_preference(user_name: String) -> Result<(), String> {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+
+        let db_client = db_client.as_ref();
+
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+            let query = format!("DELETE FROM notification_preference WHERE user_name = '{}'", user_name);
+
+            let result = conn.query_drop(query);
+
+            match result {
+                Ok(_) => {
+                    return Ok(());
+                }
+                Err(_) => {
+                    return Err("Failed to delete notification preference".to_owned());
+                }
+            }
+        }
+
+        Err("No database connection".to_owned())
+    }
+}
+
+/// One email to send to one owner, retried until it is sent or has failed
+/// `MAX_ATTEMPTS` times
+#[derive(Debug, Clone, Serialize)]
+pub struct Notification {
+    pub notification_id: i32,
+    pub user_name: String,
+    pub email: String,
+    /// assignment, overdue, review, digest or test
+    pub kind: String,
+    /// what the email is about, each subject is sent once
+    pub subject: String,
+    /// the rendered email as JSON: its subject, text and html
+    pub message: String,
+    /// pending, sent or failed
+    pub status: String,
+    pub attempts: i32,
+    pub last_error: String,
+    /// `YYYY-MM-DD HH:MM:SS`, UTC
+    pub next_attempt_at: String,
+    /// `YYYY-MM-DD HH:MM`
+    pub created_at: String,
+}
+
+type NotificationRow = (i32, String, String, String, String, String, String, i32, String, String, String);
+
+impl Notification {
+    fn from_row(row: NotificationRow) -> Notification {
+        let (notification_id, user_name, email, kind, subject, message, status, attempts, last_error, next_attempt_at, created_at) = row;
+        Notification {
+            notification_id,
+            user_name,
+            email,
+            kind,
+            subject,
+            message,
+            status,
+            attempts,
+            last_error,
+            next_attempt_at,
+            created_at,
         }
     }
 
-    pub async fn c3_get_all_stakeholder() -> Vec<C3Stakeholder> {
+    /// The last notifications, newest first
+    pub async fn get_recent_notifications(limit: i32) -> Vec<Notification> {
+        Notification::get_notifications_where(format!("1 = 1 ORDER BY notification_id DESC LIMIT {}", limit)).await
+    }
+
+    /// The pending notifications whose next attempt is due at `now`
+    pub async fn get_due_notifications(now: String) -> Vec<Notification> {
+        Notification::get_notifications_where(format!("status = 'pending' AND next_attempt_at <= '{}' ORDER BY notification_id", now)).await
+    }
+
+    /// The notifications of a kind about a subject to an owner
+    pub async fn get_notifications_of_subject(user_name: String, kind: String, subject: String) -> Vec<Notification> {
+        Notification::get_notifications_where(format!(
+            "user_name = '{}' AND kind = '{}' AND subject = '{}'",
+            user_name, kind, subject
+        )).await
+    }
+
+    async fn get_notifications_where(condition: String) -> Vec<Notification> {
         // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
-        let lock_result = unsafe { DB_CLIENT.lock() };
-    
+        let lock_result = DB_CLIENT.lock();
+
         if lock_result.is_err() {
             // kill script
-            trace_logs("Error: DB_CLIENT.lock().unwrap().is_none() return any poison".to_owned());
+            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
             std::process::exit(1);
         }
-    
+
         // check if need to create new client
         if lock_result.unwrap().is_none() {
             new_client().await;
         }
-    
+
         // perform database operations
-        let db_client = unsafe { DB_CLIENT.lock().unwrap() };
-    
+        let db_client = DB_CLIENT.lock().unwrap();
+
         let db_client = db_client.as_ref();
-    
-        let mut stakeholders: Vec<C3Stakeholder> = Vec::new();
-    
+
+        let mut notifications: Vec<Notification> = Vec::new();
+
         if let Some(pool) = db_client {
             let mut conn = pool.get_conn().unwrap();
-    
-            let query = format!("SELECT stakeholder_id, category, stakeholder_name, dependance, penetration, maturite_ssi, confiance FROM c3_stakeholders ORDER BY stakeholder_id ASC");
-    
-            let result = conn.query_map(
-                query,
-                |(
-                    stakeholder_id,
-                    category,
-                    stakeholder_name,
-                    dependance,
-                    penetration,
-                    maturite_ssi,
-                    confiance,
-                ): (i32, String, String, i32, i32, i32, i32)| {
-                    C3Stakeholder {
-                        stakeholder_id,
-                        category,
-                        stakeholder_name,
-                        dependance,
-                        penetration,
-                        maturite_ssi,
-                        confiance,
-                    }
-                },
+            let query = format!(
+                "SELECT notification_id, user_name, email, kind, subject, message, status, attempts, COALESCE(last_error, ''), {}, {} FROM notification WHERE {}",
+                dialect().date_format("next_attempt_at", "%Y-%m-%d %H:%i:%s"), dialect().date_format("created_at", "%Y-%m-%d %H:%i"), condition
             );
-    
+
+            let result = conn.query_map(query, Notification::from_row);
+
             // check how many rows are returned
             match result {
-                Ok(fetched_stakeholders) => {
-                    for stakeholder in fetched_stakeholders {
-                        stakeholders.push(stakeholder);
+                Ok(fetched_notifications) => {
+                    for notification in fetched_notifications {
+                        notifications.push(notification);
                     }
                 }
                 Err(_) => {
-                    return stakeholders;
+                    return notifications;
                 }
             }
-    
-            return stakeholders;
+
+            return notifications;
         }
-    
+
         println!("No database connection");
-        return stakeholders;
+        notifications
     }
-    
-    pub async fn c3_create_stakeholder(
-        category: String,
-        stakeholder_name: String,
-        dependance: i32,
-        penetration: i32,
-        maturite_ssi: i32,
-        confiance: i32,
-    ) {
+
+    pub async fn create_notification(
+        user_name: String,
+        email: String,
+        kind: String,
+        subject: String,
+        message: String,
+        next_attempt_at: String,
+    ) -> Result<(), String> {
         // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
-        let lock_result = unsafe { DB_CLIENT.lock() };
-    
+        let lock_result = DB_CLIENT.lock();
+
         if lock_result.is_err() {
             // kill script
-            trace_logs("Error: DB_CLIENT.lock().unwrap().is_none() return any poison".to_owned());
+            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
             std::process::exit(1);
         }
-    
+
         // check if need to create new client
         if lock_result.unwrap().is_none() {
             new_client().await;
         }
-    
+
         // perform database operations
-        let db_client = unsafe { DB_CLIENT.lock().unwrap() };
-    
+        let db_client = DB_CLIENT.lock().unwrap();
+
         let db_client = db_client.as_ref();
-    
+
         if let Some(pool) = db_client {
             let mut conn = pool.get_conn().unwrap();
-    
-            let query = format!("INSERT INTO c3_stakeholders (category, stakeholder_name, dependance, penetration, maturite_ssi, confiance) VALUES ('{}', '{}', '{}', '{}', '{}', '{}')", category, stakeholder_name, dependance, penetration, maturite_ssi, confiance);
-    
+            let query = format!(
+                "INSERT INTO notification (user_name, email, kind, subject, message, next_attempt_at) VALUES ('{}', '{}', '{}', '{}', '{}', '{}')",
+                user_name, email, kind, subject, message, next_attempt_at
+            );
+
             let result = conn.query_drop(query);
-    
+
             match result {
                 Ok(_) => {
-                    return;
+                    return Ok(());
                 }
                 Err(_) => {
-                    return;
+                    return Err("Failed to insert new notification".to_owned());
                 }
             }
         }
-    
-        println!("No database connection");
-        return;
+
+        Err("No database connection".to_owned())
     }
-    
-    pub async fn c3_delete_stakeholder_by_id(stakeholder_id: i32) {
+
+    /// Keep the outcome of an attempt
+    pub async fn record_attempt(
+        notification_id: i32,
+        status: String,
+        attempts: i32,
+        last_error: String,
+        next_attempt_at: String,
+    ) -> Result<(), String> {
         // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
-        let lock_result = unsafe { DB_CLIENT.lock() };
-    
+        let lock_result = DB_CLIENT.lock();
+
         if lock_result.is_err() {
             // kill script
-            trace_logs("Error: DB_CLIENT.lock().unwrap().is_none() return any poison".to_owned());
+            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
             std::process::exit(1);
         }
-    
+
         // check if need to create new client
         if lock_result.unwrap().is_none() {
             new_client().await;
         }
-    
+
         // perform database operations
-        let db_client = unsafe { DB_CLIENT.lock().unwrap() };
-    
+        let db_client = DB_CLIENT.lock().unwrap();
+
         let db_client = db_client.as_ref();
-    
+
         if let Some(pool) = db_client {
             let mut conn = pool.get_conn().unwrap();
-    
             let query = format!(
-                "DELETE FROM c3_stakeholders WHERE stakeholder_id = '{}'",
-                stakeholder_id
+                "UPDATE notification SET status = '{}', attempts = '{}', last_error = '{}', next_attempt_at = '{}' WHERE notification_id = '{}'",
+                status, attempts, last_error, next_attempt_at, notification_id
             );
-    
+
             let result = conn.query_drop(query);
-    
+
             match result {
                 Ok(_) => {
-                    return;
+                    return Ok(());
                 }
                 Err(_) => {
-                    return;
+                    return Err("Failed to update notification".to_owned());
                 }
             }
         }
-    
-        println!("No database connection");
-        return;
+
+        Err("No database connection".to_owned())
     }
-    
-    pub async fn c3_get_stakeholder_detail(stakeholder_id: i32) -> Vec<C3Stakeholder> {
+}
+
+//
+//     __     _
+//   _|  |___| |_
+//  |  |  | . | . |
+//  |_____|___|___|
+//
+
+/// The state of a recurring job of the scheduler, kept so that a restart
+/// does not run it again before it is due
+#[derive(Debug, Clone, Serialize)]
+pub struct Job {
+    pub job_name: String,
+    /// success, failed, running or interrupted, empty before the first run
+    pub last_status: String,
+    /// what the last run did, or why it failed
+    pub last_message: String,
+    /// `YYYY-MM-DD HH:MM:SS`, UTC, empty before the first run
+    pub last_started_at: String,
+    pub last_finished_at: String,
+    pub last_duration_ms: i32,
+    /// `YYYY-MM-DD HH:MM:SS`, UTC
+    pub next_run_at: String,
+    pub run_count: i32,
+    pub failure_count: i32,
+}
+
+type JobRow = (String, String, String, String, String, i32, String, i32, i32);
+
+impl Job {
+    fn from_row(row: JobRow) -> Job {
+        let (job_name, last_status, last_message, last_started_at, last_finished_at, last_duration_ms, next_run_at, run_count, failure_count) = row;
+        Job {
+            job_name,
+            last_status,
+            last_message,
+            last_started_at,
+            last_finished_at,
+            last_duration_ms,
+            next_run_at,
+            run_count,
+            failure_count,
+        }
+    }
+
+    pub async fn get_all_jobs() -> Vec<Job> {
         // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
-        let lock_result = unsafe { DB_CLIENT.lock() };
-    
+        let lock_result = DB_CLIENT.lock();
+
         if lock_result.is_err() {
             // kill script
-            trace_logs("Error: DB_CLIENT.lock().unwrap().is_none() return any poison".to_owned());
+            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
             std::process::exit(1);
         }
-    
+
         // check if need to create new client
         if lock_result.unwrap().is_none() {
             new_client().await;
         }
-    
-        let mut stakeholders: Vec<C3Stakeholder> = Vec::new();
-    
+
         // perform database operations
-        let db_client = unsafe { DB_CLIENT.lock().unwrap() };
-    
+        let db_client = DB_CLIENT.lock().unwrap();
+
         let db_client = db_client.as_ref();
-    
+
+        let mut jobs: Vec<Job> = Vec::new();
+
         if let Some(pool) = db_client {
             let mut conn = pool.get_conn().unwrap();
-    
-            let query = format!("SELECT stakeholder_id, category, stakeholder_name, dependance, penetration, maturite_ssi, confiance FROM c3_stakeholders WHERE stakeholder_id = '{}' ORDER BY stakeholder_id ASC", stakeholder_id);
-    
-            let result = conn.query_map(
-                query,
-                |(
-                    stakeholder_id,
-                    category,
-                    stakeholder_name,
-                    dependance,
-                    penetration,
-                    maturite_ssi,
-                    confiance,
-                ): (i32, String, String, i32, i32, i32, i32)| {
-                    C3Stakeholder {
-                        stakeholder_id,
-                        category,
-                        stakeholder_name,
-                        dependance,
-                        penetration,
-                        maturite_ssi,
-                        confiance,
-                    }
-                },
+            let query = format!(
+                "SELECT job_name, last_status, COALESCE(last_message, ''), COALESCE({}, ''), COALESCE({}, ''), last_duration_ms, {}, run_count, failure_count FROM job ORDER BY job_name",
+                dialect().date_format("last_started_at", "%Y-%m-%d %H:%i:%s"),
+                dialect().date_format("last_finished_at", "%Y-%m-%d %H:%i:%s"),
+                dialect().date_format("next_run_at", "%Y-%m-%d %H:%i:%s"),
             );
-    
+
+            let result = conn.query_map(query, Job::from_row);
+
             // check how many rows are returned
             match result {
-                Ok(fetched_stakeholders) => {
-                    for stakeholder in fetched_stakeholders {
-                        stakeholders.push(stakeholder);
+                Ok(fetched_jobs) => {
+                    for job in fetched_jobs {
+                        jobs.push(job);
                     }
                 }
                 Err(_) => {
-                    return stakeholders;
+                    return jobs;
                 }
             }
-    
-            return stakeholders;
+
+            return jobs;
         }
-    
+
         println!("No database connection");
-        return stakeholders;
+        jobs
     }
-    
-    
-}
 
+    async fn update_jobs(query: String) -> Result<(), String> {
+        // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
+        let lock_result = DB_CLIENT.lock();
+
+        if lock_result.is_err() {
+            // kill script
+            trace_logs("Error: DB_CLIENT.lock().unwrap() is_none() return any poison".to_owned());
+            std::process::exit(1);
+        }
+
+        // check if need to create new client
+        if lock_result.unwrap().is_none() {
+            new_client().await;
+        }
+
+        // perform database operations
+        let db_client = DB_CLIENT.lock().unwrap();
+
+        let db_client = db_client.as_ref();
+
+        if let Some(pool) = db_client {
+            let mut conn = pool.get_conn().unwrap();
+
+            let result = conn.query_drop(query);
+
+            match result {
+                Ok(_) => {
+                    return Ok(());
+                }
+                Err(_) => {
+                    return Err("Failed to update job".to_owned());
+                }
+            }
+        }
+
+        Err("No database connection".to_owned())
+    }
+
+    /// Register a job seen for the first time
+    pub async fn create_job(job_name: String, next_run_at: String) -> Result<(), String> {
+        Job::update_jobs(format!(
+            "INSERT INTO job (job_name, next_run_at) VALUES ('{}', '{}')",
+            job_name, next_run_at
+        )).await
+    }
+
+    /// Run a job at `next_run_at`, now to trigger it
+    pub async fn schedule_job(job_name: String, next_run_at: String) -> Result<(), String> {
+        Job::update_jobs(format!(
+            "UPDATE job SET next_run_at = '{}' WHERE job_name = '{}'",
+            next_run_at, job_name
+        )).await
+    }
+
+    pub async fn start_job(job_name: String, started_at: String) -> Result<(), String> {
+        Job::update_jobs(format!(
+            "UPDATE job SET last_status = 'running', last_started_at = '{}' WHERE job_name = '{}'",
+            started_at, job_name
+        )).await
+    }
+
+    /// Keep the outcome of a run and when the next one is due
+    pub async fn finish_job(
+        job_name: String,
+        status: String,
+        message: String,
+        finished_at: String,
+        duration_ms: i32,
+        next_run_at: String,
+    ) -> Result<(), String> {
+        let failed = if status == "failed" { 1 } else { 0 };
+        Job::update_jobs(format!(
+            "UPDATE job SET last_status = '{}', last_message = '{}', last_finished_at = '{}', last_duration_ms = '{}', next_run_at = '{}', run_count = run_count + 1, failure_count = failure_count + {} WHERE job_name = '{}'",
+            status, message, finished_at, duration_ms, next_run_at, failed, job_name
+        )).await
+    }
+
+    /// The runs cut by a stop of the server, they are due again
+    pub async fn interrupt_running_jobs() -> Result<(), String> {
+        Job::update_jobs("UPDATE job SET last_status = 'interrupted' WHERE last_status = 'running'".to_string()).await
+    }
+}
 
 //
 //   ____      _       _
@@ -2934,82 +7162,68 @@ impl C3Stakeholder {
 //  |____/|__,|_| |__,|___|__,|___|___|
 //
 
-static mut DB_CLIENT: Lazy<Arc<Mutex<Option<mysql::Pool>>>> =
+static DB_CLIENT: Lazy<Arc<Mutex<Option<Box<dyn Backend>>>>> =
     Lazy::new(|| Arc::new(Mutex::new(None)));
 
 async fn new_client() {
-    let (tx, rx) = mpsc::channel();
-
-    thread::spawn(move || {
-        tokio::runtime::Runtime::new().unwrap().block_on(async {
-            periodic_database().await;
-            tx.send(()).unwrap(); // Signal that work is done
-        });
-    });
-
-    reset_database().await
-}
-
-async fn periodic_database() {
-    let mut interval = interval(Duration::from_secs(300));
-    loop {
-        interval.tick().await;
-        reset_database().await;
-    }
-}
-
-async fn reset_database() {
-    let mut h = "127.0.0.1";
-
-    let config = fs::read_to_string("config/default.json").unwrap();
-    let config: serde_json::Value = serde_json::from_str(config.as_str()).unwrap();
-
-    let port: u16 = config.get("db_port").unwrap().as_u64().unwrap() as u16;
-    let host: String = config.get("db_host").unwrap().as_str().unwrap().to_owned();
-    let username: String = config
-        .get("db_username")
-        .unwrap()
-        .as_str()
-        .unwrap()
-        .to_owned();
-    let password: String = config
-        .get("db_password")
-        .unwrap()
-        .as_str()
-        .unwrap()
-        .to_owned();
-
-    // check if process arg --prod is used
-    if std::env::args().any(|arg| arg == "--prod") {
-        h = host.as_str();
-    }
-
-    // Define MySQL connection options
-    let opts = mysql::OptsBuilder::new()
-        .ip_or_hostname(Some(h))
-        .tcp_port(port)
-        .db_name(Some("matryriska"))
-        .user(Some(username))
-        .pass(Some(password));
-
-    // hcekc if DB_CLIENT.lock().unwrap().is_none() return any poison error
-    if mysql::Pool::new(opts.clone()).is_err() {
-        return;
-    }
+    let _ = reset_database().await;
+}
 
-    // Create a new MySQL connection pool
-    let pool = mysql::Pool::new(opts).unwrap();
+/// Open the database again, the scheduler refreshes the connection every
+/// few minutes
+pub async fn reset_database() -> Result<(), String> {
+    let backend = match open_backend(dialect()) {
+        Ok(backend) => backend,
+        Err(e) => {
+            trace_logs(format!("Error: cannot open the database: {}", e));
+            return Err(e);
+        }
+    };
+
+    let mut db_client = DB_CLIENT.lock().unwrap();
+    *db_client = Some(backend);
+    Ok(())
+}
 
-    unsafe {
-        let mut db_client = DB_CLIENT.lock().unwrap();
-        *db_client = Some(pool);
+/// The backend of a dialect, from the `db_*` keys of the config
+pub fn open_backend(dialect: Dialect) -> Result<Box<dyn Backend>, String> {
+    let config = config();
+
+    match dialect {
+        Dialect::Sqlite => {
+            // the file is relative to the working directory, like the config
+            let path = config.db_path.as_str();
+            Ok(Box::new(SqliteBackend::open(path).map_err(|e| format!("{}: {}", path, e))?))
+        }
+        Dialect::Postgres => {
+            let mut opts = postgres::Config::new();
+            opts.host(&config.db_host)
+                .port(config.db_port)
+                .dbname("matryriska")
+                .user(&config.db_username)
+                .password(&config.db_password);
+
+            Ok(Box::new(PostgresBackend::open(opts)?))
+        }
+        Dialect::MySql => {
+            // Define MySQL connection options
+            let opts = mysql::OptsBuilder::new()
+                .ip_or_hostname(Some(config.db_host.as_str()))
+                .tcp_port(config.db_port)
+                .db_name(Some("matryriska"))
+                .user(Some(config.db_username.as_str()))
+                .pass(Some(config.db_password.as_str()));
+
+            // Create a new MySQL connection pool
+            Ok(Box::new(MysqlBackend::open(opts)?))
+        }
     }
 }
 
 pub async fn check_db_is_up() -> bool {
-    reset_database().await;
+    let _ = reset_database().await;
 
-    let db_client = unsafe { DB_CLIENT.lock().unwrap() };
+    let db_client = DB_CLIENT.lock().unwrap();
 
     if db_client.is_none() {
         return false;
@@ -3035,7 +7249,7 @@ pub async fn check_db_is_up() -> bool {
 
 pub async fn check_if_table_exist(table_name: String) -> bool {
     // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
-    let lock_result = unsafe { DB_CLIENT.lock() };
+    let lock_result = DB_CLIENT.lock();
 
     if lock_result.is_err() {
         // kill script
@@ -3049,17 +7263,14 @@ pub async fn check_if_table_exist(table_name: String) -> bool {
     }
 
     // perform database operations
-    let db_client = unsafe { DB_CLIENT.lock().unwrap() };
+    let db_client = DB_CLIENT.lock().unwrap();
 
     let db_client = db_client.as_ref();
 
     if let Some(pool) = db_client {
         let mut conn = pool.get_conn().unwrap();
 
-        let query = format!(
-            "SELECT table_name FROM information_schema.tables WHERE table_name = '{}' LIMIT 1",
-            table_name
-        );
+        let query = dialect().table_exists(&table_name);
 
         let result = conn.query_map(query, |(table_name): (String)| table_name);
 
@@ -3082,9 +7293,24 @@ pub async fn check_if_table_exist(table_name: String) -> bool {
     return false;
 }
 
+/// `CREATE TABLE` of a table of `db.json`, in the given dialect
+pub fn create_table_query(dialect: Dialect, table_name: &str, column: &[serde_json::Value]) -> String {
+    let mut query = format!("CREATE TABLE {} (", table_name);
+
+    for (i, col) in column.iter().enumerate() {
+        if i == column.len() - 1 {
+            query.push_str(&format!("{} {})", col["name"], dialect.column_type(col["type"].as_str().unwrap_or(""))));
+        } else {
+            query.push_str(&format!("{} {}, ", col["name"], dialect.column_type(col["type"].as_str().unwrap_or(""))));
+        }
+    }
+
+    query.replace("\"", "")
+}
+
 pub async fn create_table(table_name: String, column: Vec<serde_json::Value>) {
     // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
-    let lock_result = unsafe { DB_CLIENT.lock() };
+    let lock_result = DB_CLIENT.lock();
 
     if lock_result.is_err() {
         // kill script
@@ -3098,24 +7324,14 @@ pub async fn create_table(table_name: String, column: Vec<serde_json::Value>) {
     }
 
     // perform database operations
-    let db_client = unsafe { DB_CLIENT.lock().unwrap() };
+    let db_client = DB_CLIENT.lock().unwrap();
 
     let db_client = db_client.as_ref();
 
     if let Some(pool) = db_client {
         let mut conn = pool.get_conn().unwrap();
 
-        let mut query = format!("CREATE TABLE {} (", table_name);
-
-        for (i, col) in column.iter().enumerate() {
-            if i == column.len() - 1 {
-                query.push_str(&format!("{} {})", col["name"], col["type"]));
-            } else {
-                query.push_str(&format!("{} {}, ", col["name"], col["type"]));
-            }
-        }
-
-        query = query.replace("\"", "");
+        let query = create_table_query(dialect(), &table_name, &column);
 
         let result = conn.query_drop(query);
 
@@ -3135,7 +7351,7 @@ pub async fn create_table(table_name: String, column: Vec<serde_json::Value>) {
 
 pub async fn check_column_exist(table_name: String, column_name: String) -> bool {
     // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
-    let lock_result = unsafe { DB_CLIENT.lock() };
+    let lock_result = DB_CLIENT.lock();
 
     if lock_result.is_err() {
         // kill script
@@ -3149,14 +7365,14 @@ pub async fn check_column_exist(table_name: String, column_name: String) -> bool
     }
 
     // perform database operations
-    let db_client = unsafe { DB_CLIENT.lock().unwrap() };
+    let db_client = DB_CLIENT.lock().unwrap();
 
     let db_client = db_client.as_ref();
 
     if let Some(pool) = db_client {
         let mut conn = pool.get_conn().unwrap();
 
-        let query = format!("SELECT column_name FROM information_schema.columns WHERE table_name = '{}' AND column_name = '{}' LIMIT 1", table_name, column_name);
+        let query = dialect().column_exists(&table_name, &column_name);
 
         let result = conn.query_map(query, |(column_name): (String)| column_name);
 
@@ -3181,7 +7397,7 @@ pub async fn check_column_exist(table_name: String, column_name: String) -> bool
 
 pub async fn add_column(table_name: String, column_name: String, column_type: String) {
     // check if DB_CLIENT.lock().unwrap().is_none() return any poison error
-    let lock_result = unsafe { DB_CLIENT.lock() };
+    let lock_result = DB_CLIENT.lock();
 
     if lock_result.is_err() {
         // kill script
@@ -3195,7 +7411,7 @@ pub async fn add_column(table_name: String, column_name: String, column_type: St
     }
 
     // perform database operations
-    let db_client = unsafe { DB_CLIENT.lock().unwrap() };
+    let db_client = DB_CLIENT.lock().unwrap();
 
     let db_client = db_client.as_ref();
 
@@ -3204,7 +7420,7 @@ pub async fn add_column(table_name: String, column_name: String, column_type: St
 
         let query = format!(
             "ALTER TABLE {} ADD COLUMN {} {}",
-            table_name, column_name, column_type
+            table_name, column_name, dialect().column_type(&column_type)
         );
 
         let result = conn.query_drop(query);
@@ -110,4 +110,46 @@ pub fn is_valid_url_local(input: &str) -> bool {
 pub fn is_valid_domain(input: &str) -> bool {
     let re = Regex::new(r"^[a-zA-Z0-9\.-]{3,25}$").unwrap();
     re.is_match(input)
-}
\ No newline at end of file
+}
+/// A `YYYY-MM-DD` calendar date
+pub fn is_valid_date(input: &str) -> bool {
+    input.len() == 10 && chrono::NaiveDate::parse_from_str(input, "%Y-%m-%d").is_ok()
+}
+
+/// Levels of `calculate_risk` (web/routes/scenario.rs), from the lowest
+pub const RISK_LEVELS: [&str; 5] = ["LOW", "MEDIUM", "HIGH", "CRITICAL", "EXTREME"];
+
+/// Position of a `calculate_risk` level, 0 for N/A
+pub fn risk_level_rank(level: &str) -> usize {
+    RISK_LEVELS.iter().position(|l| *l == level).map(|p| p + 1).unwrap_or(0)
+}
+
+/// Parse an EBIOS rating, only integers between 1 and 4 (included) are valid
+pub fn parse_scale(value: &str) -> Option<i32> {
+    match value.trim().parse::<i32>() {
+        Ok(v) if (1..=4).contains(&v) => Some(v),
+        _ => None,
+    }
+}
+
+/// The EBIOS ratings of `keys` in the body, in the same order, or the first
+/// key whose rating is not valid
+pub fn parse_scales<'a>(body: &Value, keys: &[&'a str]) -> Result<Vec<i32>, &'a str> {
+    keys.iter()
+        .map(|key| parse_scale(&extract_string_from_obj_value(body.get(key))).ok_or(*key))
+        .collect()
+}
+
+/// Escape a text for a SQL string literal, the MySQL way the storage reads
+/// for every backend: the backslashes first, then the quotes
+pub fn sql_escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('\'', "\\'")
+}
+
+/// Escape a text for the content or the attributes of an SVG drawn by hand
+pub fn svg_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,199 @@
+// the languages of the UI: the texts are written in English in the templates
+// and the code, locales/<code>.json translates them for another locale
+use std::collections::HashMap;
+use std::fs;
+use std::future::Future;
+
+use actix_web::HttpRequest;
+use once_cell::sync::OnceCell;
+use regex::Regex;
+use serde::Deserialize;
+
+use crate::helper::config::config;
+
+pub const LOCALE_DIR: &str = "locales";
+
+/// The language the texts are written in, it needs no catalogue
+pub const SOURCE_LOCALE: &str = "en";
+
+/// The locale chosen with the language menu, it wins over the browser
+pub const LOCALE_COOKIE: &str = "matryriska_locale";
+
+/// The messages of a locale, by their English text
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Catalogue {
+    #[serde(skip)]
+    pub code: String,
+    /// name of the language, in the language itself
+    pub name: String,
+    pub messages: HashMap<String, String>,
+}
+
+static CATALOGUES: OnceCell<Vec<Catalogue>> = OnceCell::new();
+
+tokio::task_local! {
+    // the locale of the request being handled, see `scope`
+    static LOCALE: String;
+}
+
+fn source_catalogue() -> Catalogue {
+    Catalogue { code: SOURCE_LOCALE.to_string(), name: "English".to_string(), messages: HashMap::new() }
+}
+
+/// Read the catalogues of `dir`, named by their locale code, and check that
+/// each message keeps the `{placeholders}` of its text
+pub fn load(dir: &str) -> Result<Vec<Catalogue>, Vec<String>> {
+    let mut errors = Vec::new();
+    let mut catalogues = vec![source_catalogue()];
+
+    let mut files = match fs::read_dir(dir) {
+        Ok(entries) => entries.flatten().map(|e| e.path()).filter(|p| p.extension().is_some_and(|ext| ext == "json")).collect::<Vec<_>>(),
+        Err(e) => return Err(vec![format!("{}: {}", dir, e)]),
+    };
+    files.sort();
+
+    let placeholder = Regex::new(r"\{[a-z_]+\}").unwrap();
+    for path in files {
+        let name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+        let code = path.file_stem().unwrap_or_default().to_string_lossy().to_lowercase();
+        let mut catalogue = match fs::read_to_string(&path).map_err(|e| e.to_string()).and_then(|s| serde_json::from_str::<Catalogue>(&s).map_err(|e| e.to_string())) {
+            Ok(catalogue) => catalogue,
+            Err(e) => {
+                errors.push(format!("{}: {}", name, e));
+                continue;
+            }
+        };
+
+        let mut keys = catalogue.messages.keys().collect::<Vec<_>>();
+        keys.sort();
+        for text in keys {
+            let mut expected = placeholder.find_iter(text).map(|m| m.as_str()).collect::<Vec<_>>();
+            let mut found = placeholder.find_iter(&catalogue.messages[text]).map(|m| m.as_str()).collect::<Vec<_>>();
+            expected.sort();
+            found.sort();
+            if expected != found {
+                errors.push(format!("{}: \"{}\" must keep the placeholders {:?}", name, text, expected));
+            }
+        }
+
+        catalogue.code = code;
+        catalogues.push(catalogue);
+    }
+
+    if errors.is_empty() {
+        Ok(catalogues)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Read the catalogues once at startup, an invalid one stops the server
+/// with the list of the errors
+pub fn init() {
+    match load(LOCALE_DIR) {
+        Ok(catalogues) => {
+            let _ = CATALOGUES.set(catalogues);
+        }
+        Err(errors) => {
+            eprintln!("Invalid translations:");
+            for e in errors {
+                eprintln!("  - {}", e);
+            }
+            std::process::exit(2);
+        }
+    }
+}
+
+/// The catalogues; outside of the server (tests, tools) they are read on
+/// first use, English alone standing in for invalid ones
+pub fn catalogues() -> &'static [Catalogue] {
+    CATALOGUES.get_or_init(|| load(LOCALE_DIR).unwrap_or_else(|_| vec![source_catalogue()]))
+}
+
+pub fn is_locale(code: &str) -> bool {
+    catalogues().iter().any(|c| c.code == code)
+}
+
+/// The code and name of every locale, for the language menu
+pub fn locales() -> Vec<(String, String)> {
+    catalogues().iter().map(|c| (c.code.clone(), c.name.clone())).collect()
+}
+
+/// The best locale of an `Accept-Language` header, `fr-FR` gets `fr`
+pub fn from_accept_language(header: &str) -> Option<String> {
+    let mut wanted = header.split(',').filter_map(|part| {
+        let mut fields = part.trim().split(';');
+        let tag = fields.next()?.trim().to_lowercase();
+        let quality = fields
+            .find_map(|f| f.trim().strip_prefix("q="))
+            .map(|q| q.trim().parse::<f32>().unwrap_or(0.0))
+            .unwrap_or(1.0);
+        if tag.is_empty() || tag == "*" || quality <= 0.0 {
+            return None;
+        }
+        Some((tag, quality))
+    }).collect::<Vec<_>>();
+    // the stable sort keeps the order of the header for equal weights
+    wanted.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    wanted.into_iter().find_map(|(tag, _)| {
+        let primary = tag.split('-').next().unwrap_or_default().to_string();
+        [tag, primary].into_iter().find(|code| is_locale(code))
+    })
+}
+
+/// The locale of a request: the one chosen in the language menu, else the
+/// best one for the browser, else `default_locale`
+pub fn negotiate(req: &HttpRequest) -> String {
+    if let Some(cookie) = req.cookie(LOCALE_COOKIE) {
+        if is_locale(cookie.value()) {
+            return cookie.value().to_string();
+        }
+    }
+    req.headers()
+        .get("Accept-Language")
+        .and_then(|h| h.to_str().ok())
+        .and_then(from_accept_language)
+        .unwrap_or_else(|| config().default_locale.clone())
+}
+
+/// Run `f` in `locale`, the templates and `t` use it
+pub async fn scope<F: Future>(locale: String, f: F) -> F::Output {
+    LOCALE.scope(locale, f).await
+}
+
+/// The locale of the request being handled
+pub fn current_locale() -> String {
+    LOCALE.try_with(|l| l.clone()).unwrap_or_else(|_| config().default_locale.clone())
+}
+
+/// `text` in `locale`, the English text when it has no translation
+pub fn translate(locale: &str, text: &str) -> String {
+    catalogues()
+        .iter()
+        .find(|c| c.code == locale)
+        .and_then(|c| c.messages.get(text))
+        .filter(|m| !m.is_empty())
+        .cloned()
+        .unwrap_or_else(|| text.to_string())
+}
+
+/// `text` in the locale of the request
+pub fn t(text: &str) -> String {
+    translate(&current_locale(), text)
+}
+
+/// Replace the `{placeholders}` of a translated text, in a single pass so
+/// that a value is never read as a placeholder
+pub fn fill(text: String, values: &[(&str, String)]) -> String {
+    let placeholder = Regex::new(r"\{([a-z_]+)\}").unwrap();
+    placeholder.replace_all(&text, |cap: &regex::Captures| {
+        values.iter().find(|(key, _)| *key == &cap[1]).map(|(_, value)| value.clone()).unwrap_or_else(|| cap[0].to_string())
+    }).to_string()
+}
+
+/// `(value, label)` pairs with the labels in the locale of the request
+pub fn labels<'a>(pairs: &[(&'a str, &str)]) -> Vec<(&'a str, String)> {
+    pairs.iter().map(|(value, label)| (*value, t(label))).collect()
+}
@@ -0,0 +1,96 @@
+// copy of the configured database into PostgreSQL, run with --migrate-to-postgres
+use std::fs;
+
+use crate::helper::database::{create_table_query, open_backend};
+use crate::helper::functions::sql_escape;
+use crate::helper::storage::postgres::PostgresBackend;
+use crate::helper::storage::{dialect, Backend, Conn, Dialect, SqlValue};
+
+/// Copy the configured database (MySQL, or SQLite) into the PostgreSQL
+/// database of `target`, a connection string such as
+/// `host=db user=matryriska password=... dbname=matryriska`. The tables of
+/// `db.json` are created when missing and emptied before the copy. Returns
+/// the number of rows copied
+pub fn to_postgres(target: &str) -> Result<usize, String> {
+    if dialect() == Dialect::Postgres {
+        return Err("the configured database is already PostgreSQL".to_owned());
+    }
+
+    let config = target.parse::<postgres::Config>().map_err(|e| format!("invalid connection string: {}", e))?;
+    let mut source = open_backend(dialect())?.get_conn()?;
+    let mut target = PostgresBackend::open(config)?.get_conn()?;
+
+    copy_tables(&mut source, &mut target)
+}
+
+/// Copy every table of `db.json` from `source` to the PostgreSQL `target`,
+/// in a single transaction
+pub fn copy_tables(source: &mut Conn, target: &mut Conn) -> Result<usize, String> {
+    let file = fs::read_to_string("assets/_internals/db.json").map_err(|e| e.to_string())?;
+    let tables: Vec<serde_json::Value> = serde_json::from_str(&file).map_err(|e| e.to_string())?;
+
+    target.query_drop("BEGIN")?;
+
+    let mut copied = 0;
+    for table in tables.iter() {
+        match copy_table(source, target, table) {
+            Ok(rows) => copied += rows,
+            Err(e) => {
+                let _ = target.query_drop("ROLLBACK");
+                return Err(format!("{}: {}", table["name"].as_str().unwrap_or(""), e));
+            }
+        }
+    }
+
+    target.query_drop("COMMIT")?;
+    Ok(copied)
+}
+
+/// a text value, escaped the MySQL way like every query of database.rs
+fn literal(value: SqlValue) -> String {
+    match value {
+        SqlValue::Null => "NULL".to_owned(),
+        SqlValue::Int(i) => format!("'{}'", i),
+        SqlValue::Float(f) => format!("'{}'", f),
+        SqlValue::Text(s) => format!("'{}'", sql_escape(&s)),
+    }
+}
+
+fn copy_table(source: &mut Conn, target: &mut Conn, table: &serde_json::Value) -> Result<usize, String> {
+    let pg = Dialect::Postgres;
+    let table_name = table["name"].as_str().unwrap_or("");
+    let columns = table["columns"].as_array().cloned().unwrap_or_default();
+    let names = columns.iter().map(|c| c["name"].as_str().unwrap_or("")).collect::<Vec<&str>>();
+
+    // the schema of the running version, the source is upgraded by the startup
+    if target.query_map(pg.table_exists(table_name), |name: String| name)?.is_empty() {
+        target.query_drop(create_table_query(pg, table_name, &columns))?;
+    }
+    for col in columns.iter() {
+        let name = col["name"].as_str().unwrap_or("");
+        if target.query_map(pg.column_exists(table_name, name), |name: String| name)?.is_empty() {
+            target.query_drop(format!("ALTER TABLE {} ADD COLUMN {} {}", table_name, name, pg.column_type(col["type"].as_str().unwrap_or(""))))?;
+        }
+    }
+
+    target.query_drop(format!("DELETE FROM {}", table_name))?;
+
+    let rows = source.query_map(format!("SELECT {} FROM {}", names.join(", "), table_name), |row: Vec<SqlValue>| row)?;
+    for chunk in rows.chunks(100) {
+        let values = chunk.iter()
+            .map(|row| format!("({})", row.iter().cloned().map(literal).collect::<Vec<String>>().join(", ")))
+            .collect::<Vec<String>>();
+        target.query_drop(format!("INSERT INTO {} ({}) VALUES {}", table_name, names.join(", "), values.join(", ")))?;
+    }
+
+    // the copied ids are explicit, the sequences continue after them
+    for col in columns.iter().filter(|c| c["type"] == "serial") {
+        let name = col["name"].as_str().unwrap_or("");
+        target.query_drop(format!(
+            "SELECT setval(pg_get_serial_sequence('{}', '{}'), COALESCE((SELECT MAX({}) FROM {}), 0) + 1, false)",
+            table_name, name, name, table_name
+        ))?;
+    }
+
+    Ok(rows.len())
+}
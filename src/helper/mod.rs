@@ -1,6 +1,20 @@
-pub mod find_insert;
-pub mod replace_in_body;
 pub mod trace;
 pub mod database;
 pub mod functions;
-pub mod start;
\ No newline at end of file
+pub mod start;
+pub mod referential;
+pub mod oscal;
+pub mod snapshot;
+pub mod threat;
+pub mod trend;
+pub mod storage;
+pub mod migrate;
+pub mod config;
+pub mod webhook;
+pub mod template;
+pub mod security;
+pub mod i18n;
+pub mod search;
+pub mod notification;
+pub mod backup;
+pub mod scheduler;
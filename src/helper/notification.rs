@@ -0,0 +1,362 @@
+// email notifications: the emails are rendered from html/emails, queued in
+// `notification` and sent over SMTP by the jobs of the scheduler, when
+// `smtp.host` is set
+use std::time::Duration;
+
+use chrono::{DateTime, Datelike, Local, NaiveDate, Timelike, Weekday};
+use lettre::message::{Mailbox, MultiPart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use minijinja::{context, Value};
+use once_cell::sync::OnceCell;
+use serde::{Deserialize, Serialize};
+
+use crate::api::mods::countermeasure::status_label;
+use crate::helper::config::{config, SmtpConfig};
+use crate::helper::database::{Countermeasure, Notification, NotificationPreference, Risk};
+use crate::helper::functions::sql_escape;
+use crate::helper::i18n::{self, fill, is_locale, t};
+use crate::helper::template::{render, RENDER_ERROR};
+use crate::helper::trace::trace_logs;
+use crate::helper::webhook::now_after;
+use crate::web::routes::countermeasure::today;
+
+/// The kinds of emails, with their label
+pub const KINDS: [(&str, &str); 5] = [
+    ("assignment", "Countermeasure assigned"),
+    ("overdue", "Countermeasure overdue"),
+    ("review", "Risk review due"),
+    ("digest", "Weekly digest"),
+    ("test", "Test"),
+];
+
+/// Attempts before an email is given up
+pub const MAX_ATTEMPTS: i32 = 5;
+/// Wait after the first failed attempt, doubled after each of the next ones
+const BACKOFF_SECONDS: i64 = 60;
+const TIMEOUT_SECONDS: u64 = 20;
+/// The digest lists the reviews planned in the next days
+pub const DIGEST_DAYS: i64 = 7;
+
+/// A rendered email, kept as JSON in the queue
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Email {
+    pub subject: String,
+    pub text: String,
+    pub html: String,
+}
+
+/// What the digest of an owner lists
+#[derive(Debug, Clone, Serialize)]
+pub struct Digest {
+    /// the measures not implemented yet, the earliest due first
+    pub measures: Vec<Countermeasure>,
+    /// the open risks whose review is planned before `DIGEST_DAYS`
+    pub reviews: Vec<Risk>,
+}
+
+impl Digest {
+    pub fn is_empty(&self) -> bool {
+        self.measures.is_empty() && self.reviews.is_empty()
+    }
+}
+
+pub fn enabled() -> bool {
+    !config().smtp.host.is_empty()
+}
+
+pub fn kind_label(kind: &str) -> String {
+    KINDS.iter().find(|(k, _)| *k == kind).map(|(_, label)| t(label)).unwrap_or(kind.to_string())
+}
+
+/// Whether `owner`, as typed in a risk or a countermeasure, is `user_name`
+pub fn is_owner(owner: &str, user_name: &str) -> bool {
+    !owner.trim().is_empty() && owner.trim().eq_ignore_ascii_case(user_name.trim())
+}
+
+/// Wait before the attempt following `attempts` failed ones
+pub fn backoff_seconds(attempts: i32) -> i64 {
+    BACKOFF_SECONDS * 2_i64.pow(attempts.clamp(1, MAX_ATTEMPTS) as u32 - 1)
+}
+
+/// A closed risk is no longer reviewed; `date` is a `YYYY-MM-DD` date
+pub fn is_review_due(risk: &Risk, date: &str) -> bool {
+    risk.risk_status != "closed" && !risk.risk_next_review.is_empty() && risk.risk_next_review.as_str() <= date
+}
+
+/// The digest of `user_name` on `today`, a `YYYY-MM-DD` date
+pub fn digest_of(user_name: &str, measures: &[Countermeasure], risks: &[Risk], today: &str) -> Digest {
+    let horizon = NaiveDate::parse_from_str(today, "%Y-%m-%d")
+        .map(|d| (d + chrono::Duration::days(DIGEST_DAYS)).format("%Y-%m-%d").to_string())
+        .unwrap_or(today.to_string());
+
+    let mut measures = measures.iter()
+        .filter(|c| is_owner(&c.owner, user_name) && c.status != "implemented" && c.status != "verified")
+        .cloned()
+        .collect::<Vec<Countermeasure>>();
+    // without a deadline last
+    measures.sort_by(|a, b| (a.due_date.is_empty(), &a.due_date, &a.title).cmp(&(b.due_date.is_empty(), &b.due_date, &b.title)));
+
+    let mut reviews = risks.iter()
+        .filter(|r| is_owner(&r.risk_owner, user_name) && is_review_due(r, &horizon))
+        .cloned()
+        .collect::<Vec<Risk>>();
+    reviews.sort_by(|a, b| a.risk_next_review.cmp(&b.risk_next_review));
+
+    Digest { measures, reviews }
+}
+
+/// The ISO week of the digest sent at `now`, `None` before its day and hour
+pub fn digest_week(now: &DateTime<Local>, smtp: &SmtpConfig) -> Option<String> {
+    let day = smtp.digest_day.parse::<Weekday>().ok()?;
+    if now.weekday() != day || now.hour() < smtp.digest_hour {
+        return None;
+    }
+    let week = now.iso_week();
+    Some(format!("{}-W{:02}", week.year(), week.week()))
+}
+
+/// An address of MatryRiska, for the links of the emails
+pub fn link(path: &str) -> String {
+    format!("{}{}", config().smtp.base_url.trim_end_matches('/'), path)
+}
+
+/// Render `emails/<kind>.html` and `emails/<kind>.txt` in `locale`, with the
+/// subject translated and filled with `values`
+pub async fn compose(kind: &str, locale: &str, subject: &str, values: &[(&str, String)], context: Value) -> Result<Email, String> {
+    i18n::scope(locale.to_string(), async {
+        let html = render(&format!("emails/{}.html", kind), &context);
+        let text = render(&format!("emails/{}.txt", kind), &context);
+        if html == RENDER_ERROR || text == RENDER_ERROR {
+            return Err(format!("the {} email cannot be rendered", kind));
+        }
+        Ok(Email { subject: fill(t(subject), values), text, html })
+    }).await
+}
+
+/// The email as a MIME message, its text and html as alternatives
+pub fn message(from: &str, to: &str, email: &Email) -> Result<Message, String> {
+    let from = from.parse::<Mailbox>().map_err(|e| format!("invalid sender {}: {}", from, e))?;
+    let to = to.parse::<Mailbox>().map_err(|e| format!("invalid recipient {}: {}", to, e))?;
+
+    Message::builder()
+        .from(from)
+        .to(to)
+        .subject(email.subject.clone())
+        .multipart(MultiPart::alternative_plain_html(email.text.clone(), email.html.clone()))
+        .map_err(|e| e.to_string())
+}
+
+/// The connection to the SMTP server of the config
+pub fn transport(smtp: &SmtpConfig) -> Result<AsyncSmtpTransport<Tokio1Executor>, String> {
+    let builder = match smtp.tls.as_str() {
+        "tls" => AsyncSmtpTransport::<Tokio1Executor>::relay(&smtp.host).map_err(|e| e.to_string())?,
+        "starttls" => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&smtp.host).map_err(|e| e.to_string())?,
+        // a relay on the same host or network
+        _ => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&smtp.host),
+    };
+
+    let mut builder = builder.port(smtp.port).timeout(Some(Duration::from_secs(TIMEOUT_SECONDS)));
+    if !smtp.username.is_empty() {
+        builder = builder.credentials(Credentials::new(smtp.username.clone(), smtp.password.clone()));
+    }
+    Ok(builder.build())
+}
+
+/// Send one email now
+pub async fn send(transport: &AsyncSmtpTransport<Tokio1Executor>, from: &str, to: &str, email: &Email) -> Result<(), String> {
+    let message = message(from, to, email)?;
+    transport.send(message).await.map(|_| ()).map_err(|e| e.to_string())
+}
+
+fn locale_of(preference: &NotificationPreference) -> String {
+    if is_locale(&preference.locale) { preference.locale.clone() } else { config().default_locale.clone() }
+}
+
+/// Render an email for an owner and queue it; with a `subject`, only once
+/// per subject
+async fn notify(preference: &NotificationPreference, kind: &str, subject: &str, title: &str, values: &[(&str, String)], context: Value) {
+    if !subject.is_empty() {
+        let sent = Notification::get_notifications_of_subject(sql_escape(&preference.user_name), kind.to_string(), sql_escape(subject)).await;
+        if !sent.is_empty() {
+            return;
+        }
+    }
+
+    let context = context! {
+        user_name => preference.user_name,
+        preferences_url => link("/notification"),
+        ..context
+    };
+    let email = match compose(kind, &locale_of(preference), title, values, context).await {
+        Ok(email) => email,
+        Err(e) => {
+            trace_logs(format!("Notification {} to {}: {}", kind, preference.user_name, e));
+            return;
+        }
+    };
+
+    let result = Notification::create_notification(
+        sql_escape(&preference.user_name),
+        sql_escape(&preference.email),
+        kind.to_string(),
+        sql_escape(subject),
+        sql_escape(&serde_json::to_string(&email).unwrap_or_default()),
+        now_after(0),
+    ).await;
+
+    if let Err(e) = result {
+        trace_logs(format!("Notification {} to {}: {}", kind, preference.user_name, e));
+    }
+}
+
+fn measure_context(ctm: &Countermeasure) -> Value {
+    context! {
+        ctm,
+        status => status_label(&ctm.status),
+        url => link(&format!("/countermeasure/detail/{}", ctm.ctm_uuid)),
+    }
+}
+
+/// Tell its owner a countermeasure was assigned to them
+pub async fn assigned(ctm: &Countermeasure) {
+    if !enabled() {
+        return;
+    }
+    let preference = match NotificationPreference::get_preference(&ctm.owner).await {
+        Some(p) if p.assignments && is_owner(&ctm.owner, &p.user_name) => p,
+        _ => return,
+    };
+
+    notify(&preference, "assignment", "", "Countermeasure assigned to you: {title}", &[("title", ctm.title.clone())], measure_context(ctm)).await;
+}
+
+/// Queue the test email of an owner
+pub async fn test_send(preference: &NotificationPreference) {
+    notify(preference, "test", "", "MatryRiska test email", &[], context! { url => link("/") }).await;
+}
+
+/// A measure is overdue once per due date, rescheduling it can notify again.
+/// Returns the number of overdue measures
+pub async fn check_overdue() -> usize {
+    let preferences = NotificationPreference::get_all_preferences().await;
+    let overdue = Countermeasure::get_overdue_countermeasures(today()).await;
+    for ctm in overdue.iter() {
+        if let Some(preference) = preferences.iter().find(|p| p.overdue && is_owner(&ctm.owner, &p.user_name)) {
+            notify(
+                preference,
+                "overdue",
+                &format!("{}:{}", ctm.ctm_uuid, ctm.due_date),
+                "Countermeasure overdue: {title}",
+                &[("title", ctm.title.clone())],
+                measure_context(ctm),
+            ).await;
+        }
+    }
+    overdue.len()
+}
+
+/// A review is due once per planned date, planning the next one can notify
+/// again. Returns the number of reviews due
+pub async fn check_reviews() -> usize {
+    let preferences = NotificationPreference::get_all_preferences().await;
+    let today = today();
+    let risks = Risk::select_all_risk().await;
+    let due = risks.iter().filter(|r| is_review_due(r, &today)).collect::<Vec<&Risk>>();
+    for risk in due.iter() {
+        if let Some(preference) = preferences.iter().find(|p| p.reviews && is_owner(&risk.risk_owner, &p.user_name)) {
+            notify(
+                preference,
+                "review",
+                &format!("{}:{}", risk.risk_uuid, risk.risk_next_review),
+                "Risk review due: {name}",
+                &[("name", risk.risk_name.clone())],
+                context! { risk, url => link(&format!("/risk/detail/{}", risk.risk_uuid)) },
+            ).await;
+        }
+    }
+    due.len()
+}
+
+/// Once a week, on `smtp.digest_day`, what waits for each owner; nothing is
+/// sent to an owner with nothing to do. Returns the number of owners with a
+/// digest, `None` out of its day
+pub async fn send_digests() -> Option<usize> {
+    let week = digest_week(&Local::now(), &config().smtp)?;
+
+    let today = today();
+    let preferences = NotificationPreference::get_all_preferences().await;
+    let risks = Risk::select_all_risk().await;
+    let measures = Countermeasure::get_all_countermeasures().await;
+    let mut count = 0;
+    for preference in preferences.iter().filter(|p| p.digest) {
+        let digest = digest_of(&preference.user_name, &measures, &risks, &today);
+        if digest.is_empty() {
+            continue;
+        }
+
+        let measures = digest.measures.iter()
+            .map(|ctm| context! { overdue => !ctm.due_date.is_empty() && ctm.due_date < today, ..measure_context(ctm) })
+            .collect::<Vec<Value>>();
+        let reviews = digest.reviews.iter()
+            .map(|risk| context! { risk, overdue => risk.risk_next_review < today, url => link(&format!("/risk/detail/{}", risk.risk_uuid)) })
+            .collect::<Vec<Value>>();
+
+        notify(
+            preference,
+            "digest",
+            &week,
+            "Your weekly MatryRiska digest",
+            &[],
+            context! { measures, reviews, days => DIGEST_DAYS, url => link("/") },
+        ).await;
+        count += 1;
+    }
+    Some(count)
+}
+
+static TRANSPORT: OnceCell<AsyncSmtpTransport<Tokio1Executor>> = OnceCell::new();
+
+/// Attempt the queued emails that are due, returns how many
+pub async fn deliver_due() -> Result<usize, String> {
+    let smtp = &config().smtp;
+    let transport = TRANSPORT.get_or_try_init(|| transport(smtp))
+        .map_err(|e| format!("cannot reach {}: {}", smtp.host, e))?;
+
+    let notifications = Notification::get_due_notifications(now_after(0)).await;
+    let count = notifications.len();
+    for notification in notifications {
+        deliver(transport, &smtp.from, notification).await;
+    }
+    Ok(count)
+}
+
+/// One attempt of an email, rescheduled with a backoff when it fails
+async fn deliver(transport: &AsyncSmtpTransport<Tokio1Executor>, from: &str, notification: Notification) {
+    let result = match serde_json::from_str::<Email>(&notification.message) {
+        Ok(email) => send(transport, from, &notification.email, &email).await,
+        Err(e) => Err(e.to_string()),
+    };
+    let error = result.err().unwrap_or_default();
+
+    let attempts = notification.attempts + 1;
+    let (status, next_attempt_at) = if error.is_empty() {
+        ("sent", now_after(0))
+    } else if attempts >= MAX_ATTEMPTS {
+        ("failed", now_after(0))
+    } else {
+        ("pending", now_after(backoff_seconds(attempts)))
+    };
+
+    if !error.is_empty() {
+        trace_logs(format!("Notification {} to {}: attempt {} failed, {}", notification.notification_id, notification.email, attempts, error));
+    }
+
+    let _ = Notification::record_attempt(
+        notification.notification_id,
+        status.to_string(),
+        attempts,
+        sql_escape(&error.chars().take(1000).collect::<String>()),
+        next_attempt_at,
+    ).await;
+}
@@ -0,0 +1,305 @@
+use chrono::{SecondsFormat, Utc};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::helper::database::{Countermeasure, Gap, Requirement, Risk, Scenario, ScenarioRisk};
+use crate::helper::i18n::{fill, t};
+use crate::web::routes::scenario::calculate_risk;
+
+/// OSCAL version of the exported documents
+pub const OSCAL_VERSION: &str = "1.1.2";
+
+/// Namespace of the MatryRiska props, and of the v5 uuids of the rows without one
+const MATRYRISKA_NS: &str = "https://github.com/Sn0wAlice/MatryRiska/ns/oscal";
+const UUID_NS: Uuid = Uuid::from_u128(0x6d617472_7972_4973_8b61_6f7363616c21);
+
+/// A scenario of a risk with its rating and its countermeasures, exported as
+/// an OSCAL risk
+#[derive(Debug, Clone)]
+pub struct RiskFinding {
+    pub risk: Risk,
+    pub scenario: Scenario,
+    pub rating: Option<ScenarioRisk>,
+    pub countermeasures: Vec<Countermeasure>,
+}
+
+/// Everything a study exports to OSCAL
+#[derive(Debug, Clone, Default)]
+pub struct OscalExport {
+    /// the gaps, with the control they assess when they belong to a referential
+    pub gaps: Vec<(Gap, Option<Requirement>)>,
+    pub findings: Vec<RiskFinding>,
+}
+
+impl OscalExport {
+    pub async fn load() -> Self {
+        let mut export = OscalExport::default();
+
+        for gap in Gap::c1_get_all_gaps().await {
+            let requirement = if gap.requirement_id == 0 {
+                None
+            } else {
+                Requirement::c1_get_requirement_by_id(gap.requirement_id).await.pop()
+            };
+            export.gaps.push((gap, requirement));
+        }
+
+        for risk in Risk::select_all_risk().await {
+            for scenario in Scenario::get_all_scenario_of_risk(risk.risk_uuid.to_string()).await {
+                export.findings.push(RiskFinding {
+                    risk: risk.clone(),
+                    rating: ScenarioRisk::get_scenario_risk(scenario.scenario_uuid.to_string()).await.pop(),
+                    countermeasures: Countermeasure::get_all_countermeasure_of_sc(scenario.scenario_uuid.to_string()).await,
+                    scenario,
+                });
+            }
+        }
+
+        export
+    }
+
+    /// OSCAL Assessment Results: one result holding the gap findings and the risks
+    pub fn assessment_results(&self) -> Value {
+        let now = now();
+        // MatryRiska keeps no assessment plan, the import points at the study
+        // described in the back-matter
+        let plan = derived_uuid("resource/assessment-plan");
+
+        let result = json!({
+            "uuid": Uuid::new_v4().to_string(),
+            "title": t("MatryRiska assessment"),
+            "description": t("Gap analysis and risk scenarios of the study."),
+            "start": now,
+            "reviewed-controls": {
+                "control-selections": [{ "include-all": {} }]
+            },
+            "observations": self.gaps.iter().map(|(g, r)| observation(g, r.as_ref(), &now)).collect::<Vec<Value>>(),
+            "risks": self.findings.iter().map(risk).collect::<Vec<Value>>(),
+            "findings": self.gaps.iter().map(|(g, r)| finding(g, r.as_ref())).collect::<Vec<Value>>(),
+        });
+
+        json!({
+            "assessment-results": {
+                "uuid": Uuid::new_v4().to_string(),
+                "metadata": metadata(&t("MatryRiska assessment results"), &now),
+                "import-ap": { "href": format!("#{}", plan) },
+                "results": [strip_empty(result)],
+                "back-matter": {
+                    "resources": [{
+                        "uuid": plan,
+                        "title": t("MatryRiska study"),
+                        "description": t("The risk study the results were assessed in, MatryRiska keeps no separate assessment plan."),
+                    }],
+                },
+            }
+        })
+    }
+
+    /// OSCAL POA&M: an item per open risk and per gap not fully applied
+    pub fn poam(&self) -> Value {
+        let now = now();
+
+        let open_gaps = self.gaps.iter().filter(|(g, _)| g.application_state < 100).collect::<Vec<_>>();
+        let open_risks = self.findings.iter().filter(|f| risk_status(f) != "closed").collect::<Vec<_>>();
+
+        let mut items: Vec<Value> = Vec::new();
+        for f in open_risks.iter() {
+            items.push(json!({
+                "uuid": derived_uuid(&format!("poam-item/risk/{}", f.scenario.scenario_uuid)),
+                "title": risk_title(f),
+                "description": or_default(&f.scenario.threat_description, &t("No threat description.")),
+                "related-risks": [{ "risk-uuid": f.scenario.scenario_uuid.to_string() }],
+            }));
+        }
+        for (g, r) in open_gaps.iter() {
+            items.push(json!({
+                "uuid": derived_uuid(&format!("poam-item/gap/{}", g.gap_id)),
+                "title": gap_title(g, r.as_ref()),
+                "description": or_default(&g.gap, &t("Control not fully applied.")),
+                "related-observations": [{ "observation-uuid": derived_uuid(&format!("observation/gap/{}", g.gap_id)) }],
+                "related-findings": [{ "finding-uuid": derived_uuid(&format!("finding/gap/{}", g.gap_id)) }],
+            }));
+        }
+
+        // OSCAL requires at least one item
+        if items.is_empty() {
+            items.push(json!({
+                "uuid": derived_uuid("poam-item/none"),
+                "title": t("No open item"),
+                "description": t("Every risk is closed and every control is fully applied."),
+            }));
+        }
+
+        let poam = json!({
+            "uuid": Uuid::new_v4().to_string(),
+            "metadata": metadata(&t("MatryRiska plan of action and milestones"), &now),
+            "observations": open_gaps.iter().map(|(g, r)| observation(g, r.as_ref(), &now)).collect::<Vec<Value>>(),
+            "risks": open_risks.iter().map(|f| risk(f)).collect::<Vec<Value>>(),
+            "findings": open_gaps.iter().map(|(g, r)| finding(g, r.as_ref())).collect::<Vec<Value>>(),
+            "poam-items": items,
+        });
+
+        json!({ "plan-of-action-and-milestones": strip_empty(poam) })
+    }
+}
+
+fn now() -> String {
+    Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+/// Stable uuid of a row that has no uuid of its own
+fn derived_uuid(name: &str) -> String {
+    Uuid::new_v5(&UUID_NS, name.as_bytes()).to_string()
+}
+
+/// OSCAL ids are tokens: a letter or `_` first, then letters, digits, `.`, `-` or `_`
+pub fn token(text: &str) -> String {
+    let mut out = text.chars()
+        .map(|c| if c.is_alphanumeric() || c == '.' || c == '-' || c == '_' { c } else { '_' })
+        .collect::<String>();
+    if !out.chars().next().map(|c| c.is_alphabetic() || c == '_').unwrap_or(false) {
+        out.insert(0, '_');
+    }
+    out
+}
+
+fn or_default(text: &str, default: &str) -> String {
+    if text.trim().is_empty() { default.to_string() } else { text.to_string() }
+}
+
+/// OSCAL forbids empty arrays, drop them from an assembly
+fn strip_empty(mut v: Value) -> Value {
+    if let Some(obj) = v.as_object_mut() {
+        obj.retain(|_, value| !matches!(value, Value::Array(a) if a.is_empty()));
+    }
+    v
+}
+
+fn prop(name: &str, value: String) -> Value {
+    json!({ "name": name, "ns": MATRYRISKA_NS, "value": value })
+}
+
+fn metadata(title: &str, now: &str) -> Value {
+    json!({
+        "title": title,
+        "last-modified": now,
+        "version": env!("CARGO_PKG_VERSION"),
+        "oscal-version": OSCAL_VERSION,
+        "parties": [{
+            "uuid": derived_uuid("party/matryriska"),
+            "type": "organization",
+            "name": "MatryRiska",
+        }],
+    })
+}
+
+fn gap_title(g: &Gap, r: Option<&Requirement>) -> String {
+    match r {
+        Some(r) => format!("{} {}", r.code, r.title),
+        None => or_default(format!("{} {}", g.referential_type, g.referential_name).trim(), &fill(t("Gap #{id}"), &[("id", g.gap_id.to_string())])),
+    }
+}
+
+fn observation(g: &Gap, r: Option<&Requirement>, now: &str) -> Value {
+    json!({
+        "uuid": derived_uuid(&format!("observation/gap/{}", g.gap_id)),
+        "title": gap_title(g, r),
+        "description": or_default(&g.gap_justification, &fill(t("Control applied at {percent}%."), &[("percent", g.application_state.to_string())])),
+        "props": [prop("application-state", g.application_state.to_string())],
+        "methods": ["EXAMINE"],
+        "collected": now,
+    })
+}
+
+fn finding(g: &Gap, r: Option<&Requirement>) -> Value {
+    let target_id = match r {
+        Some(r) => token(&r.code),
+        None => format!("gap-{}", g.gap_id),
+    };
+
+    let mut finding = json!({
+        "uuid": derived_uuid(&format!("finding/gap/{}", g.gap_id)),
+        "title": gap_title(g, r),
+        "description": or_default(&g.gap, &t("No gap identified.")),
+        "props": [
+            prop("referential", g.referential_type.clone()),
+            prop("application-state", g.application_state.to_string()),
+        ],
+        "target": {
+            "type": "objective-id",
+            "target-id": target_id,
+            "status": { "state": if g.application_state >= 100 { "satisfied" } else { "not-satisfied" } },
+        },
+        "related-observations": [{ "observation-uuid": derived_uuid(&format!("observation/gap/{}", g.gap_id)) }],
+    });
+
+    if !g.proposed_measures.trim().is_empty() {
+        finding["remarks"] = json!(g.proposed_measures);
+    }
+    finding
+}
+
+fn risk_title(f: &RiskFinding) -> String {
+    format!("{}: {}", f.risk.risk_name, or_default(&f.scenario.scenario_description, &t("scenario")))
+}
+
+/// closed when every countermeasure is implemented, remediating while some
+/// are planned, open without countermeasure
+fn risk_status(f: &RiskFinding) -> &'static str {
+    if f.countermeasures.is_empty() {
+        "open"
+    } else if f.countermeasures.iter().all(|c| c.is_done()) {
+        "closed"
+    } else {
+        "remediating"
+    }
+}
+
+/// a proposed countermeasure is only a recommendation until it is approved
+fn lifecycle(c: &Countermeasure) -> &'static str {
+    match c.status.as_str() {
+        "proposed" => "recommendation",
+        "implemented" | "verified" => "completed",
+        _ => "planned",
+    }
+}
+
+fn risk(f: &RiskFinding) -> Value {
+    let mut risk = json!({
+        "uuid": f.scenario.scenario_uuid.to_string(),
+        "title": risk_title(f),
+        "description": or_default(&f.risk.risk_description, &t("No description.")),
+        "statement": or_default(&f.scenario.threat_description, &t("No threat description.")),
+        "props": [prop("risk-uuid", f.risk.risk_uuid.to_string())],
+        "status": risk_status(f),
+    });
+
+    if let Some(rating) = f.rating.as_ref() {
+        let impact = rating.operational.max(rating.legal_compliance).max(rating.financial).max(rating.reputation);
+        let level = calculate_risk(rating.likelihood, rating.operational, rating.legal_compliance, rating.financial, rating.reputation);
+        let facet = |name: &str, value: String| json!({ "name": name, "system": MATRYRISKA_NS, "value": value });
+
+        risk["characterizations"] = json!([{
+            "origin": { "actors": [{ "type": "party", "actor-uuid": derived_uuid("party/matryriska") }] },
+            "facets": [
+                facet("likelihood", rating.likelihood.to_string()),
+                facet("impact", impact.to_string()),
+                facet("risk", level.to_string()),
+            ],
+        }]);
+    }
+
+    if !f.countermeasures.is_empty() {
+        risk["remediations"] = json!(f.countermeasures.iter().map(|c| json!({
+            // a shared countermeasure remediates several risks, each response needs its own uuid
+            "uuid": derived_uuid(&format!("remediation/{}/{}", c.ctm_uuid, f.scenario.scenario_uuid)),
+            "lifecycle": lifecycle(c),
+            "title": or_default(&c.title, &t("Countermeasure")),
+            "description": or_default(&c.description, &t("No description.")),
+            "props": [prop("countermeasure-uuid", c.ctm_uuid.to_string()), prop("solved", c.solved.to_string()), prop("status", c.status.clone())],
+            "remarks": or_default(&c.solved_description, "-"),
+        })).collect::<Vec<Value>>());
+    }
+
+    risk
+}
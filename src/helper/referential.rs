@@ -0,0 +1,401 @@
+use std::fs;
+use regex::Regex;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::helper::database::{Countermeasure, Gap, Requirement};
+
+/// Directory of the control catalogues shipped with MatryRiska
+pub const CATALOGUE_DIR: &str = "assets/_internals/referentials";
+
+/// A control catalogue as stored in `CATALOGUE_DIR`
+#[derive(Debug, Clone, Deserialize)]
+pub struct Catalogue {
+    pub key: String,
+    pub name: String,
+    pub version: String,
+    #[serde(default)]
+    pub publisher: String,
+    #[serde(default)]
+    pub description: String,
+    pub requirements: Vec<CatalogueNode>,
+}
+
+/// A domain when it has children, a control otherwise
+#[derive(Debug, Clone, Deserialize)]
+pub struct CatalogueNode {
+    pub code: String,
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub children: Vec<CatalogueNode>,
+}
+
+/// Why a catalogue cannot be read: the status of the API, and for a file
+/// serde refused, where and why
+#[derive(Debug, Clone, PartialEq)]
+pub struct CatalogueError {
+    pub status: &'static str,
+    pub message: String,
+}
+
+impl From<&'static str> for CatalogueError {
+    fn from(status: &'static str) -> Self {
+        CatalogueError { status, message: String::new() }
+    }
+}
+
+/// A file serde refused, its message gives the line, the column and the field
+fn not_valid(e: impl std::fmt::Display) -> CatalogueError {
+    CatalogueError { status: "catalogue_not_valid", message: e.to_string() }
+}
+
+impl Catalogue {
+    /// Every catalogue of `CATALOGUE_DIR`, sorted by name, unreadable files are skipped
+    pub fn available() -> Vec<Catalogue> {
+        let mut catalogues: Vec<Catalogue> = Vec::new();
+
+        if let Ok(entries) = fs::read_dir(CATALOGUE_DIR) {
+            for entry in entries.flatten() {
+                let content = match fs::read_to_string(entry.path()) {
+                    Ok(c) => c,
+                    Err(_) => continue,
+                };
+                if let Ok(catalogue) = serde_json::from_str::<Catalogue>(&content) {
+                    catalogues.push(catalogue);
+                }
+            }
+        }
+
+        catalogues.sort_by(|a, b| a.name.cmp(&b.name));
+        catalogues
+    }
+
+    pub fn find(key: &str) -> Option<Catalogue> {
+        Catalogue::available().into_iter().find(|c| c.key == key)
+    }
+
+    /// Read a catalogue in the MatryRiska format, as JSON or YAML, or an OSCAL
+    /// catalog in JSON. `format` is `json`, `yaml` or `oscal`, guessed when empty
+    pub fn parse(content: &str, format: &str) -> Result<Catalogue, CatalogueError> {
+        let format = if format.is_empty() {
+            match serde_json::from_str::<Value>(content) {
+                Ok(v) if v.get("catalog").is_some() => "oscal",
+                Ok(_) => "json",
+                Err(_) => "yaml",
+            }
+        } else {
+            format
+        };
+
+        let catalogue = match format {
+            "json" => serde_json::from_str::<Catalogue>(content).map_err(not_valid)?,
+            "yaml" => serde_yaml::from_str::<Catalogue>(content).map_err(not_valid)?,
+            "oscal" => {
+                let v = serde_json::from_str::<Value>(content).map_err(not_valid)?;
+                Catalogue::from_oscal(&v).ok_or_else(|| not_valid("not an OSCAL catalog: catalog.metadata.title is missing"))?
+            }
+            _ => return Err("format_not_valid".into()),
+        };
+
+        catalogue.check()?;
+        Ok(catalogue)
+    }
+
+    /// Map an OSCAL catalog on the requirement tree: groups become domains,
+    /// controls and their enhancements become requirements
+    fn from_oscal(v: &Value) -> Option<Catalogue> {
+        let catalog = v.get("catalog")?;
+        let metadata = catalog.get("metadata")?;
+        let name = metadata.get("title")?.as_str()?.to_string();
+
+        let publisher = metadata.get("parties")
+            .and_then(|p| p.as_array())
+            .and_then(|p| p.first())
+            .and_then(|p| p.get("name"))
+            .and_then(|n| n.as_str())
+            .unwrap_or("")
+            .to_string();
+
+        fn nodes(parent: &Value) -> Vec<CatalogueNode> {
+            let mut out: Vec<CatalogueNode> = Vec::new();
+            for key in ["groups", "controls"] {
+                for child in parent.get(key).and_then(|c| c.as_array()).into_iter().flatten() {
+                    let code = child.get("props")
+                        .and_then(|p| p.as_array())
+                        .and_then(|p| p.iter().find(|prop| prop.get("name").and_then(|n| n.as_str()) == Some("label")))
+                        .and_then(|p| p.get("value"))
+                        .or(child.get("id"))
+                        .and_then(|c| c.as_str())
+                        .unwrap_or("")
+                        .to_string();
+
+                    out.push(CatalogueNode {
+                        code,
+                        title: child.get("title").and_then(|t| t.as_str()).unwrap_or("").to_string(),
+                        description: statement(child),
+                        children: nodes(child),
+                    });
+                }
+            }
+            out
+        }
+
+        // prose of the statement part and its items, parameters are shown by id
+        fn statement(control: &Value) -> String {
+            fn prose(part: &Value, out: &mut Vec<String>) {
+                if let Some(p) = part.get("prose").and_then(|p| p.as_str()) {
+                    out.push(p.to_string());
+                }
+                for sub in part.get("parts").and_then(|p| p.as_array()).into_iter().flatten() {
+                    prose(sub, out);
+                }
+            }
+
+            let mut out: Vec<String> = Vec::new();
+            for part in control.get("parts").and_then(|p| p.as_array()).into_iter().flatten() {
+                if part.get("name").and_then(|n| n.as_str()) == Some("statement") {
+                    prose(part, &mut out);
+                }
+            }
+
+            let param = Regex::new(r"\{\{\s*insert:\s*param,\s*([^\s}]+)\s*\}\}").unwrap();
+            param.replace_all(&out.join("\n"), "[$1]").to_string()
+        }
+
+        Some(Catalogue {
+            key: slug(&name),
+            name,
+            version: metadata.get("version").and_then(|v| v.as_str()).unwrap_or("").to_string(),
+            publisher,
+            description: String::new(),
+            requirements: nodes(catalog),
+        })
+    }
+
+    /// Reject catalogues the requirement tree cannot hold
+    pub fn check(&self) -> Result<(), &'static str> {
+        if self.key.is_empty() || self.key.len() > 100 {
+            return Err("key_not_valid");
+        }
+        if self.name.is_empty() || self.name.len() > 255 {
+            return Err("name_not_valid");
+        }
+        if self.version.is_empty() || self.version.len() > 50 {
+            return Err("version_not_valid");
+        }
+        if self.publisher.len() > 255 {
+            return Err("publisher_too_long");
+        }
+
+        let requirements = self.flatten();
+        if requirements.is_empty() {
+            return Err("no_requirements");
+        }
+
+        let mut codes: Vec<&str> = Vec::new();
+        for r in requirements.iter() {
+            if r.code.is_empty() || r.code.len() > 50 {
+                return Err("requirement_code_not_valid");
+            }
+            if r.title.len() > 500 {
+                return Err("requirement_title_too_long");
+            }
+            if codes.contains(&r.code.as_str()) {
+                return Err("requirement_code_duplicated");
+            }
+            codes.push(&r.code);
+        }
+
+        Ok(())
+    }
+
+    /// Flatten the tree depth first, each requirement keeps the code of its parent
+    pub fn flatten(&self) -> Vec<Requirement> {
+        fn walk(nodes: &[CatalogueNode], parent: &str, out: &mut Vec<Requirement>) {
+            for n in nodes.iter() {
+                out.push(Requirement {
+                    requirement_id: 0,
+                    referential_id: 0,
+                    code: n.code.clone(),
+                    parent_code: parent.to_string(),
+                    title: n.title.clone(),
+                    description: n.description.clone(),
+                    position: out.len() as i32,
+                });
+                walk(&n.children, &n.code, out);
+            }
+        }
+
+        let mut out: Vec<Requirement> = Vec::new();
+        walk(&self.requirements, "", &mut out);
+        out
+    }
+}
+
+
+/// `ISO/IEC 27001:2022` -> `iso-iec-27001-2022`
+pub fn slug(text: &str) -> String {
+    text.to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<&str>>()
+        .join("-")
+        .chars()
+        .take(100)
+        .collect()
+}
+
+
+/// Requirements added, changed or removed between two versions of a
+/// referential, by code
+#[derive(Debug, Clone, Default)]
+pub struct CatalogueDiff {
+    pub added: Vec<String>,
+    pub changed: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+impl CatalogueDiff {
+    /// A requirement changed when its title, its description or its parent differs
+    pub fn between(old: &[Requirement], new: &[Requirement]) -> Self {
+        let mut diff = CatalogueDiff::default();
+
+        for n in new.iter() {
+            match old.iter().find(|o| o.code == n.code) {
+                None => diff.added.push(n.code.clone()),
+                Some(o) => {
+                    if o.title != n.title || o.description != n.description || o.parent_code != n.parent_code {
+                        diff.changed.push(n.code.clone());
+                    }
+                }
+            }
+        }
+
+        for o in old.iter() {
+            if !new.iter().any(|n| n.code == o.code) {
+                diff.removed.push(o.code.clone());
+            }
+        }
+
+        diff
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.changed.is_empty() && self.removed.is_empty()
+    }
+}
+
+
+/// Compliance of a domain of the requirement tree, from the gaps of its controls
+#[derive(Debug, Clone)]
+pub struct DomainScore {
+    pub code: String,
+    pub title: String,
+    pub depth: usize,
+    /// controls under the domain
+    pub total: usize,
+    /// controls with a gap assessment
+    pub assessed: usize,
+    /// average application state of the controls, 0 for the controls not assessed yet
+    pub score: f64,
+}
+
+impl DomainScore {
+    /// Maturity level from 0 to 5 of the score
+    pub fn level(&self) -> i32 {
+        (self.score / 20.0).round() as i32
+    }
+}
+
+/// Contribution of a countermeasure to the control it addresses: nothing
+/// until the work starts, its progress while in progress, fully once
+/// implemented
+pub fn measure_compliance(c: &Countermeasure) -> i32 {
+    match c.status.as_str() {
+        "implemented" | "verified" => 100,
+        "in_progress" => c.solved.clamp(0, 90),
+        _ => 0,
+    }
+}
+
+/// Application state of a gap derived from its countermeasures, none when no
+/// measure addresses it and the state is assessed by hand
+pub fn derived_state(measures: &[Countermeasure]) -> Option<i32> {
+    if measures.is_empty() {
+        return None;
+    }
+    let total = measures.iter().map(measure_compliance).sum::<i32>();
+    Some((total as f64 / measures.len() as f64).round() as i32)
+}
+
+pub fn is_control(requirements: &[Requirement], r: &Requirement) -> bool {
+    !requirements.iter().any(|c| c.parent_code == r.code)
+}
+
+/// Depth of each requirement in the tree, 0 for the top domains
+pub fn depth(requirements: &[Requirement], r: &Requirement) -> usize {
+    let mut depth = 0;
+    let mut parent = r.parent_code.clone();
+    while !parent.is_empty() && depth < requirements.len() {
+        depth += 1;
+        parent = match requirements.iter().find(|p| p.code == parent) {
+            Some(p) => p.parent_code.clone(),
+            None => String::new(),
+        };
+    }
+    depth
+}
+
+/// Roll the compliance of the controls up to every domain of the tree, the
+/// whole referential is returned first with an empty code
+pub fn rollup(requirements: &[Requirement], gaps: &[Gap]) -> Vec<DomainScore> {
+    let state = |r: &Requirement| -> Option<i32> {
+        gaps.iter()
+            .find(|g| g.requirement_id == r.requirement_id)
+            .map(|g| g.application_state)
+    };
+
+    let score_of = |code: &str, title: &str, depth: usize| -> DomainScore {
+        // the controls below `code`, the whole tree for an empty code
+        let controls = requirements.iter()
+            .filter(|r| is_control(requirements, r))
+            .filter(|r| code.is_empty() || is_below(requirements, r, code))
+            .collect::<Vec<&Requirement>>();
+
+        let states = controls.iter().filter_map(|r| state(r)).collect::<Vec<i32>>();
+        let score = if controls.is_empty() { 0.0 } else { states.iter().sum::<i32>() as f64 / controls.len() as f64 };
+
+        DomainScore {
+            code: code.to_string(),
+            title: title.to_string(),
+            depth,
+            total: controls.len(),
+            assessed: states.len(),
+            score,
+        }
+    };
+
+    let mut scores = vec![score_of("", "Overall", 0)];
+    for r in requirements.iter().filter(|r| !is_control(requirements, r)) {
+        scores.push(score_of(&r.code, &r.title, depth(requirements, r)));
+    }
+    scores
+}
+
+fn is_below(requirements: &[Requirement], r: &Requirement, code: &str) -> bool {
+    let mut parent = r.parent_code.clone();
+    let mut hops = 0;
+    while !parent.is_empty() && hops < requirements.len() {
+        if parent == code {
+            return true;
+        }
+        hops += 1;
+        parent = match requirements.iter().find(|p| p.code == parent) {
+            Some(p) => p.parent_code.clone(),
+            None => String::new(),
+        };
+    }
+    false
+}
@@ -0,0 +1,251 @@
+// recurring jobs: the background work of the server, run by one worker
+// thread. The state of each job is kept in `job`, a restart does not run a
+// job again before it is due, and a job can be run now from the /job page
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::rc::Rc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use chrono::Local;
+use tokio::task::{spawn_blocking, spawn_local, LocalSet};
+use tokio::time::interval;
+
+use crate::helper::backup::write_backup;
+use crate::helper::config::config;
+use crate::helper::database::{reset_database, Job, Snapshot};
+use crate::helper::functions::sql_escape;
+use crate::helper::snapshot::StudyState;
+use crate::helper::trace::trace_logs;
+use crate::helper::webhook::now_after;
+use crate::helper::{notification, webhook};
+
+/// How often the worker looks for the jobs due
+const TICK_SECONDS: u64 = 5;
+
+/// `next_run_at` of the jobs only run by hand
+pub const NEVER: &str = "9999-12-31 23:59:59";
+
+/// A job the scheduler knows
+#[derive(Debug, Clone)]
+pub struct JobSpec {
+    pub name: &'static str,
+    pub label: &'static str,
+    pub description: &'static str,
+    /// seconds between two runs, 0 for a job only run by hand
+    pub every_seconds: u64,
+    /// why the job cannot run with this config
+    pub disabled: Option<&'static str>,
+}
+
+/// The jobs, with the intervals of the config
+pub fn jobs() -> Vec<JobSpec> {
+    let scheduler = &config().scheduler;
+    let webhooks = if webhook::enabled() { None } else { Some("Webhooks are off") };
+    let emails = if notification::enabled() { None } else { Some("No SMTP server is set") };
+
+    vec![
+        JobSpec {
+            name: "database_refresh",
+            label: "Database connection",
+            description: "Open the connection to the database again",
+            every_seconds: 300,
+            disabled: None,
+        },
+        JobSpec {
+            name: "webhook_deliveries",
+            label: "Webhook deliveries",
+            description: "POST the queued webhook events and retry the failed ones",
+            every_seconds: 5,
+            disabled: webhooks,
+        },
+        JobSpec {
+            name: "email_deliveries",
+            label: "Email deliveries",
+            description: "Send the queued emails and retry the failed ones",
+            every_seconds: 30,
+            disabled: emails,
+        },
+        JobSpec {
+            name: "overdue_countermeasures",
+            label: "Overdue countermeasures",
+            description: "Find the countermeasures past their due date, for the webhooks and the emails",
+            every_seconds: 3600,
+            disabled: if webhooks.is_none() || emails.is_none() { None } else { Some("Webhooks are off and no SMTP server is set") },
+        },
+        JobSpec {
+            name: "review_reminders",
+            label: "Review reminders",
+            description: "Email the owners of the risks due for review",
+            every_seconds: 3600,
+            disabled: emails,
+        },
+        JobSpec {
+            name: "weekly_digests",
+            label: "Weekly digests",
+            description: "On the day of the digest, email each owner what waits for them",
+            every_seconds: 3600,
+            disabled: emails,
+        },
+        JobSpec {
+            name: "database_backup",
+            label: "Database backup",
+            description: "Write a JSON copy of every table in the backup directory",
+            every_seconds: scheduler.backup_hours as u64 * 3600,
+            disabled: if scheduler.backup_dir.is_empty() { Some("No backup directory is set") } else { None },
+        },
+        JobSpec {
+            name: "study_snapshot",
+            label: "Study snapshot",
+            description: "Freeze the state of the study in a snapshot",
+            every_seconds: scheduler.snapshot_hours as u64 * 3600,
+            disabled: None,
+        },
+    ]
+}
+
+pub fn job_spec(name: &str) -> Option<JobSpec> {
+    jobs().into_iter().find(|job| job.name == name)
+}
+
+/// Whether a job should start at `now`, `YYYY-MM-DD HH:MM:SS` UTC
+pub fn is_due(spec: &JobSpec, state: &Job, now: &str) -> bool {
+    spec.disabled.is_none() && state.last_status != "running" && state.next_run_at.as_str() <= now
+}
+
+/// The next run of a job started `seconds_ago`, never for a job only run
+/// by hand
+pub fn next_run_at(spec: &JobSpec, seconds_ago: i64) -> String {
+    if spec.every_seconds == 0 {
+        NEVER.to_string()
+    } else {
+        now_after(spec.every_seconds as i64 - seconds_ago)
+    }
+}
+
+/// The overdue countermeasures found for the webhooks and for the emails,
+/// `None` for the side that is off
+pub fn overdue_message(webhooks: Option<usize>, emails: Option<usize>) -> String {
+    match (webhooks, emails) {
+        (Some(w), Some(e)) => format!("{} overdue countermeasures for webhooks, {} for emails", w, e),
+        (Some(w), None) => format!("{} overdue countermeasures for webhooks", w),
+        (None, Some(e)) => format!("{} overdue countermeasures for emails", e),
+        (None, None) => "Webhooks are off and no SMTP server is set".to_string(),
+    }
+}
+
+/// What a run does, the message kept for the page
+pub async fn run(name: &str) -> Result<String, String> {
+    match name {
+        "database_refresh" => {
+            reset_database().await?;
+            Ok("Connection opened".to_string())
+        }
+        "webhook_deliveries" => webhook::deliver_due().await.map(|n| format!("{} deliveries attempted", n)),
+        "email_deliveries" => notification::deliver_due().await.map(|n| format!("{} emails attempted", n)),
+        "overdue_countermeasures" => {
+            let webhooks = if webhook::enabled() { Some(webhook::check_overdue().await) } else { None };
+            let emails = if notification::enabled() { Some(notification::check_overdue().await) } else { None };
+            Ok(overdue_message(webhooks, emails))
+        }
+        "review_reminders" => Ok(format!("{} reviews due", notification::check_reviews().await)),
+        "weekly_digests" => Ok(match notification::send_digests().await {
+            Some(n) => format!("{} owners with a digest", n),
+            None => "Not the time of the digest".to_string(),
+        }),
+        "database_backup" => {
+            let scheduler = &config().scheduler;
+            let (dir, keep) = (scheduler.backup_dir.clone(), scheduler.backup_keep);
+            spawn_blocking(move || write_backup(&dir, keep)).await.map_err(|e| e.to_string())?
+        }
+        "study_snapshot" => {
+            let content = serde_json::to_string(&StudyState::load().await).map_err(|e| e.to_string())?;
+            let name = format!("Scheduled {}", Local::now().format("%Y-%m-%d %H:%M"));
+            Snapshot::create_snapshot(sql_escape(&name), sql_escape(&content)).await?;
+            Ok(name)
+        }
+        _ => Err(format!("unknown job {}", name)),
+    }
+}
+
+/// Run a job now and keep its outcome
+async fn run_job(spec: JobSpec) {
+    let started = Instant::now();
+    let _ = Job::start_job(spec.name.to_string(), now_after(0)).await;
+
+    let result = run(spec.name).await;
+    let elapsed = started.elapsed();
+
+    let (status, message) = match result {
+        Ok(message) => ("success", message),
+        Err(e) => {
+            trace_logs(format!("Job {} failed: {}", spec.name, e));
+            ("failed", e)
+        }
+    };
+
+    let _ = Job::finish_job(
+        spec.name.to_string(),
+        status.to_string(),
+        sql_escape(&message.chars().take(1000).collect::<String>()),
+        now_after(0),
+        elapsed.as_millis().min(i32::MAX as u128) as i32,
+        next_run_at(&spec, elapsed.as_secs() as i64),
+    ).await;
+}
+
+/// Register the jobs seen for the first time, due now unless only run by
+/// hand, and bring the next run of the others within their interval; the
+/// runs cut by the last stop are due again
+async fn register() {
+    let _ = Job::interrupt_running_jobs().await;
+
+    let states = Job::get_all_jobs().await;
+    for spec in jobs() {
+        match states.iter().find(|s| s.job_name == spec.name) {
+            None => {
+                let first = if spec.every_seconds == 0 { NEVER.to_string() } else { now_after(0) };
+                let _ = Job::create_job(spec.name.to_string(), first).await;
+            }
+            Some(state) => {
+                let latest = next_run_at(&spec, 0);
+                if state.next_run_at > latest {
+                    let _ = Job::schedule_job(spec.name.to_string(), latest).await;
+                }
+            }
+        }
+    }
+}
+
+/// Start the worker, on its own thread and runtime; the jobs run side by
+/// side, a job never twice at once
+pub fn start() {
+    thread::spawn(|| {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        LocalSet::new().block_on(&runtime, async {
+            register().await;
+
+            let running: Rc<RefCell<HashSet<&'static str>>> = Rc::new(RefCell::new(HashSet::new()));
+            let mut tick = interval(Duration::from_secs(TICK_SECONDS));
+            loop {
+                tick.tick().await;
+
+                let now = now_after(0);
+                let states = Job::get_all_jobs().await;
+                for spec in jobs() {
+                    let due = states.iter().find(|s| s.job_name == spec.name).is_some_and(|state| is_due(&spec, state, &now));
+                    if !due || !running.borrow_mut().insert(spec.name) {
+                        continue;
+                    }
+
+                    let running = running.clone();
+                    spawn_local(async move {
+                        let name = spec.name;
+                        run_job(spec).await;
+                        running.borrow_mut().remove(name);
+                    });
+                }
+            }
+        });
+    });
+}
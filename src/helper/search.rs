@@ -0,0 +1,390 @@
+// the global search of the top bar: the entities of the study containing
+// every word of the query, ranked and grouped by kind
+use std::collections::HashMap;
+
+use regex::Regex;
+use serde::Serialize;
+
+use crate::helper::database::{BienSupport, C2RiskSources, C3Stakeholder, Countermeasure, FearedEvent, Gap, Risk, Scenario, ValeurMetier};
+use crate::helper::i18n::{fill, t};
+
+/// A shorter query would match nearly everything
+pub const MIN_QUERY_LEN: usize = 2;
+
+/// Longer queries are cut, the words after are ignored
+pub const MAX_QUERY_LEN: usize = 200;
+
+/// Results shown for each kind of entity
+pub const MAX_RESULTS: usize = 20;
+
+/// The kinds of entities, in the order of the groups with the same score
+pub const KINDS: [(&str, &str); 9] = [
+    ("risk", "Risks"),
+    ("scenario", "Scenarios"),
+    ("countermeasure", "Countermeasures"),
+    ("business_value", "Business values"),
+    ("asset", "Supporting assets"),
+    ("feared_event", "Feared events"),
+    ("gap", "Gaps"),
+    ("risk_source", "Risk sources"),
+    ("stakeholder", "Stakeholders"),
+];
+
+// a word in the title weighs as much as 5 in the other texts, and the
+// repetitions of a word count up to 5 times so that a long description does
+// not outrank a title
+const TITLE_WEIGHT: usize = 5;
+const MAX_REPEAT: usize = 5;
+// the words of the query next to each other, then the whole title
+const PHRASE_BONUS: usize = 10;
+const EXACT_BONUS: usize = 20;
+
+// characters kept around the first match of a text
+const SNIPPET_CONTEXT: usize = 60;
+const TITLE_LEN: usize = 120;
+
+/// An entity as the search sees it
+#[derive(Debug, Clone, Default)]
+pub struct Document {
+    /// one of `KINDS`
+    pub kind: &'static str,
+    pub url: String,
+    pub title: String,
+    /// what the entity belongs to, the risk of a scenario for instance
+    pub context: String,
+    pub texts: Vec<String>,
+}
+
+/// A piece of a highlighted text
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Part {
+    pub text: String,
+    pub matched: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Hit {
+    pub url: String,
+    pub title: Vec<Part>,
+    pub context: String,
+    /// the text around the first match outside of the title
+    pub snippet: Vec<Part>,
+    pub score: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Group {
+    pub kind: &'static str,
+    pub label: String,
+    /// every hit of the kind, only the `MAX_RESULTS` best are kept
+    pub total: usize,
+    pub hits: Vec<Hit>,
+}
+
+/// `text` in lowercase, character by character, with the range of the
+/// original character each byte comes from
+fn fold(text: &str) -> (String, Vec<(usize, usize)>) {
+    let mut lower = String::with_capacity(text.len());
+    let mut origins = Vec::with_capacity(text.len());
+    for (i, c) in text.char_indices() {
+        for l in c.to_lowercase() {
+            lower.push(l);
+            origins.resize(lower.len(), (i, i + c.len_utf8()));
+        }
+    }
+    (lower, origins)
+}
+
+/// The lowercase of the search, the same for the query, the scores and the
+/// highlights
+pub fn lowercase(text: &str) -> String {
+    fold(text).0
+}
+
+/// The lowercase words of a query, none when it is too short
+pub fn terms(query: &str) -> Vec<String> {
+    let query = lowercase(&query.trim().chars().take(MAX_QUERY_LEN).collect::<String>());
+    if query.chars().count() < MIN_QUERY_LEN {
+        return Vec::new();
+    }
+
+    let mut terms = Vec::new();
+    for word in query.split_whitespace() {
+        if !terms.iter().any(|t: &String| t == word) {
+            terms.push(word.to_string());
+        }
+    }
+    terms
+}
+
+/// The words of a query, found in the lowercase of a text
+pub struct Matcher {
+    terms: Vec<String>,
+    words: Regex,
+}
+
+impl Matcher {
+    /// `None` when the query is too short
+    pub fn new(query: &str) -> Option<Matcher> {
+        let terms = terms(query);
+        if terms.is_empty() {
+            return None;
+        }
+
+        // the longest first
+        let mut sorted = terms.clone();
+        sorted.sort_by_key(|t| std::cmp::Reverse(t.len()));
+        let words = Regex::new(&sorted.iter().map(|t| regex::escape(t)).collect::<Vec<String>>().join("|")).unwrap();
+        Some(Matcher { terms, words })
+    }
+
+    pub fn terms(&self) -> &[String] {
+        &self.terms
+    }
+
+    /// The byte ranges of `text` holding a word of the query
+    pub fn find_iter(&self, text: &str) -> Vec<(usize, usize)> {
+        let (lower, origins) = fold(text);
+        self.words.find_iter(&lower).map(|m| (origins[m.start()].0, origins[m.end() - 1].1)).collect()
+    }
+}
+
+/// The score of `doc`, `None` when a word of the query is missing
+pub fn score(doc: &Document, matcher: &Matcher) -> Option<usize> {
+    let terms = matcher.terms();
+    let title = lowercase(&doc.title);
+    let texts = doc.texts.iter().map(|text| lowercase(text)).collect::<Vec<String>>();
+
+    let mut score = 0;
+    for term in terms {
+        let in_title = title.matches(term.as_str()).count().min(MAX_REPEAT);
+        let in_texts = texts.iter().map(|text| text.matches(term.as_str()).count()).sum::<usize>().min(MAX_REPEAT);
+        if in_title + in_texts == 0 {
+            return None;
+        }
+        score += TITLE_WEIGHT * in_title + in_texts;
+    }
+
+    let phrase = terms.join(" ");
+    if terms.len() > 1 && (title.contains(&phrase) || texts.iter().any(|text| text.contains(&phrase))) {
+        score += PHRASE_BONUS;
+    }
+    if title.trim() == phrase {
+        score += EXACT_BONUS;
+    }
+    Some(score)
+}
+
+/// `text` cut in pieces, the words of the query marked
+pub fn highlight(text: &str, matcher: &Matcher) -> Vec<Part> {
+    let mut parts = Vec::new();
+    let mut last = 0;
+    for (start, end) in matcher.find_iter(text) {
+        // a word ending inside a character already marked
+        if end <= last {
+            continue;
+        }
+        let start = start.max(last);
+        if start > last {
+            parts.push(Part { text: text[last..start].to_string(), matched: false });
+        }
+        parts.push(Part { text: text[start..end].to_string(), matched: true });
+        last = end;
+    }
+    if last < text.len() {
+        parts.push(Part { text: text[last..].to_string(), matched: false });
+    }
+    parts
+}
+
+/// At most `len` characters of `text`, with an ellipsis when cut
+fn truncate(text: &str, len: usize) -> String {
+    match text.char_indices().nth(len) {
+        Some((end, _)) => format!("{}…", text[..end].trim_end()),
+        None => text.to_string(),
+    }
+}
+
+/// The first text containing a word, around its first match; else the
+/// beginning of the first text
+fn snippet(texts: &[String], matcher: &Matcher) -> Vec<Part> {
+    let (text, found) = match texts.iter().find_map(|text| matcher.find_iter(text).first().map(|m| (text, *m))) {
+        Some((text, m)) => (text, Some(m)),
+        None => match texts.iter().find(|text| !text.trim().is_empty()) {
+            Some(text) => (text, None),
+            None => return Vec::new(),
+        },
+    };
+
+    let piece = match found {
+        Some((start, end)) => {
+            let mut from = text[..start].char_indices().rev().take(SNIPPET_CONTEXT).last().map(|(i, _)| i).unwrap_or(start);
+            let mut to = text[end..].char_indices().nth(SNIPPET_CONTEXT).map(|(i, _)| end + i).unwrap_or(text.len());
+            // on whole words
+            if from > 0 {
+                from = text[from..start].find(char::is_whitespace).map(|i| from + i).unwrap_or(from);
+            }
+            if to < text.len() {
+                to = text[end..to].rfind(char::is_whitespace).map(|i| end + i).unwrap_or(to);
+            }
+            format!(
+                "{}{}{}",
+                if from > 0 { "…" } else { "" },
+                text[from..to].split_whitespace().collect::<Vec<&str>>().join(" "),
+                if to < text.len() { "…" } else { "" }
+            )
+        }
+        None => truncate(&text.split_whitespace().collect::<Vec<&str>>().join(" "), 2 * SNIPPET_CONTEXT),
+    };
+    highlight(&piece, matcher)
+}
+
+/// The documents matching `query`, best first, grouped by kind; the groups
+/// with the best hits come first
+pub fn rank(query: &str, documents: Vec<Document>) -> Vec<Group> {
+    let matcher = match Matcher::new(query) {
+        Some(matcher) => matcher,
+        None => return Vec::new(),
+    };
+
+    let mut by_kind: HashMap<&str, Vec<(usize, Document)>> = HashMap::new();
+    for doc in documents {
+        if let Some(score) = score(&doc, &matcher) {
+            by_kind.entry(doc.kind).or_default().push((score, doc));
+        }
+    }
+
+    let mut groups = KINDS.iter().filter_map(|(kind, label)| {
+        let mut found = by_kind.remove(kind)?;
+        found.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.title.to_lowercase().cmp(&b.1.title.to_lowercase())));
+        let total = found.len();
+        let hits = found.into_iter().take(MAX_RESULTS).map(|(score, doc)| Hit {
+            title: highlight(&truncate(&doc.title, TITLE_LEN), &matcher),
+            snippet: snippet(&doc.texts, &matcher),
+            url: doc.url,
+            context: doc.context,
+            score,
+        }).collect::<Vec<Hit>>();
+        Some(Group { kind, label: t(label), total, hits })
+    }).collect::<Vec<Group>>();
+
+    // stable, the order of `KINDS` for the same best score
+    groups.sort_by_key(|g| std::cmp::Reverse(g.hits.first().map(|h| h.score).unwrap_or(0)));
+    groups
+}
+
+fn or_empty(text: &Option<String>) -> String {
+    text.clone().unwrap_or_default()
+}
+
+/// Every searchable entity of the study
+pub async fn documents() -> Vec<Document> {
+    let mut documents = Vec::new();
+
+    for risk in Risk::select_all_risk().await {
+        for scenario in Scenario::get_all_scenario_of_risk(risk.risk_uuid.to_string()).await {
+            documents.push(Document {
+                kind: "scenario",
+                url: format!("/scenario/detail/{}", scenario.scenario_uuid),
+                title: scenario.scenario_description,
+                context: risk.risk_name.clone(),
+                texts: vec![scenario.threat_description],
+            });
+        }
+        documents.push(Document {
+            kind: "risk",
+            url: format!("/risk/detail/{}", risk.risk_uuid),
+            title: risk.risk_name,
+            context: String::new(),
+            texts: vec![risk.risk_description],
+        });
+    }
+
+    for ctm in Countermeasure::get_all_countermeasures().await {
+        documents.push(Document {
+            kind: "countermeasure",
+            url: format!("/countermeasure/detail/{}", ctm.ctm_uuid),
+            title: ctm.title,
+            context: String::new(),
+            texts: vec![ctm.description, ctm.solved_description],
+        });
+    }
+
+    let values = ValeurMetier::c1_get_all_valeurmetier_no_limit().await;
+    let value_names = values.iter().map(|v| (v.valeur_id, v.valeur_name.clone())).collect::<HashMap<i32, String>>();
+    for value in values {
+        documents.push(Document {
+            kind: "business_value",
+            url: format!("/c1/vm/detail/{}", value.valeur_id),
+            title: value.valeur_name,
+            context: String::new(),
+            texts: vec![value.valeur_description],
+        });
+    }
+
+    for asset in BienSupport::c1_get_all_assets().await {
+        documents.push(Document {
+            kind: "asset",
+            url: format!("/c1/asset/detail/{}", asset.support_id),
+            title: asset.support_name,
+            context: String::new(),
+            texts: vec![asset.support_description],
+        });
+    }
+
+    // a feared event has no detail page
+    for event in FearedEvent::c1_get_all_feared_event().await {
+        documents.push(Document {
+            kind: "feared_event",
+            url: format!("/c1/fevnt/update/{}", event.event_id),
+            title: event.evenement_redoute,
+            context: value_names.get(&event.valeur_metier).cloned().unwrap_or_default(),
+            texts: vec![event.impact],
+        });
+    }
+
+    for gap in Gap::c1_get_all_gaps().await {
+        let title = if gap.gap.trim().is_empty() {
+            fill(t("Gap #{id}"), &[("id", gap.gap_id.to_string())])
+        } else {
+            gap.gap
+        };
+        documents.push(Document {
+            kind: "gap",
+            url: format!("/c1/gaps/{}", gap.gap_id),
+            title,
+            context: format!("{} {}", gap.referential_type, gap.referential_name).trim().to_string(),
+            texts: vec![gap.gap_justification, gap.proposed_measures],
+        });
+    }
+
+    for source in C2RiskSources::c2_get_all_risk().await {
+        documents.push(Document {
+            kind: "risk_source",
+            url: format!("/c2/{}", source.risk_id),
+            texts: vec![source.objectifs_vises, or_empty(&source.motivation), or_empty(&source.ressources), or_empty(&source.justification_exclusion_sr_ov)],
+            title: source.source_risque,
+            context: String::new(),
+        });
+    }
+
+    for stakeholder in C3Stakeholder::c3_get_all_stakeholder().await {
+        documents.push(Document {
+            kind: "stakeholder",
+            url: format!("/c3/stakeholder/{}", stakeholder.stakeholder_id),
+            title: stakeholder.stakeholder_name,
+            context: stakeholder.category,
+            texts: vec![or_empty(&stakeholder.mesures_securite)],
+        });
+    }
+
+    documents
+}
+
+/// Search the whole study
+pub async fn search(query: &str) -> Vec<Group> {
+    if terms(query).is_empty() {
+        return Vec::new();
+    }
+    rank(query, documents().await)
+}
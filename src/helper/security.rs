@@ -0,0 +1,114 @@
+// the protection of the API against the other sites: the CORS origins, and
+// a CSRF token kept in a cookie, written in every page and sent back by the
+// pages with each call of the API
+use std::future::Future;
+
+use actix_cors::Cors;
+use actix_web::cookie::{Cookie, SameSite};
+use actix_web::http::header;
+use actix_web::HttpRequest;
+use rand::Rng;
+
+use crate::helper::config::config;
+
+pub const CSRF_COOKIE: &str = "matryriska_csrf";
+
+/// Header carrying the token of the page, set by html/partials/head.html
+pub const CSRF_HEADER: &str = "X-CSRF-Token";
+
+tokio::task_local! {
+    // the token of the page being rendered, see `scope`
+    static TOKEN: String;
+}
+
+/// The CORS of the server: only the `security.allowed_origins` get an
+/// `Access-Control-Allow-Origin`, with the cookies
+pub fn cors() -> Cors {
+    let mut cors = Cors::default()
+        .allowed_methods(vec!["GET", "POST"])
+        .allowed_headers(vec![header::CONTENT_TYPE])
+        .allowed_header(CSRF_HEADER)
+        .supports_credentials();
+    for origin in config().security.allowed_origins.iter() {
+        cors = cors.allowed_origin(origin);
+    }
+    cors
+}
+
+pub fn generate_token() -> String {
+    let bytes: [u8; 32] = rand::thread_rng().gen();
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect::<String>()
+}
+
+fn is_token(value: &str) -> bool {
+    value.len() == 64 && value.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+/// The token of the cookie of the request, if it has a valid one
+pub fn request_token(req: &HttpRequest) -> Option<String> {
+    req.cookie(CSRF_COOKIE).map(|c| c.value().to_string()).filter(|t| is_token(t))
+}
+
+/// The cookie keeping the token, for the whole site and until the browser
+/// is closed; the pages read the token from their meta tag, not the cookie
+pub fn token_cookie(token: &str) -> Cookie<'static> {
+    let same_site = match config().security.cookie_same_site.to_lowercase().as_str() {
+        "lax" => SameSite::Lax,
+        "none" => SameSite::None,
+        _ => SameSite::Strict,
+    };
+    Cookie::build(CSRF_COOKIE, token.to_string())
+        .path("/")
+        .http_only(true)
+        .same_site(same_site)
+        .secure(config().security.cookie_secure)
+        .finish()
+}
+
+/// Run `f` with the token of the page, `render` writes it in the templates
+pub async fn scope<F: Future>(token: String, f: F) -> F::Output {
+    TOKEN.scope(token, f).await
+}
+
+/// The token of the page being rendered
+pub fn current_token() -> Option<String> {
+    TOKEN.try_with(|t| t.clone()).ok()
+}
+
+/// Whether `origin` may call the API: the server itself, or one of the
+/// `security.allowed_origins`
+pub fn is_allowed_origin(req: &HttpRequest, origin: &str) -> bool {
+    let info = req.connection_info();
+    let own = format!("{}://{}", info.scheme(), info.host());
+    origin.eq_ignore_ascii_case(&own) || config().security.allowed_origins.iter().any(|o| o.eq_ignore_ascii_case(origin))
+}
+
+/// Check a call of the API: a browser must send it from an allowed origin,
+/// and it must carry the token of the cookie in the `X-CSRF-Token` header
+pub fn verify(req: &HttpRequest) -> Result<(), &'static str> {
+    if let Some(origin) = req.headers().get(header::ORIGIN) {
+        match origin.to_str() {
+            Ok(origin) if is_allowed_origin(req, origin) => {}
+            _ => return Err("origin not allowed"),
+        }
+    }
+
+    let expected = match request_token(req) {
+        Some(token) => token,
+        None => return Err("missing csrf cookie"),
+    };
+    let sent = match req.headers().get(CSRF_HEADER).and_then(|h| h.to_str().ok()) {
+        Some(token) => token,
+        None => return Err("missing csrf token"),
+    };
+
+    if !same_secret(sent, &expected) {
+        return Err("invalid csrf token");
+    }
+    Ok(())
+}
+
+/// Compare two secrets in constant time
+pub fn same_secret(sent: &str, expected: &str) -> bool {
+    sent.len() == expected.len() && sent.bytes().zip(expected.bytes()).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+}
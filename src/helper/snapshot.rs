@@ -0,0 +1,230 @@
+use serde::{Deserialize, Serialize};
+
+use crate::helper::database::{C3Stakeholder, Countermeasure, Gap, Requirement, Risk, Scenario, ScenarioRisk};
+use crate::helper::threat::{residual_threat_level, threat_level};
+use crate::helper::functions::risk_level_rank;
+use crate::web::routes::scenario::calculate_risk;
+
+/// Frozen state of a study, stored as JSON in a snapshot. Every field has a
+/// default so that snapshots taken by older versions still load
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StudyState {
+    pub risks: Vec<RiskState>,
+    pub scenarios: Vec<ScenarioState>,
+    pub countermeasures: Vec<MeasureState>,
+    pub gaps: Vec<GapState>,
+    pub stakeholders: Vec<StakeholderState>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RiskState {
+    pub risk_uuid: String,
+    pub risk_name: String,
+    pub risk_status: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScenarioState {
+    pub scenario_uuid: String,
+    pub risk_uuid: String,
+    pub scenario_description: String,
+    pub likelihood: i32,
+    pub reputation: i32,
+    pub operational: i32,
+    pub legal_compliance: i32,
+    pub financial: i32,
+    /// `calculate_risk` level
+    pub level: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MeasureState {
+    pub ctm_uuid: String,
+    pub title: String,
+    pub status: String,
+    pub solved: i32,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GapState {
+    pub gap_id: i32,
+    pub title: String,
+    pub application_state: i32,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StakeholderState {
+    pub stakeholder_id: i32,
+    pub stakeholder_name: String,
+    pub dependance: i32,
+    pub penetration: i32,
+    pub maturite_ssi: i32,
+    pub confiance: i32,
+    pub threat_level: f64,
+    pub residual_threat_level: f64,
+}
+
+impl MeasureState {
+    /// implemented and verified measures are closed
+    pub fn is_closed(&self) -> bool {
+        self.status == "implemented" || self.status == "verified"
+    }
+}
+
+impl StudyState {
+    /// The live state of the study
+    pub async fn load() -> Self {
+        let mut state = StudyState::default();
+
+        for risk in Risk::select_all_risk().await {
+            for scenario in Scenario::get_all_scenario_of_risk(risk.risk_uuid.to_string()).await {
+                let rating = ScenarioRisk::get_scenario_risk(scenario.scenario_uuid.to_string()).await.pop()
+                    .unwrap_or(ScenarioRisk::default());
+                state.scenarios.push(ScenarioState {
+                    scenario_uuid: scenario.scenario_uuid.to_string(),
+                    risk_uuid: risk.risk_uuid.to_string(),
+                    scenario_description: scenario.scenario_description,
+                    likelihood: rating.likelihood,
+                    reputation: rating.reputation,
+                    operational: rating.operational,
+                    legal_compliance: rating.legal_compliance,
+                    financial: rating.financial,
+                    level: calculate_risk(rating.likelihood, rating.operational, rating.legal_compliance, rating.financial, rating.reputation).to_string(),
+                });
+            }
+
+            state.risks.push(RiskState {
+                risk_uuid: risk.risk_uuid.to_string(),
+                risk_name: risk.risk_name,
+                risk_status: risk.risk_status,
+            });
+        }
+
+        for ctm in Countermeasure::get_all_countermeasures().await {
+            state.countermeasures.push(MeasureState {
+                ctm_uuid: ctm.ctm_uuid.to_string(),
+                title: ctm.title,
+                status: ctm.status,
+                solved: ctm.solved,
+            });
+        }
+
+        for gap in Gap::c1_get_all_gaps().await {
+            let requirement = if gap.requirement_id == 0 {
+                None
+            } else {
+                Requirement::c1_get_requirement_by_id(gap.requirement_id).await.pop()
+            };
+            let title = match requirement {
+                Some(r) => format!("{} {}", r.code, r.title),
+                None => format!("{} {} #{}", gap.referential_type, gap.referential_name, gap.gap_id).trim().to_string(),
+            };
+            state.gaps.push(GapState {
+                gap_id: gap.gap_id,
+                title,
+                application_state: gap.application_state,
+            });
+        }
+
+        for stakeholder in C3Stakeholder::c3_get_all_stakeholder().await {
+            state.stakeholders.push(StakeholderState {
+                stakeholder_id: stakeholder.stakeholder_id,
+                stakeholder_name: stakeholder.stakeholder_name.clone(),
+                dependance: stakeholder.dependance,
+                penetration: stakeholder.penetration,
+                maturite_ssi: stakeholder.maturite_ssi,
+                confiance: stakeholder.confiance,
+                threat_level: threat_level(&stakeholder).2,
+                residual_threat_level: residual_threat_level(&stakeholder).2,
+            });
+        }
+
+        state
+    }
+}
+
+/// What changed between two states of a study
+#[derive(Debug, Clone, Default)]
+pub struct StudyDiff {
+    pub new_risks: Vec<RiskState>,
+    pub removed_risks: Vec<RiskState>,
+    /// (before, after) of the scenarios whose level changed
+    pub level_changes: Vec<(ScenarioState, ScenarioState)>,
+    pub new_scenarios: Vec<ScenarioState>,
+    pub removed_scenarios: Vec<ScenarioState>,
+    /// measures closed since, the new ones already closed included
+    pub closed_measures: Vec<MeasureState>,
+    /// measures closed before and open again
+    pub reopened_measures: Vec<MeasureState>,
+    /// (before, after) of the gaps whose application state changed
+    pub gap_changes: Vec<(GapState, GapState)>,
+    /// (before, after) of the stakeholders whose threat level changed
+    pub stakeholder_changes: Vec<(StakeholderState, StakeholderState)>,
+}
+
+impl StudyDiff {
+    pub fn between(from: &StudyState, to: &StudyState) -> Self {
+        let mut diff = StudyDiff::default();
+
+        for risk in to.risks.iter() {
+            if !from.risks.iter().any(|r| r.risk_uuid == risk.risk_uuid) {
+                diff.new_risks.push(risk.clone());
+            }
+        }
+        for risk in from.risks.iter() {
+            if !to.risks.iter().any(|r| r.risk_uuid == risk.risk_uuid) {
+                diff.removed_risks.push(risk.clone());
+            }
+        }
+
+        for after in to.scenarios.iter() {
+            match from.scenarios.iter().find(|s| s.scenario_uuid == after.scenario_uuid) {
+                Some(before) if before.level != after.level => diff.level_changes.push((before.clone(), after.clone())),
+                Some(_) => {}
+                None => diff.new_scenarios.push(after.clone()),
+            }
+        }
+        for before in from.scenarios.iter() {
+            if !to.scenarios.iter().any(|s| s.scenario_uuid == before.scenario_uuid) {
+                diff.removed_scenarios.push(before.clone());
+            }
+        }
+        // the worst increases first
+        diff.level_changes.sort_by_key(|(before, after)| risk_level_rank(&before.level) as i64 - risk_level_rank(&after.level) as i64);
+
+        for after in to.countermeasures.iter() {
+            let before = from.countermeasures.iter().find(|c| c.ctm_uuid == after.ctm_uuid);
+            let was_closed = before.map(|c| c.is_closed()).unwrap_or(false);
+            if after.is_closed() && !was_closed {
+                diff.closed_measures.push(after.clone());
+            }
+            if !after.is_closed() && was_closed {
+                diff.reopened_measures.push(after.clone());
+            }
+        }
+
+        for after in to.gaps.iter() {
+            if let Some(before) = from.gaps.iter().find(|g| g.gap_id == after.gap_id) {
+                if before.application_state != after.application_state {
+                    diff.gap_changes.push((before.clone(), after.clone()));
+                }
+            }
+        }
+
+        for after in to.stakeholders.iter() {
+            if let Some(before) = from.stakeholders.iter().find(|s| s.stakeholder_id == after.stakeholder_id) {
+                if before.threat_level != after.threat_level || before.residual_threat_level != after.residual_threat_level {
+                    diff.stakeholder_changes.push((before.clone(), after.clone()));
+                }
+            }
+        }
+
+        diff
+    }
+}
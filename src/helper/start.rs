@@ -1,8 +1,9 @@
 
 use std::fs;
 
-use crate::helper::database::{check_if_table_exist, create_table, check_column_exist, add_column, check_db_is_up};
+use crate::helper::database::{check_if_table_exist, create_table, check_column_exist, add_column, check_db_is_up, GravityScale, AssetLink, Countermeasure, CountermeasureLink, ScenarioRiskHistory, CountermeasureHistory};
 use crate::helper::trace::trace_logs;
+use crate::helper::config::{Config, CONFIG_FILE};
 
 pub async fn startup() {
     // check all the necessary database archi
@@ -12,18 +13,12 @@ pub async fn startup() {
         fs::create_dir("config").unwrap();
     }
 
-    // check file config/default.json exist
-    if !fs::metadata("config/default.json").is_ok() {
-        fs::write("config/default.json", r#"{
-            "db_port": 3306,
-            "db_host": "172.20.0.202",
-            "db_username": "matryriska",
-            "db_password": "StrongPassword123",
-            "web_port": 8080
-        }"#).unwrap();
+    // write the defaults as a starting point, without any password; the
+    // settings can also come from MATRYRISKA_* variables and flags
+    if fs::metadata(CONFIG_FILE).is_err() {
+        let _ = fs::write(CONFIG_FILE, serde_json::to_string_pretty(&Config::default()).unwrap());
     }
 
-
     wait_for_the_db_to_up().await;
    
 
@@ -49,11 +44,39 @@ pub async fn startup() {
         }
     }
 
+    // supporting assets used to belong to a single business value
+    AssetLink::c1_migrate_legacy_links().await;
+
+    // countermeasures used to only have a solved percentage
+    Countermeasure::migrate_legacy_status().await;
+
+    // countermeasures used to belong to a single scenario
+    CountermeasureLink::migrate_legacy_links().await;
+
+    // the trends start from the current ratings and progress
+    ScenarioRiskHistory::record_changes().await;
+    CountermeasureHistory::record_changes().await;
+
+    // seed the EBIOS gravity scale, each study can then adapt it from the UI
+    if GravityScale::c1_get_gravity_scale().await.is_empty() {
+        for (niveau, libelle, description) in DEFAULT_GRAVITY_SCALE.iter() {
+            GravityScale::c1_create_gravity_level(*niveau, libelle.to_string(), description.replace("'", "\\'")).await;
+        }
+    }
+
     trace_logs("Database setup completed !".to_owned());
 
 }
 
 
+/// The default 4 levels gravity scale of the EBIOS RM guide
+const DEFAULT_GRAVITY_SCALE: [(i32, &str, &str); 4] = [
+    (1, "Minimal impact", "There is no operational or performance impact on activities, nor on the safety of people and assets. The company will handle the situation with minor difficulties, consuming available margins to adapt."),
+    (2, "Moderate performance degradation", "The company experiences a decline in activity performance without affecting the safety of people and assets. While the situation presents challenges, it can be managed, though in a degraded mode."),
+    (3, "Severe performance degradation", "The company's activity suffers significant setbacks, possibly impacting the safety of people and assets. The situation can be overcome, but only with serious difficulty, forcing operations into a highly degraded mode."),
+    (4, "Inability to maintain operations", "The company is unable to sustain all or part of its activities, with potentially severe impacts on the safety of people and assets. The company is unlikely to overcome this situation, posing a threat to its survival."),
+];
+
 async fn wait_for_the_db_to_up() {
     // check if the database is up
     let mut done = false;
@@ -0,0 +1,297 @@
+// the storage backends, the queries of database.rs run on any of them
+use crate::helper::config::config;
+
+pub mod mysql;
+pub mod postgres;
+pub mod sqlite;
+
+/// A value read from a row, whatever the backend
+#[derive(Debug, Clone, PartialEq)]
+pub enum SqlValue {
+    Null,
+    Int(i64),
+    Float(f64),
+    Text(String),
+}
+
+/// Conversion of a column to a Rust value
+pub trait FromSqlValue: Sized {
+    fn from_sql_value(value: SqlValue) -> Result<Self, String>;
+}
+
+impl FromSqlValue for String {
+    fn from_sql_value(value: SqlValue) -> Result<Self, String> {
+        match value {
+            SqlValue::Text(s) => Ok(s),
+            SqlValue::Int(i) => Ok(i.to_string()),
+            SqlValue::Float(f) => Ok(f.to_string()),
+            SqlValue::Null => Err("NULL read as a string".to_owned()),
+        }
+    }
+}
+
+impl FromSqlValue for i64 {
+    fn from_sql_value(value: SqlValue) -> Result<Self, String> {
+        match value {
+            SqlValue::Int(i) => Ok(i),
+            SqlValue::Float(f) => Ok(f as i64),
+            SqlValue::Text(s) => s.trim().parse::<i64>().map_err(|e| format!("{} is not an integer: {}", s, e)),
+            SqlValue::Null => Err("NULL read as an integer".to_owned()),
+        }
+    }
+}
+
+impl FromSqlValue for i32 {
+    fn from_sql_value(value: SqlValue) -> Result<Self, String> {
+        i64::from_sql_value(value).map(|i| i as i32)
+    }
+}
+
+impl FromSqlValue for f64 {
+    fn from_sql_value(value: SqlValue) -> Result<Self, String> {
+        match value {
+            SqlValue::Float(f) => Ok(f),
+            SqlValue::Int(i) => Ok(i as f64),
+            SqlValue::Text(s) => s.trim().parse::<f64>().map_err(|e| format!("{} is not a number: {}", s, e)),
+            SqlValue::Null => Err("NULL read as a number".to_owned()),
+        }
+    }
+}
+
+impl FromSqlValue for bool {
+    fn from_sql_value(value: SqlValue) -> Result<Self, String> {
+        match value {
+            // PostgreSQL booleans read as text
+            SqlValue::Text(s) if s == "t" || s == "true" => Ok(true),
+            SqlValue::Text(s) if s == "f" || s == "false" => Ok(false),
+            value => i64::from_sql_value(value).map(|i| i != 0),
+        }
+    }
+}
+
+impl<T: FromSqlValue> FromSqlValue for Option<T> {
+    fn from_sql_value(value: SqlValue) -> Result<Self, String> {
+        match value {
+            SqlValue::Null => Ok(None),
+            value => T::from_sql_value(value).map(Some),
+        }
+    }
+}
+
+/// Conversion of a whole row, to a value or to a tuple of values
+pub trait FromSqlRow: Sized {
+    fn from_sql_row(row: Vec<SqlValue>) -> Result<Self, String>;
+}
+
+impl<T: FromSqlValue> FromSqlRow for T {
+    fn from_sql_row(row: Vec<SqlValue>) -> Result<Self, String> {
+        match row.into_iter().next() {
+            Some(value) => T::from_sql_value(value),
+            None => Err("empty row".to_owned()),
+        }
+    }
+}
+
+/// the raw values of a row
+impl FromSqlRow for Vec<SqlValue> {
+    fn from_sql_row(row: Vec<SqlValue>) -> Result<Self, String> {
+        Ok(row)
+    }
+}
+
+macro_rules! tuple_from_sql_row {
+    ($count:expr; $($name:ident),+) => {
+        impl<$($name: FromSqlValue),+> FromSqlRow for ($($name,)+) {
+            fn from_sql_row(row: Vec<SqlValue>) -> Result<Self, String> {
+                if row.len() != $count {
+                    return Err(format!("expected {} columns, got {}", $count, row.len()));
+                }
+                let mut values = row.into_iter();
+                Ok(($($name::from_sql_value(values.next().unwrap_or(SqlValue::Null))?,)+))
+            }
+        }
+    };
+}
+
+tuple_from_sql_row!(1; A);
+tuple_from_sql_row!(2; A, B);
+tuple_from_sql_row!(3; A, B, C);
+tuple_from_sql_row!(4; A, B, C, D);
+tuple_from_sql_row!(5; A, B, C, D, E);
+tuple_from_sql_row!(6; A, B, C, D, E, F);
+tuple_from_sql_row!(7; A, B, C, D, E, F, G);
+tuple_from_sql_row!(8; A, B, C, D, E, F, G, H);
+tuple_from_sql_row!(9; A, B, C, D, E, F, G, H, I);
+tuple_from_sql_row!(10; A, B, C, D, E, F, G, H, I, J);
+tuple_from_sql_row!(11; A, B, C, D, E, F, G, H, I, J, K);
+tuple_from_sql_row!(12; A, B, C, D, E, F, G, H, I, J, K, L);
+
+/// A connection of a backend, runs one statement at a time
+pub trait Connection {
+    fn query_rows(&mut self, query: &str) -> Result<Vec<Vec<SqlValue>>, String>;
+    fn execute(&mut self, query: &str) -> Result<(), String>;
+    /// id of the last row inserted by this connection
+    fn last_insert_id(&self) -> u64;
+}
+
+/// A storage backend, hands out connections
+pub trait Backend: Send {
+    fn get_conn(&self) -> Result<Conn, String>;
+}
+
+/// The connection handed out by a backend
+pub struct Conn(Box<dyn Connection>);
+
+impl Conn {
+    pub fn new(connection: impl Connection + 'static) -> Self {
+        Conn(Box::new(connection))
+    }
+
+    /// Run a query and map each of its rows
+    pub fn query_map<Q, T, U, F>(&mut self, query: Q, mut f: F) -> Result<Vec<U>, String>
+    where
+        Q: AsRef<str>,
+        T: FromSqlRow,
+        F: FnMut(T) -> U,
+    {
+        let rows = self.0.query_rows(query.as_ref())?;
+        let mut mapped = Vec::with_capacity(rows.len());
+        for row in rows {
+            mapped.push(f(T::from_sql_row(row)?));
+        }
+        Ok(mapped)
+    }
+
+    /// Run a statement without result
+    pub fn query_drop<Q: AsRef<str>>(&mut self, query: Q) -> Result<(), String> {
+        self.0.execute(query.as_ref())
+    }
+
+    pub fn last_insert_id(&self) -> u64 {
+        self.0.last_insert_id()
+    }
+}
+
+/// The SQL flavour of the configured backend
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dialect {
+    MySql,
+    Sqlite,
+    Postgres,
+}
+
+/// The dialect of the backend selected in the config
+pub fn dialect() -> Dialect {
+    Dialect::from_name(&config().db_backend)
+}
+
+impl Dialect {
+    /// `db_backend` of the config, MySQL when unknown
+    pub fn from_name(db_backend: &str) -> Self {
+        match db_backend {
+            "sqlite" => Dialect::Sqlite,
+            "postgres" => Dialect::Postgres,
+            _ => Dialect::MySql,
+        }
+    }
+
+    /// `expr` formatted as text, `format` uses the MySQL `DATE_FORMAT`
+    /// specifiers `%Y`, `%m`, `%d`, `%H`, `%i` and `%s`
+    pub fn date_format(&self, expr: &str, format: &str) -> String {
+        match self {
+            Dialect::MySql => format!("DATE_FORMAT({}, '{}')", expr, format),
+            Dialect::Sqlite => format!("strftime('{}', {})", format.replace("%i", "%M").replace("%s", "%S"), expr),
+            Dialect::Postgres => {
+                let format = format.replace("%Y", "YYYY").replace("%m", "MM").replace("%d", "DD")
+                    .replace("%H", "HH24").replace("%i", "MI").replace("%s", "SS");
+                format!("to_char({}, '{}')", expr, format)
+            }
+        }
+    }
+
+    /// Query returning a row when the table exists
+    pub fn table_exists(&self, table_name: &str) -> String {
+        match self {
+            Dialect::MySql => format!("SELECT table_name FROM information_schema.tables WHERE table_name = '{}' LIMIT 1", table_name),
+            Dialect::Sqlite => format!("SELECT name FROM sqlite_master WHERE type = 'table' AND name = '{}' LIMIT 1", table_name),
+            Dialect::Postgres => format!("SELECT table_name FROM information_schema.tables WHERE table_schema = current_schema() AND table_name = '{}' LIMIT 1", table_name),
+        }
+    }
+
+    /// Query returning a row when the column exists
+    pub fn column_exists(&self, table_name: &str, column_name: &str) -> String {
+        match self {
+            Dialect::MySql => format!("SELECT column_name FROM information_schema.columns WHERE table_name = '{}' AND column_name = '{}' LIMIT 1", table_name, column_name),
+            Dialect::Sqlite => format!("SELECT name FROM pragma_table_info('{}') WHERE name = '{}' LIMIT 1", table_name, column_name),
+            Dialect::Postgres => format!("SELECT column_name FROM information_schema.columns WHERE table_schema = current_schema() AND table_name = '{}' AND column_name = '{}' LIMIT 1", table_name, column_name),
+        }
+    }
+
+    /// A column type of `db.json` in this dialect; `serial` is an auto
+    /// incremented integer primary key, the other types are written the
+    /// MySQL way and translated for PostgreSQL
+    pub fn column_type(&self, column_type: &str) -> String {
+        match (self, column_type) {
+            (Dialect::MySql, "serial") => "int primary key auto_increment".to_owned(),
+            (Dialect::Sqlite, "serial") => "integer primary key autoincrement".to_owned(),
+            (Dialect::Postgres, "serial") => "serial primary key".to_owned(),
+            (Dialect::Postgres, column_type) => {
+                let (base, constraints) = column_type.split_once(' ').unwrap_or((column_type, ""));
+                let base = match base {
+                    "datetime" => "timestamp(0)",
+                    "longtext" => "text",
+                    "tinyint(1)" => "smallint",
+                    base => base,
+                };
+                format!("{} {}", base, constraints).trim().to_owned()
+            }
+            (_, column_type) => column_type.to_owned(),
+        }
+    }
+}
+
+/// The queries escape their string literals the MySQL way, with backslashes;
+/// SQLite and PostgreSQL only know the doubled quote, the escapes are read as
+/// MySQL would
+pub fn standard_literals(query: &str) -> String {
+    let mut sql = String::with_capacity(query.len());
+    let mut chars = query.chars().peekable();
+    let mut in_literal = false;
+
+    while let Some(c) = chars.next() {
+        if !in_literal {
+            in_literal = c == '\'';
+            sql.push(c);
+            continue;
+        }
+
+        match c {
+            '\\' => match chars.next() {
+                Some('\'') => sql.push_str("''"),
+                Some('b') => sql.push('\u{8}'),
+                Some('n') => sql.push('\n'),
+                Some('r') => sql.push('\r'),
+                Some('t') => sql.push('\t'),
+                Some('Z') => sql.push('\u{1a}'),
+                // LIKE wildcards keep their backslash
+                Some(w @ ('%' | '_')) => {
+                    sql.push('\\');
+                    sql.push(w);
+                }
+                Some(other) => sql.push(other),
+                None => sql.push('\\'),
+            },
+            '\'' if chars.peek() == Some(&'\'') => {
+                chars.next();
+                sql.push_str("''");
+            }
+            '\'' => {
+                in_literal = false;
+                sql.push(c);
+            }
+            c => sql.push(c),
+        }
+    }
+
+    sql
+}
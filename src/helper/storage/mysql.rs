@@ -0,0 +1,54 @@
+// MySQL server backend
+use mysql::prelude::Queryable;
+use mysql::{Opts, Pool, PooledConn, Value};
+
+use crate::helper::storage::{Backend, Conn, Connection, SqlValue};
+
+pub struct MysqlBackend {
+    pool: Pool,
+}
+
+impl MysqlBackend {
+    pub fn open(opts: impl Into<Opts>) -> Result<Self, String> {
+        let pool = Pool::new(opts).map_err(|e| e.to_string())?;
+        Ok(MysqlBackend { pool })
+    }
+}
+
+impl Backend for MysqlBackend {
+    fn get_conn(&self) -> Result<Conn, String> {
+        let conn = self.pool.get_conn().map_err(|e| e.to_string())?;
+        Ok(Conn::new(MysqlConnection(conn)))
+    }
+}
+
+struct MysqlConnection(PooledConn);
+
+/// the text protocol returns most values as bytes, they are read as text
+fn sql_value(value: Value) -> SqlValue {
+    match value {
+        Value::NULL => SqlValue::Null,
+        Value::Bytes(bytes) => SqlValue::Text(String::from_utf8_lossy(&bytes).into_owned()),
+        Value::Int(i) => SqlValue::Int(i),
+        Value::UInt(u) => SqlValue::Int(u as i64),
+        Value::Float(f) => SqlValue::Float(f as f64),
+        Value::Double(d) => SqlValue::Float(d),
+        Value::Date(y, m, d, h, i, s, _) => SqlValue::Text(format!("{:04}-{:02}-{:02} {:02}:{:02}:{:02}", y, m, d, h, i, s)),
+        Value::Time(negative, days, h, i, s, _) => SqlValue::Text(format!("{}{:02}:{:02}:{:02}", if negative { "-" } else { "" }, days * 24 + h as u32, i, s)),
+    }
+}
+
+impl Connection for MysqlConnection {
+    fn query_rows(&mut self, query: &str) -> Result<Vec<Vec<SqlValue>>, String> {
+        let rows = self.0.query::<mysql::Row, _>(query).map_err(|e| e.to_string())?;
+        Ok(rows.into_iter().map(|row| row.unwrap().into_iter().map(sql_value).collect()).collect())
+    }
+
+    fn execute(&mut self, query: &str) -> Result<(), String> {
+        self.0.query_drop(query).map_err(|e| e.to_string())
+    }
+
+    fn last_insert_id(&self) -> u64 {
+        self.0.last_insert_id()
+    }
+}
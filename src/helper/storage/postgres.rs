@@ -0,0 +1,119 @@
+// PostgreSQL server backend
+use std::sync::mpsc::{self, Sender};
+use std::thread;
+
+use postgres::{Client, Config, NoTls, SimpleQueryMessage};
+
+use crate::helper::storage::{standard_literals, Backend, Conn, Connection, SqlValue};
+
+/// A statement for the client thread, with where to send its result
+enum Request {
+    Query(String, Sender<Result<Vec<Vec<SqlValue>>, String>>),
+    Execute(String, Sender<Result<(), String>>),
+}
+
+/// The blocking client drives its own runtime, it cannot run on the actix
+/// workers; it lives on its own thread and the connections send it the
+/// statements
+pub struct PostgresBackend {
+    requests: Sender<Request>,
+}
+
+impl PostgresBackend {
+    pub fn open(config: Config) -> Result<Self, String> {
+        let (requests, received) = mpsc::channel::<Request>();
+        let (ready, connected) = mpsc::channel::<Result<(), String>>();
+
+        thread::spawn(move || {
+            let mut client = match config.connect(NoTls) {
+                Ok(client) => {
+                    let _ = ready.send(Ok(()));
+                    client
+                }
+                Err(e) => {
+                    let _ = ready.send(Err(error(e)));
+                    return;
+                }
+            };
+
+            // ends when the backend and its connections are dropped
+            for request in received {
+                match request {
+                    Request::Query(query, reply) => {
+                        let _ = reply.send(query_rows(&mut client, &query));
+                    }
+                    Request::Execute(query, reply) => {
+                        let _ = reply.send(client.batch_execute(&query).map_err(error));
+                    }
+                }
+            }
+        });
+
+        connected.recv().map_err(|e| e.to_string())??;
+        Ok(PostgresBackend { requests })
+    }
+}
+
+/// the message of the server rather than a bare `db error`
+fn error(e: postgres::Error) -> String {
+    match e.as_db_error() {
+        Some(db) => format!("{}: {}", e, db.message()),
+        None => e.to_string(),
+    }
+}
+
+/// the simple query protocol returns every value as text
+fn query_rows(client: &mut Client, query: &str) -> Result<Vec<Vec<SqlValue>>, String> {
+    let messages = client.simple_query(query).map_err(error)?;
+
+    let mut rows = Vec::new();
+    for message in messages {
+        if let SimpleQueryMessage::Row(row) = message {
+            rows.push((0..row.len()).map(|i| match row.get(i) {
+                Some(value) => SqlValue::Text(value.to_owned()),
+                None => SqlValue::Null,
+            }).collect());
+        }
+    }
+
+    Ok(rows)
+}
+
+impl Backend for PostgresBackend {
+    fn get_conn(&self) -> Result<Conn, String> {
+        Ok(Conn::new(PostgresConnection(self.requests.clone())))
+    }
+}
+
+struct PostgresConnection(Sender<Request>);
+
+impl Connection for PostgresConnection {
+    fn query_rows(&mut self, query: &str) -> Result<Vec<Vec<SqlValue>>, String> {
+        let (reply, result) = mpsc::channel();
+        self.0.send(Request::Query(standard_literals(query), reply)).map_err(|e| e.to_string())?;
+        result.recv().map_err(|e| e.to_string())?
+    }
+
+    fn execute(&mut self, query: &str) -> Result<(), String> {
+        let (reply, result) = mpsc::channel();
+        self.0.send(Request::Execute(standard_literals(query), reply)).map_err(|e| e.to_string())?;
+        result.recv().map_err(|e| e.to_string())?
+    }
+
+    /// the value of the last sequence used by the session, the client is
+    /// shared but the database lock serializes the statements
+    fn last_insert_id(&self) -> u64 {
+        let (reply, result) = mpsc::channel();
+        if self.0.send(Request::Query("SELECT lastval()".to_owned(), reply)).is_err() {
+            return 0;
+        }
+
+        match result.recv() {
+            Ok(Ok(rows)) => match rows.first().and_then(|row| row.first()) {
+                Some(SqlValue::Text(id)) => id.parse::<u64>().unwrap_or(0),
+                _ => 0,
+            },
+            _ => 0,
+        }
+    }
+}
@@ -0,0 +1,71 @@
+// embedded SQLite file backend, for single-user and demo deployments
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use rusqlite::types::ValueRef;
+
+use crate::helper::storage::{standard_literals, Backend, Conn, Connection, SqlValue};
+
+pub struct SqliteBackend {
+    connection: Arc<Mutex<rusqlite::Connection>>,
+}
+
+impl SqliteBackend {
+    /// Open the database file, created with its folder on first use
+    pub fn open(path: &str) -> Result<Self, String> {
+        if let Some(parent) = Path::new(path).parent() {
+            if !parent.as_os_str().is_empty() {
+                fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+            }
+        }
+
+        let connection = rusqlite::Connection::open(path).map_err(|e| e.to_string())?;
+        Ok(SqliteBackend { connection: Arc::new(Mutex::new(connection)) })
+    }
+}
+
+impl Backend for SqliteBackend {
+    fn get_conn(&self) -> Result<Conn, String> {
+        Ok(Conn::new(SqliteConnection(self.connection.clone())))
+    }
+}
+
+struct SqliteConnection(Arc<Mutex<rusqlite::Connection>>);
+
+impl Connection for SqliteConnection {
+    fn query_rows(&mut self, query: &str) -> Result<Vec<Vec<SqlValue>>, String> {
+        let connection = self.0.lock().map_err(|e| e.to_string())?;
+        let mut statement = connection.prepare(&standard_literals(query)).map_err(|e| e.to_string())?;
+        let column_count = statement.column_count();
+
+        let mut rows = statement.query([]).map_err(|e| e.to_string())?;
+        let mut values = Vec::new();
+        while let Some(row) = rows.next().map_err(|e| e.to_string())? {
+            let mut columns = Vec::with_capacity(column_count);
+            for i in 0..column_count {
+                columns.push(match row.get_ref(i).map_err(|e| e.to_string())? {
+                    ValueRef::Null => SqlValue::Null,
+                    ValueRef::Integer(i) => SqlValue::Int(i),
+                    ValueRef::Real(f) => SqlValue::Float(f),
+                    ValueRef::Text(t) | ValueRef::Blob(t) => SqlValue::Text(String::from_utf8_lossy(t).into_owned()),
+                });
+            }
+            values.push(columns);
+        }
+
+        Ok(values)
+    }
+
+    fn execute(&mut self, query: &str) -> Result<(), String> {
+        let connection = self.0.lock().map_err(|e| e.to_string())?;
+        connection.execute_batch(&standard_literals(query)).map_err(|e| e.to_string())
+    }
+
+    fn last_insert_id(&self) -> u64 {
+        match self.0.lock() {
+            Ok(connection) => connection.last_insert_rowid() as u64,
+            Err(_) => 0,
+        }
+    }
+}
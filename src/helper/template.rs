@@ -0,0 +1,144 @@
+// the HTML templates of html/, rendered with minijinja: the pages extend
+// layouts/base.html and include the partials, the values are escaped unless
+// they are marked `safe`; the text templates of the emails are not escaped
+use std::fs;
+use std::path::Path;
+
+use minijinja::value::Kwargs;
+use minijinja::{context, escape_formatter, Environment, State, Value};
+use once_cell::sync::OnceCell;
+use regex::Regex;
+use serde::Serialize;
+
+use crate::helper::i18n::{current_locale, fill, locales, translate};
+use crate::helper::security::current_token;
+use crate::helper::trace::trace_error;
+
+pub const TEMPLATE_DIR: &str = "html";
+
+/// Returned by `render` when a page cannot be rendered
+pub const RENDER_ERROR: &str = "__500";
+
+static TEMPLATES: OnceCell<Environment<'static>> = OnceCell::new();
+
+/// Compile every template of `dir`, named by their path below it, and check
+/// that the templates they extend, include or import exist
+pub fn load(dir: &str) -> Result<Environment<'static>, Vec<String>> {
+    let mut env = Environment::new();
+    // an empty optional column renders empty, not `none`
+    env.set_formatter(|out, state, value| {
+        escape_formatter(out, state, if value.is_none() { &Value::UNDEFINED } else { value })
+    });
+    // a number written the Rust way, 16 rather than 16.0
+    env.add_filter("number", |value: f64| value.to_string());
+    // a multiline text, escaped then broken on its newlines
+    env.add_filter("nl2br", |value: Option<String>| {
+        let escaped = minijinja::HtmlEscape(value.as_deref().unwrap_or("")).to_string();
+        Value::from_safe_string(escaped.replace('\n', "<br>"))
+    });
+    // a text of the UI in the locale of the page, the `{placeholders}` of
+    // the translation filled by name: `_("Risk #{id}", id=risk.risk_id)`
+    env.add_function("_", |state: &State, text: String, values: Kwargs| -> Result<String, minijinja::Error> {
+        let locale = state.lookup("locale").and_then(|l| l.as_str().map(str::to_string)).unwrap_or_else(current_locale);
+        let mut filled = Vec::new();
+        for key in values.args() {
+            let value = values.get::<Value>(key)?;
+            filled.push((key, if value.is_none() || value.is_undefined() { String::new() } else { value.to_string() }));
+        }
+        Ok(fill(translate(&locale, &text), &filled))
+    });
+
+    let mut errors = Vec::new();
+    let mut sources = Vec::new();
+    let mut files = Vec::new();
+    list_templates(Path::new(dir), &mut files);
+    files.sort();
+
+    for path in files {
+        let name = path.strip_prefix(dir).unwrap_or(&path).to_string_lossy().replace('\\', "/");
+        let source = match fs::read_to_string(&path) {
+            Ok(source) => source,
+            Err(e) => {
+                errors.push(format!("{}: {}", name, e));
+                continue;
+            }
+        };
+        sources.push((name.clone(), source.clone()));
+        if let Err(e) = env.add_template_owned(name.clone(), source) {
+            errors.push(format!("{}: {}", name, e));
+        }
+    }
+
+    let reference = Regex::new(r#"\{%-?\s*(?:extends|include|import|from)\s+"([^"]+)""#).unwrap();
+    for (name, source) in sources.iter() {
+        for cap in reference.captures_iter(source) {
+            if env.get_template(&cap[1]).is_err() {
+                errors.push(format!("{}: unknown template {}", name, &cap[1]));
+            }
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(env)
+    } else {
+        Err(errors)
+    }
+}
+
+fn list_templates(dir: &Path, files: &mut Vec<std::path::PathBuf>) {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            list_templates(&path, files);
+        } else if path.extension().is_some_and(|ext| ext == "html" || ext == "txt") {
+            files.push(path);
+        }
+    }
+}
+
+/// Compile the templates once at startup, an invalid template stops the
+/// server with the list of the errors
+pub fn init() {
+    match load(TEMPLATE_DIR) {
+        Ok(env) => {
+            let _ = TEMPLATES.set(env);
+        }
+        Err(errors) => {
+            eprintln!("Invalid templates:");
+            for e in errors {
+                eprintln!("  - {}", e);
+            }
+            std::process::exit(2);
+        }
+    }
+}
+
+/// Render a page with its context, the CSRF token of the page as
+/// `csrf_token`, its `locale` and the `locales` of the language menu
+pub fn render<S: Serialize>(name: &str, context: S) -> String {
+    let env = match TEMPLATES.get() {
+        Some(env) => env,
+        None => {
+            trace_error("Templates are not loaded".to_string());
+            return RENDER_ERROR.to_string();
+        }
+    };
+
+    let context = context! {
+        csrf_token => current_token(),
+        locale => current_locale(),
+        locales => locales(),
+        ..Value::from_serialize(&context)
+    };
+    match env.get_template(name).and_then(|t| t.render(context)) {
+        Ok(html) => html,
+        Err(e) => {
+            trace_error(format!("Template {}: {:#}", name, e));
+            RENDER_ERROR.to_string()
+        }
+    }
+}
@@ -0,0 +1,33 @@
+use crate::helper::database::C3Stakeholder;
+
+/// Compute the EBIOS threat indicators of a stakeholder.
+/// Returns (exposition, fiabilite_cyber, niveau_de_menace)
+pub fn threat_level(m: &C3Stakeholder) -> (f64, f64, f64) {
+    let exposition = m.dependance as f64 * m.penetration as f64;
+    let fiabilite_cyber = m.maturite_ssi as f64 * m.confiance as f64;
+
+    let mut niveau_de_menace = 0.0; // Initialize as a floating-point number
+    if fiabilite_cyber > 0.0 {
+        let menace_ratio = exposition / fiabilite_cyber;
+        niveau_de_menace = (menace_ratio * 100.0).round() / 100.0;
+    }
+
+    (exposition, fiabilite_cyber, niveau_de_menace)
+}
+
+/// Same as threat_level, but once the security measures are in place:
+/// each residual rating falls back to the current one when it is not evaluated
+pub fn residual_threat_level(m: &C3Stakeholder) -> (f64, f64, f64) {
+    let mut residual = m.clone();
+    residual.dependance = residual_or_current(m.dependance_residuelle, m.dependance);
+    residual.penetration = residual_or_current(m.penetration_residuelle, m.penetration);
+    residual.maturite_ssi = residual_or_current(m.maturite_ssi_residuelle, m.maturite_ssi);
+    residual.confiance = residual_or_current(m.confiance_residuelle, m.confiance);
+
+    threat_level(&residual)
+}
+
+/// A residual rating, the current one when it is not evaluated
+pub fn residual_or_current(residual: Option<i32>, current: i32) -> i32 {
+    residual.unwrap_or(current)
+}
@@ -0,0 +1,108 @@
+use chrono::{Datelike, Duration, NaiveDate};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::helper::database::{Countermeasure, CountermeasureHistory, ScenarioRiskHistory};
+use crate::web::routes::main::average_resolution;
+use crate::helper::functions::RISK_LEVELS;
+use crate::web::routes::scenario::calculate_risk;
+
+pub const PERIODS: [(&str, &str); 2] = [("week", "Weekly"), ("month", "Monthly")];
+
+/// How many weeks or months the trends go back
+const MAX_BUCKETS: usize = 26;
+
+/// A point of a trend: its label and the last instant it covers
+#[derive(Debug, Clone)]
+pub struct Bucket {
+    pub label: String,
+    /// `YYYY-MM-DD HH:MM:SS`, compared as a string to the recorded dates
+    pub end: String,
+}
+
+/// The scenarios and measures of a risk, the trends follow them back in time
+#[derive(Debug, Clone)]
+pub struct RiskScope {
+    pub name: String,
+    pub scenarios: Vec<Uuid>,
+    pub measures: Vec<Uuid>,
+}
+
+/// Weeks (from monday) or months from `first` to `today`, only the latest ones
+pub fn buckets(period: &str, first: NaiveDate, today: NaiveDate) -> Vec<Bucket> {
+    let mut buckets = Vec::new();
+
+    let mut start = match period {
+        "month" => first.with_day(1).unwrap_or(first),
+        _ => first - Duration::days(first.weekday().num_days_from_monday() as i64),
+    };
+
+    while start <= today {
+        let next = match period {
+            "month" => {
+                let (year, month) = if start.month() == 12 { (start.year() + 1, 1) } else { (start.year(), start.month() + 1) };
+                NaiveDate::from_ymd_opt(year, month, 1).unwrap_or(start + Duration::days(31))
+            }
+            _ => start + Duration::days(7),
+        };
+
+        buckets.push(Bucket {
+            label: start.format(if period == "month" { "%Y-%m" } else { "%Y-%m-%d" }).to_string(),
+            end: format!("{} 23:59:59", (next - Duration::days(1)).format("%Y-%m-%d")),
+        });
+        start = next;
+    }
+
+    let skip = buckets.len().saturating_sub(MAX_BUCKETS);
+    buckets.split_off(skip)
+}
+
+/// Trends of the given risks: the average resolution of each risk, and the
+/// number of their scenarios per level, at the end of each period. A
+/// scenario or a measure counts from its first record on
+pub fn compute(period: &str, today: NaiveDate, scopes: &[RiskScope], ratings: &[ScenarioRiskHistory], progress: &[CountermeasureHistory]) -> Value {
+    let first = ratings.iter().map(|r| r.recorded_at.as_str())
+        .chain(progress.iter().map(|p| p.recorded_at.as_str()))
+        .min()
+        .and_then(|d| NaiveDate::parse_from_str(d.get(0..10).unwrap_or(""), "%Y-%m-%d").ok());
+
+    let buckets = match first {
+        Some(first) => buckets(period, first, today),
+        None => Vec::new(),
+    };
+
+    // histories are ordered by record, the last one before the end of the period wins
+    let solved_at = |ctm_uuid: &Uuid, end: &str| progress.iter().rev()
+        .find(|p| p.ctm_uuid == *ctm_uuid && p.recorded_at.as_str() <= end)
+        .map(|p| p.solved);
+    let level_at = |scenario_uuid: &Uuid, end: &str| ratings.iter().rev()
+        .find(|r| r.scenario_uuid == *scenario_uuid && r.recorded_at.as_str() <= end)
+        .map(|r| calculate_risk(r.likelihood, r.operational, r.legal_compliance, r.financial, r.reputation));
+
+    let resolution = scopes.iter().map(|scope| {
+        let data = buckets.iter().map(|b| {
+            let measures = scope.measures.iter()
+                .filter_map(|c| solved_at(c, &b.end))
+                .map(|solved| Countermeasure { solved, ..Countermeasure::default() })
+                .collect::<Vec<Countermeasure>>();
+            if measures.is_empty() { None } else { Some(average_resolution(measures)) }
+        }).collect::<Vec<Option<f64>>>();
+        json!({ "name": scope.name, "data": data })
+    }).collect::<Vec<Value>>();
+
+    let levels = RISK_LEVELS.iter().map(|level| {
+        let data = buckets.iter().map(|b| {
+            scopes.iter()
+                .flat_map(|scope| scope.scenarios.iter())
+                .filter(|s| level_at(s, &b.end) == Some(*level))
+                .count()
+        }).collect::<Vec<usize>>();
+        json!({ "name": level, "data": data })
+    }).collect::<Vec<Value>>();
+
+    json!({
+        "labels": buckets.iter().map(|b| b.label.clone()).collect::<Vec<String>>(),
+        "resolution": resolution,
+        "levels": levels,
+    })
+}
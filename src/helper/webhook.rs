@@ -0,0 +1,219 @@
+// outbound webhooks: the events are queued in webhook_delivery and POSTed by
+// the jobs of the scheduler, with --webhook (or `webhooks` in the config)
+use std::time::Duration;
+
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use once_cell::sync::OnceCell;
+use rand::Rng;
+use serde_json::{json, Value};
+use sha2::Sha256;
+
+use crate::helper::config::config;
+use crate::helper::database::{Countermeasure, Webhook, WebhookDelivery};
+use crate::helper::functions::sql_escape;
+use crate::helper::trace::trace_logs;
+use crate::web::routes::countermeasure::today;
+
+/// The events a webhook can be registered for, with their label
+pub const EVENT_TYPES: [(&str, &str); 5] = [
+    ("risk.created", "Risk created"),
+    ("scenario.level_changed", "Scenario level changed"),
+    ("countermeasure.solved", "Countermeasure solved"),
+    ("countermeasure.overdue", "Countermeasure overdue"),
+    ("stakeholder.threat_above_threshold", "Stakeholder threat above threshold"),
+];
+
+/// Sent by the test button, to a single webhook
+pub const TEST_EVENT: &str = "webhook.test";
+
+pub const SIGNATURE_HEADER: &str = "X-MatryRiska-Signature";
+pub const EVENT_HEADER: &str = "X-MatryRiska-Event";
+pub const DELIVERY_HEADER: &str = "X-MatryRiska-Delivery";
+/// Unix time of the attempt, in seconds, part of the signed content
+pub const TIMESTAMP_HEADER: &str = "X-MatryRiska-Timestamp";
+
+/// Attempts before a delivery is given up
+pub const MAX_ATTEMPTS: i32 = 6;
+/// Wait after the first failed attempt, doubled after each of the next ones
+const BACKOFF_SECONDS: i64 = 30;
+const TIMEOUT_SECONDS: u64 = 10;
+
+static CLIENT: OnceCell<reqwest::Client> = OnceCell::new();
+
+pub fn enabled() -> bool {
+    config().webhooks
+}
+
+pub fn is_event_type(event_type: &str) -> bool {
+    EVENT_TYPES.iter().any(|(e, _)| *e == event_type)
+}
+
+/// `sha256=` and the hex HMAC-SHA256 of `timestamp.body`, keyed by the
+/// secret of the webhook. Signing the timestamp lets the receiver refuse a
+/// replayed delivery
+pub fn sign(secret: &str, timestamp: i64, body: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC takes a key of any size");
+    mac.update(format!("{}.{}", timestamp, body).as_bytes());
+    let signature = mac.finalize().into_bytes();
+    format!("sha256={}", signature.iter().map(|byte| format!("{:02x}", byte)).collect::<String>())
+}
+
+pub fn generate_secret() -> String {
+    let bytes: [u8; 32] = rand::thread_rng().gen();
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect::<String>()
+}
+
+/// The body POSTed for an event
+pub fn payload(event_type: &str, data: Value) -> String {
+    json!({
+        "event": event_type,
+        "occurred_at": Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string(),
+        "data": data,
+    }).to_string()
+}
+
+/// `YYYY-MM-DD HH:MM:SS` in UTC, like `next_attempt_at`
+pub fn now_after(seconds: i64) -> String {
+    (Utc::now() + chrono::Duration::seconds(seconds)).format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+/// Wait before the attempt following `attempts` failed ones
+pub fn backoff_seconds(attempts: i32) -> i64 {
+    BACKOFF_SECONDS * 2_i64.pow(attempts.clamp(1, MAX_ATTEMPTS) as u32 - 1)
+}
+
+async fn enqueue(webhook: &Webhook, event_type: &str, subject: &str, body: &str) {
+    let result = WebhookDelivery::create_delivery(
+        webhook.webhook_id,
+        event_type.to_string(),
+        sql_escape(subject),
+        sql_escape(body),
+        now_after(0),
+    ).await;
+
+    if let Err(e) = result {
+        trace_logs(format!("Webhook {}: {}", webhook.webhook_id, e));
+    }
+}
+
+/// Queue an event for the webhooks registered for its type
+pub async fn emit(event_type: &str, subject: &str, data: Value) {
+    if !enabled() {
+        return;
+    }
+
+    let body = payload(event_type, data);
+    for webhook in Webhook::get_webhooks_of_event(event_type.to_string()).await {
+        enqueue(&webhook, event_type, subject, &body).await;
+    }
+}
+
+/// Same as `emit`, skipping the webhooks already sent this event about
+/// this subject
+pub async fn emit_once(event_type: &str, subject: &str, data: Value) {
+    if !enabled() {
+        return;
+    }
+
+    let body = payload(event_type, data);
+    for webhook in Webhook::get_webhooks_of_event(event_type.to_string()).await {
+        let sent = WebhookDelivery::get_deliveries_of_subject(webhook.webhook_id, event_type.to_string(), sql_escape(subject)).await;
+        if sent.is_empty() {
+            enqueue(&webhook, event_type, subject, &body).await;
+        }
+    }
+}
+
+/// Queue the test event for one webhook
+pub async fn test_fire(webhook: &Webhook) {
+    let body = payload(TEST_EVENT, json!({
+        "webhook_id": webhook.webhook_id,
+        "event_type": webhook.event_type,
+    }));
+    enqueue(webhook, TEST_EVENT, "", &body).await;
+}
+
+/// Attempt the pending deliveries that are due, returns how many
+pub async fn deliver_due() -> Result<usize, String> {
+    let client = CLIENT.get_or_try_init(|| {
+        reqwest::Client::builder()
+            .timeout(Duration::from_secs(TIMEOUT_SECONDS))
+            .redirect(reqwest::redirect::Policy::none())
+            .build()
+    }).map_err(|e| format!("cannot build the HTTP client: {}", e))?;
+
+    let deliveries = WebhookDelivery::get_due_deliveries(now_after(0)).await;
+    let count = deliveries.len();
+    for delivery in deliveries {
+        deliver(client, delivery).await;
+    }
+    Ok(count)
+}
+
+/// One attempt of a delivery, rescheduled with a backoff when it fails
+async fn deliver(client: &reqwest::Client, delivery: WebhookDelivery) {
+    let webhook = match Webhook::get_webhook(delivery.webhook_id).await.pop() {
+        Some(webhook) => webhook,
+        None => return,
+    };
+
+    // each attempt is signed at its own time
+    let timestamp = Utc::now().timestamp();
+    let response = client.post(&webhook.url)
+        .header("Content-Type", "application/json")
+        .header("User-Agent", "MatryRiska-Webhook")
+        .header(EVENT_HEADER, &delivery.event_type)
+        .header(DELIVERY_HEADER, delivery.delivery_id.to_string())
+        .header(TIMESTAMP_HEADER, timestamp.to_string())
+        .header(SIGNATURE_HEADER, sign(&webhook.secret, timestamp, &delivery.payload))
+        .body(delivery.payload.clone())
+        .send()
+        .await;
+
+    let (response_code, error) = match response {
+        Ok(r) if r.status().is_success() => (Some(r.status().as_u16() as i32), String::new()),
+        Ok(r) => (Some(r.status().as_u16() as i32), format!("HTTP {}", r.status())),
+        Err(e) => (None, e.to_string()),
+    };
+
+    let attempts = delivery.attempts + 1;
+    let (status, next_attempt_at) = if error.is_empty() {
+        ("delivered", now_after(0))
+    } else if attempts >= MAX_ATTEMPTS {
+        ("failed", now_after(0))
+    } else {
+        ("pending", now_after(backoff_seconds(attempts)))
+    };
+
+    if !error.is_empty() {
+        trace_logs(format!("Webhook delivery {} to {}: attempt {} failed, {}", delivery.delivery_id, webhook.url, attempts, error));
+    }
+
+    let _ = WebhookDelivery::record_attempt(
+        delivery.delivery_id,
+        status.to_string(),
+        attempts,
+        response_code,
+        sql_escape(&error.chars().take(1000).collect::<String>()),
+        next_attempt_at,
+    ).await;
+}
+
+/// A measure is overdue once per due date, rescheduling it can fire again.
+/// Returns the number of overdue measures
+pub async fn check_overdue() -> usize {
+    let overdue = Countermeasure::get_overdue_countermeasures(today()).await;
+    for ctm in overdue.iter() {
+        emit_once("countermeasure.overdue", &format!("{}:{}", ctm.ctm_uuid, ctm.due_date), json!({
+            "ctm_uuid": ctm.ctm_uuid.to_string(),
+            "scenario_uuid": ctm.scenario_uuid.to_string(),
+            "title": ctm.title,
+            "owner": ctm.owner,
+            "due_date": ctm.due_date,
+            "priority": ctm.priority,
+            "status": ctm.status,
+        })).await;
+    }
+    overdue.len()
+}
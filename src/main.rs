@@ -1,29 +1,75 @@
 extern crate matryriska;
 use std::fs;
-use actix_cors::Cors;
 use actix_web::{HttpServer,App};
 use matryriska::{api,web,assets};
 
 // init the tracing module
 use matryriska::helper::trace::{init_trace,trace_logs};
 use matryriska::helper::start::startup;
+use matryriska::helper::{i18n, migrate, notification, scheduler, security, template, webhook};
+use matryriska::helper::config::{self, flag_value, Config};
 
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
+    let args: Vec<String> = std::env::args().collect();
+
+    // the catalogues of locales/, `default_locale` must be one of them
+    i18n::init();
+
+    // defaults, config file, MATRYRISKA_* variables then flags
+    let loaded = match Config::load(&args) {
+        Ok(c) => c,
+        Err(errors) => {
+            eprintln!("Invalid configuration:");
+            for e in errors {
+                eprintln!("  - {}", e);
+            }
+            std::process::exit(2);
+        }
+    };
+
+    if args.iter().any(|arg| arg == "--print-config") {
+        println!("{}", serde_json::to_string_pretty(&loaded.redacted()).unwrap());
+        return Ok(());
+    }
+    config::init(loaded);
+
+    // every template of html/ is compiled before serving
+    template::init();
+
     println!("{}", fs::read_to_string("utils/ascii.art").unwrap().as_str());
     init_trace();
     trace_logs("Server is starting...".to_string());
 
     startup().await;
 
-    let config = fs::read_to_string("config/default.json").unwrap();
-    let config: serde_json::Value = serde_json::from_str(config.as_str()).unwrap();
+    // copy the database into PostgreSQL and stop, see the README
+    if let Some(target) = flag_value(&args, "--migrate-to-postgres") {
+        match migrate::to_postgres(&target) {
+            Ok(rows) => {
+                trace_logs(format!("Migration completed, {} rows copied", rows));
+                return Ok(());
+            }
+            Err(e) => {
+                trace_logs(format!("Migration failed: {}", e));
+                std::process::exit(1);
+            }
+        }
+    }
+
+    // the recurring jobs: webhooks, emails, backups... see the README
+    scheduler::start();
+    if webhook::enabled() {
+        trace_logs("Webhooks are enabled".to_string());
+    }
+    if notification::enabled() {
+        trace_logs(format!("Email notifications are sent through {}", config::config().smtp.host));
+    }
 
-    let port: u16 = config.get("web_port").unwrap().as_u64().unwrap() as u16;
+    let port: u16 = config::config().web_port;
     trace_logs(format!("Server is running on port: {}",port));
     HttpServer::new(|| {
-        let cors = Cors::default().allow_any_origin().allow_any_method().allow_any_header();
-        App::new().wrap(cors).service(api::init::init_api()).service(assets::init::init_assets()).service(web::init::init_web())
+        App::new().wrap(security::cors()).service(api::init::init_api()).service(assets::init::init_assets()).service(web::init::init_web())
     }).bind(("0.0.0.0",port))?.run().await
 }
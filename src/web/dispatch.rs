@@ -1,24 +1,48 @@
-use actix_web::{get,web,HttpResponse,Responder};
-use std::fs;
-use crate::helper::{find_insert::find_insert,replace_in_body::replace_in_body};
+use actix_web::{get,web,HttpRequest,HttpResponse,Responder};
+use crate::helper::i18n::{self, negotiate};
+use crate::helper::security::{request_token, generate_token, scope, token_cookie};
+use crate::helper::template::{render, RENDER_ERROR};
 use crate::helper::trace::{trace_logs,trace_warn};
 
 // import the routes pages
 use crate::web::routes::*;
 
 #[get("/{path:.*}")]
-#[tracing::instrument(level = "info", name = "Dispatch request", skip(path))]
-pub async fn dispatch(path: web::Path<String>) -> impl Responder {
-  let path_arg = path.into_inner().clone();
+#[tracing::instrument(level = "info", name = "Dispatch request", skip(path, req))]
+pub async fn dispatch(path: web::Path<String>, req: HttpRequest) -> impl Responder {
+  // the CSRF token of the browser, a new one on its first page
+  let existing = request_token(&req);
+  let token = existing.clone().unwrap_or_else(generate_token);
+
+  let content_body = i18n::scope(negotiate(&req), scope(token.clone(), page(path.into_inner(), &req))).await;
+
+  if content_body == RENDER_ERROR {
+    return HttpResponse::InternalServerError().content_type("text/plain").body("The page cannot be rendered");
+  }
+
+  let mut response = HttpResponse::Ok();
+  if existing.is_none() {
+    response.cookie(token_cookie(&token));
+  }
+  return response.content_type("text/html").body(content_body)
+}
+
+async fn page(path_arg: String, req: &HttpRequest) -> String {
   let mut content_body = String::new();
 
   // show the request in the tracing log, with the timestamp, level, and the request path
   trace_logs(format!("Request: {}", path_arg));
   
   match path_arg.as_str() {
-    "" => { content_body = home::home().await; },
-    "main" => { content_body = main::main().await; },
+    "" => { content_body = home::home(req.query_string().to_string()).await; },
+    "main" => { content_body = main::main(req.query_string().to_string()).await; },
     "risk/create" => { content_body = risk::create().await; },
+    "acceptance" => { content_body = acceptance::list().await; },
+    "snapshot" => { content_body = snapshot::list().await; },
+    "webhook" => { content_body = webhook::list().await; },
+    "notification" => { content_body = notification::list().await; },
+    "job" => { content_body = job::list().await; },
+    "search" => { content_body = search::page(req.query_string().to_string()).await; },
 
 
     path if path.starts_with("c1/") => { content_body = c1::c1(path_arg).await; },
@@ -26,6 +50,8 @@ pub async fn dispatch(path: web::Path<String>) -> impl Responder {
     path if path.starts_with("c3/") => { content_body = c3::c3(path_arg).await; },
 
 
+    path if path.starts_with("snapshot/diff/") => { content_body = snapshot::diff(path_arg).await; },
+
     path if path.starts_with("risk/detail/") => { content_body = risk::detail(path_arg).await; },
     path if path.starts_with("risk/update/") => { content_body = risk::update(path_arg).await; },
     path if path.starts_with("risk/delete/") => { content_body = risk::delete(path_arg).await; },
@@ -35,6 +61,8 @@ pub async fn dispatch(path: web::Path<String>) -> impl Responder {
     path if path.starts_with("scenario/update/") => { content_body = scenario::update(path_arg).await; },
     path if path.starts_with("scenario/delete/") => { content_body = scenario::delete(path_arg).await; },
 
+    "countermeasure/overdue" => { content_body = countermeasure::overdue().await; },
+    "countermeasure/library" => { content_body = countermeasure::library().await; },
     path if path.starts_with("countermeasure/create/") => { content_body = countermeasure::create(path_arg).await; },
     path if path.starts_with("countermeasure/detail/") => { content_body = countermeasure::detail(path_arg).await; },
     path if path.starts_with("countermeasure/update/") => { content_body = countermeasure::update(path_arg).await; },
@@ -45,25 +73,10 @@ pub async fn dispatch(path: web::Path<String>) -> impl Responder {
     }
   }
 
-    // inject the 404 if the content is __404
-  if content_body.contains("__404") {
-    content_body = fs::read_to_string("html/404/index.html").unwrap();
-  }
-
-  // [START] - Pass all the injector here
-  let tab_to_insert = find_insert(content_body.clone());
-
-  // for each tab_to_insert, we will insert the content of the file
-  for(tab, file) in tab_to_insert.iter().zip(tab_to_insert.iter()){
-    // check if file exists
-    if fs::metadata(format!("html/inject/{}.html", file)).is_ok(){
-      let file_content = fs::read_to_string(format!("html/inject/{}.html", file)).unwrap();
-      let inject_name = format!("inject_{}", tab.to_string());
-      let replace_vec = vec![(inject_name, file_content)];
-      content_body = replace_in_body(content_body.clone(), replace_vec);
-    }
+    // render the 404 if the content is __404
+  if content_body == "__404" {
+    content_body = render("404/index.html", ());
   }
-  // [END] - Pass all the injector here
 
-  return HttpResponse::Ok().content_type("text/html").body(content_body)
+  content_body
 }
\ No newline at end of file
@@ -5,4 +5,10 @@ pub mod scenario;
 pub mod countermeasure;
 pub mod c1;
 pub mod c2;
-pub mod c3;
\ No newline at end of file
+pub mod c3;
+pub mod acceptance;
+pub mod snapshot;
+pub mod webhook;
+pub mod search;
+pub mod notification;
+pub mod job;
\ No newline at end of file
@@ -0,0 +1,64 @@
+// the web controller of the risk acceptances
+use chrono::{Duration, Local};
+use minijinja::{context, Value};
+
+use crate::api::mods::acceptance::{requires_elevation, residual_level};
+use crate::helper::config::config;
+use crate::helper::database::{Risk, RiskAcceptance, Scenario};
+use crate::helper::i18n::t;
+use crate::helper::template::render;
+use crate::web::routes::countermeasure::today;
+use crate::web::routes::risk::get_id;
+
+/// An acceptance with its state at `today`
+fn with_state(acceptance: &RiskAcceptance, today: &str) -> Value {
+    context! { state => acceptance.state(today), ..Value::from_serialize(acceptance) }
+}
+
+/// The acceptances of a risk or of a scenario, with the form to sign off
+/// its current residual level; rendered by `acceptance/files/card.html`
+pub async fn card(target_type: &str, target_uuid: String) -> Value {
+    let today = today();
+    let acceptances = RiskAcceptance::get_acceptances_of(target_uuid.clone()).await;
+    let level = residual_level(target_type, &target_uuid).await;
+    let acceptance = &config().risk_acceptance;
+
+    context! {
+        acceptances => acceptances.iter().map(|a| with_state(a, &today)).collect::<Vec<Value>>(),
+        standing => acceptances.iter().any(|a| a.is_active(&today)),
+        requires_elevation => requires_elevation(acceptance, level),
+        elevation_configured => !acceptance.elevation_token.is_empty(),
+        max_level => acceptance.max_level.to_uppercase(),
+        residual_level => level,
+        expires_on => (Local::now() + Duration::days(365)).format("%Y-%m-%d").to_string(),
+        target_type,
+        target_uuid,
+    }
+}
+
+/// Register of every acceptance
+pub async fn list() -> String {
+    let today = today();
+    let risks = Risk::select_all_risk().await;
+
+    let mut list = Vec::new();
+    for a in RiskAcceptance::get_all_acceptances().await {
+        let (target_label, target_name) = match a.target_type.as_str() {
+            "risk" => (
+                t("Risk"),
+                risks.iter().find(|r| r.risk_uuid == a.target_uuid).map(|r| r.risk_name.clone()).unwrap_or(get_id(a.target_uuid)),
+            ),
+            _ => (
+                t("Scenario"),
+                Scenario::get_scenario_detail(a.target_uuid.to_string()).await
+                    .first()
+                    .map(|s| s.scenario_description.clone())
+                    .unwrap_or(get_id(a.target_uuid)),
+            ),
+        };
+
+        list.push(context! { target_label, target_name, ..with_state(&a, &today) });
+    }
+
+    render("acceptance/index.html", context! { acceptances => list })
+}
@@ -1,7 +1,15 @@
 // The web controler for the C1 route
 
-use std::fs;
-use crate::helper::database::{Mission, ValeurMetier, BienSupport, FearedEvent, Gap};
+use std::collections::HashMap;
+
+use minijinja::{context, Value};
+
+use crate::helper::database::{Mission, ValeurMetier, BienSupport, AssetLink, AssetDependency, FearedEvent, GravityScale, Gap, GapMeasure, Referential, Requirement, Countermeasure};
+use crate::helper::referential::{Catalogue, depth, derived_state, is_control, measure_compliance, rollup};
+use crate::helper::i18n::{labels, t};
+use crate::helper::functions::svg_escape;
+use crate::helper::template::render;
+use crate::web::routes::countermeasure::{measure_context, today};
 
 
 #[tracing::instrument(level = "info")]
@@ -12,26 +20,61 @@ pub async fn c1(path:String) -> String {
         return coremission().await;
     } else if path == "c1/coremissions/create" {
         return coremission_create().await;
+    } else if path.starts_with("c1/coremissions/update/") {
+        let mission_id = path.replace("c1/coremissions/update/", "");
+        return coremission_update(mission_id.parse::<i32>().unwrap_or(0)).await;
     } else if path.starts_with("c1/coremissions/detail/") {
         let mission_id = path.replace("c1/coremissions/detail/", "");
         return coremission_detail(mission_id.parse::<i32>().unwrap_or(0)).await;
     } else if path.starts_with("c1/vm/create/") {
         let mission_id = path.replace("c1/vm/create/", "");
         return vm_create(mission_id.parse::<i32>().unwrap_or(0)).await;
+    } else if path.starts_with("c1/vm/update/") {
+        let vm_id = path.replace("c1/vm/update/", "");
+        return vm_update(vm_id.parse::<i32>().unwrap_or(0)).await;
     } else if path.starts_with("c1/vm/detail/") {
         let vm_id = path.replace("c1/vm/detail/", "");
         return vm_detail(vm_id.parse::<i32>().unwrap_or(0)).await;
+    } else if path == "c1/asset" {
+        return assets().await;
+    } else if path == "c1/asset/create" {
+        return asset_create(0).await;
+    } else if path == "c1/graph" {
+        return asset_graph().await;
+    } else if path.starts_with("c1/asset/detail/") {
+        let asset_id = path.replace("c1/asset/detail/", "");
+        return asset_detail(asset_id.parse::<i32>().unwrap_or(0)).await;
     } else if path.starts_with("c1/asset/create/") {
         let vm_id = path.replace("c1/asset/create/", "");
         return asset_create(vm_id.parse::<i32>().unwrap_or(0)).await;
+    } else if path.starts_with("c1/asset/update/") {
+        let asset_id = path.replace("c1/asset/update/", "");
+        return asset_update(asset_id.parse::<i32>().unwrap_or(0)).await;
     } else if path == "c1/fevnt" {
         return fevnt().await;
     } else if path == "c1/fevnt/create" {
         return fevnt_create().await;
+    } else if path == "c1/fevnt/pivot" {
+        return fevnt_pivot().await;
+    } else if path == "c1/gravity" {
+        return gravity().await;
+    } else if path.starts_with("c1/fevnt/update/") {
+        let event_id = path.replace("c1/fevnt/update/", "");
+        return fevnt_update(event_id.parse::<i32>().unwrap_or(0)).await;
+    } else if path == "c1/coverage" {
+        return coverage().await;
+    } else if path == "c1/referentials" {
+        return referentials().await;
+    } else if path.starts_with("c1/referential/") {
+        let referential_id = path.replace("c1/referential/", "");
+        return referential_detail(referential_id.parse::<i32>().unwrap_or(0)).await;
     } else if path == "c1/gaps" {
         return gaps().await;
     } else if path == "c1/gaps/create" {
         return gaps_create().await;
+    } else if path.starts_with("c1/gaps/update/") {
+        let gaps_id = path.replace("c1/gaps/update/", "");
+        return gaps_update(gaps_id.parse::<i32>().unwrap_or(0)).await;
     } else if path.starts_with("c1/gaps/") {
         let vm_id = path.replace("c1/gaps/", "");
         return gaps_detail(vm_id.parse::<i32>().unwrap_or(0)).await;
@@ -45,24 +88,21 @@ async fn coremission() -> String {
     // Get all missions
     let missions = Mission::c1_get_all_missions().await;
 
-    let mut str = String::new();
-    let base = fs::read_to_string("html/c1/files/m-solo.html").unwrap();
-
-    for m in missions {
-        let new = base.replace("{{mission_name}}", &m.mission_name)
-            .replace("{{mission_id}}", &m.mission_id.to_string());
-        str.push_str(&new);
-    }
+    render("c1/list-missions.html", context! { missions })
+}
 
+async fn coremission_create() -> String {
+    render("c1/create-mission.html", ())
+}
 
-    let index = fs::read_to_string("html/c1/list-missions.html").unwrap()
-        .replace("{{mission_list}}", &str);
+async fn coremission_update(mission_id:i32) -> String {
 
-    return index;
-}
+    let mission = Mission::c1_get_mission_by_id(mission_id).await;
+    if mission.is_empty() {
+        return "__404".to_string();
+    }
 
-async fn coremission_create() -> String {
-    return fs::read_to_string("html/c1/create-mission.html").unwrap();
+    render("c1/update-mission.html", context! { mission => mission[0] })
 }
 
 async fn coremission_detail(mission_id:i32) -> String {
@@ -73,139 +113,631 @@ async fn coremission_detail(mission_id:i32) -> String {
         return "__404".to_string();
     }
 
-    let mission = &mission[0];
-
     let get_all_valeurmetier = ValeurMetier::c1_get_all_valeurmetier(mission_id).await;
 
+    render("c1/detail-mission.html", context! {
+        mission => mission[0],
+        vms => get_all_valeurmetier,
+    })
+}
 
-    let mut str = String::new();
-    let base = fs::read_to_string("html/c1/files/vm-solo.html").unwrap();
+async fn vm_detail(vm_id:i32) -> String {
 
-    for m in get_all_valeurmetier {
-        let new = base.replace("{{vm_name}}", &m.valeur_name)
-            .replace("{{vm_id}}", &m.valeur_id.to_string())
-            .replace("{{vm_description}}", &m.valeur_description)
-            .replace("{{vm_nature}}", &m.valeur_nature.to_string())
-            .replace("{{vm_resp}}", &m.responsable.to_string());
+    let vm = ValeurMetier::c1_get_valermetier_by_id(vm_id).await;
 
-        str.push_str(&new);
+    if vm.len() == 0 {
+        return "__404".to_string();
     }
 
+    let get_all_asset = BienSupport::c1_get_asset_by_vmid(vm_id).await;
+
+    // the assets not yet supporting this value can be linked to it
+    let linkable = BienSupport::c1_get_all_assets().await.into_iter()
+        .filter(|a| !get_all_asset.iter().any(|linked| linked.support_id == a.support_id))
+        .collect::<Vec<BienSupport>>();
 
-    return fs::read_to_string("html/c1/detail-mission.html").unwrap()
-        .replace("{{mission_title}}", &mission.mission_name)
-        .replace("{{job_list}}", &str)
-        .replace("{{mission_id}}", mission_id.to_string().as_str());
+    render("c1/detail-vm.html", context! {
+        vm => vm[0],
+        assets => get_all_asset,
+        linkable,
+    })
 }
 
-async fn vm_detail(vm_id:i32) -> String {
+async fn vm_create(mission_id:i32) -> String {
+    render("c1/create-vm.html", context! { mission_id })
+}
+
+async fn vm_update(vm_id:i32) -> String {
 
     let vm = ValeurMetier::c1_get_valermetier_by_id(vm_id).await;
+    if vm.is_empty() {
+        return "__404".to_string();
+    }
 
-    if vm.len() == 0 {
+    render("c1/update-vm.html", context! {
+        vm => vm[0],
+        sources => labels(&[("processus", "Process"), ("information", "Information")]),
+    })
+}
+
+async fn asset_create(vm_id:i32) -> String {
+    // without a business value the asset is created on its own
+    let back_url = if vm_id == 0 { "/c1/asset".to_string() } else { format!("/c1/vm/detail/{}", vm_id) };
+    let vm_id = if vm_id == 0 { String::new() } else { vm_id.to_string() };
+
+    render("c1/create-asset.html", context! { back_url, vm_id })
+}
+
+async fn asset_update(asset_id:i32) -> String {
+
+    let asset = BienSupport::c1_get_asset_by_id(asset_id).await;
+    if asset.is_empty() {
         return "__404".to_string();
     }
 
-    let vm = &vm[0];
+    render("c1/update-asset.html", context! { asset => asset[0] })
+}
 
-    let get_all_asset = BienSupport::c1_get_asset_by_vmid(vm_id).await;
+async fn assets() -> String {
+
+    let all = BienSupport::c1_get_all_assets().await;
+    let graph = AssetGraph::load().await;
+
+    let list = all.iter().map(|m| {
+        let vms = graph.links.iter()
+            .filter(|l| l.support_id == m.support_id)
+            .map(|l| graph.vm_name(l.valeur_id))
+            .collect::<Vec<String>>()
+            .join(", ");
+
+        let dependencies = graph.dependencies.iter()
+            .filter(|d| d.support_id == m.support_id)
+            .map(|d| graph.asset_name(d.depend_de))
+            .collect::<Vec<String>>()
+            .join(", ");
+
+        context! {
+            vms,
+            dependencies,
+            gravity => graph.gravity(graph.asset_gravity(m.support_id)),
+            ..Value::from_serialize(m)
+        }
+    }).collect::<Vec<Value>>();
+
+    render("c1/list-assets.html", context! { assets => list })
+}
+
+async fn asset_detail(asset_id:i32) -> String {
 
-    let mut str = String::new();
-    let base = fs::read_to_string("html/c1/files/asset-solo.html").unwrap();
+    let asset = BienSupport::c1_get_asset_by_id(asset_id).await;
+    if asset.is_empty() {
+        return "__404".to_string();
+    }
 
-    for m in get_all_asset {
-        let new = base.replace("{{asset_name}}", &m.support_name)
-            .replace("{{asset_id}}", &m.support_id.to_string())
-            .replace("{{asset_description}}", &m.support_description)
-            .replace("{{asset_owner}}", &m.support_responsable);
+    let graph = AssetGraph::load().await;
 
-        str.push_str(&new);
+    let mut vms = Vec::new();
+    let mut vm_options = Vec::new();
+    for v in graph.vms.iter() {
+        if graph.links.iter().any(|l| l.support_id == asset_id && l.valeur_id == v.valeur_id) {
+            vms.push(context! { gravity => graph.gravity(graph.vm_gravity(v.valeur_id)), ..Value::from_serialize(v) });
+        } else {
+            vm_options.push(v);
+        }
     }
 
+    let mut dependencies = Vec::new();
+    let mut dependents = Vec::new();
+    let mut asset_options = Vec::new();
+    for a in graph.assets.iter() {
+        let row = || context! { gravity => graph.gravity(graph.asset_gravity(a.support_id)), ..Value::from_serialize(a) };
+
+        if graph.dependencies.iter().any(|d| d.support_id == asset_id && d.depend_de == a.support_id) {
+            dependencies.push(row());
+        } else if a.support_id != asset_id {
+            asset_options.push(a);
+        }
+
+        if graph.dependencies.iter().any(|d| d.support_id == a.support_id && d.depend_de == asset_id) {
+            dependents.push(row());
+        }
+    }
 
-    return fs::read_to_string("html/c1/detail-vm.html").unwrap()
-        .replace("{{mission_id}}", &vm.mission_id.to_string())
-        .replace("{{vm_id}}", &vm.valeur_id.to_string())
-        .replace("{{vm_source}}", &vm.valeur_nature)
-        .replace("{{vm_description}}", &vm.valeur_description)
-        .replace("{{vm_responsable}}", &vm.responsable)
-        .replace("{{vm_name}}", &vm.valeur_name)
-        .replace("{{asset_list}}", &str);
+    render("c1/detail-asset.html", context! {
+        asset => asset[0],
+        gravity => graph.gravity(graph.asset_gravity(asset_id)),
+        vms,
+        vm_options,
+        dependencies,
+        dependents,
+        asset_options,
+    })
 }
 
-async fn vm_create(mission_id:i32) -> String {
-    return fs::read_to_string("html/c1/create-vm.html").unwrap()
-        .replace("{{mission_id}}", mission_id.to_string().as_str());
+async fn asset_graph() -> String {
+    let graph = AssetGraph::load().await;
+
+    render("c1/graph-assets.html", context! { graph_svg => graph.render() })
 }
 
-async fn asset_create(vm_id:i32) -> String {
-    return fs::read_to_string("html/c1/create-asset.html").unwrap()
-        .replace("{{vm_id}}", vm_id.to_string().as_str());
+/// Business values, supporting assets and the edges between them, with the
+/// feared event gravity propagated along the edges
+pub struct AssetGraph {
+    pub vms: Vec<ValeurMetier>,
+    pub assets: Vec<BienSupport>,
+    pub links: Vec<AssetLink>,
+    pub dependencies: Vec<AssetDependency>,
+    pub scale: Vec<GravityScale>,
+    vm_gravity: HashMap<i32, i32>,
+    asset_gravity: HashMap<i32, i32>,
+}
+
+impl AssetGraph {
+    /// A graph with no gravity yet, see `propagate`
+    pub fn new(vms: Vec<ValeurMetier>, assets: Vec<BienSupport>, links: Vec<AssetLink>, dependencies: Vec<AssetDependency>, scale: Vec<GravityScale>) -> Self {
+        AssetGraph {
+            vms,
+            assets,
+            links,
+            dependencies,
+            scale,
+            vm_gravity: HashMap::new(),
+            asset_gravity: HashMap::new(),
+        }
+    }
+
+    pub async fn load() -> Self {
+        let mut graph = AssetGraph::new(
+            ValeurMetier::c1_get_all_valeurmetier_no_limit().await,
+            BienSupport::c1_get_all_assets().await,
+            AssetLink::c1_get_all_asset_links().await,
+            AssetDependency::c1_get_all_asset_dependencies().await,
+            GravityScale::c1_get_gravity_scale().await,
+        );
+
+        graph.propagate(&FearedEvent::c1_get_all_feared_event().await);
+        graph
+    }
+
+    /// A business value takes the highest gravity of its feared events, an
+    /// asset the highest gravity of the business values it supports and of
+    /// the assets depending on it
+    pub fn propagate(&mut self, events: &[FearedEvent]) {
+        self.vm_gravity.clear();
+        self.asset_gravity.clear();
+
+        for e in events.iter() {
+            let g = self.vm_gravity.entry(e.valeur_metier).or_insert(0);
+            *g = (*g).max(e.gravite);
+        }
+
+        for l in self.links.iter() {
+            let from_vm = self.vm_gravity.get(&l.valeur_id).copied().unwrap_or(0);
+            let g = self.asset_gravity.entry(l.support_id).or_insert(0);
+            *g = (*g).max(from_vm);
+        }
+
+        // push the gravity down the dependency edges until nothing moves, a
+        // longest path is at most one edge per asset
+        for _ in 0..=self.assets.len() {
+            let mut changed = false;
+            for d in self.dependencies.iter() {
+                let from = self.asset_gravity.get(&d.support_id).copied().unwrap_or(0);
+                let g = self.asset_gravity.entry(d.depend_de).or_insert(0);
+                if from > *g {
+                    *g = from;
+                    changed = true;
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+    }
+
+    pub fn vm_gravity(&self, valeur_id: i32) -> i32 {
+        self.vm_gravity.get(&valeur_id).copied().unwrap_or(0)
+    }
+
+    pub fn asset_gravity(&self, support_id: i32) -> i32 {
+        self.asset_gravity.get(&support_id).copied().unwrap_or(0)
+    }
+
+    fn vm_name(&self, valeur_id: i32) -> String {
+        self.vms.iter()
+            .find(|v| v.valeur_id == valeur_id)
+            .map(|v| format!("#{} {}", v.valeur_id, v.valeur_name))
+            .unwrap_or(format!("#{}", valeur_id))
+    }
+
+    fn asset_name(&self, support_id: i32) -> String {
+        self.assets.iter()
+            .find(|a| a.support_id == support_id)
+            .map(|a| format!("#{} {}", a.support_id, a.support_name))
+            .unwrap_or(format!("#{}", support_id))
+    }
+
+    /// The level, color and label of a gravity, for the `gravity` badge
+    fn gravity(&self, niveau: i32) -> Value {
+        context! {
+            niveau,
+            color => gravity_color(niveau, &self.scale),
+            label => gravity_label(niveau, &self.scale),
+        }
+    }
+
+    /// Column of each asset: 1 for the assets no other asset depends on, then
+    /// one more column per dependency level
+    fn asset_depth(&self) -> HashMap<i32, usize> {
+        let mut depth: HashMap<i32, usize> = self.assets.iter().map(|a| (a.support_id, 1)).collect();
+
+        for _ in 0..=self.assets.len() {
+            let mut changed = false;
+            for d in self.dependencies.iter() {
+                let from = depth.get(&d.support_id).copied().unwrap_or(1);
+                let to = depth.entry(d.depend_de).or_insert(1);
+                if from + 1 > *to && from < self.assets.len() + 1 {
+                    *to = from + 1;
+                    changed = true;
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+
+        depth
+    }
+
+    /// Render the graph as a layered SVG, business values in the first column
+    pub fn render(&self) -> String {
+        const COLUMN_WIDTH: f64 = 260.0;
+        const ROW_HEIGHT: f64 = 56.0;
+        const NODE_WIDTH: f64 = 200.0;
+        const NODE_HEIGHT: f64 = 36.0;
+        const MARGIN: f64 = 20.0;
+
+        let depth = self.asset_depth();
+        let columns = depth.values().copied().max().unwrap_or(0) + 1;
+
+        // (x, y) of the top left corner of every node, per column
+        let mut vm_pos: HashMap<i32, (f64, f64)> = HashMap::new();
+        let mut asset_pos: HashMap<i32, (f64, f64)> = HashMap::new();
+        let mut rows = vec![0usize; columns];
+
+        for v in self.vms.iter() {
+            vm_pos.insert(v.valeur_id, (MARGIN, MARGIN + rows[0] as f64 * ROW_HEIGHT));
+            rows[0] += 1;
+        }
+        for a in self.assets.iter() {
+            let c = depth.get(&a.support_id).copied().unwrap_or(1);
+            asset_pos.insert(a.support_id, (MARGIN + c as f64 * COLUMN_WIDTH, MARGIN + rows[c] as f64 * ROW_HEIGHT));
+            rows[c] += 1;
+        }
+
+        let width = MARGIN * 2.0 + (columns as f64 - 1.0) * COLUMN_WIDTH + NODE_WIDTH;
+        let height = MARGIN * 2.0 + rows.iter().copied().max().unwrap_or(1).max(1) as f64 * ROW_HEIGHT;
+
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\" font-family=\"sans-serif\" font-size=\"12\">",
+            w = width, h = height
+        );
+        svg.push_str("<defs><marker id=\"arrow\" markerWidth=\"8\" markerHeight=\"8\" refX=\"8\" refY=\"4\" orient=\"auto\"><path d=\"M0,0 L8,4 L0,8 z\" fill=\"#888\"/></marker></defs>");
+
+        let edge = |from: (f64, f64), to: (f64, f64), dashed: bool| -> String {
+            let (x1, y1) = (from.0 + NODE_WIDTH, from.1 + NODE_HEIGHT / 2.0);
+            let (x2, y2) = (to.0, to.1 + NODE_HEIGHT / 2.0);
+            format!(
+                "<path d=\"M{x1},{y1} C{cx},{y1} {cx},{y2} {x2},{y2}\" fill=\"none\" stroke=\"#888\" stroke-width=\"1.5\"{dash} marker-end=\"url(#arrow)\"/>",
+                x1 = x1, y1 = y1, x2 = x2, y2 = y2, cx = (x1 + x2) / 2.0,
+                dash = if dashed { " stroke-dasharray=\"5,4\"" } else { "" }
+            )
+        };
+
+        for l in self.links.iter() {
+            if let (Some(from), Some(to)) = (vm_pos.get(&l.valeur_id), asset_pos.get(&l.support_id)) {
+                svg.push_str(&edge(*from, *to, false));
+            }
+        }
+        for d in self.dependencies.iter() {
+            if let (Some(from), Some(to)) = (asset_pos.get(&d.support_id), asset_pos.get(&d.depend_de)) {
+                svg.push_str(&edge(*from, *to, true));
+            }
+        }
+
+        let node = |pos: (f64, f64), href: String, label: String, niveau: i32, rounded: bool| -> String {
+            let fill = if niveau == 0 { "#f1f1f1" } else { gravity_color(niveau, &self.scale) };
+            let gravity = if niveau == 0 { String::new() } else { format!(" (G{})", niveau) };
+            let mut label = label;
+            if label.chars().count() > 24 {
+                label = label.chars().take(23).collect::<String>() + "…";
+            }
+            format!(
+                "<a href=\"{href}\"><rect x=\"{x}\" y=\"{y}\" width=\"{w}\" height=\"{h}\" rx=\"{rx}\" fill=\"{fill}\" stroke=\"#555\"/><text x=\"{tx}\" y=\"{ty}\" text-anchor=\"middle\" dominant-baseline=\"middle\">{label}{gravity}</text></a>",
+                href = href, x = pos.0, y = pos.1, w = NODE_WIDTH, h = NODE_HEIGHT, rx = if rounded { 18 } else { 4 },
+                fill = fill, tx = pos.0 + NODE_WIDTH / 2.0, ty = pos.1 + NODE_HEIGHT / 2.0,
+                label = svg_escape(&label), gravity = gravity
+            )
+        };
+
+        for v in self.vms.iter() {
+            svg.push_str(&node(vm_pos[&v.valeur_id], format!("/c1/vm/detail/{}", v.valeur_id), v.valeur_name.clone(), self.vm_gravity(v.valeur_id), true));
+        }
+        for a in self.assets.iter() {
+            svg.push_str(&node(asset_pos[&a.support_id], format!("/c1/asset/detail/{}", a.support_id), a.support_name.clone(), self.asset_gravity(a.support_id), false));
+        }
+
+        svg.push_str("</svg>");
+        svg
+    }
 }
 
 async fn fevnt() -> String {
 
     let all = FearedEvent::c1_get_all_feared_event().await;
+    let scale = GravityScale::c1_get_gravity_scale().await;
+    let vm = ValeurMetier::c1_get_all_valeurmetier_no_limit().await;
+
+    let events = all.iter().map(|m| {
+        let vm_name = vm.iter()
+            .find(|v| v.valeur_id == m.valeur_metier)
+            .map(|v| format!("#{} {}", v.valeur_id, v.valeur_name))
+            .unwrap_or(m.valeur_metier.to_string());
+
+        context! {
+            vm_name,
+            criterion => t(m.critere_label()),
+            categories => category_labels(&m.impact_categories),
+            gravity_color => gravity_color(m.gravite, &scale),
+            gravity_label => gravity_label(m.gravite, &scale),
+            ..Value::from_serialize(m)
+        }
+    }).collect::<Vec<Value>>();
+
+    render("c1/list-fevnt.html", context! {
+        events,
+        scale => scale_levels(&scale).into_iter().rev().collect::<Vec<Value>>(),
+    })
+}
+
+async fn fevnt_create() -> String {
 
-    let mut str = String::new();
-    let base = fs::read_to_string("html/c1/files/fevnt-solo.html").unwrap();
+    let vm = ValeurMetier::c1_get_all_valeurmetier_no_limit().await;
+    let scale = GravityScale::c1_get_gravity_scale().await;
+
+    render("c1/create-fevnt.html", context! {
+        event => FearedEvent::default(),
+        vms => vm,
+        criteria => labels(&FearedEvent::CRITERIA),
+        categories => category_checkboxes(""),
+        scale,
+    })
+}
 
-    for m in all {
-        let new = base.replace("{{fevnt_name}}", &m.evenement_redoute)
-            .replace("{{fevnt_id}}", &m.event_id.to_string())
-            .replace("{{fevnt_impacts}}", &m.impact.replace("\n", "<br>"))
-            .replace("{{fevnt_vm}}", &m.valeur_metier.to_string())
-            .replace("{{fevnt_gravity}}", &m.gravite.to_string());
+async fn fevnt_update(event_id:i32) -> String {
 
-        str.push_str(&new);
+    let event = FearedEvent::c1_get_feared_event_by_id(event_id).await;
+    if event.is_empty() {
+        return "__404".to_string();
     }
 
-    return fs::read_to_string("html/c1/list-fevnt.html").unwrap()
-        .replace("{{fevnt_list}}", &str);
+    let event = &event[0];
+
+    let vm = ValeurMetier::c1_get_all_valeurmetier_no_limit().await;
+    let scale = GravityScale::c1_get_gravity_scale().await;
+
+    render("c1/update-fevnt.html", context! {
+        event,
+        vms => vm,
+        criteria => labels(&FearedEvent::CRITERIA),
+        categories => category_checkboxes(&event.impact_categories),
+        scale,
+    })
 }
 
-async fn fevnt_create() -> String {
+async fn fevnt_pivot() -> String {
 
     let vm = ValeurMetier::c1_get_all_valeurmetier_no_limit().await;
+    let events = FearedEvent::c1_get_all_feared_event().await;
+    let scale = GravityScale::c1_get_gravity_scale().await;
+
+    // one row per business value, one cell per criterion holding the
+    // highest gravity and the feared events behind it
+    let mut rows = Vec::new();
+    for v in vm {
+        let related:Vec<&FearedEvent> = events.iter().filter(|e| e.valeur_metier == v.valeur_id).collect();
+
+        let mut cells = Vec::new();
+        for (key, _) in FearedEvent::CRITERIA.iter() {
+            let on_criterion:Vec<&FearedEvent> = related.iter().filter(|e| e.critere == *key).copied().collect();
+
+            cells.push(match on_criterion.iter().map(|e| e.gravite).max() {
+                Some(max) => context! {
+                    max,
+                    color => gravity_color(max, &scale),
+                    label => gravity_label(max, &scale),
+                    events => on_criterion,
+                },
+                None => Value::from(()),
+            });
+        }
+
+        rows.push(context! {
+            vm => v,
+            cells,
+            categories => category_labels(&related.iter().map(|e| e.impact_categories.as_str()).collect::<Vec<&str>>().join(",")),
+            worst => related.iter().map(|e| e.gravite).max(),
+        });
+    }
 
-    let mut str = String::new();
-    
-    for m in vm {
-        let new = format!(" <option value=\"{}\">#{} {}</option>", m.valeur_id, m.mission_id, m.valeur_name);
-        str.push_str(&new);
+    render("c1/pivot-fevnt.html", context! {
+        criteria => labels(&FearedEvent::CRITERIA),
+        rows,
+    })
+}
+
+async fn gravity() -> String {
+
+    let scale = GravityScale::c1_get_gravity_scale().await;
+
+    render("c1/gravity-scale.html", context! { levels => scale_levels(&scale) })
+}
+
+/// The levels of the scale, with their color
+fn scale_levels(scale:&[GravityScale]) -> Vec<Value> {
+    scale.iter()
+        .map(|l| context! { color => gravity_color(l.niveau, scale), ..Value::from_serialize(l) })
+        .collect()
+}
+
+/// Color of a gravity level, from green (lowest) to red (highest), whatever
+/// the number of levels of the scale
+fn gravity_color(niveau:i32, scale:&[GravityScale]) -> &'static str {
+    const COLORS: [&str; 4] = ["rgb(187, 255, 148)", "rgb(255, 234, 148)", "rgb(255, 187, 148)", "rgb(255, 148, 148)"];
+
+    let max = scale.iter().map(|l| l.niveau).max().unwrap_or(4);
+    if max <= 1 || niveau < 1 {
+        return COLORS[0];
     }
 
+    let index = ((niveau.min(max) - 1) as f64 / (max - 1) as f64 * 3.0).round() as usize;
+    COLORS[index]
+}
 
-    return fs::read_to_string("html/c1/create-fevnt.html").unwrap()
-        .replace("{{vm_list}}", &str);
+fn gravity_label(niveau:i32, scale:&[GravityScale]) -> String {
+    scale.iter()
+        .find(|l| l.niveau == niveau)
+        .map(|l| l.libelle.clone())
+        .unwrap_or_default()
 }
 
-async fn gaps() -> String {
+/// The impact categories, checked when they are in `current`
+fn category_checkboxes(current:&str) -> Vec<Value> {
+    let current:Vec<&str> = current.split(',').collect();
 
-    let all = Gap::c1_get_all_gaps().await;
+    FearedEvent::IMPACT_CATEGORIES.iter()
+        .map(|(key, label)| context! { key, label => t(label), checked => current.contains(key) })
+        .collect()
+}
+
+/// Human readable list of the impact categories stored as `a,b,c`
+fn category_labels(categories:&str) -> String {
+    let keys:Vec<&str> = categories.split(',').collect();
+
+    FearedEvent::IMPACT_CATEGORIES.iter()
+        .filter(|(key, _)| keys.contains(key))
+        .map(|(_, label)| t(label))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
 
-    let mut str = String::new();
-    let base = fs::read_to_string("html/c1/files/gaps-solo.html").unwrap();
+async fn referentials() -> String {
 
-    for m in all {
-        let new = base.replace("{{gap_id}}", &m.gap_id.to_string())
-            .replace("{{referential_type}}", &m.referential_type)
-            .replace("{{referential_name}}", &m.referential_name.to_string())
-            .replace("{{application_state}}", &m.application_state.to_string())
-            .replace("{{gap}}", &m.gap)
-            .replace("{{gap_justification}}", &m.gap_justification)
-            .replace("{{proposed_measures}}", &m.proposed_measures);
+    let imported = Referential::c1_get_all_referentials().await;
+    let gaps = Gap::c1_get_all_gaps().await;
 
-        str.push_str(&new);
+    let mut referential_list = Vec::new();
+    for r in imported.iter() {
+        let requirements = Requirement::c1_get_requirements_by_referential(r.referential_id).await;
+        let overall = &rollup(&requirements, &gaps)[0];
+
+        referential_list.push(context! {
+            assessed => overall.assessed,
+            total => overall.total,
+            score => overall.score,
+            ..Value::from_serialize(r)
+        });
     }
 
+    // imported, importable or updatable
+    let catalogue_list = Catalogue::available().iter().map(|c| context! {
+        key => c.key,
+        name => c.name,
+        version => c.version,
+        publisher => c.publisher,
+        description => c.description,
+        state => match imported.iter().find(|r| r.ref_key == c.key) {
+            Some(r) if r.version == c.version => "imported",
+            Some(_) => "outdated",
+            None => "available",
+        },
+    }).collect::<Vec<Value>>();
+
+    render("c1/list-referentials.html", context! {
+        referentials => referential_list,
+        catalogue => catalogue_list,
+    })
+}
+
+async fn referential_detail(referential_id:i32) -> String {
+
+    let referential = Referential::c1_get_referential_by_id(referential_id).await;
+    if referential.is_empty() {
+        return "__404".to_string();
+    }
 
-    return fs::read_to_string("html/c1/list-gaps.html").unwrap()
-        .replace("{{gaps_list}}", &str);
+    let requirements = Requirement::c1_get_requirements_by_referential(referential_id).await;
+    let gaps = Gap::c1_get_all_gaps().await;
+    let measures = GapMeasure::c1_get_all_gap_measures().await;
+
+    let domains = rollup(&requirements, &gaps).iter().map(|d| context! {
+        code => d.code,
+        title => d.title,
+        pad => 12 + d.depth * 20,
+        assessed => d.assessed,
+        total => d.total,
+        score => d.score,
+        level => d.level(),
+    }).collect::<Vec<Value>>();
+
+    let tree = requirements.iter().map(|r| {
+        let gap = gaps.iter().find(|g| g.requirement_id == r.requirement_id);
+
+        // the state of a control addressed by countermeasures follows them
+        let measure_count = gap.map(|g| measures.iter().filter(|m| m.gap_id == g.gap_id).count()).unwrap_or(0);
+
+        context! {
+            pad => 12 + depth(&requirements, r) * 20,
+            control => is_control(&requirements, r),
+            gap,
+            measure_count,
+            ..Value::from_serialize(r)
+        }
+    }).collect::<Vec<Value>>();
+
+    render("c1/detail-referential.html", context! {
+        referential => referential[0],
+        domains,
+        requirements => tree,
+        versions => Referential::c1_get_versions(referential_id).await,
+    })
+}
+
+async fn gaps() -> String {
+
+    let all = Gap::c1_get_all_gaps().await;
+
+    render("c1/list-gaps.html", context! { gaps => all })
 }
 
 async fn gaps_create() -> String {
-    return fs::read_to_string("html/c1/create-gaps.html").unwrap();
+    render("c1/create-gaps.html", ())
+}
+
+async fn gaps_update(gaps_id:i32) -> String {
+    let g = Gap::c1_get_gaps_by_id(gaps_id).await;
+
+    if g.is_empty() {
+        return "__404".to_string();
+    }
+
+    let measures = Countermeasure::get_countermeasures_of_gap(gaps_id).await;
+
+    render("c1/update-gaps.html", context! {
+        gap => g[0],
+        measure_count => measures.len(),
+        derived_state => derived_state(&measures),
+    })
 }
 
 async fn gaps_detail(gaps_id:i32) -> String {
@@ -215,17 +747,70 @@ async fn gaps_detail(gaps_id:i32) -> String {
         return "__404".to_string();
     }
 
-    let g = &g[0];
-
-    return fs::read_to_string("html/c1/detail-gaps.html").unwrap()
-        .replace("{{referential_type}}", &g.referential_type)
-        .replace("{{referential_name}}", &g.referential_name)
-        .replace("{{application_state}}", &g.application_state.to_string())
-        .replace("{{gap}}", &g.gap)
-        .replace("{{gap_justification}}", &g.gap_justification.replace("\n", "<br>"))
-        .replace("{{proposed_measures}}", &g.proposed_measures.replace("\n", "<br>"))
-        .replace("{{gap_id}}", &gaps_id.to_string());
+    let today = today();
+    let measures = Countermeasure::get_countermeasures_of_gap(gaps_id).await;
+    let ctm_list = measures.iter()
+        .map(|c| context! { contribution => measure_compliance(c), ..measure_context(c, &today) })
+        .collect::<Vec<Value>>();
+
+    let catalogue = Countermeasure::get_all_countermeasures().await.into_iter()
+        .filter(|c| !measures.iter().any(|m| m.ctm_uuid == c.ctm_uuid))
+        .collect::<Vec<Countermeasure>>();
+
+    render("c1/detail-gaps.html", context! {
+        gap => g[0],
+        countermeasures => ctm_list,
+        catalogue,
+    })
 }
 
 
 
+
+
+/// Controls of every referential without countermeasure, and countermeasures
+/// addressing no control
+async fn coverage() -> String {
+    let gaps = Gap::c1_get_all_gaps().await;
+    let measures = GapMeasure::c1_get_all_gap_measures().await;
+
+    let covered = |requirement_id: i32| -> bool {
+        gaps.iter()
+            .filter(|g| g.requirement_id == requirement_id)
+            .any(|g| measures.iter().any(|m| m.gap_id == g.gap_id))
+    };
+
+    let mut referential_list = Vec::new();
+    let mut uncovered_list = Vec::new();
+    for referential in Referential::c1_get_all_referentials().await.iter() {
+        let requirements = Requirement::c1_get_requirements_by_referential(referential.referential_id).await;
+        let controls = requirements.iter().filter(|r| is_control(&requirements, r)).collect::<Vec<&Requirement>>();
+        let uncovered = controls.iter().filter(|r| !covered(r.requirement_id)).collect::<Vec<_>>();
+
+        let score = if controls.is_empty() { 0.0 } else { (controls.len() - uncovered.len()) as f64 * 100.0 / controls.len() as f64 };
+        referential_list.push(context! {
+            referential,
+            covered => controls.len() - uncovered.len(),
+            total => controls.len(),
+            score,
+        });
+
+        for r in uncovered {
+            uncovered_list.push(context! { referential, requirement => r });
+        }
+    }
+
+    // a measure maps to a control through a gap of a referential
+    let control_gaps = gaps.iter().filter(|g| g.requirement_id > 0).map(|g| g.gap_id).collect::<Vec<i32>>();
+    let today = today();
+    let unmapped_list = Countermeasure::get_all_countermeasures().await.iter()
+        .filter(|c| !measures.iter().any(|m| m.ctm_uuid == c.ctm_uuid && control_gaps.contains(&m.gap_id)))
+        .map(|c| measure_context(c, &today))
+        .collect::<Vec<Value>>();
+
+    render("c1/coverage.html", context! {
+        referentials => referential_list,
+        uncovered => uncovered_list,
+        unmapped => unmapped_list,
+    })
+}
@@ -1,7 +1,15 @@
 // The web controller for the C3 route
 
-use std::fs;
+use std::f64::consts::PI;
+
+use minijinja::{context, Value};
+
+use crate::helper::config::{config, RadarConfig};
 use crate::helper::database::C3Stakeholder;
+use crate::helper::i18n::{fill, t};
+use crate::helper::functions::svg_escape;
+use crate::helper::template::render;
+use crate::helper::threat::{residual_or_current, residual_threat_level, threat_level};
 
 #[tracing::instrument(level = "info")]
 pub async fn c3(path: String) -> String {
@@ -9,10 +17,15 @@ pub async fn c3(path: String) -> String {
         return main().await;
     } else if path == "c3/stakeholder/create" {
         return create().await;
+    } else if path.starts_with("c3/stakeholder/update/") {
+        let id = path.trim_start_matches("c3/stakeholder/update/").parse::<i32>().unwrap_or(0);
+        return update(id).await;
+    } else if path == "c3/radar" || path == "c3/radar/" {
+        return radar().await;
     } else if path.starts_with("c3/stakeholder/") {
         let id = path.trim_start_matches("c3/stakeholder/").parse::<i32>().unwrap_or(0);
         return detail(id).await;
-    } 
+    }
 
     "__404".to_string()
 }
@@ -21,70 +34,258 @@ async fn main() -> String {
     // Fetch all stakeholders asynchronously
     let all = C3Stakeholder::c3_get_all_stakeholder().await;
 
-    // Initialize an empty string to store the generated HTML
-    let mut str = String::new();
+    let list = all.iter().map(stakeholder_context).collect::<Vec<Value>>();
 
-    // Read the base HTML template for each stakeholder item
-    let base = fs::read_to_string("html/c3/files/stakeholder-solo.html").unwrap();
-
-    // Loop through each stakeholder and replace placeholders with actual values
-    for m in all {
-        
-        let exposition = m.dependance as f64 * m.penetration as f64;
-        let fiabilite_cyber = m.maturite_ssi as f64 * m.confiance as f64;
+    render("c3/list-stakeholders.html", context! { stakeholders => list })
+}
 
+async fn create() -> String {
+    render("c3/create-stakeholder.html", ())
+}
 
-        let mut niveau_de_menace = 0.0; // Initialize as a floating-point number
-        if fiabilite_cyber > 0.0 {
-            let menace_ratio = exposition / fiabilite_cyber;
-            niveau_de_menace = (menace_ratio * 100.0).round() / 100.0;
-        }
+async fn detail(id: i32) -> String {
+    let detail = C3Stakeholder::c3_get_stakeholder_detail(id).await;
 
-        // Replace placeholders in the base template with actual stakeholder data
-        let new = base.replace("{{stakeholder_id}}", &m.stakeholder_id.to_string())
-            .replace("{{category}}", &m.category)
-            .replace("{{stakeholder_name}}", &m.stakeholder_name)
-            .replace("{{dependance}}", &m.dependance.to_string())
-            .replace("{{penetration}}", &m.penetration.to_string())
-            .replace("{{maturite_ssi}}", &m.maturite_ssi.to_string())
-            .replace("{{confiance}}", &m.confiance.to_string())
-
-
-            .replace("{{exposition}}", exposition.to_string().as_str())
-            .replace("{{fiabilite_cyber}}", fiabilite_cyber.to_string().as_str())
-            .replace("{{niveau_de_menace}}", niveau_de_menace.to_string().as_str());
-        
-        // Append the updated template for this item to the main HTML string
-        str.push_str(&new);
+    if detail.is_empty() {
+        return "__404".to_string();
     }
 
+    let detail = &detail[0];
 
-    // Read the main stakeholder list HTML template
-    fs::read_to_string("html/c3/list-stakeholders.html").unwrap()
-        .replace("{{stakeholder_list}}", &str)
-}
+    let (_, _, niveau_de_menace) = threat_level(detail);
+    let (_, _, niveau_de_menace_residuel) = residual_threat_level(detail);
+    let radar = &config().c3_radar;
 
-async fn create() -> String {
-    // Read the HTML template for creating a stakeholder
-    fs::read_to_string("html/c3/create-stakeholder.html").unwrap()
+    render("c3/detail-stakeholder.html", context! {
+        s => stakeholder_context(detail),
+        zone => t(zone_label(zone(radar, niveau_de_menace).0)),
+        residual_zone => t(zone_label(zone(radar, niveau_de_menace_residuel).0)),
+    })
 }
 
-async fn detail(id: i32) -> String {
+async fn update(id: i32) -> String {
     let detail = C3Stakeholder::c3_get_stakeholder_detail(id).await;
-    
+
     if detail.is_empty() {
         return "__404".to_string();
     }
 
-    let detail = detail[0].clone();
-
-    // Read the detailed stakeholder HTML template
-    fs::read_to_string("html/c3/detail-stakeholder.html").unwrap()
-        .replace("{{stakeholder_id}}", &detail.stakeholder_id.to_string())
-        .replace("{{category}}", &detail.category)
-        .replace("{{stakeholder_name}}", &detail.stakeholder_name)
-        .replace("{{dependance}}", &detail.dependance.to_string())
-        .replace("{{penetration}}", &detail.penetration.to_string())
-        .replace("{{maturite_ssi}}", &detail.maturite_ssi.to_string())
-        .replace("{{confiance}}", &detail.confiance.to_string())
-}
\ No newline at end of file
+    render("c3/update-stakeholder.html", context! { s => detail[0] })
+}
+
+async fn radar() -> String {
+    let all = C3Stakeholder::c3_get_all_stakeholder().await;
+    let radar = &config().c3_radar;
+
+    // Count the stakeholders in each zone for the legend
+    let mut counts = [0; 4];
+    for m in all.iter() {
+        let (_, _, niveau_de_menace) = threat_level(m);
+        let (_, _, index) = zone(radar, niveau_de_menace);
+        counts[index] += 1;
+    }
+
+    render("c3/radar-stakeholders.html", context! {
+        radar_svg => render_radar(&all, radar),
+        thresholds => radar,
+        counts,
+    })
+}
+
+// ----- Utils -----
+
+/// A stakeholder with its threat indicators, and its ratings and indicators
+/// once the security measures are in place
+fn stakeholder_context(m: &C3Stakeholder) -> Value {
+    let (exposition, fiabilite_cyber, niveau_de_menace) = threat_level(m);
+    let (exposition_residuelle, fiabilite_cyber_residuelle, niveau_de_menace_residuel) = residual_threat_level(m);
+
+    context! {
+        exposition,
+        fiabilite_cyber,
+        niveau_de_menace,
+        residual => context! {
+            dependance => residual_or_current(m.dependance_residuelle, m.dependance),
+            penetration => residual_or_current(m.penetration_residuelle, m.penetration),
+            maturite_ssi => residual_or_current(m.maturite_ssi_residuelle, m.maturite_ssi),
+            confiance => residual_or_current(m.confiance_residuelle, m.confiance),
+            exposition => exposition_residuelle,
+            fiabilite_cyber => fiabilite_cyber_residuelle,
+            niveau_de_menace => niveau_de_menace_residuel,
+        },
+        ..Value::from_serialize(m)
+    }
+}
+
+/// Zones of the radar, from the center, with their label
+pub const ZONES: [(&str, &str); 4] = [
+    ("danger", "Danger"),
+    ("control", "Control"),
+    ("watch", "Watch"),
+    ("outside", "Outside"),
+];
+
+pub fn zone_label(zone: &str) -> &'static str {
+    ZONES.iter().find(|(z, _)| *z == zone).map(|(_, label)| *label).unwrap_or("Outside")
+}
+
+/// Returns (zone key of `ZONES`, zone color, zone index) for a threat level,
+/// the thresholds of the config are validated at startup, the zones are nested
+pub fn zone(radar: &RadarConfig, niveau_de_menace: f64) -> (&'static str, &'static str, usize) {
+    if niveau_de_menace >= radar.danger {
+        return ("danger", "#dc3545", 0);
+    }
+    if niveau_de_menace >= radar.control {
+        return ("control", "#fd7e14", 1);
+    }
+    if niveau_de_menace >= radar.watch {
+        return ("watch", "#ffc107", 2);
+    }
+    ("outside", "#198754", 3)
+}
+
+/// Distance from the center of the radar for a threat level:
+/// the higher the threat, the closer to the center
+pub fn radius(radar: &RadarConfig, niveau_de_menace: f64) -> f64 {
+    if niveau_de_menace >= radar.danger {
+        return (RADAR_DANGER * radar.danger / niveau_de_menace).max(12.0);
+    }
+    if niveau_de_menace >= radar.control {
+        let ratio = (radar.danger - niveau_de_menace) / (radar.danger - radar.control);
+        return RADAR_DANGER + ratio * (RADAR_CONTROL - RADAR_DANGER);
+    }
+    if niveau_de_menace >= radar.watch {
+        let ratio = (radar.control - niveau_de_menace) / (radar.control - radar.watch);
+        return RADAR_CONTROL + ratio * (RADAR_WATCH - RADAR_CONTROL);
+    }
+    let ratio = (radar.watch - niveau_de_menace) / radar.watch;
+    RADAR_WATCH + ratio * (RADAR_OUTER - RADAR_WATCH)
+}
+
+const RADAR_SIZE: f64 = 780.0;
+const RADAR_DANGER: f64 = 90.0;
+const RADAR_CONTROL: f64 = 170.0;
+const RADAR_WATCH: f64 = 250.0;
+const RADAR_OUTER: f64 = 290.0;
+
+/// Render the stakeholder threat radar (EBIOS workshop 3) as an SVG.
+/// Each category gets its own angular sector, stakeholders link to their detail page.
+pub fn render_radar(stakeholders: &[C3Stakeholder], radar: &RadarConfig) -> String {
+    let center = RADAR_SIZE / 2.0;
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 {size} {size}\" width=\"100%\" style=\"max-width: {size}px;\" font-family=\"sans-serif\">",
+        size = RADAR_SIZE
+    );
+
+    // zones, from the outside to the center
+    for (r, color, label) in [
+        (RADAR_OUTER, "#e9f7ef", ""),
+        (RADAR_WATCH, "#fff8e1", "Watch zone"),
+        (RADAR_CONTROL, "#ffe5d0", "Control zone"),
+        (RADAR_DANGER, "#f8d7da", "Danger zone"),
+    ] {
+        svg.push_str(&format!(
+            "<circle cx=\"{c}\" cy=\"{c}\" r=\"{r}\" fill=\"{color}\" stroke=\"#adb5bd\" stroke-dasharray=\"4 4\"/>",
+            c = center, r = r, color = color
+        ));
+        if !label.is_empty() {
+            svg.push_str(&format!(
+                "<text x=\"{x}\" y=\"{y}\" font-size=\"11\" fill=\"#6c757d\" text-anchor=\"middle\">{label}</text>",
+                x = center, y = center - r + 14.0, label = svg_escape(&t(label))
+            ));
+        }
+    }
+
+    // group the stakeholders by category, keep the categories sorted
+    let mut categories: Vec<String> = stakeholders.iter().map(|s| s.category.clone()).collect();
+    categories.sort();
+    categories.dedup();
+
+    if categories.is_empty() {
+        svg.push_str(&format!(
+            "<text x=\"{c}\" y=\"{c}\" font-size=\"14\" fill=\"#6c757d\" text-anchor=\"middle\">{label}</text></svg>",
+            c = center, label = svg_escape(&t("No stakeholder yet"))
+        ));
+        return svg;
+    }
+
+    let sector = 2.0 * PI / categories.len() as f64;
+
+    for (i, category) in categories.iter().enumerate() {
+        let start = i as f64 * sector - PI / 2.0;
+
+        // sector separator
+        if categories.len() > 1 {
+            svg.push_str(&format!(
+                "<line x1=\"{c}\" y1=\"{c}\" x2=\"{x:.1}\" y2=\"{y:.1}\" stroke=\"#adb5bd\"/>",
+                c = center,
+                x = center + RADAR_OUTER * start.cos(),
+                y = center + RADAR_OUTER * start.sin()
+            ));
+        }
+
+        // category label, outside of the radar
+        let middle = start + sector / 2.0;
+        svg.push_str(&format!(
+            "<text x=\"{x:.1}\" y=\"{y:.1}\" font-size=\"12\" font-weight=\"bold\" fill=\"#343a40\" text-anchor=\"middle\">{label}</text>",
+            x = center + (RADAR_OUTER + 18.0) * middle.cos(),
+            y = center + (RADAR_OUTER + 18.0) * middle.sin() + 4.0,
+            label = svg_escape(category)
+        ));
+
+        // spread the stakeholders of the category evenly inside the sector
+        let members: Vec<&C3Stakeholder> = stakeholders.iter().filter(|s| &s.category == category).collect();
+        let step = sector / (members.len() as f64 + 1.0);
+
+        for (j, m) in members.iter().enumerate() {
+            let (exposition, _, niveau_de_menace) = threat_level(m);
+            let (key, color, _) = zone(radar, niveau_de_menace);
+
+            let angle = start + step * (j as f64 + 1.0);
+            let r = radius(radar, niveau_de_menace);
+            let x = center + r * angle.cos();
+            let y = center + r * angle.sin();
+
+            // the bigger the exposure, the bigger the dot
+            let dot = 5.0 + exposition.min(16.0) / 2.0;
+
+            // show where the stakeholder moves once its security measures are in place
+            let (_, _, niveau_de_menace_residuel) = residual_threat_level(m);
+            if niveau_de_menace_residuel != niveau_de_menace {
+                let rr = radius(radar, niveau_de_menace_residuel);
+                svg.push_str(&format!(
+                    "<line x1=\"{x:.1}\" y1=\"{y:.1}\" x2=\"{rx:.1}\" y2=\"{ry:.1}\" stroke=\"#495057\" stroke-dasharray=\"3 3\"/><circle cx=\"{rx:.1}\" cy=\"{ry:.1}\" r=\"4\" fill=\"none\" stroke=\"#495057\"><title>{title}</title></circle>",
+                    x = x,
+                    y = y,
+                    rx = center + rr * angle.cos(),
+                    ry = center + rr * angle.sin(),
+                    title = svg_escape(&fill(t("{name} - residual threat {threat}"), &[
+                        ("name", m.stakeholder_name.clone()),
+                        ("threat", niveau_de_menace_residuel.to_string()),
+                    ]))
+                ));
+            }
+
+            svg.push_str(&format!(
+                "<a href=\"/c3/stakeholder/{id}\"><circle cx=\"{x:.1}\" cy=\"{y:.1}\" r=\"{dot:.1}\" fill=\"{color}\" stroke=\"#212529\"><title>{title}</title></circle><text x=\"{x:.1}\" y=\"{ty:.1}\" font-size=\"10\" fill=\"#212529\" text-anchor=\"middle\">{name}</text></a>",
+                id = m.stakeholder_id,
+                x = x,
+                y = y,
+                ty = y - dot - 3.0,
+                dot = dot,
+                color = color,
+                name = svg_escape(&m.stakeholder_name),
+                title = svg_escape(&fill(t("{name} ({category}) - threat {threat} - {zone}"), &[
+                    ("name", m.stakeholder_name.clone()),
+                    ("category", m.category.clone()),
+                    ("threat", niveau_de_menace.to_string()),
+                    ("zone", t(zone_label(key))),
+                ]))
+            ));
+        }
+    }
+
+    svg.push_str("</svg>");
+    svg
+}
@@ -1,23 +1,38 @@
 // export the home route handler
-use std::fs;
+use std::collections::HashMap;
 
+use chrono::Local;
+use minijinja::{context, Value};
 use uuid::Uuid;
 
+use crate::api::mods::countermeasure::{allowed_transitions, solved_range, status_label, PRIORITIES};
 use crate::helper::functions::is_uuid_v4;
-use crate::helper::database::{Scenario, Countermeasure};
+use crate::helper::database::{Scenario, Countermeasure, CountermeasureLink, Risk, Gap, GapMeasure, Referential, Requirement};
+use crate::helper::i18n::t;
+use crate::helper::referential::is_control;
+use crate::helper::template::render;
 
 #[tracing::instrument(level = "info")]
 pub async fn create(path:String) -> String {
 
-    // get the risk uuid
-    let risk_uuid = path.replace("countermeasure/create/", "");
+    // get the scenario uuid, none for a measure of the catalogue
+    let scenario_uuid = path.replace("countermeasure/create/", "");
 
-    if !is_uuid_v4(&risk_uuid) {
+    if scenario_uuid.is_empty() {
+        return render("countermeasure/create.html", context! {
+            ctm => Countermeasure::default(),
+            priorities => PRIORITIES,
+            back_url => "/countermeasure/library",
+            scenario_uuid => "",
+        });
+    }
+
+    if !is_uuid_v4(&scenario_uuid) {
         return "__404".to_string();
     }
     
-    // check if risk exist
-    let scenario_detail = Scenario::get_scenario_detail(risk_uuid).await;
+    // check if scenario exist
+    let scenario_detail = Scenario::get_scenario_detail(scenario_uuid).await;
 
     if scenario_detail.is_empty() {
         return "__404".to_string();
@@ -25,10 +40,12 @@ pub async fn create(path:String) -> String {
 
     let scenario_detail = scenario_detail.get(0).unwrap();
 
-    let index = fs::read_to_string("html/countermeasure/create.html").unwrap()
-        .replace("{{scenario_uuid}}", scenario_detail.scenario_uuid.to_string().as_str());
-
-    return index;
+    return render("countermeasure/create.html", context! {
+        ctm => Countermeasure::default(),
+        priorities => PRIORITIES,
+        back_url => format!("/scenario/detail/{}", scenario_detail.scenario_uuid),
+        scenario_uuid => scenario_detail.scenario_uuid,
+    });
 }
 
 
@@ -47,16 +64,41 @@ pub async fn detail(path: String) -> String {
 
     let ctm = ctm_detail.get(0).unwrap();
 
+    // scenarios this measure mitigates, and the ones it could be shared with
+    let scenarios = Scenario::get_scenarios_of_countermeasure(ctm.ctm_uuid.to_string()).await;
+    let mut mitigated = Vec::new();
+    for sc in scenarios.iter() {
+        let risk = Risk::get_risk_detail(sc.risk_uuid.to_string()).await.pop().unwrap_or(Risk::default());
+        mitigated.push(context! { risk, scenario => sc });
+    }
 
-    let index = fs::read_to_string("html/countermeasure/detail.html").unwrap()
-        .replace("{{ctm_uuid}}", ctm.ctm_uuid.to_string().as_str())
-        .replace("{{scenario_uuid}}", ctm.scenario_uuid.to_string().as_str())
-        .replace("{{title}}", ctm.title.as_str())
-        .replace("{{description}}", ctm.description.as_str())
-        .replace("{{solved}}", ctm.solved.to_string().as_str())
-        .replace("{{solved_description}}", ctm.solved_description.as_str());
-    
-    return index;
+    let mut shareable = Vec::new();
+    for risk in Risk::select_all_risk().await {
+        for sc in Scenario::get_all_scenario_of_risk(risk.risk_uuid.to_string()).await {
+            if scenarios.iter().any(|s| s.scenario_uuid == sc.scenario_uuid) {
+                continue;
+            }
+            shareable.push(context! { risk_name => risk.risk_name, scenario => sc });
+        }
+    }
+
+    let (gaps, control_groups) = controls(ctm).await;
+
+    let back_url = if ctm.scenario_uuid.is_nil() { "/countermeasure/library".to_string() } else { format!("/scenario/detail/{}", ctm.scenario_uuid) };
+
+    let transitions = allowed_transitions(&ctm.status).into_iter()
+        .map(|status| (status, t(status_label(status))))
+        .collect::<Vec<_>>();
+
+    render("countermeasure/detail.html", context! {
+        ctm => measure_context(ctm, &today()),
+        transitions,
+        gaps,
+        control_groups,
+        scenarios => mitigated,
+        shareable,
+        back_url,
+    })
 }
 
 pub async fn update(path: String) -> String {
@@ -72,17 +114,14 @@ pub async fn update(path: String) -> String {
       return "__404".to_string();
     }
 
-    let ctm = ctm_detail.get(0).unwrap();
-
-    let index = fs::read_to_string("html/countermeasure/update.html").unwrap()
-        .replace("{{ctm_uuid}}", ctm.ctm_uuid.to_string().as_str())
-        .replace("{{scenario_uuid}}", ctm.scenario_uuid.to_string().as_str())
-        .replace("{{title}}", ctm.title.as_str())
-        .replace("{{description}}", ctm.description.as_str())
-        .replace("{{solved}}", ctm.solved.to_string().as_str())
-        .replace("{{solved_description}}", ctm.solved_description.as_str());
+    let ctm = ctm_detail.first().unwrap();
+    let (solved_min, solved_max) = solved_range(&ctm.status);
 
-    return index;
+    render("countermeasure/update.html", context! {
+        ctm,
+        priorities => PRIORITIES,
+        solved_options => (solved_min..=solved_max).step_by(10).collect::<Vec<i32>>(),
+    })
 }
 
 
@@ -101,13 +140,91 @@ pub async fn delete(path: String) -> String {
 
   let ctm = ctm_detail.get(0).unwrap();
 
-  let index = fs::read_to_string("html/countermeasure/delete.html").unwrap()
-      .replace("{{ctm_uuid}}", ctm.ctm_uuid.to_string().as_str())
-      .replace("{{scenario_uuid}}", ctm.scenario_uuid.to_string().as_str())
-      .replace("{{title}}", ctm.title.as_str())
-      .replace("{{description}}", ctm.description.as_str())
-      .replace("{{solved}}", ctm.solved.to_string().as_str())
-      .replace("{{solved_description}}", ctm.solved_description.as_str());
+  let back_url = if ctm.scenario_uuid.is_nil() { "/countermeasure/library".to_string() } else { format!("/scenario/detail/{}", ctm.scenario_uuid) };
+
+  render("countermeasure/delete.html", context! { ctm, back_url })
+}
+
+
+/// Gaps and controls the countermeasure addresses, and the options to address
+/// another one: the controls of every referential then the free gaps
+async fn controls(ctm: &Countermeasure) -> (Vec<Gap>, Vec<Value>) {
+    let gaps = Gap::c1_get_all_gaps().await;
+    let linked = GapMeasure::c1_get_all_gap_measures().await.iter()
+        .filter(|l| l.ctm_uuid == ctm.ctm_uuid)
+        .map(|l| l.gap_id)
+        .collect::<Vec<i32>>();
+
+    let mut groups = Vec::new();
+    for referential in Referential::c1_get_all_referentials().await.iter() {
+        let requirements = Requirement::c1_get_requirements_by_referential(referential.referential_id).await;
+        let options = requirements.iter()
+            .filter(|r| is_control(&requirements, r))
+            .filter(|r| !gaps.iter().any(|g| g.requirement_id == r.requirement_id && linked.contains(&g.gap_id)))
+            .map(|r| (format!("requirement_id:{}", r.requirement_id), format!("{} {}", r.code, r.title)))
+            .collect::<Vec<_>>();
+        groups.push(context! { label => format!("{} {}", referential.name, referential.version), options });
+    }
+
+    let free_gaps = gaps.iter()
+        .filter(|g| g.requirement_id == 0 && !linked.contains(&g.gap_id))
+        .map(|g| (format!("gap_id:{}", g.gap_id), format!("{} {}", g.referential_type, g.referential_name)))
+        .collect::<Vec<_>>();
+    if !free_gaps.is_empty() {
+        groups.push(context! { label => t("Gap analysis"), options => free_gaps });
+    }
+
+    let addressed = gaps.into_iter().filter(|g| linked.contains(&g.gap_id)).collect::<Vec<Gap>>();
+
+    (addressed, groups)
+}
+
+/// The catalogue: every countermeasure, with the number of scenarios it mitigates
+pub async fn library() -> String {
+    let today = today();
+    let shared = share_counts().await;
+
+    let list = Countermeasure::get_all_countermeasures().await.iter().map(|ctm| context! {
+        shared => shared.get(&ctm.ctm_uuid).copied().unwrap_or(0),
+        ..measure_context(ctm, &today)
+    }).collect::<Vec<_>>();
+
+    render("countermeasure/library.html", context! { countermeasures => list })
+}
+
+/// Number of scenarios each countermeasure mitigates
+pub async fn share_counts() -> HashMap<Uuid, usize> {
+    let mut counts: HashMap<Uuid, usize> = HashMap::new();
+    for link in CountermeasureLink::get_all_countermeasure_links().await {
+        *counts.entry(link.ctm_uuid).or_insert(0) += 1;
+    }
+    counts
+}
 
-  return index;
+/// Countermeasures of every risk past their due date
+pub async fn overdue() -> String {
+    let today = today();
+
+    let mut list = Vec::new();
+    for ctm in Countermeasure::get_overdue_countermeasures(today.clone()).await {
+        let scenario = Scenario::get_scenario_detail(ctm.scenario_uuid.to_string()).await.pop().unwrap_or(Scenario::default());
+        let risk = Risk::get_risk_detail(scenario.risk_uuid.to_string()).await.pop().unwrap_or(Risk::default());
+
+        list.push(context! { risk, scenario, ..measure_context(&ctm, &today) });
+    }
+
+    render("countermeasure/overdue.html", context! { countermeasures => list })
+}
+
+pub fn today() -> String {
+    Local::now().format("%Y-%m-%d").to_string()
+}
+
+/// A countermeasure with the label of its status, and whether it is overdue
+pub fn measure_context(ctm: &Countermeasure, today: &str) -> Value {
+    context! {
+        status_label => t(status_label(&ctm.status)),
+        overdue => ctm.is_overdue(today),
+        ..Value::from_serialize(ctm)
+    }
 }
@@ -1,9 +1,70 @@
 // export the home route handler
-use std::fs;
+use std::collections::HashMap;
+
+use actix_web::web::Query;
+use chrono::Local;
+use minijinja::context;
+
+use crate::helper::i18n::{labels, t};
+use crate::helper::template::render;
+use crate::helper::database::{CountermeasureHistory, CountermeasureLink, Risk, Scenario, ScenarioRiskHistory};
+use crate::helper::trend::{compute, RiskScope, PERIODS};
 
 #[tracing::instrument(level = "info")]
-pub async fn home() -> String {
-  let index = fs::read_to_string("html/home/index.html").unwrap();
+pub async fn home(query: String) -> String {
+  let params = Query::<HashMap<String, String>>::from_query(&query)
+    .map(|q| q.into_inner())
+    .unwrap_or_default();
+  let risk = params.get("risk").cloned().unwrap_or_default();
+  let period = match params.get("period").map(|p| p.as_str()) {
+    Some("month") => "month",
+    _ => "week",
+  };
+
+  let risks = Risk::select_all_risk().await;
+  let links = CountermeasureLink::get_all_countermeasure_links().await;
+
+  // the trends of every risk, or of the selected one
+  let mut scopes = Vec::new();
+  for r in risks.iter().filter(|r| risk.is_empty() || r.risk_uuid.to_string() == risk) {
+    let scenarios = Scenario::get_all_scenario_of_risk(r.risk_uuid.to_string()).await
+      .iter()
+      .map(|s| s.scenario_uuid)
+      .collect::<Vec<_>>();
+    let mut measures = links.iter()
+      .filter(|l| scenarios.contains(&l.scenario_uuid))
+      .map(|l| l.ctm_uuid)
+      .collect::<Vec<_>>();
+    measures.sort();
+    measures.dedup();
+
+    scopes.push(RiskScope { name: r.risk_name.clone(), scenarios, measures });
+  }
+
+  let mut trend = compute(
+    period,
+    Local::now().date_naive(),
+    &scopes,
+    &ScenarioRiskHistory::get_all_history().await,
+    &CountermeasureHistory::get_all_history().await,
+  );
+
+  // the series of the levels are named in the language of the page
+  if let Some(levels) = trend["levels"].as_array_mut() {
+    for level in levels.iter_mut() {
+      let name = t(level["name"].as_str().unwrap_or_default());
+      level["name"] = name.into();
+    }
+  }
+
+  let risk_options = risks.iter()
+    .map(|r| (r.risk_uuid.to_string(), r.risk_name.clone()))
+    .collect::<Vec<(String, String)>>();
 
-  return index;
-}
\ No newline at end of file
+  return render("home/index.html", context! {
+    risk_options,
+    periods => labels(&PERIODS),
+    filters => context! { risk, period },
+    trend,
+  });
+}
@@ -0,0 +1,42 @@
+// the web controller of the recurring jobs of the scheduler
+use minijinja::{context, Value};
+
+use crate::helper::database::Job;
+use crate::helper::i18n::t;
+use crate::helper::scheduler::{jobs, NEVER};
+use crate::helper::template::render;
+
+fn job_color(status: &str) -> &'static str {
+    match status {
+        "success" => "bg-success",
+        "failed" => "bg-danger",
+        "running" => "bg-info",
+        "interrupted" => "bg-warning",
+        _ => "bg-secondary",
+    }
+}
+
+pub async fn list() -> String {
+    let states = Job::get_all_jobs().await;
+
+    let list = jobs().into_iter()
+        .map(|spec| {
+            let state = states.iter().find(|s| s.job_name == spec.name);
+            context! {
+                job_name => spec.name,
+                label => t(spec.label),
+                description => t(spec.description),
+                every_seconds => spec.every_seconds,
+                disabled => spec.disabled.map(t),
+                registered => state.is_some(),
+                color => job_color(state.map(|s| s.last_status.as_str()).unwrap_or("")),
+                scheduled => state.is_some_and(|s| s.next_run_at != NEVER),
+                ..Value::from_serialize(state)
+            }
+        })
+        .collect::<Vec<_>>();
+
+    render("job/index.html", context! {
+        jobs => list,
+    })
+}
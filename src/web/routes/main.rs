@@ -1,17 +1,65 @@
 // export the home route handler
-use std::fs;
+use std::cmp::Ordering;
+use std::collections::HashMap;
 
-use crate::web::routes::risk::get_id;
-use crate::helper::database::{Risk,Countermeasure, Scenario};
-
-#[tracing::instrument(level = "info")]
-pub async fn main() -> String {
-  let risk = Risk::select_all_risk().await;
+use actix_web::web::Query;
+use minijinja::context;
 
+use crate::api::mods::risk::{category_label, CATEGORIES, STATUSES};
+use crate::web::routes::countermeasure::today;
+use crate::helper::i18n::{labels, t};
+use crate::helper::template::render;
+use crate::web::routes::risk::risk_context;
+use crate::helper::database::{Risk,Countermeasure, Scenario};
 
-  let mut str = String::new();
-  let base = fs::read_to_string("html/main/files/sample.html").unwrap();
+/// Columns the register can be sorted by, with their label
+const SORTS: [(&str, &str); 7] = [
+  ("name", "Name"),
+  ("owner", "Owner"),
+  ("category", "Category"),
+  ("status", "Status"),
+  ("created", "Creation date"),
+  ("reviewed", "Last review"),
+  ("next_review", "Next review"),
+];
 
+#[tracing::instrument(level = "info")]
+pub async fn main(query: String) -> String {
+  let params = Query::<HashMap<String, String>>::from_query(&query)
+    .map(|q| q.into_inner())
+    .unwrap_or_default();
+  let param = |key: &str| params.get(key).cloned().unwrap_or_default();
+
+  let status = param("status");
+  let category = param("category");
+  let owner = param("owner");
+  let review = param("review");
+  let sort = param("sort");
+  let order = param("order");
+
+  let today = today();
+  let all_risks = Risk::select_all_risk().await;
+  let overdue_count = all_risks.iter().filter(|r| r.is_review_overdue(&today)).count();
+
+  let mut owners = all_risks.iter()
+    .map(|r| r.risk_owner.clone())
+    .filter(|o| !o.is_empty())
+    .collect::<Vec<String>>();
+  owners.sort();
+  owners.dedup();
+
+  let mut risk = all_risks.iter()
+    .filter(|r| status.is_empty() || r.risk_status == status)
+    .filter(|r| category.is_empty() || r.risk_category == category)
+    .filter(|r| owner.is_empty() || r.risk_owner == owner)
+    .filter(|r| review != "overdue" || r.is_review_overdue(&today))
+    .cloned()
+    .collect::<Vec<Risk>>();
+
+  sort_risks(&mut risk, &sort, order == "desc");
+
+
+  let mut list = Vec::new();
   for r in risk {
 
     let sc= Scenario::get_all_scenario_of_risk(r.risk_uuid.clone().to_string()).await;
@@ -23,24 +71,56 @@ pub async fn main() -> String {
       avg = 0.0;
     }
 
-    let new = base.replace("{{risk_name}}", &r.risk_name)
-      .replace("{{sc_count}}", sc.len().to_string().as_str())
-      .replace("{{ctm_count}}", ctm.len().to_string().as_str())
-      .replace("{{avg_solved}}", avg.to_string().as_str())
-      .replace("{{risk_id}}", get_id(r.risk_uuid).as_str())
-      .replace("{{risk_uuid}}", &r.risk_uuid.to_string());
-    str.push_str(&new);
+    list.push(context! {
+      sc_count => sc.len(),
+      ctm_count => ctm.len(),
+      avg_solved => avg,
+      ..risk_context(&r, &today)
+    });
   }
 
+  return render("main/index.html", context! {
+    risks => list,
+    overdue_count,
+    statuses => labels(&STATUSES),
+    categories => labels(&CATEGORIES),
+    owners => owners.iter().map(|o| (o, o)).collect::<Vec<_>>(),
+    sorts => labels(&SORTS),
+    filters => context! { status, category, owner, review, sort, order },
+  });
+}
 
-
-  let index = fs::read_to_string("html/main/index.html").unwrap()
-    .replace("{{risk_list}}", &str);
-
-  return index;
+/// Sort by name by default, the other columns keep the name order on ties;
+/// empty dates sort last
+/// Sort the register by a column of `SORTS`, the risks without a review
+/// date stay last in both directions
+pub fn sort_risks(risks: &mut [Risk], sort: &str, descending: bool) {
+  let position = |status: &str| STATUSES.iter().position(|(s, _)| *s == status).unwrap_or(0);
+  let direct = |ordering: Ordering| if descending { ordering.reverse() } else { ordering };
+  let date = |a: &String, b: &String| match (a.is_empty(), b.is_empty()) {
+    (true, true) => Ordering::Equal,
+    (true, false) => Ordering::Greater,
+    (false, true) => Ordering::Less,
+    (false, false) => direct(a.cmp(b)),
+  };
+
+  match sort {
+    "owner" => risks.sort_by(|a, b| direct(a.risk_owner.to_lowercase().cmp(&b.risk_owner.to_lowercase()))),
+    "category" => risks.sort_by(|a, b| direct(t(category_label(&a.risk_category)).cmp(&t(category_label(&b.risk_category))))),
+    "status" => risks.sort_by(|a, b| direct(position(&a.risk_status).cmp(&position(&b.risk_status)))),
+    "created" => risks.sort_by(|a, b| direct(a.risk_created_at.cmp(&b.risk_created_at))),
+    "reviewed" => risks.sort_by(|a, b| date(&a.risk_reviewed_at, &b.risk_reviewed_at)),
+    "next_review" => risks.sort_by(|a, b| date(&a.risk_next_review, &b.risk_next_review)),
+    // the risks come sorted by name
+    _ => {
+      if descending {
+        risks.reverse();
+      }
+    }
+  }
 }
 
-fn average_resolution(ctm_list: Vec<Countermeasure>) -> f64 {
+pub fn average_resolution(ctm_list: Vec<Countermeasure>) -> f64 {
   let mut total = 0.0;
   for ctm in &ctm_list {
       total += ctm.solved as f64;
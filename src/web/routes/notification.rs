@@ -0,0 +1,88 @@
+// the web controller of the email preferences of the owners and of the
+// sent emails
+use chrono::Weekday;
+use minijinja::{context, Value};
+
+use crate::helper::config::config;
+use crate::helper::database::{Countermeasure, Notification, NotificationPreference, Risk};
+use crate::helper::i18n::t;
+use crate::helper::notification::{enabled, is_owner, kind_label, Email, MAX_ATTEMPTS};
+use crate::helper::template::render;
+
+/// Emails shown in the log
+const LOG_SIZE: i32 = 100;
+
+fn notification_color(status: &str) -> &'static str {
+    match status {
+        "sent" => "bg-success",
+        "failed" => "bg-danger",
+        _ => "bg-warning",
+    }
+}
+
+/// The owners of the risks and the countermeasures, and the ones with
+/// preferences, without the duplicates that only differ by case
+fn owners(risks: &[Risk], measures: &[Countermeasure], preferences: &[NotificationPreference]) -> Vec<String> {
+    let mut owners: Vec<String> = Vec::new();
+    let names = preferences.iter().map(|p| p.user_name.clone())
+        .chain(risks.iter().map(|r| r.risk_owner.clone()))
+        .chain(measures.iter().map(|c| c.owner.clone()));
+    for name in names {
+        if !name.trim().is_empty() && !owners.iter().any(|o| is_owner(o, &name)) {
+            owners.push(name.trim().to_string());
+        }
+    }
+    owners.sort_by_key(|o| o.to_lowercase());
+    owners
+}
+
+pub async fn list() -> String {
+    let preferences = NotificationPreference::get_all_preferences().await;
+    let risks = Risk::select_all_risk().await;
+    let measures = Countermeasure::get_all_countermeasures().await;
+
+    let users = owners(&risks, &measures, &preferences).into_iter()
+        .map(|name| {
+            let preference = preferences.iter().find(|p| is_owner(&p.user_name, &name)).cloned();
+            context! {
+                risks => risks.iter().filter(|r| is_owner(&r.risk_owner, &name)).count(),
+                measures => measures.iter().filter(|c| is_owner(&c.owner, &name)).count(),
+                saved => preference.is_some(),
+                ..Value::from_serialize(preference.unwrap_or(NotificationPreference {
+                    user_name: name,
+                    email: String::new(),
+                    locale: config().default_locale.clone(),
+                    assignments: true,
+                    overdue: true,
+                    reviews: true,
+                    digest: true,
+                }))
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let log = Notification::get_recent_notifications(LOG_SIZE).await.iter()
+        .map(|n| context! {
+            kind_label => kind_label(&n.kind),
+            title => serde_json::from_str::<Email>(&n.message).map(|e| e.subject).unwrap_or_default(),
+            color => notification_color(&n.status),
+            ..Value::from_serialize(n)
+        })
+        .collect::<Vec<_>>();
+
+    render("notification/index.html", context! {
+        enabled => enabled(),
+        smtp_host => config().smtp.host,
+        digest_day => day_label(&config().smtp.digest_day),
+        digest_hour => config().smtp.digest_hour,
+        users,
+        notifications => log,
+        max_attempts => MAX_ATTEMPTS,
+    })
+}
+
+/// The label of a day of `smtp.digest_day`, `mon` or `monday`
+fn day_label(day: &str) -> String {
+    const DAYS: [&str; 7] = ["Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday", "Sunday"];
+    day.parse::<Weekday>().map(|d| t(DAYS[d.num_days_from_monday() as usize])).unwrap_or(day.to_string())
+}
@@ -1,17 +1,23 @@
 // export the home route handler
-use std::fs;
-
+use chrono::{Duration, Local};
+use minijinja::{context, Value};
 use uuid::Uuid;
 
+use crate::api::mods::risk::{category_label, status_label, CATEGORIES, STATUSES};
 use crate::helper::functions::is_uuid_v4;
-use crate::helper::database::{Risk, Scenario, Countermeasure};
+use crate::helper::i18n::{labels, t};
+use crate::helper::template::render;
+use crate::web::routes::acceptance;
+use crate::helper::database::{Risk, Scenario, Countermeasure, CountermeasureLink};
+use crate::web::routes::countermeasure::{measure_context, share_counts, today};
 
 #[tracing::instrument(level = "info")]
 pub async fn create() -> String {
-
-  let index = fs::read_to_string("html/risk/create.html").unwrap();
-
-  return index;
+  return render("risk/create.html", context! {
+    risk => risk_context(&Risk::default(), &today()),
+    categories => labels(&CATEGORIES),
+    statuses => labels(&STATUSES),
+  });
 }
 
 pub async fn detail(path: String) -> String {
@@ -31,34 +37,34 @@ pub async fn detail(path: String) -> String {
 
 
   let scenario_list = Scenario::get_all_scenario_of_risk(risk_uuid.clone()).await;
-  let scenario_count = scenario_list.len();
-
-  let mut str = String::new();
-  let base_scenario = fs::read_to_string("html/risk/files/scenario.html").unwrap();
-
-  for scenario in scenario_list {
-
-    let scenario = base_scenario
-      .replace("{{scenario_id}}", get_id(scenario.scenario_uuid).as_str())
-      .replace("{{scenario_uuid}}", scenario.scenario_uuid.to_string().as_str())
-      .replace("{{scenario_description}}", &scenario.scenario_description.as_str())
-      .replace("{{threat_description}}", scenario.threat_description.as_str());
-
-    str.push_str(scenario.as_str());
-  }
+  let scenario_ids = scenario_list.iter().map(|s| s.scenario_uuid).collect::<Vec<Uuid>>();
 
   let countermeasure = Countermeasure::get_all_countermeasure_from_risk_uuid(risk_uuid.clone()).await;
 
-
-  let index = fs::read_to_string("html/risk/detail.html").unwrap()
-    .replace("{{risk_title}}", risk_detail.risk_name.as_str())
-    .replace("{{risk_uuid}}", risk_detail.risk_uuid.to_string().as_str())
-    .replace("{{risk_description}}", risk_detail.risk_description.as_str())
-    .replace("{{scenario_count}}", scenario_count.to_string().as_str())
-    .replace("{{ctm_count}}", countermeasure.len().to_string().as_str())
-    .replace("{{sc_list}}", str.as_str());
-
-  return index;
+  // a shared measure is listed once, with the scenarios of this risk it mitigates
+  let links = CountermeasureLink::get_all_countermeasure_links().await;
+  let shared = share_counts().await;
+  let today = today();
+  let ctm_list = countermeasure.iter().map(|ctm| {
+    let covered = links.iter()
+      .filter(|l| l.ctm_uuid == ctm.ctm_uuid && scenario_ids.contains(&l.scenario_uuid))
+      .map(|l| l.scenario_uuid)
+      .collect::<Vec<Uuid>>();
+
+    context! {
+      covered,
+      shared => shared.get(&ctm.ctm_uuid).copied().unwrap_or(0),
+      ..measure_context(ctm, &today)
+    }
+  }).collect::<Vec<Value>>();
+
+  render("risk/detail.html", context! {
+    risk => risk_context(risk_detail, &today),
+    scenarios => scenario_list,
+    countermeasures => ctm_list,
+    acceptance => acceptance::card("risk", risk_uuid.clone()).await,
+    next_review_input => (Local::now() + Duration::days(365)).format("%Y-%m-%d").to_string(),
+  })
 }
 
 
@@ -78,17 +84,11 @@ pub async fn update(path: String) -> String {
 
   let risk_detail = risk_detail.get(0).unwrap();
 
-
-  let countermeasure = Countermeasure::get_all_countermeasure_from_risk_uuid(risk_uuid.clone()).await;
-
-
-  let index = fs::read_to_string("html/risk/update.html").unwrap()
-    .replace("{{risk_title}}", risk_detail.risk_name.as_str())
-    .replace("{{risk_uuid}}", risk_detail.risk_uuid.to_string().as_str())
-    .replace("{{risk_description}}", risk_detail.risk_description.as_str())
-    .replace("{{ctm_count}}", countermeasure.len().to_string().as_str());
-
-  return index;
+  render("risk/update.html", context! {
+    risk => risk_context(risk_detail, &today()),
+    categories => labels(&CATEGORIES),
+    statuses => labels(&STATUSES),
+  })
 }
 
 
@@ -105,21 +105,23 @@ pub async fn delete(path: String) -> String {
     return "__404".to_string();
   }
 
-  let risk_detail = risk_detail.get(0).unwrap();
-
-
-  let index = fs::read_to_string("html/risk/delete.html").unwrap()
-    .replace("{{risk_title}}", risk_detail.risk_name.as_str())
-    .replace("{{risk_uuid}}", risk_detail.risk_uuid.to_string().as_str())
-    .replace("{{risk_description}}", risk_detail.risk_description.as_str());
-
-  return index;
+  render("risk/delete.html", context! { risk => risk_detail.first().unwrap() })
 }
 
 
+/// A risk with the labels of its category and status, and whether its
+/// review is overdue
+pub fn risk_context(risk: &Risk, today: &str) -> Value {
+  context! {
+    category_label => t(category_label(&risk.risk_category)),
+    status_label => t(status_label(&risk.risk_status)),
+    review_overdue => risk.is_review_overdue(today),
+    ..Value::from_serialize(risk)
+  }
+}
 
 pub fn get_id(uuid:Uuid) -> String {
   // only keep the first 8 characters
   let id = uuid.to_string();
   return id.chars().take(8).collect();
-}
\ No newline at end of file
+}
@@ -1,7 +1,9 @@
 // export the home route handler
-use std::fs;
+use minijinja::context;
 
-use crate::web::routes::risk::get_id;
+use crate::helper::template::render;
+use crate::web::routes::acceptance;
+use crate::web::routes::countermeasure::{measure_context, share_counts, today};
 use crate::helper::functions::is_uuid_v4;
 use crate::helper::database::{Risk, Scenario, ScenarioRisk, Countermeasure};
 
@@ -22,14 +24,7 @@ pub async fn create(path:String) -> String {
         return "__404".to_string();
     }
 
-    let risk_detail = risk_detail.get(0).unwrap();
-
-    let index = fs::read_to_string("html/scenario/create.html").unwrap()
-        .replace("{{risk_title}}", risk_detail.risk_name.as_str())
-        .replace("{{risk_uuid}}", risk_detail.risk_uuid.to_string().as_str())
-        .replace("{{risk_description}}", risk_detail.risk_description.as_str());
-
-    return index;
+    return render("scenario/create.html", context! { risk => risk_detail.first().unwrap() });
 }
 
 
@@ -56,34 +51,27 @@ pub async fn detail(path:String) -> String {
 
     let countermeasure = Countermeasure::get_all_countermeasure_of_sc(scenario_detail.scenario_uuid.to_string()).await;
 
-    let mut countermeasure_html = String::new();
-    let base_countermeasure = fs::read_to_string("html/scenario/files/countermeasure.html").unwrap();
-
-    for cm in countermeasure {
-        let cm_html = base_countermeasure.replace("{{cm_uuid}}", cm.ctm_uuid.to_string().as_str())
-            .replace("{{cm_title}}", cm.title.as_str())
-            .replace("{{cm_id}}", get_id(cm.ctm_uuid).to_string().as_str())
-            .replace("{{cm_solved}}", cm.solved.to_string().as_str())
-            .replace("{{cm_description}}", cm.description.as_str());
-
-        countermeasure_html.push_str(cm_html.as_str());
-    }
-
-
-    let index = fs::read_to_string("html/scenario/detail.html").unwrap()
-        .replace("{{scenario_uuid}}", scenario_detail.scenario_uuid.to_string().as_str())
-        .replace("{{scenario_note}}", scenario_detail.add_note.to_string().as_str())
-        .replace("{{scenario_description}}", scenario_detail.scenario_description.as_str())
-        .replace("{{scenario_threat}}", scenario_detail.threat_description.to_string().as_str())
-        .replace("{{sc_likelihood}}", scenario_risk.likelihood.to_string().as_str())
-        .replace("{{sc_operational}}", scenario_risk.operational.to_string().as_str())
-        .replace("{{sc_legal_compliance}}", scenario_risk.legal_compliance.to_string().as_str())
-        .replace("{{sc_financial}}", scenario_risk.financial.to_string().as_str())
-        .replace("{{sc_final_risk}}", calculate_risk(scenario_risk.likelihood, scenario_risk.operational, scenario_risk.legal_compliance, scenario_risk.financial, scenario_risk.reputation))
-        .replace("{{sc_reputation}}", scenario_risk.reputation.to_string().as_str())
-        .replace("{{ctm_list}}", countermeasure_html.as_str());
-
-    return index;
+    let today = today();
+    let shared = share_counts().await;
+
+    // measures of the catalogue not mitigating this scenario yet
+    let catalogue = Countermeasure::get_all_countermeasures().await.into_iter()
+        .filter(|cm| !countermeasure.iter().any(|c| c.ctm_uuid == cm.ctm_uuid))
+        .collect::<Vec<Countermeasure>>();
+
+    let ctm_list = countermeasure.iter().map(|cm| context! {
+        shared => shared.get(&cm.ctm_uuid).copied().unwrap_or(0),
+        ..measure_context(cm, &today)
+    }).collect::<Vec<_>>();
+
+    render("scenario/detail.html", context! {
+        scenario => scenario_detail,
+        rating => scenario_risk,
+        final_risk => calculate_risk(scenario_risk.likelihood, scenario_risk.operational, scenario_risk.legal_compliance, scenario_risk.financial, scenario_risk.reputation),
+        catalogue,
+        countermeasures => ctm_list,
+        acceptance => acceptance::card("scenario", scenario_detail.scenario_uuid.to_string()).await,
+    })
 }
 
 pub async fn update(path:String) -> String {
@@ -104,21 +92,11 @@ pub async fn update(path:String) -> String {
     let scenario_detail = scenario_detail.get(0).unwrap();
 
     let scenario_risk = ScenarioRisk::get_scenario_risk(scenario_detail.scenario_uuid.to_string()).await;
-    let scenario_risk = scenario_risk.get(0).unwrap();
-
 
-    let index = fs::read_to_string("html/scenario/update.html").unwrap()
-        .replace("{{scenario_uuid}}", scenario_detail.scenario_uuid.to_string().as_str())
-        .replace("{{scenario_note}}", scenario_detail.add_note.to_string().as_str())
-        .replace("{{scenario_description}}", scenario_detail.scenario_description.as_str())
-        .replace("{{scenario_threat}}", scenario_detail.threat_description.to_string().as_str())
-        .replace("{{sc_likelihood}}", scenario_risk.likelihood.to_string().as_str())
-        .replace("{{sc_operational}}", scenario_risk.operational.to_string().as_str())
-        .replace("{{sc_legal_compliance}}", scenario_risk.legal_compliance.to_string().as_str())
-        .replace("{{sc_financial}}", scenario_risk.financial.to_string().as_str())
-        .replace("{{sc_reputation}}", scenario_risk.reputation.to_string().as_str());
-
-    return index;
+    render("scenario/update.html", context! {
+        scenario => scenario_detail,
+        rating => scenario_risk.first().unwrap(),
+    })
 
 }
 
@@ -137,16 +115,7 @@ pub async fn delete(path:String) -> String {
         return "__404".to_string();
     }
 
-    let scenario_detail = scenario_detail.get(0).unwrap();
-
-
-    let index = fs::read_to_string("html/scenario/delete.html").unwrap()
-        .replace("{{scenario_uuid}}", scenario_detail.scenario_uuid.to_string().as_str())
-        .replace("{{scenario_note}}", scenario_detail.add_note.to_string().as_str())
-        .replace("{{scenario_description}}", scenario_detail.scenario_description.as_str())
-        .replace("{{scenario_threat}}", scenario_detail.threat_description.to_string().as_str());
-
-    return index;
+    render("scenario/delete.html", context! { scenario => scenario_detail.first().unwrap() })
 
 }
 
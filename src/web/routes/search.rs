@@ -0,0 +1,27 @@
+// the web controller of the global search
+use std::collections::HashMap;
+
+use actix_web::web::Query;
+use minijinja::context;
+
+use crate::helper::search::{search, terms, MIN_QUERY_LEN};
+use crate::helper::template::render;
+
+/// The results of the search bar, `/search?q=...`
+pub async fn page(query: String) -> String {
+    let params = Query::<HashMap<String, String>>::from_query(&query)
+        .map(|q| q.into_inner())
+        .unwrap_or_default();
+    let search_query = params.get("q").cloned().unwrap_or_default();
+
+    let groups = search(&search_query).await;
+    let total = groups.iter().map(|g| g.total).sum::<usize>();
+
+    render("search/index.html", context! {
+        too_short => terms(&search_query).is_empty(),
+        min_length => MIN_QUERY_LEN,
+        search_query,
+        groups,
+        total,
+    })
+}
@@ -0,0 +1,94 @@
+// the web controller of the study snapshots
+use minijinja::{context, Value};
+
+use crate::api::mods::countermeasure::status_label;
+use crate::helper::database::Snapshot;
+use crate::helper::i18n::t;
+use crate::helper::snapshot::{StudyDiff, StudyState};
+use crate::helper::template::render;
+use crate::helper::functions::risk_level_rank;
+
+pub async fn list() -> String {
+    let snapshots = Snapshot::get_all_snapshots().await;
+
+    render("snapshot/index.html", context! { snapshots })
+}
+
+/// `live` or the id of a snapshot, with the label to show
+async fn load(side: &str) -> Option<(StudyState, String)> {
+    if side == "live" {
+        return Some((StudyState::load().await, t("Live state")));
+    }
+
+    let snapshot = Snapshot::get_snapshot(side.parse::<i32>().ok()?).await.pop()?;
+    let state = serde_json::from_str::<StudyState>(&snapshot.content).ok()?;
+    Some((state, format!("{} ({})", snapshot.snapshot_name, snapshot.created_at)))
+}
+
+pub async fn diff(path: String) -> String {
+    let sides = path.replace("snapshot/diff/", "");
+    let (from, to) = match sides.split_once('/') {
+        Some(s) => s,
+        None => return "__404".to_string(),
+    };
+
+    let ((from, from_label), (to, to_label)) = match (load(from).await, load(to).await) {
+        (Some(f), Some(t)) => (f, t),
+        _ => return "__404".to_string(),
+    };
+
+    let diff = StudyDiff::between(&from, &to);
+    let risk_name = |risk_uuid: &str| to.risks.iter().chain(from.risks.iter())
+        .find(|r| r.risk_uuid == risk_uuid)
+        .map(|r| r.risk_name.clone())
+        .unwrap_or_default();
+
+    let level_changes = diff.level_changes.iter().map(|(before, after)| context! {
+        scenario_uuid => after.scenario_uuid,
+        scenario_description => after.scenario_description,
+        risk_name => risk_name(&after.risk_uuid),
+        before => before.level,
+        after => after.level,
+        worse => risk_level_rank(&after.level) > risk_level_rank(&before.level),
+    }).collect::<Vec<Value>>();
+
+    let mut risk_changes = diff.new_risks.iter()
+        .map(|r| context! { risk_name => r.risk_name, added => true })
+        .collect::<Vec<Value>>();
+    risk_changes.extend(diff.removed_risks.iter()
+        .map(|r| context! { risk_name => r.risk_name, added => false }));
+
+    let mut scenario_changes = diff.new_scenarios.iter()
+        .map(|s| context! { scenario_description => s.scenario_description, risk_name => risk_name(&s.risk_uuid), level => s.level, added => true })
+        .collect::<Vec<Value>>();
+    scenario_changes.extend(diff.removed_scenarios.iter()
+        .map(|s| context! { scenario_description => s.scenario_description, risk_name => risk_name(&s.risk_uuid), level => s.level, added => false }));
+
+    let mut measure_changes = diff.closed_measures.iter()
+        .map(|c| context! { title => c.title, status => t(status_label(&c.status)), closed => true })
+        .collect::<Vec<Value>>();
+    measure_changes.extend(diff.reopened_measures.iter()
+        .map(|c| context! { title => c.title, status => t(status_label(&c.status)), closed => false }));
+
+    let gap_changes = diff.gap_changes.iter()
+        .map(|(before, after)| context! { before, after })
+        .collect::<Vec<Value>>();
+
+    let stakeholder_changes = diff.stakeholder_changes.iter()
+        .map(|(before, after)| context! { before, after })
+        .collect::<Vec<Value>>();
+
+    render("snapshot/diff.html", context! {
+        from_label,
+        to_label,
+        new_risk_count => diff.new_risks.len(),
+        removed_risk_count => diff.removed_risks.len(),
+        closed_count => diff.closed_measures.len(),
+        level_changes,
+        risk_changes,
+        scenario_changes,
+        measure_changes,
+        gap_changes,
+        stakeholder_changes,
+    })
+}
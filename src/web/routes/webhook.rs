@@ -0,0 +1,54 @@
+// the web controller of the webhooks and of their delivery log
+use minijinja::{context, Value};
+
+use crate::helper::database::{Webhook, WebhookDelivery};
+use crate::helper::i18n::t;
+use crate::helper::template::render;
+use crate::helper::webhook::{enabled, EVENT_TYPES, MAX_ATTEMPTS, TEST_EVENT};
+
+/// Deliveries shown in the log
+const LOG_SIZE: i32 = 100;
+
+fn event_label(event_type: &str) -> String {
+    if event_type == TEST_EVENT {
+        return t("Test");
+    }
+    EVENT_TYPES.iter().find(|(e, _)| *e == event_type).map(|(_, label)| t(label)).unwrap_or(event_type.to_string())
+}
+
+fn delivery_color(status: &str) -> &'static str {
+    match status {
+        "delivered" => "bg-success",
+        "failed" => "bg-danger",
+        _ => "bg-warning",
+    }
+}
+
+pub async fn list() -> String {
+    let webhooks = Webhook::get_all_webhooks().await;
+
+    let list = webhooks.iter()
+        .map(|w| context! { event => event_label(&w.event_type), ..Value::from_serialize(w) })
+        .collect::<Vec<_>>();
+
+    let log = WebhookDelivery::get_recent_deliveries(LOG_SIZE).await.iter()
+        .map(|d| context! {
+            event => event_label(&d.event_type),
+            url => webhooks.iter().find(|w| w.webhook_id == d.webhook_id).map(|w| w.url.clone()).unwrap_or_default(),
+            color => delivery_color(&d.status),
+            ..Value::from_serialize(d)
+        })
+        .collect::<Vec<_>>();
+
+    let event_types = EVENT_TYPES.iter()
+        .map(|(value, label)| context! { value, label => t(label) })
+        .collect::<Vec<_>>();
+
+    render("webhook/index.html", context! {
+        enabled => enabled(),
+        event_types,
+        webhooks => list,
+        deliveries => log,
+        max_attempts => MAX_ATTEMPTS,
+    })
+}
@@ -0,0 +1,42 @@
+use matryriska::api::mods::acceptance::{is_elevated, requires_elevation};
+use matryriska::helper::config::AcceptanceConfig;
+
+fn policy(elevation_token: &str) -> AcceptanceConfig {
+    AcceptanceConfig {
+        max_level: "medium".to_string(),
+        elevated_users: vec!["ciso".to_string()],
+        elevation_token: elevation_token.to_string(),
+    }
+}
+
+#[test]
+fn only_the_levels_above_the_max_need_elevation() {
+    let acceptance = AcceptanceConfig::default();
+
+    assert!(!requires_elevation(&acceptance, "LOW"));
+    assert!(!requires_elevation(&acceptance, "MEDIUM"));
+    assert!(requires_elevation(&acceptance, "HIGH"));
+    assert!(requires_elevation(&acceptance, "CRITICAL"));
+
+    // the level of the config is not case sensitive
+    assert!(!requires_elevation(&policy("s3cret-token"), "MEDIUM"));
+    assert!(requires_elevation(&policy("s3cret-token"), "HIGH"));
+}
+
+#[test]
+fn a_typed_name_is_not_enough_to_be_elevated() {
+    let policy = policy("s3cret-token");
+
+    assert!(is_elevated(&policy, "CISO ", "s3cret-token"));
+    // the name is free text, the token is the proof
+    assert!(!is_elevated(&policy, "ciso", ""));
+    assert!(!is_elevated(&policy, "ciso", "s3cret-tokem"));
+    assert!(!is_elevated(&policy, "alice", "s3cret-token"));
+}
+
+#[test]
+fn nobody_is_elevated_without_a_token_in_the_config() {
+    let policy = policy("");
+
+    assert!(!is_elevated(&policy, "ciso", ""));
+}
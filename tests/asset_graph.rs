@@ -0,0 +1,71 @@
+use matryriska::helper::database::{AssetDependency, AssetLink, BienSupport, FearedEvent};
+use matryriska::web::routes::c1::AssetGraph;
+
+fn asset(support_id: i32) -> BienSupport {
+    BienSupport { support_id, ..BienSupport::default() }
+}
+
+fn event(valeur_metier: i32, gravite: i32) -> FearedEvent {
+    FearedEvent { valeur_metier, gravite, ..FearedEvent::default() }
+}
+
+fn link(support_id: i32, valeur_id: i32) -> AssetLink {
+    AssetLink { support_id, valeur_id }
+}
+
+fn depends(support_id: i32, depend_de: i32) -> AssetDependency {
+    AssetDependency { support_id, depend_de }
+}
+
+#[test]
+fn a_business_value_takes_its_worst_feared_event() {
+    let mut graph = AssetGraph::new(vec![], vec![asset(1)], vec![link(1, 10)], vec![], vec![]);
+    graph.propagate(&[event(10, 2), event(10, 4), event(10, 3), event(20, 1)]);
+
+    assert_eq!(graph.vm_gravity(10), 4);
+    assert_eq!(graph.vm_gravity(20), 1);
+    // a business value without feared event has no gravity
+    assert_eq!(graph.vm_gravity(30), 0);
+    assert_eq!(graph.asset_gravity(1), 4);
+}
+
+#[test]
+fn an_asset_shared_by_several_business_values_takes_the_worst() {
+    let mut graph = AssetGraph::new(
+        vec![],
+        vec![asset(1), asset(2), asset(3)],
+        vec![link(1, 10), link(1, 20), link(2, 10), link(3, 20)],
+        vec![],
+        vec![],
+    );
+    graph.propagate(&[event(10, 2), event(20, 3)]);
+
+    assert_eq!(graph.asset_gravity(1), 3);
+    assert_eq!(graph.asset_gravity(2), 2);
+    assert_eq!(graph.asset_gravity(3), 3);
+}
+
+#[test]
+fn the_gravity_goes_down_the_dependencies() {
+    // 1 and 2 both depend on the shared asset 3, which depends on 4; the
+    // dependencies are listed leaf first so that one pass is not enough
+    let mut graph = AssetGraph::new(
+        vec![],
+        vec![asset(1), asset(2), asset(3), asset(4)],
+        vec![link(1, 10), link(2, 20)],
+        vec![depends(3, 4), depends(1, 3), depends(2, 3)],
+        vec![],
+    );
+    graph.propagate(&[event(10, 2), event(20, 4)]);
+
+    // the gravity does not go back up, 1 keeps its own below the shared 3
+    assert_eq!(graph.asset_gravity(1), 2);
+    assert_eq!(graph.asset_gravity(2), 4);
+    assert_eq!(graph.asset_gravity(3), 4);
+    assert_eq!(graph.asset_gravity(4), 4);
+
+    // a new propagation starts over
+    graph.propagate(&[event(10, 1)]);
+    assert_eq!(graph.asset_gravity(2), 0);
+    assert_eq!(graph.asset_gravity(4), 1);
+}
@@ -0,0 +1,119 @@
+use std::env;
+use std::fs;
+
+use matryriska::helper::config::Config;
+
+fn args(list: &[&str]) -> Vec<String> {
+    let mut args = vec!["matryriska".to_string()];
+    args.extend(list.iter().map(|a| a.to_string()));
+    args
+}
+
+fn config_file(name: &str, content: &str) -> String {
+    let path = env::temp_dir().join(format!("matryriska-{}-{}.json", name, std::process::id()));
+    fs::write(&path, content).unwrap();
+    path.to_str().unwrap().to_string()
+}
+
+// the only test touching the environment, the others run alongside it
+#[test]
+fn layers_override_each_other_in_order() {
+    let path = config_file("layers", r#"{"db_host": "file-host", "db_port": 3307, "web_port": 9000, "c3_radar": {"danger": 3.0}}"#);
+
+    env::set_var("MATRYRISKA_DB_PORT", "3308");
+    env::set_var("MATRYRISKA_WEB_PORT", "9001");
+    let config = Config::load(&args(&["--config", &path, "--web-port=9002", "--risk-acceptance-elevated-users", "alice, bob"]));
+    env::remove_var("MATRYRISKA_DB_PORT");
+    env::remove_var("MATRYRISKA_WEB_PORT");
+
+    let config = config.unwrap();
+    assert_eq!(config.db_host, "file-host");
+    assert_eq!(config.db_port, 3308);
+    assert_eq!(config.web_port, 9002);
+    assert_eq!(config.db_username, "matryriska");
+    assert_eq!(config.c3_radar.danger, 3.0);
+    assert_eq!(config.c3_radar.control, 0.9);
+    assert_eq!(config.risk_acceptance.elevated_users, vec!["alice", "bob"]);
+}
+
+#[test]
+fn every_problem_is_reported() {
+    let path = config_file("invalid", r#"{"db_backend": "oracle", "c3_radar": {"danger": 0.1}}"#);
+
+    let errors = Config::load(&args(&["--config", &path, "--web-port", "http", "--verbose", "--risk-acceptance-max-level", "severe", "--security-allowed-origins", "https://intranet.example.org, intranet", "--security-cookie-same-site", "None"])).unwrap_err();
+    assert!(errors.iter().any(|e| e.starts_with("db_backend:")));
+    assert!(errors.iter().any(|e| e.starts_with("c3_radar:")));
+    assert!(errors.iter().any(|e| e == "--web-port: http is not a port number"));
+    assert!(errors.iter().any(|e| e == "--verbose: unknown flag"));
+    assert!(errors.iter().any(|e| e.starts_with("risk_acceptance.max_level:")));
+    assert!(errors.iter().any(|e| e.starts_with("security.allowed_origins: intranet is not an origin")));
+    assert!(errors.iter().any(|e| e == "security.cookie_same_site: None requires security.cookie_secure"));
+
+    let path = config_file("unknown-key", r#"{"db_hostname": "db"}"#);
+    let errors = Config::load(&args(&["--config", &path])).unwrap_err();
+    assert!(errors[0].contains("unknown field `db_hostname`"));
+
+    let errors = Config::load(&args(&["--config", "/nonexistent/matryriska.json"])).unwrap_err();
+    assert!(errors[0].starts_with("/nonexistent/matryriska.json:"));
+}
+
+#[test]
+fn the_other_flags_are_not_config_keys() {
+    let path = config_file("flags", r#"{"db_backend": "sqlite"}"#);
+
+    let config = Config::load(&args(&["--prod", "--config", &path, "--migrate-to-postgres", "host=pg dbname=matryriska", "--webhook"])).unwrap();
+    assert_eq!(config.db_backend, "sqlite");
+    // the switch of the Dockerfile
+    assert!(config.webhooks);
+}
+
+#[test]
+fn the_password_is_not_printed() {
+    let mut config = Config { db_password: "StrongPassword123".to_string(), ..Config::default() };
+    config.smtp.password = "SmtpSecret456".to_string();
+
+    let printed = serde_json::to_string(&config.redacted()).unwrap();
+    assert!(!printed.contains("StrongPassword123"));
+    assert!(!printed.contains("SmtpSecret456"));
+    assert_eq!(config.db_password, "StrongPassword123");
+}
+
+#[test]
+fn the_smtp_server_is_checked() {
+    let path = config_file("smtp", r#"{"smtp": {"host": "smtp.example.org", "from": "MatryRiska <matryriska@example.org>", "digest_day": "fri"}}"#);
+    let config = Config::load(&args(&["--config", &path, "--smtp-port", "465", "--smtp-tls", "TLS", "--smtp-base-url", "https://matryriska.example.org/"])).unwrap();
+    assert_eq!(config.smtp.host, "smtp.example.org");
+    assert_eq!(config.smtp.port, 465);
+    assert_eq!(config.smtp.tls, "tls");
+    assert_eq!(config.smtp.base_url, "https://matryriska.example.org");
+    assert_eq!(config.smtp.digest_hour, 8);
+
+    let errors = Config::load(&args(&["--config", &path, "--smtp-tls", "ssl", "--smtp-from", "matryriska", "--smtp-digest-day", "someday", "--smtp-digest-hour", "24", "--smtp-base-url", "matryriska.example.org"])).unwrap_err();
+    assert!(errors.iter().any(|e| e == "smtp.tls: ssl is not one of starttls, tls, none"));
+    assert!(errors.iter().any(|e| e.starts_with("smtp.from: matryriska is not an email address")));
+    assert!(errors.iter().any(|e| e == "smtp.digest_day: someday is not a day of the week"));
+    assert!(errors.iter().any(|e| e == "smtp.digest_hour: 24 is not between 0 and 23"));
+    assert!(errors.iter().any(|e| e.starts_with("smtp.base_url: matryriska.example.org is not an address")));
+}
+
+#[test]
+fn the_scheduler_is_tuned_with_flags() {
+    let config = Config::load(&args(&["--scheduler-backup-hours", "6", "--scheduler-backup-keep", "0", "--scheduler-snapshot-hours", "168"])).unwrap();
+    assert_eq!(config.scheduler.backup_hours, 6);
+    assert_eq!(config.scheduler.backup_keep, 0);
+    assert_eq!(config.scheduler.backup_dir, "backups");
+    assert_eq!(config.scheduler.snapshot_hours, 168);
+
+    let errors = Config::load(&args(&["--scheduler-backup-hours", "-1", "--scheduler-backup-dir", ""])).unwrap_err();
+    assert!(errors.iter().any(|e| e == "--scheduler-backup-hours: -1 is not a positive whole number"));
+    assert!(errors.iter().any(|e| e == "scheduler.backup_dir: the directory of the backups is required"));
+}
+
+#[test]
+fn the_elevation_token_is_not_printed() {
+    let config = Config::load(&args(&["--risk-acceptance-elevation-token", "Elevate789"])).unwrap();
+    assert_eq!(config.risk_acceptance.elevation_token, "Elevate789");
+
+    let printed = serde_json::to_string(&config.redacted()).unwrap();
+    assert!(!printed.contains("Elevate789"));
+}
@@ -0,0 +1,11 @@
+use matryriska::api::mods::countermeasure::{solved_range, OPEN_SOLVED_MAX, STATUSES};
+
+#[test]
+fn solved_follows_the_status() {
+    // an implemented measure is fully solved, an open one never is
+    assert_eq!(solved_range("implemented"), (100, 100));
+    assert_eq!(solved_range("verified"), (100, 100));
+    for (status, _) in STATUSES.iter().filter(|(s, _)| *s != "implemented" && *s != "verified") {
+        assert_eq!(solved_range(status), (0, OPEN_SOLVED_MAX));
+    }
+}
@@ -0,0 +1,107 @@
+use std::fs;
+use std::path::Path;
+
+use actix_web::cookie::Cookie;
+use actix_web::http::header;
+use actix_web::test::{call_service, init_service, read_body, TestRequest};
+use actix_web::App;
+use regex::Regex;
+
+use matryriska::helper::i18n::{self, from_accept_language, translate, LOCALE_COOKIE};
+use matryriska::helper::template;
+use matryriska::web;
+use matryriska::helper::config::RadarConfig;
+use matryriska::web::routes::c3::{zone, zone_label, ZONES};
+
+fn texts(dir: &Path, pattern: &Regex, extension: &str, found: &mut Vec<(String, String)>) {
+    for entry in fs::read_dir(dir).unwrap().flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            texts(&path, pattern, extension, found);
+        } else if path.extension().is_some_and(|e| e == extension) {
+            let content = fs::read_to_string(&path).unwrap();
+            for cap in pattern.captures_iter(&content) {
+                found.push((path.display().to_string(), cap[1].replace("\\\"", "\"")));
+            }
+        }
+    }
+}
+
+#[test]
+fn every_text_is_translated_in_french() {
+    let mut found = Vec::new();
+    texts(Path::new("html"), &Regex::new(r#"_\("((?:[^"\\]|\\.)*)""#).unwrap(), "html", &mut found);
+    texts(Path::new("html"), &Regex::new(r#"_\("((?:[^"\\]|\\.)*)""#).unwrap(), "txt", &mut found);
+    texts(Path::new("src"), &Regex::new(r#"\bt\("((?:[^"\\]|\\.)*)"\)"#).unwrap(), "rs", &mut found);
+    assert!(found.len() > 500);
+
+    let catalogue = i18n::load(i18n::LOCALE_DIR).unwrap().into_iter().find(|c| c.code == "fr").unwrap();
+    let missing = found.iter().filter(|(_, text)| !catalogue.messages.contains_key(text)).collect::<Vec<_>>();
+    assert!(missing.is_empty(), "missing in locales/fr.json: {:#?}", missing);
+}
+
+#[test]
+fn the_radar_zones_are_keys_translated_by_label() {
+    let radar = RadarConfig::default();
+    assert_eq!(zone(&radar, 3.0).0, "danger");
+    assert_eq!(zone(&radar, 0.1).0, "outside");
+
+    let catalogue = i18n::load(i18n::LOCALE_DIR).unwrap().into_iter().find(|c| c.code == "fr").unwrap();
+    for (zone, label) in ZONES {
+        assert_eq!(zone_label(zone), label);
+        assert!(catalogue.messages.contains_key(label), "{}", label);
+    }
+    assert_eq!(translate("fr", zone_label("watch")), "Veille");
+}
+
+#[test]
+fn the_browser_language_picks_the_locale() {
+    assert_eq!(from_accept_language("fr-FR,fr;q=0.9,en;q=0.8").as_deref(), Some("fr"));
+    assert_eq!(from_accept_language("de-DE,de;q=0.9,en;q=0.5").as_deref(), Some("en"));
+    assert_eq!(from_accept_language("en;q=0.4,fr;q=0.7").as_deref(), Some("fr"));
+    assert_eq!(from_accept_language("fr;q=0,en").as_deref(), Some("en"));
+    assert_eq!(from_accept_language("de, *").as_deref(), None);
+
+    assert_eq!(translate("fr", "Risk register"), "Registre des risques");
+    // no translation, the English text
+    assert_eq!(translate("fr", "Not a text of the UI"), "Not a text of the UI");
+    assert_eq!(translate("en", "Risk register"), "Risk register");
+}
+
+#[test]
+fn a_translation_keeps_the_placeholders() {
+    let dir = std::env::temp_dir().join(format!("matryriska-locales-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("de.json"), r#"{"name": "Deutsch", "messages": {"Update to {version}": "Aktualisieren"}}"#).unwrap();
+    fs::write(dir.join("es.json"), r#"{"name": "Español", "texts": {}}"#).unwrap();
+
+    let errors = i18n::load(dir.to_str().unwrap()).unwrap_err();
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(errors.len(), 2);
+    assert!(errors[0].starts_with("de.json: \"Update to {version}\" must keep the placeholders"));
+    assert!(errors[1].starts_with("es.json: unknown field `texts`"));
+}
+
+#[actix_web::test]
+async fn the_pages_are_rendered_in_the_negotiated_locale() {
+    template::init();
+    let app = init_service(App::new().service(web::init::init_web())).await;
+
+    let page = |req: TestRequest| {
+        let app = &app;
+        async move { String::from_utf8(read_body(call_service(app, req.uri("/nothing/here").to_request()).await).await.to_vec()).unwrap() }
+    };
+
+    let html = page(TestRequest::get().insert_header((header::ACCEPT_LANGUAGE, "fr-FR,fr;q=0.9"))).await;
+    assert!(html.contains(r#"<html lang="fr""#));
+    assert!(html.contains("Oups ! Cette page est introuvable"));
+
+    let html = page(TestRequest::get()).await;
+    assert!(html.contains(r#"<html lang="en""#));
+    assert!(html.contains("Oops! Sorry page does not found"));
+
+    // the language menu wins over the browser
+    let html = page(TestRequest::get().insert_header((header::ACCEPT_LANGUAGE, "fr")).cookie(Cookie::new(LOCALE_COOKIE, "en"))).await;
+    assert!(html.contains("Oops! Sorry page does not found"));
+}
@@ -0,0 +1,195 @@
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::sync::mpsc;
+use std::thread;
+
+use chrono::{Local, TimeZone};
+use minijinja::context;
+use uuid::Uuid;
+
+use matryriska::api::mods::risk::STATUSES;
+use matryriska::helper::config::SmtpConfig;
+use matryriska::helper::database::{Countermeasure, Risk};
+use matryriska::helper::notification::{backoff_seconds, compose, digest_of, digest_week, send, transport, Email, MAX_ATTEMPTS};
+use matryriska::helper::template;
+
+/// A local SMTP server accepting every email, the transcript of each
+/// connection is sent on the channel
+fn smtp_sink() -> (u16, mpsc::Receiver<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let (tx, rx) = mpsc::channel();
+
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let mut writer = stream.try_clone().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut transcript = String::new();
+            let mut in_data = false;
+            let mut line = String::new();
+
+            writer.write_all(b"220 sink ESMTP\r\n").unwrap();
+            while reader.read_line(&mut line).unwrap_or(0) > 0 {
+                transcript.push_str(&line);
+                let command = line.to_uppercase();
+                let reply = if in_data {
+                    in_data = line != ".\r\n";
+                    if in_data { "" } else { "250 queued\r\n" }
+                } else if command.starts_with("EHLO") {
+                    "250-sink\r\n250 8BITMIME\r\n"
+                } else if command.starts_with("DATA") {
+                    in_data = true;
+                    "354 end with a dot\r\n"
+                } else if command.starts_with("QUIT") {
+                    "221 bye\r\n"
+                } else {
+                    "250 OK\r\n"
+                };
+                writer.write_all(reply.as_bytes()).unwrap();
+                line.clear();
+                if command.starts_with("QUIT") {
+                    break;
+                }
+            }
+            let _ = tx.send(transcript);
+        }
+    });
+
+    (port, rx)
+}
+
+fn smtp(port: u16) -> SmtpConfig {
+    SmtpConfig { host: "127.0.0.1".to_string(), port, tls: "none".to_string(), ..SmtpConfig::default() }
+}
+
+fn measure(title: &str, owner: &str, due_date: &str, status: &str) -> Countermeasure {
+    Countermeasure {
+        ctm_uuid: Uuid::new_v4(),
+        scenario_uuid: Uuid::nil(),
+        title: title.to_string(),
+        description: String::new(),
+        solved: 0,
+        solved_description: String::new(),
+        owner: owner.to_string(),
+        due_date: due_date.to_string(),
+        priority: "high".to_string(),
+        cost: 0,
+        effort: 0,
+        status: status.to_string(),
+    }
+}
+
+fn risk(name: &str, owner: &str, next_review: &str, status: &str) -> Risk {
+    assert!(STATUSES.iter().any(|(s, _)| *s == status), "not a risk status: {}", status);
+    Risk {
+        risk_name: name.to_string(),
+        risk_owner: owner.to_string(),
+        risk_next_review: next_review.to_string(),
+        risk_status: status.to_string(),
+        ..Risk::default()
+    }
+}
+
+#[actix_web::test]
+async fn an_email_is_sent_as_text_and_html() {
+    template::init();
+    let ctm = measure("Patch the VPN", "alice", "2026-11-02", "approved");
+    let email = compose(
+        "assignment",
+        "en",
+        "Countermeasure assigned to you: {title}",
+        &[("title", ctm.title.clone())],
+        context! { ctm, status => "Approved", url => "http://localhost:8080/countermeasure/detail/1", user_name => "alice", preferences_url => "http://localhost:8080/notification" },
+    ).await.unwrap();
+
+    assert_eq!(email.subject, "Countermeasure assigned to you: Patch the VPN");
+    assert!(email.text.starts_with("Hello alice,\n\nThe countermeasure Patch the VPN is assigned to you."));
+    assert!(email.text.contains("Due date: 2026-11-02"));
+    assert!(email.text.contains("Open the countermeasure: http://localhost:8080/countermeasure/detail/1"));
+
+    let (port, received) = smtp_sink();
+    send(&transport(&smtp(port)).unwrap(), "MatryRiska <matryriska@localhost>", "alice@example.org", &email).await.unwrap();
+
+    let transcript = received.recv().unwrap();
+    assert!(transcript.contains("MAIL FROM:<matryriska@localhost>"));
+    assert!(transcript.contains("RCPT TO:<alice@example.org>"));
+    assert!(transcript.contains("Subject: Countermeasure assigned to you: Patch the VPN"));
+    assert!(transcript.contains("multipart/alternative"));
+    assert!(transcript.contains("Content-Type: text/plain; charset=utf-8"));
+    assert!(transcript.contains("Content-Type: text/html; charset=utf-8"));
+    assert!(transcript.contains("Priority: high"));
+}
+
+#[actix_web::test]
+async fn an_email_is_written_in_the_language_of_its_owner() {
+    template::init();
+    let risk = risk("R&D <lab>", "bob", "2026-10-12", "assessed");
+    let email = compose(
+        "review",
+        "fr",
+        "Risk review due: {name}",
+        &[("name", risk.risk_name.clone())],
+        context! { risk, url => "http://localhost:8080/risk/detail/1", user_name => "bob", preferences_url => "http://localhost:8080/notification" },
+    ).await.unwrap();
+
+    assert_eq!(email.subject, "Revue de risque à faire : R&D <lab>");
+    assert!(email.html.contains(r#"<html lang="fr">"#));
+    // escaped in the html only
+    assert!(email.html.contains("R&amp;D &lt;lab&gt;"));
+    assert!(email.text.contains("La revue du risque R&D <lab> était prévue le 2026-10-12."));
+}
+
+#[actix_web::test]
+async fn an_unreachable_server_is_an_error() {
+    let port = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+    let email = Email { subject: "Test".to_string(), text: "text".to_string(), html: "<p>html</p>".to_string() };
+
+    assert!(send(&transport(&smtp(port)).unwrap(), "matryriska@localhost", "alice@example.org", &email).await.is_err());
+    assert!(send(&transport(&smtp(port)).unwrap(), "matryriska@localhost", "not an address", &email).await.unwrap_err().starts_with("invalid recipient"));
+}
+
+#[test]
+fn the_digest_lists_what_waits_for_an_owner() {
+    let measures = vec![
+        measure("No deadline", "Alice", "", "proposed"),
+        measure("Late", "alice", "2026-10-01", "in_progress"),
+        measure("Done", "alice", "2026-09-01", "implemented"),
+        measure("Next month", " alice ", "2026-11-20", "approved"),
+        measure("Not hers", "bob", "2026-10-01", "approved"),
+    ];
+    let risks = vec![
+        risk("Overdue review", "alice", "2026-10-10", "treated"),
+        risk("Review this week", "ALICE", "2026-10-25", "identified"),
+        risk("Review next month", "alice", "2026-11-20", "identified"),
+        risk("Closed", "alice", "2026-10-10", "closed"),
+        risk("Never reviewed", "alice", "", "identified"),
+    ];
+
+    let digest = digest_of("alice", &measures, &risks, "2026-10-19");
+    let titles = digest.measures.iter().map(|c| c.title.as_str()).collect::<Vec<&str>>();
+    assert_eq!(titles, vec!["Late", "Next month", "No deadline"]);
+    let names = digest.reviews.iter().map(|r| r.risk_name.as_str()).collect::<Vec<&str>>();
+    assert_eq!(names, vec!["Overdue review", "Review this week"]);
+
+    assert!(digest_of("carol", &measures, &risks, "2026-10-19").is_empty());
+}
+
+#[test]
+fn the_digest_is_sent_on_its_day_once_a_week() {
+    let smtp = SmtpConfig { digest_day: "monday".to_string(), digest_hour: 8, ..SmtpConfig::default() };
+    let at = |day: u32, hour: u32| Local.with_ymd_and_hms(2026, 10, day, hour, 0, 0).unwrap();
+
+    // 2026-10-19 is a Monday
+    assert_eq!(digest_week(&at(19, 8), &smtp).as_deref(), Some("2026-W43"));
+    assert_eq!(digest_week(&at(19, 23), &smtp).as_deref(), Some("2026-W43"));
+    assert_eq!(digest_week(&at(19, 7), &smtp), None);
+    assert_eq!(digest_week(&at(20, 9), &smtp), None);
+    assert_eq!(digest_week(&at(26, 9), &smtp).as_deref(), Some("2026-W44"));
+}
+
+#[test]
+fn failed_emails_back_off_exponentially() {
+    let waits = (1..MAX_ATTEMPTS).map(backoff_seconds).collect::<Vec<i64>>();
+
+    assert_eq!(waits, vec![60, 120, 240, 480]);
+}
@@ -0,0 +1,148 @@
+use std::fs;
+use std::path::Path;
+
+use jsonschema::JSONSchema;
+use serde_json::Value;
+use uuid::Uuid;
+
+use matryriska::helper::database::{Countermeasure, Gap, Requirement, Risk, Scenario, ScenarioRisk};
+use matryriska::helper::oscal::{token, OscalExport, RiskFinding};
+
+fn validate_with(path: &str, document: &Value) {
+    let schema: Value = serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap();
+    let compiled = JSONSchema::compile(&schema).expect("invalid schema");
+    if let Err(errors) = compiled.validate(document) {
+        let errors = errors.map(|e| format!("{} at {}", e, e.instance_path)).collect::<Vec<String>>();
+        panic!("document does not match {}:\n{}\n{}", path, errors.join("\n"), document);
+    };
+}
+
+/// Validate against the official NIST schema vendored by
+/// tests/schemas/fetch-nist.sh, and against the subset of the assemblies
+/// MatryRiska emits
+fn validate(model: &str, document: &Value) {
+    let official = format!("tests/schemas/oscal_{}_schema.json", model);
+    if Path::new(&official).exists() {
+        validate_with(&official, document);
+    } else {
+        eprintln!("{} is missing, run tests/schemas/fetch-nist.sh", official);
+    }
+    validate_with(&format!("tests/schemas/oscal_{}_subset_schema.json", model), document);
+}
+
+fn gap(gap_id: i32, requirement_id: i32, application_state: i32) -> Gap {
+    Gap {
+        gap_id,
+        referential_type: "ISO 27001".to_string(),
+        referential_name: "Annex A".to_string(),
+        application_state,
+        gap: if application_state < 100 { "No formal process".to_string() } else { String::new() },
+        gap_justification: String::new(),
+        proposed_measures: "Write the policy".to_string(),
+        requirement_id,
+        needs_review: false,
+        review_reason: String::new(),
+    }
+}
+
+fn requirement(requirement_id: i32, code: &str) -> Requirement {
+    Requirement {
+        requirement_id,
+        referential_id: 1,
+        code: code.to_string(),
+        parent_code: "A.5".to_string(),
+        title: "Policies for information security".to_string(),
+        description: String::new(),
+        position: requirement_id,
+    }
+}
+
+fn finding(solved: &[i32], rated: bool) -> RiskFinding {
+    let risk = Risk {
+        risk_uuid: Uuid::new_v4(),
+        risk_name: "Ransomware".to_string(),
+        risk_description: "Encryption of the file servers".to_string(),
+        ..Risk::default()
+    };
+    let scenario = Scenario {
+        scenario_uuid: Uuid::new_v4(),
+        risk_uuid: risk.risk_uuid,
+        scenario_description: "Phishing of an administrator".to_string(),
+        threat_description: "Organised crime".to_string(),
+        add_note: String::new(),
+    };
+    RiskFinding {
+        rating: rated.then_some(ScenarioRisk {
+            scenario_uuid: scenario.scenario_uuid,
+            likelihood: 3,
+            reputation: 2,
+            operational: 4,
+            legal_compliance: 1,
+            financial: 3,
+        }),
+        countermeasures: solved.iter().map(|s| Countermeasure {
+            ctm_uuid: Uuid::new_v4(),
+            scenario_uuid: scenario.scenario_uuid,
+            title: "MFA".to_string(),
+            description: String::new(),
+            solved: *s,
+            solved_description: String::new(),
+            status: if *s >= 100 { "implemented" } else if *s > 0 { "in_progress" } else { "proposed" }.to_string(),
+            ..Countermeasure::default()
+        }).collect(),
+        risk,
+        scenario,
+    }
+}
+
+fn study() -> OscalExport {
+    OscalExport {
+        gaps: vec![
+            (gap(1, 10, 40), Some(requirement(10, "A.5.1"))),
+            (gap(2, 11, 100), Some(requirement(11, "5.1 (a)"))),
+            (gap(3, 0, 0), None),
+        ],
+        findings: vec![finding(&[], true), finding(&[50, 100], true), finding(&[100], false)],
+    }
+}
+
+#[test]
+fn assessment_results_match_the_schema() {
+    let document = study().assessment_results();
+    validate("assessment-results", &document);
+
+    // the imported plan is a resource of the back-matter
+    let href = document["assessment-results"]["import-ap"]["href"].as_str().unwrap();
+    assert_eq!(href, format!("#{}", document["assessment-results"]["back-matter"]["resources"][0]["uuid"].as_str().unwrap()));
+
+    let result = &document["assessment-results"]["results"][0];
+    assert_eq!(result["findings"].as_array().unwrap().len(), 3);
+    assert_eq!(result["risks"].as_array().unwrap().len(), 3);
+}
+
+#[test]
+fn poam_matches_the_schema() {
+    let document = study().poam();
+    validate("poam", &document);
+
+    // the fully applied gap and the risk with every countermeasure solved are done
+    let poam = &document["plan-of-action-and-milestones"];
+    assert_eq!(poam["poam-items"].as_array().unwrap().len(), 4);
+    assert_eq!(poam["risks"].as_array().unwrap().len(), 2);
+}
+
+#[test]
+fn empty_study_matches_the_schemas() {
+    let export = OscalExport::default();
+    validate("assessment-results", &export.assessment_results());
+    validate("poam", &export.poam());
+    assert_eq!(export.poam()["plan-of-action-and-milestones"]["poam-items"].as_array().unwrap().len(), 1);
+}
+
+#[test]
+fn control_codes_become_tokens() {
+    assert_eq!(token("A.5.1"), "A.5.1");
+    assert_eq!(token("5.1 (a)"), "_5.1__a_");
+    assert_eq!(token("GV.OC-01"), "GV.OC-01");
+    assert_eq!(token(""), "_");
+}
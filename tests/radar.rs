@@ -0,0 +1,79 @@
+use matryriska::helper::config::RadarConfig;
+use matryriska::helper::database::C3Stakeholder;
+use matryriska::helper::threat::{residual_threat_level, threat_level};
+use matryriska::web::routes::c3::{radius, zone};
+
+fn stakeholder(dependance: i32, penetration: i32, maturite_ssi: i32, confiance: i32) -> C3Stakeholder {
+    C3Stakeholder { dependance, penetration, maturite_ssi, confiance, ..C3Stakeholder::default() }
+}
+
+fn close(a: f64, b: f64) -> bool {
+    (a - b).abs() < 1e-6
+}
+
+#[test]
+fn the_threat_level_is_the_exposure_over_the_cyber_reliability() {
+    assert_eq!(threat_level(&stakeholder(3, 4, 2, 2)), (12.0, 4.0, 3.0));
+    // rounded to two decimals
+    assert_eq!(threat_level(&stakeholder(1, 1, 1, 3)).2, 0.33);
+    // no reliability rated yet, no threat
+    assert_eq!(threat_level(&stakeholder(4, 4, 0, 3)), (16.0, 0.0, 0.0));
+
+    // the residual ratings not evaluated fall back to the current ones
+    let mut m = stakeholder(3, 4, 2, 2);
+    m.penetration_residuelle = Some(1);
+    m.confiance_residuelle = Some(3);
+    assert_eq!(residual_threat_level(&m), (3.0, 6.0, 0.5));
+    assert_eq!(residual_threat_level(&stakeholder(3, 4, 2, 2)), threat_level(&stakeholder(3, 4, 2, 2)));
+}
+
+#[test]
+fn a_threshold_belongs_to_the_inner_zone() {
+    let radar = RadarConfig::default();
+    let zones = [
+        (radar.danger, "danger", 0),
+        (radar.danger - 0.01, "control", 1),
+        (radar.control, "control", 1),
+        (radar.control - 0.01, "watch", 2),
+        (radar.watch, "watch", 2),
+        (radar.watch - 0.01, "outside", 3),
+        (0.0, "outside", 3),
+    ];
+    for (niveau, key, index) in zones {
+        let (z, _, i) = zone(&radar, niveau);
+        assert_eq!((z, i), (key, index), "{}", niveau);
+    }
+
+    // the thresholds come from the config
+    let radar = RadarConfig { danger: 4.0, control: 2.0, watch: 1.0 };
+    assert_eq!(zone(&radar, 3.0).0, "control");
+    assert_eq!(zone(&radar, 1.0).0, "watch");
+}
+
+#[test]
+fn the_radius_shrinks_with_the_threat_without_jump_at_the_thresholds() {
+    let radar = RadarConfig::default();
+
+    // the circles of the zones
+    assert!(close(radius(&radar, radar.danger), 90.0));
+    assert!(close(radius(&radar, radar.control), 170.0));
+    assert!(close(radius(&radar, radar.watch), 250.0));
+    assert!(close(radius(&radar, 0.0), 290.0));
+
+    // just below a threshold is next to its circle
+    for threshold in [radar.danger, radar.control, radar.watch] {
+        let gap = radius(&radar, threshold - 1e-9) - radius(&radar, threshold);
+        assert!((0.0..1e-3).contains(&gap), "{}: {}", threshold, gap);
+    }
+
+    // inside the danger zone, the higher the closer, down to a minimum
+    assert!(close(radius(&radar, 2.0 * radar.danger), 45.0));
+    assert!(close(radius(&radar, 1000.0), 12.0));
+
+    let mut previous = f64::MAX;
+    for step in 0..=60 {
+        let r = radius(&radar, step as f64 * 0.05);
+        assert!(r <= previous, "{}", step);
+        previous = r;
+    }
+}
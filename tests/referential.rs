@@ -0,0 +1,90 @@
+use matryriska::helper::database::{Countermeasure, Requirement};
+use matryriska::helper::referential::{derived_state, Catalogue, CatalogueDiff};
+use uuid::Uuid;
+
+fn requirement(code: &str, parent_code: &str, title: &str) -> Requirement {
+    Requirement {
+        requirement_id: 0,
+        referential_id: 0,
+        code: code.to_string(),
+        parent_code: parent_code.to_string(),
+        title: title.to_string(),
+        description: String::new(),
+        position: 0,
+    }
+}
+
+fn measure(status: &str, solved: i32) -> Countermeasure {
+    Countermeasure {
+        ctm_uuid: Uuid::new_v4(),
+        scenario_uuid: Uuid::nil(),
+        title: String::new(),
+        description: String::new(),
+        solved,
+        solved_description: String::new(),
+        owner: String::new(),
+        due_date: String::new(),
+        priority: String::new(),
+        cost: 0,
+        effort: 0,
+        status: status.to_string(),
+    }
+}
+
+#[test]
+fn a_refused_catalogue_says_where() {
+    let e = Catalogue::parse("{\"key\": \"iso\", \"name\": \"ISO\", \"version\": 2022}", "json").unwrap_err();
+    assert_eq!(e.status, "catalogue_not_valid");
+    assert!(e.message.contains("line 1 column"), "{}", e.message);
+
+    let e = Catalogue::parse("key: iso\nname: ISO\nversion: '2022'\n", "yaml").unwrap_err();
+    assert_eq!(e.status, "catalogue_not_valid");
+    assert!(e.message.contains("requirements"), "{}", e.message);
+
+    let e = Catalogue::parse("{}", "xml").unwrap_err();
+    assert_eq!(e.status, "format_not_valid");
+    assert!(e.message.is_empty());
+}
+
+#[test]
+fn the_diff_lists_added_changed_and_removed_measures() {
+    let old = vec![
+        requirement("A", "", "Organisation"),
+        requirement("A.1", "A", "Policies"),
+        requirement("A.2", "A", "Roles"),
+        requirement("A.3", "A", "Segregation"),
+    ];
+    let new = vec![
+        requirement("A", "", "Organisation"),
+        requirement("A.1", "A", "Information security policies"),
+        requirement("A.3", "", "Segregation"),
+        requirement("A.4", "A", "Threat intelligence"),
+    ];
+
+    let diff = CatalogueDiff::between(&old, &new);
+    assert_eq!(diff.added, vec!["A.4"]);
+    // a new title and a new parent both change a requirement
+    assert_eq!(diff.changed, vec!["A.1", "A.3"]);
+    assert_eq!(diff.removed, vec!["A.2"]);
+    assert!(!diff.is_empty());
+
+    assert!(CatalogueDiff::between(&old, &old).is_empty());
+}
+
+#[test]
+fn the_measures_derive_the_state_of_a_gap() {
+    // without a measure the state stays assessed by hand
+    assert_eq!(derived_state(&[]), None);
+
+    // nothing before the work starts, the progress capped below full while
+    // in progress, full once implemented
+    assert_eq!(derived_state(&[
+        measure("proposed", 50),
+        measure("approved", 0),
+        measure("in_progress", 95),
+        measure("implemented", 0),
+    ]), Some(48));
+    assert_eq!(derived_state(&[measure("in_progress", 33), measure("implemented", 0), measure("approved", 0)]), Some(44));
+
+    assert_eq!(derived_state(&[measure("verified", 0), measure("verified", 100)]), Some(100));
+}
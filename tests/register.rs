@@ -0,0 +1,33 @@
+use matryriska::helper::database::Risk;
+use matryriska::web::routes::main::sort_risks;
+
+fn risk(name: &str, next_review: &str) -> Risk {
+    Risk {
+        risk_name: name.to_string(),
+        risk_next_review: next_review.to_string(),
+        ..Risk::default()
+    }
+}
+
+fn names(risks: &[Risk]) -> Vec<&str> {
+    risks.iter().map(|r| r.risk_name.as_str()).collect()
+}
+
+#[test]
+fn the_risks_without_review_date_stay_last() {
+    let mut risks = vec![risk("A", ""), risk("B", "2026-12-01"), risk("C", "2026-11-01"), risk("D", "")];
+
+    sort_risks(&mut risks, "next_review", false);
+    assert_eq!(names(&risks), vec!["C", "B", "A", "D"]);
+
+    sort_risks(&mut risks, "next_review", true);
+    assert_eq!(names(&risks), vec!["B", "C", "A", "D"]);
+}
+
+#[test]
+fn the_register_comes_by_name_in_both_directions() {
+    let mut risks = vec![risk("A", ""), risk("B", ""), risk("C", "")];
+
+    sort_risks(&mut risks, "name", true);
+    assert_eq!(names(&risks), vec!["C", "B", "A"]);
+}
@@ -0,0 +1,114 @@
+use std::env;
+use std::fs;
+
+use matryriska::helper::backup::{dump, prune, FILE_PREFIX};
+use matryriska::helper::database::{create_table_query, Job};
+use matryriska::helper::scheduler::{is_due, job_spec, jobs, next_run_at, overdue_message, NEVER};
+use matryriska::helper::storage::sqlite::SqliteBackend;
+use matryriska::helper::storage::{Backend, Dialect};
+use matryriska::helper::webhook::now_after;
+
+fn state(job_name: &str, last_status: &str, next_run_at: &str) -> Job {
+    Job {
+        job_name: job_name.to_string(),
+        last_status: last_status.to_string(),
+        last_message: String::new(),
+        last_started_at: String::new(),
+        last_finished_at: String::new(),
+        last_duration_ms: 0,
+        next_run_at: next_run_at.to_string(),
+        run_count: 0,
+        failure_count: 0,
+    }
+}
+
+fn temp_dir(name: &str) -> String {
+    let path = env::temp_dir().join(format!("matryriska-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&path);
+    fs::create_dir_all(&path).unwrap();
+    path.to_str().unwrap().to_string()
+}
+
+#[test]
+fn the_jobs_follow_the_config() {
+    // the defaults: no webhooks, no SMTP server, a backup a day, no snapshot
+    let names = jobs().iter().map(|j| j.name).collect::<Vec<&str>>();
+    assert!(names.contains(&"database_refresh"));
+    assert!(job_spec("unknown").is_none());
+
+    assert_eq!(job_spec("webhook_deliveries").unwrap().disabled, Some("Webhooks are off"));
+    assert_eq!(job_spec("email_deliveries").unwrap().disabled, Some("No SMTP server is set"));
+    assert!(job_spec("overdue_countermeasures").unwrap().disabled.is_some());
+    assert_eq!(job_spec("database_backup").unwrap().every_seconds, 24 * 3600);
+    assert!(job_spec("database_backup").unwrap().disabled.is_none());
+    assert_eq!(job_spec("study_snapshot").unwrap().every_seconds, 0);
+}
+
+#[test]
+fn a_job_runs_once_due() {
+    let refresh = job_spec("database_refresh").unwrap();
+    let now = "2026-10-19 10:00:00";
+
+    assert!(is_due(&refresh, &state("database_refresh", "success", "2026-10-19 10:00:00"), now));
+    assert!(is_due(&refresh, &state("database_refresh", "interrupted", "2026-10-19 09:00:00"), now));
+    assert!(!is_due(&refresh, &state("database_refresh", "success", "2026-10-19 10:05:00"), now));
+    // never twice at once
+    assert!(!is_due(&refresh, &state("database_refresh", "running", "2026-10-19 09:00:00"), now));
+    // not with this config, even when triggered
+    let webhooks = job_spec("webhook_deliveries").unwrap();
+    assert!(!is_due(&webhooks, &state("webhook_deliveries", "", "2026-10-19 09:00:00"), now));
+}
+
+#[test]
+fn the_next_run_is_one_interval_after_the_start() {
+    let refresh = job_spec("database_refresh").unwrap();
+    let next = next_run_at(&refresh, 20);
+    assert!(next >= now_after(280) && next <= now_after(281));
+
+    // a job only run by hand waits for the next trigger
+    assert_eq!(next_run_at(&job_spec("study_snapshot").unwrap(), 0), NEVER);
+    assert!(!is_due(&job_spec("study_snapshot").unwrap(), &state("study_snapshot", "success", NEVER), &now_after(0)));
+}
+
+#[test]
+fn the_overdue_run_reports_each_side() {
+    assert_eq!(overdue_message(Some(2), Some(3)), "2 overdue countermeasures for webhooks, 3 for emails");
+    assert_eq!(overdue_message(Some(2), None), "2 overdue countermeasures for webhooks");
+    assert_eq!(overdue_message(None, Some(0)), "0 overdue countermeasures for emails");
+}
+
+#[test]
+fn a_backup_holds_every_table() {
+    let path = env::temp_dir().join(format!("matryriska-backup-{}.db", std::process::id()));
+    let _ = fs::remove_file(&path);
+    let backend = SqliteBackend::open(path.to_str().unwrap()).unwrap();
+    let mut conn = backend.get_conn().unwrap();
+
+    let tables: Vec<serde_json::Value> = serde_json::from_str(&fs::read_to_string("assets/_internals/db.json").unwrap()).unwrap();
+    for table in tables.iter() {
+        let columns = table["columns"].as_array().unwrap();
+        conn.query_drop(create_table_query(Dialect::Sqlite, table["name"].as_str().unwrap(), columns)).unwrap();
+    }
+    conn.query_drop("INSERT INTO risk (risk_uuid, risk_name, risk_description) VALUES ('r-1', 'O\\'Brien', 'backup')").unwrap();
+
+    let backup = dump(&mut conn).unwrap();
+    assert_eq!(backup["tables"].as_object().unwrap().len(), tables.len());
+    assert_eq!(backup["tables"]["risk"][0]["risk_name"], "O'Brien");
+    assert_eq!(backup["tables"]["job"], serde_json::json!([]));
+}
+
+#[test]
+fn only_the_last_backups_are_kept() {
+    let dir = temp_dir("backups");
+    for name in ["20261017-080000", "20261018-080000", "20261019-080000"] {
+        fs::write(format!("{}/{}{}.json", dir, FILE_PREFIX, name), "{}").unwrap();
+    }
+    fs::write(format!("{}/notes.txt", dir), "not a backup").unwrap();
+
+    assert_eq!(prune(&dir, 0).unwrap(), 0);
+    assert_eq!(prune(&dir, 2).unwrap(), 1);
+
+    let mut left = fs::read_dir(&dir).unwrap().flatten().map(|e| e.file_name().into_string().unwrap()).collect::<Vec<String>>();
+    left.sort();
+    assert_eq!(left, vec!["matryriska-20261018-080000.json", "matryriska-20261019-080000.json", "notes.txt"]);
+}
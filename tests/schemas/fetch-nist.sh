@@ -0,0 +1,13 @@
+#!/bin/sh
+# Vendor the official NIST OSCAL JSON schemas of the version MatryRiska
+# exports (OSCAL_VERSION in src/helper/oscal.rs); tests/oscal_export.rs
+# validates the exports against them
+set -e
+
+VERSION=1.1.2
+DIR=$(dirname "$0")
+
+for schema in oscal_assessment-results_schema.json oscal_poam_schema.json; do
+    curl -fsSL -o "$DIR/$schema" "https://github.com/usnistgov/OSCAL/releases/download/v$VERSION/$schema"
+    echo "$DIR/$schema"
+done
@@ -0,0 +1,105 @@
+use matryriska::helper::search::{highlight, rank, score, terms, Document, Matcher, Part, MAX_RESULTS};
+
+fn doc(kind: &'static str, title: &str, text: &str) -> Document {
+    Document {
+        kind,
+        url: format!("/{}/{}", kind, title.to_lowercase().replace(' ', "-")),
+        title: title.to_string(),
+        context: String::new(),
+        texts: vec![text.to_string()],
+    }
+}
+
+fn marked(parts: &[Part]) -> Vec<&str> {
+    parts.iter().filter(|p| p.matched).map(|p| p.text.as_str()).collect()
+}
+
+#[test]
+fn a_query_is_split_in_lowercase_words() {
+    assert_eq!(terms("  Ransomware  ERP ransomware "), vec!["ransomware", "erp"]);
+    assert!(terms("a").is_empty());
+    assert!(terms("   ").is_empty());
+    assert!(Matcher::new("a").is_none());
+    assert!(rank("a", vec![doc("risk", "a", "a")]).is_empty());
+}
+
+#[test]
+fn every_word_must_be_found() {
+    let matcher = Matcher::new("ransomware erp").unwrap();
+    assert!(score(&doc("risk", "Ransomware on the ERP", ""), &matcher).is_some());
+    assert!(score(&doc("risk", "Ransomware", "on the ERP servers"), &matcher).is_some());
+    assert!(score(&doc("risk", "Ransomware", "on the laptops"), &matcher).is_none());
+}
+
+#[test]
+fn a_title_outranks_a_description() {
+    let groups = rank("ransomware", vec![
+        doc("risk", "Supplier outage", "The provider is hit by ransomware, ransomware everywhere"),
+        doc("risk", "Ransomware on the ERP", "Encryption of the servers"),
+        doc("risk", "Fire", "The datacenter burns"),
+    ]);
+    assert_eq!(groups.len(), 1);
+    assert_eq!(groups[0].total, 2);
+    assert_eq!(groups[0].hits[0].url, "/risk/ransomware-on-the-erp");
+    assert!(groups[0].hits[0].score > groups[0].hits[1].score);
+
+    // the exact title first
+    let groups = rank("phishing", vec![doc("risk", "Phishing campaign", ""), doc("risk", "Phishing", "")]);
+    assert_eq!(groups[0].hits[0].url, "/risk/phishing");
+}
+
+#[test]
+fn the_groups_with_the_best_hits_come_first() {
+    let groups = rank("backup", vec![
+        doc("risk", "Lost data", "no backup"),
+        doc("countermeasure", "Backup", ""),
+        doc("stakeholder", "Cloud provider", "backup of the hosting"),
+        doc("asset", "Storage", "backup"),
+    ]);
+    let kinds = groups.iter().map(|g| g.kind).collect::<Vec<&str>>();
+    // the same score for the others, in the order of the kinds
+    assert_eq!(kinds, vec!["countermeasure", "risk", "asset", "stakeholder"]);
+}
+
+#[test]
+fn a_group_keeps_its_best_results() {
+    let documents = (0..MAX_RESULTS + 5).map(|i| doc("gap", &format!("Gap {}", i), "missing policy")).collect();
+    let groups = rank("policy", documents);
+    assert_eq!(groups[0].total, MAX_RESULTS + 5);
+    assert_eq!(groups[0].hits.len(), MAX_RESULTS);
+}
+
+#[test]
+fn the_words_are_highlighted_in_their_case() {
+    let matcher = Matcher::new("ERP ransomware").unwrap();
+    let parts = highlight("Ransomware on the ERP and the erp", &matcher);
+    assert_eq!(marked(&parts), vec!["Ransomware", "ERP", "erp"]);
+    assert_eq!(parts.iter().map(|p| p.text.as_str()).collect::<String>(), "Ransomware on the ERP and the erp");
+}
+
+#[test]
+fn what_is_scored_is_what_is_highlighted() {
+    // lowercase changes the length of these characters: the Kelvin sign and
+    // the dotted capital I
+    for (query, title) in [("kelvin", "\u{212A}elvin scale"), ("İzmir", "Office in İzmir")] {
+        let groups = rank(query, vec![doc("asset", title, "")]);
+        assert_eq!(groups.len(), 1, "{}", query);
+        let hit = &groups[0].hits[0];
+        assert_eq!(marked(&hit.title).len(), 1, "{}", query);
+        assert_eq!(hit.title.iter().map(|p| p.text.as_str()).collect::<String>(), title);
+    }
+}
+
+#[test]
+fn a_snippet_is_cut_around_the_first_match() {
+    let text = format!("{} the ransomware spreads {}", "word ".repeat(30), "word ".repeat(30));
+    let groups = rank("ransomware", vec![doc("scenario", "Phishing", &text)]);
+    let snippet = groups[0].hits[0].snippet.iter().map(|p| p.text.as_str()).collect::<String>();
+    assert!(snippet.starts_with("…word "));
+    assert!(snippet.ends_with(" word…"));
+    assert_eq!(marked(&groups[0].hits[0].snippet), vec!["ransomware"]);
+
+    // no match outside of the title, the beginning of the description
+    let groups = rank("phishing", vec![doc("scenario", "Phishing", "A mail with a link")]);
+    assert_eq!(groups[0].hits[0].snippet, vec![Part { text: "A mail with a link".to_string(), matched: false }]);
+}
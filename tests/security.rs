@@ -0,0 +1,104 @@
+use actix_web::cookie::{Cookie, SameSite};
+use actix_web::http::{header, StatusCode};
+use actix_web::{test, App};
+
+use matryriska::api;
+use matryriska::helper::security::{self, CSRF_COOKIE, CSRF_HEADER};
+use matryriska::helper::template;
+use matryriska::web;
+
+const TOKEN: &str = "5f2b7c1e9a0d4c8b3e6f1a2d7c9b0e4f8a1d3c5e7b9f0a2c4e6d8b1f3a5c7e9d";
+const RISK: &str = r#"{"risk_uuid": "6f1b2c3d-4e5f-4a6b-8c7d-9e0f1a2b3c4d"}"#;
+
+fn risk_delete() -> test::TestRequest {
+    test::TestRequest::post()
+        .uri("/api/risk/delete")
+        .insert_header((header::HOST, "localhost:8080"))
+        .insert_header((header::CONTENT_TYPE, "application/json"))
+        .set_payload(RISK)
+}
+
+#[actix_web::test]
+async fn a_cross_origin_delete_is_rejected() {
+    let app = test::init_service(App::new().wrap(security::cors()).service(api::init::init_api())).await;
+
+    // even with the token of the victim, another site is refused
+    let req = risk_delete()
+        .insert_header((header::ORIGIN, "https://evil.example.org"))
+        .cookie(Cookie::new(CSRF_COOKIE, TOKEN))
+        .insert_header((CSRF_HEADER, TOKEN))
+        .to_request();
+    let res = test::call_service(&app, req).await;
+
+    assert_eq!(res.status(), StatusCode::FORBIDDEN);
+    assert!(res.headers().get(header::ACCESS_CONTROL_ALLOW_ORIGIN).is_none());
+    let body: serde_json::Value = test::read_body_json(res).await;
+    assert_eq!(body["error"], "origin not allowed");
+}
+
+#[actix_web::test]
+async fn a_delete_without_the_token_is_rejected() {
+    let app = test::init_service(App::new().wrap(security::cors()).service(api::init::init_api())).await;
+
+    // a form of another site, the browser sends no token
+    let res = test::call_service(&app, risk_delete().to_request()).await;
+    assert_eq!(res.status(), StatusCode::FORBIDDEN);
+    let body: serde_json::Value = test::read_body_json(res).await;
+    assert_eq!(body["error"], "missing csrf cookie");
+
+    let req = risk_delete().cookie(Cookie::new(CSRF_COOKIE, TOKEN)).to_request();
+    let body: serde_json::Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(body["error"], "missing csrf token");
+
+    let req = risk_delete()
+        .cookie(Cookie::new(CSRF_COOKIE, TOKEN))
+        .insert_header((CSRF_HEADER, TOKEN.replace('5', "6")))
+        .to_request();
+    let body: serde_json::Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(body["error"], "invalid csrf token");
+}
+
+#[actix_web::test]
+async fn a_call_from_the_pages_is_accepted() {
+    let app = test::init_service(App::new().wrap(security::cors()).service(api::init::init_api())).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/")
+        .insert_header((header::HOST, "localhost:8080"))
+        .insert_header((header::ORIGIN, "http://localhost:8080"))
+        .cookie(Cookie::new(CSRF_COOKIE, TOKEN))
+        .insert_header((CSRF_HEADER, TOKEN))
+        .set_payload("{}")
+        .to_request();
+    let res = test::call_service(&app, req).await;
+
+    assert_eq!(res.status(), StatusCode::OK);
+    let body: serde_json::Value = test::read_body_json(res).await;
+    assert_eq!(body["status"], "OK");
+}
+
+#[actix_web::test]
+async fn the_pages_carry_the_token_of_their_cookie() {
+    template::init();
+    let app = test::init_service(App::new().wrap(security::cors()).service(web::init::init_web())).await;
+
+    // a page that needs no database
+    let res = test::call_service(&app, test::TestRequest::get().uri("/nothing/here").to_request()).await;
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let cookie = res.response().cookies().find(|c| c.name() == CSRF_COOKIE).unwrap().into_owned();
+    assert_eq!(cookie.same_site(), Some(SameSite::Strict));
+    assert_eq!(cookie.http_only(), Some(true));
+    assert_eq!(cookie.path(), Some("/"));
+    assert_eq!(cookie.value().len(), 64);
+
+    let html = String::from_utf8(test::read_body(res).await.to_vec()).unwrap();
+    assert!(html.contains(&format!(r#"<meta name="csrf-token" content="{}">"#, cookie.value())));
+
+    // the browser keeps its token, no new cookie
+    let req = test::TestRequest::get().uri("/nothing/here").cookie(Cookie::new(CSRF_COOKIE, TOKEN)).to_request();
+    let res = test::call_service(&app, req).await;
+    assert!(res.response().cookies().next().is_none());
+    let html = String::from_utf8(test::read_body(res).await.to_vec()).unwrap();
+    assert!(html.contains(TOKEN));
+}
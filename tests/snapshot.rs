@@ -0,0 +1,105 @@
+use matryriska::helper::snapshot::{GapState, MeasureState, RiskState, ScenarioState, StakeholderState, StudyDiff, StudyState};
+
+fn risk(uuid: &str) -> RiskState {
+    RiskState {
+        risk_uuid: uuid.to_string(),
+        risk_name: format!("Risk {}", uuid),
+        risk_status: "open".to_string(),
+    }
+}
+
+fn scenario(uuid: &str, level: &str) -> ScenarioState {
+    ScenarioState {
+        scenario_uuid: uuid.to_string(),
+        level: level.to_string(),
+        ..ScenarioState::default()
+    }
+}
+
+fn measure(uuid: &str, status: &str) -> MeasureState {
+    MeasureState {
+        ctm_uuid: uuid.to_string(),
+        title: format!("Measure {}", uuid),
+        status: status.to_string(),
+        solved: 0,
+    }
+}
+
+fn uuids<T>(items: &[T], uuid: fn(&T) -> &str) -> Vec<String> {
+    items.iter().map(|i| uuid(i).to_string()).collect()
+}
+
+#[test]
+fn the_diff_lists_added_and_removed_entities() {
+    let from = StudyState {
+        risks: vec![risk("r1"), risk("r2")],
+        scenarios: vec![scenario("s1", "LOW"), scenario("s2", "HIGH")],
+        ..StudyState::default()
+    };
+    let to = StudyState {
+        risks: vec![risk("r2"), risk("r3")],
+        scenarios: vec![scenario("s2", "HIGH"), scenario("s3", "MEDIUM")],
+        ..StudyState::default()
+    };
+
+    let diff = StudyDiff::between(&from, &to);
+    assert_eq!(uuids(&diff.new_risks, |r| &r.risk_uuid), vec!["r3"]);
+    assert_eq!(uuids(&diff.removed_risks, |r| &r.risk_uuid), vec!["r1"]);
+    assert_eq!(uuids(&diff.new_scenarios, |s| &s.scenario_uuid), vec!["s3"]);
+    assert_eq!(uuids(&diff.removed_scenarios, |s| &s.scenario_uuid), vec!["s1"]);
+    // a scenario kept at the same level is no change
+    assert!(diff.level_changes.is_empty());
+
+    let same = StudyDiff::between(&from, &from);
+    assert!(same.new_risks.is_empty() && same.removed_risks.is_empty());
+    assert!(same.new_scenarios.is_empty() && same.removed_scenarios.is_empty());
+}
+
+#[test]
+fn the_level_changes_come_worst_increase_first() {
+    let from = StudyState {
+        scenarios: vec![scenario("s1", "LOW"), scenario("s2", "LOW"), scenario("s3", "EXTREME"), scenario("s4", "N/A")],
+        ..StudyState::default()
+    };
+    let to = StudyState {
+        scenarios: vec![scenario("s1", "MEDIUM"), scenario("s2", "CRITICAL"), scenario("s3", "HIGH"), scenario("s4", "LOW")],
+        ..StudyState::default()
+    };
+
+    let diff = StudyDiff::between(&from, &to);
+    let changes: Vec<(&str, &str, &str)> = diff.level_changes.iter()
+        .map(|(before, after)| (after.scenario_uuid.as_str(), before.level.as_str(), after.level.as_str()))
+        .collect();
+    assert_eq!(changes, vec![
+        ("s2", "LOW", "CRITICAL"),
+        ("s1", "LOW", "MEDIUM"),
+        // an unrated scenario ranks below LOW
+        ("s4", "N/A", "LOW"),
+        ("s3", "EXTREME", "HIGH"),
+    ]);
+}
+
+#[test]
+fn the_diff_follows_measures_gaps_and_stakeholders() {
+    let from = StudyState {
+        countermeasures: vec![measure("c1", "planned"), measure("c2", "verified"), measure("c3", "implemented")],
+        gaps: vec![GapState { gap_id: 1, title: "A.1".to_string(), application_state: 0 }, GapState { gap_id: 2, title: "A.2".to_string(), application_state: 1 }],
+        stakeholders: vec![StakeholderState { stakeholder_id: 1, threat_level: 1.0, residual_threat_level: 0.5, ..StakeholderState::default() }],
+        ..StudyState::default()
+    };
+    let to = StudyState {
+        countermeasures: vec![measure("c1", "implemented"), measure("c2", "in_progress"), measure("c3", "verified"), measure("c4", "verified")],
+        gaps: vec![GapState { gap_id: 1, title: "A.1".to_string(), application_state: 2 }, GapState { gap_id: 2, title: "A.2".to_string(), application_state: 1 }],
+        stakeholders: vec![StakeholderState { stakeholder_id: 1, threat_level: 1.0, residual_threat_level: 0.25, ..StakeholderState::default() }],
+        ..StudyState::default()
+    };
+
+    let diff = StudyDiff::between(&from, &to);
+    // a new measure already closed counts, a closed one staying closed does not
+    assert_eq!(uuids(&diff.closed_measures, |c| &c.ctm_uuid), vec!["c1", "c4"]);
+    assert_eq!(uuids(&diff.reopened_measures, |c| &c.ctm_uuid), vec!["c2"]);
+    assert_eq!(diff.gap_changes.len(), 1);
+    assert_eq!((diff.gap_changes[0].0.application_state, diff.gap_changes[0].1.application_state), (0, 2));
+    assert_eq!(diff.stakeholder_changes.len(), 1);
+    assert_eq!(diff.stakeholder_changes[0].1.residual_threat_level, 0.25);
+}
@@ -0,0 +1,74 @@
+use std::env;
+use std::fs;
+
+use matryriska::helper::functions::sql_escape;
+use matryriska::helper::storage::sqlite::SqliteBackend;
+use matryriska::helper::storage::{standard_literals, Backend, Dialect};
+
+fn backend(name: &str) -> SqliteBackend {
+    let path = env::temp_dir().join(format!("matryriska-{}-{}.db", name, std::process::id()));
+    let _ = fs::remove_file(&path);
+    SqliteBackend::open(path.to_str().unwrap()).unwrap()
+}
+
+#[test]
+fn mysql_escapes_are_read_as_mysql_would() {
+    assert_eq!(standard_literals("SELECT 'O\\'Brien'"), "SELECT 'O''Brien'");
+    assert_eq!(standard_literals("SELECT 'a\\\\b', 'c\\nd'"), "SELECT 'a\\b', 'c\nd'");
+    assert_eq!(standard_literals("SELECT 'it''s', 'x\\%'"), "SELECT 'it''s', 'x\\%'");
+    // outside of a literal nothing changes
+    assert_eq!(standard_literals("SELECT a\\b FROM t"), "SELECT a\\b FROM t");
+}
+
+#[test]
+fn an_escaped_text_stays_in_its_literal() {
+    let backend = backend("escape");
+    let mut conn = backend.get_conn().unwrap();
+    conn.query_drop("CREATE TABLE item (name varchar(255) not null, note varchar(255))").unwrap();
+
+    // a backslash before the quote would close the literal with a quote-only escape
+    let name = "x\\'), ('injected";
+    conn.query_drop(format!("INSERT INTO item (name, note) VALUES ('{}', 'kept')", sql_escape(name))).unwrap();
+
+    let rows = conn.query_map("SELECT name, note FROM item", |(name, note): (String, String)| (name, note)).unwrap();
+    assert_eq!(rows, vec![(name.to_string(), "kept".to_string())]);
+    assert_eq!(sql_escape("O'Brien \\"), "O\\'Brien \\\\");
+}
+
+#[test]
+fn rows_round_trip_through_the_portable_schema() {
+    let backend = backend("round-trip");
+    let mut conn = backend.get_conn().unwrap();
+    let d = Dialect::Sqlite;
+
+    conn.query_drop(format!(
+        "CREATE TABLE item (item_id {}, name varchar(255) not null, score int, created_at datetime not null default CURRENT_TIMESTAMP)",
+        d.column_type("serial")
+    )).unwrap();
+
+    conn.query_drop("INSERT INTO item (name, score) VALUES ('O\\'Brien', '7')").unwrap();
+    assert_eq!(conn.last_insert_id(), 1);
+    conn.query_drop("INSERT INTO item (name) VALUES ('second')").unwrap();
+    assert_eq!(conn.last_insert_id(), 2);
+
+    let items = conn.query_map(
+        format!("SELECT item_id, name, score, {} FROM item ORDER BY item_id", d.date_format("created_at", "%Y-%m-%d")),
+        |(item_id, name, score, created_at): (i32, String, Option<i32>, String)| (item_id, name, score, created_at.len()),
+    ).unwrap();
+    assert_eq!(items, vec![(1, "O'Brien".to_string(), Some(7), 10), (2, "second".to_string(), None, 10)]);
+
+    assert_eq!(conn.query_map(d.table_exists("item"), |name: String| name).unwrap().len(), 1);
+    assert_eq!(conn.query_map(d.column_exists("item", "score"), |name: String| name).unwrap().len(), 1);
+    assert!(conn.query_map(d.column_exists("item", "missing"), |name: String| name).unwrap().is_empty());
+}
+
+#[test]
+fn mysql_schema_translates_to_postgres() {
+    let d = Dialect::Postgres;
+    assert_eq!(d.column_type("serial"), "serial primary key");
+    assert_eq!(d.column_type("datetime not null default CURRENT_TIMESTAMP"), "timestamp(0) not null default CURRENT_TIMESTAMP");
+    assert_eq!(d.column_type("longtext not null"), "text not null");
+    assert_eq!(d.column_type("tinyint(1) not null default 0"), "smallint not null default 0");
+    assert_eq!(d.column_type("varchar(36) primary key"), "varchar(36) primary key");
+    assert_eq!(d.date_format("created_at", "%Y-%m-%d %H:%i:%s"), "to_char(created_at, 'YYYY-MM-DD HH24:MI:SS')");
+}
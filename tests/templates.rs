@@ -0,0 +1,43 @@
+use std::env;
+use std::fs;
+
+use minijinja::context;
+
+use matryriska::helper::template::load;
+
+#[test]
+fn every_page_compiles() {
+    if let Err(errors) = load("html") {
+        panic!("invalid templates: {:?}", errors);
+    }
+}
+
+#[test]
+fn user_text_is_escaped() {
+    let templates = load("html").unwrap();
+
+    let html = templates.get_template("c2/detail-risk.html").unwrap().render(context! {
+        risk => context! { risk_id => 1, source_risque => "<script>alert(1)</script>", objectifs_vises => "\"quoted\" & 'single'", retenu => true },
+    }).unwrap();
+
+    assert!(!html.contains("<script>alert(1)</script>"));
+    assert!(html.contains("&lt;script&gt;alert(1)&lt;&#x2f;script&gt;"));
+    assert!(html.contains("&quot;quoted&quot; &amp; &#x27;single&#x27;"));
+    // the layout and its partials are there
+    assert!(html.contains("MatryRiska | "));
+    assert!(html.contains("startbar"));
+}
+
+#[test]
+fn a_missing_template_is_reported() {
+    let dir = env::temp_dir().join(format!("matryriska-templates-{}", std::process::id()));
+    fs::create_dir_all(dir.join("pages")).unwrap();
+    fs::write(dir.join("pages/index.html"), r#"{% extends "layouts/missing.html" %}"#).unwrap();
+    fs::write(dir.join("pages/broken.html"), "{% if %}").unwrap();
+
+    let errors = load(dir.to_str().unwrap()).unwrap_err();
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert!(errors.iter().any(|e| e == "pages/index.html: unknown template layouts/missing.html"));
+    assert!(errors.iter().any(|e| e.starts_with("pages/broken.html:")));
+}
@@ -0,0 +1,32 @@
+use matryriska::helper::webhook::{backoff_seconds, payload, sign, MAX_ATTEMPTS};
+
+#[test]
+fn payloads_are_signed_with_hmac_sha256() {
+    // the receiver recomputes it over the timestamp header, a dot and the raw body
+    assert_eq!(
+        sign("key", 1792396800, "The quick brown fox jumps over the lazy dog"),
+        "sha256=aac12d4cef43e2ebb4935aefded2f9189a89a9553129aa1cbd43649fbc04cf42"
+    );
+    assert_ne!(sign("other", 1792396800, "body"), sign("key", 1792396800, "body"));
+}
+
+#[test]
+fn a_replayed_payload_does_not_match_a_new_timestamp() {
+    assert_ne!(sign("key", 1792396800, "body"), sign("key", 1792397100, "body"));
+}
+
+#[test]
+fn the_payload_names_its_event() {
+    let body: serde_json::Value = serde_json::from_str(&payload("risk.created", serde_json::json!({"name": "O'Brien \"quoted\""}))).unwrap();
+
+    assert_eq!(body["event"], "risk.created");
+    assert_eq!(body["data"]["name"], "O'Brien \"quoted\"");
+    assert!(body["occurred_at"].as_str().unwrap().ends_with('Z'));
+}
+
+#[test]
+fn retries_back_off_exponentially() {
+    let waits = (1..MAX_ATTEMPTS).map(backoff_seconds).collect::<Vec<i64>>();
+
+    assert_eq!(waits, vec![30, 60, 120, 240, 480]);
+}